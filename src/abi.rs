@@ -0,0 +1,457 @@
+//! ABI encoding and decoding helpers
+//!
+//! `abi_encode`, `abi_decode` and `encode_calldata` wrap `cast_abi_encode`, `cast_decode_abi`
+//! and `cast_calldata`. The signature is parsed up front so a malformed signature or the
+//! wrong number of arguments is reported before `cast` runs, and results pair each value
+//! with its ABI type.
+
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::cast;
+use crate::error::ToolError;
+use crate::foundry::FoundryExecutor;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// A function signature split into its parts, e.g. `balanceOf(address)(uint256)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signature {
+    pub name: String,
+    pub inputs: Vec<String>,
+    /// Return types, when the signature has a second parameter list
+    pub outputs: Option<Vec<String>>,
+}
+
+/// Get the ABI encoding tools
+pub fn get_abi_tools() -> Vec<Tool> {
+    vec![abi_encode_tool(), encode_calldata_tool(), abi_decode_tool()]
+}
+
+fn signature_property(example: &str) -> Value {
+    json!({
+        "type": "string",
+        "description": format!("Function signature, e.g. '{}'", example)
+    })
+}
+
+fn args_property() -> Value {
+    json!({
+        "type": "array",
+        "items": {},
+        "description": "Argument values in parameter order; arrays and tuples as strings like '[1,2]' or '(0x..,5)'"
+    })
+}
+
+fn tool(name: &str, description: &str, props: JsonObject, required: Value) -> Tool {
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+    schema.insert("required".to_string(), required);
+    Tool::new(name.to_string(), description.to_string(), Arc::new(schema))
+}
+
+fn abi_encode_tool() -> Tool {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "signature".to_string(),
+        signature_property("(address,uint256)' or 'transfer(address,uint256)"),
+    );
+    props.insert("args".to_string(), args_property());
+    tool(
+        "abi_encode",
+        "ABI-encode arguments for the given parameter types, without a function selector. Returns the hex encoding alongside each typed argument.",
+        props,
+        json!(["signature"]),
+    )
+}
+
+fn encode_calldata_tool() -> Tool {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "signature".to_string(),
+        signature_property("transfer(address,uint256)"),
+    );
+    props.insert("args".to_string(), args_property());
+    tool(
+        "encode_calldata",
+        "Encode a function call as calldata: the 4-byte selector followed by the ABI-encoded arguments. Returns the calldata, the selector and each typed argument.",
+        props,
+        json!(["signature"]),
+    )
+}
+
+fn abi_decode_tool() -> Tool {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "signature".to_string(),
+        signature_property("balanceOf(address)(uint256)"),
+    );
+    props.insert(
+        "data".to_string(),
+        json!({
+            "type": "string",
+            "description": "Hex ABI-encoded data to decode"
+        }),
+    );
+    props.insert(
+        "input".to_string(),
+        json!({
+            "type": "boolean",
+            "description": "Decode the data as the function's inputs rather than its return values (default: false, which needs the return types in the signature)"
+        }),
+    );
+    tool(
+        "abi_decode",
+        "Decode ABI-encoded return data (or, with input: true, arguments) against a function signature. Returns each decoded value with its type.",
+        props,
+        json!(["signature", "data"]),
+    )
+}
+
+/// Split a parameter list on its top-level commas, leaving tuple and array types whole
+pub fn split_params(list: &str) -> Vec<String> {
+    let mut params = Vec::new();
+    let mut depth = 0i32;
+    let mut current = String::new();
+    for c in list.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                params.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() || !params.is_empty() {
+        params.push(current.trim().to_string());
+    }
+    params
+}
+
+/// Parse and validate a signature such as `transfer(address,uint256)` or `f()(uint256)`
+pub fn parse_signature(signature: &str) -> Result<Signature, ToolError> {
+    let invalid = |reason: &str| {
+        ToolError::InvalidArguments(format!("Invalid signature '{}': {}", signature, reason))
+    };
+
+    let signature = signature.trim();
+    let open = signature
+        .find('(')
+        .ok_or_else(|| invalid("expected a parameter list like 'name(uint256,address)'"))?;
+    let name = &signature[..open];
+    let valid_name = name.chars().enumerate().all(|(i, c)| {
+        c == '_' || c == '$' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit())
+    });
+    if !valid_name {
+        return Err(invalid("the function name is not a valid identifier"));
+    }
+
+    // Collect each top-level parenthesized group
+    let mut groups = Vec::new();
+    let mut depth = 0i32;
+    let mut start = open;
+    for (i, c) in signature[open..].char_indices().map(|(i, c)| (i + open, c)) {
+        match c {
+            '(' => {
+                if depth == 0 {
+                    start = i;
+                }
+                depth += 1;
+            }
+            ')' => {
+                depth -= 1;
+                if depth < 0 {
+                    return Err(invalid("unbalanced parentheses"));
+                }
+                if depth == 0 {
+                    groups.push(&signature[start + 1..i]);
+                }
+            }
+            '[' | ']' | ',' | ' ' | '_' => {}
+            c if c.is_ascii_alphanumeric() => {
+                if depth == 0 {
+                    return Err(invalid("unexpected text after the parameter list"));
+                }
+            }
+            c => return Err(invalid(&format!("unexpected character '{}'", c))),
+        }
+    }
+    if depth != 0 {
+        return Err(invalid("unbalanced parentheses"));
+    }
+    if groups.len() > 2 {
+        return Err(invalid(
+            "expected at most an input and an output parameter list",
+        ));
+    }
+
+    let inputs = split_params(groups[0]);
+    let outputs = groups.get(1).map(|g| split_params(g));
+    if inputs
+        .iter()
+        .chain(outputs.iter().flatten())
+        .any(|p| p.is_empty())
+    {
+        return Err(invalid("empty parameter type"));
+    }
+
+    Ok(Signature {
+        name: name.to_string(),
+        inputs,
+        outputs,
+    })
+}
+
+/// Argument values as `cast` expects them on the command line
+pub fn arg_strings(args: &JsonObject) -> Vec<String> {
+    args.get("args")
+        .and_then(|v| v.as_array())
+        .map(|values| {
+            values
+                .iter()
+                .map(|arg| match arg {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Check that there is one argument per input parameter
+pub fn check_arg_count(signature: &Signature, args: &[String]) -> Result<(), ToolError> {
+    if signature.inputs.len() != args.len() {
+        return Err(ToolError::InvalidArguments(format!(
+            "Signature takes {} arguments but {} were given",
+            signature.inputs.len(),
+            args.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Pair each value with its ABI type
+pub fn typed_values(types: &[String], values: &[String]) -> Vec<Value> {
+    types
+        .iter()
+        .zip(values)
+        .map(|(ty, value)| json!({ "type": ty, "value": value }))
+        .collect()
+}
+
+fn required_str<'a>(args: &'a JsonObject, name: &str) -> Result<&'a str, rmcp::ErrorData> {
+    args.get(name).and_then(|v| v.as_str()).ok_or_else(|| {
+        rmcp::ErrorData::invalid_params(format!("Missing '{}' parameter", name), None)
+    })
+}
+
+fn success(text: String, structured: Value) -> CallToolResult {
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(structured);
+    result
+}
+
+/// Handle abi_encode and encode_calldata tool calls
+pub async fn handle_abi_encode(
+    tool_name: &str,
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let args = arguments
+        .as_ref()
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing arguments", None))?;
+    let sig_str = required_str(args, "signature")?;
+    let signature = parse_signature(sig_str)?;
+    let values = arg_strings(args);
+    check_arg_count(&signature, &values)?;
+
+    let with_selector = tool_name == "encode_calldata";
+    if with_selector && signature.name.is_empty() {
+        return Err(ToolError::InvalidArguments(
+            "Calldata needs a function name in the signature, e.g. 'transfer(address,uint256)'"
+                .to_string(),
+        )
+        .into());
+    }
+
+    let name = if with_selector {
+        "cast_calldata"
+    } else {
+        "cast_abi_encode"
+    };
+    let mut encode_args = JsonObject::new();
+    encode_args.insert("sig".to_string(), json!(sig_str.trim()));
+    encode_args.insert("args".to_string(), json!(values));
+
+    let encoded = match cast::run_tool(executor, name, encode_args, &[]) {
+        Ok(output) => output,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+    let arguments = typed_values(&signature.inputs, &values);
+
+    if with_selector {
+        let selector = encoded.get(..10).unwrap_or(&encoded).to_string();
+        Ok(success(
+            format!("Calldata: {}\nSelector: {}", encoded, selector),
+            json!({
+                "signature": sig_str.trim(),
+                "calldata": encoded,
+                "selector": selector,
+                "arguments": arguments,
+            }),
+        ))
+    } else {
+        Ok(success(
+            format!("Encoded: {}", encoded),
+            json!({
+                "signature": sig_str.trim(),
+                "encoded": encoded,
+                "arguments": arguments,
+            }),
+        ))
+    }
+}
+
+/// Handle abi_decode tool call
+pub async fn handle_abi_decode(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let args = arguments
+        .as_ref()
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing arguments", None))?;
+    let sig_str = required_str(args, "signature")?;
+    let data = required_str(args, "data")?.trim();
+    let input = args.get("input").and_then(|v| v.as_bool()).unwrap_or(false);
+    let signature = parse_signature(sig_str)?;
+
+    let hex = data.strip_prefix("0x").unwrap_or(data);
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) || !hex.len().is_multiple_of(2) {
+        return Err(ToolError::InvalidArguments(format!("'{}' is not hex data", data)).into());
+    }
+
+    let types = if input {
+        signature.inputs.clone()
+    } else {
+        signature.outputs.clone().ok_or_else(|| {
+            ToolError::InvalidArguments(
+                "Decoding return data needs the return types in the signature, e.g. 'balanceOf(address)(uint256)'; set input: true to decode arguments".to_string(),
+            )
+        })?
+    };
+
+    let mut decode_args = JsonObject::new();
+    decode_args.insert("sig".to_string(), json!(sig_str.trim()));
+    decode_args.insert("calldata".to_string(), json!(data));
+    decode_args.insert("input".to_string(), json!(input));
+
+    let output = match cast::run_tool(executor, "cast_decode_abi", decode_args, &[]) {
+        Ok(output) => output,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+    let values: Vec<String> = output.lines().map(|l| l.trim().to_string()).collect();
+
+    // cast prints one line per top-level value; fall back to raw lines if that doesn't hold
+    let (text, decoded) = if values.len() == types.len() {
+        let text = types
+            .iter()
+            .zip(&values)
+            .map(|(ty, value)| format!("{}: {}", ty, value))
+            .collect::<Vec<_>>()
+            .join("\n");
+        (text, typed_values(&types, &values))
+    } else {
+        let decoded = values.iter().map(|v| json!({ "value": v })).collect();
+        (output, decoded)
+    };
+
+    Ok(success(
+        text,
+        json!({
+            "signature": sig_str.trim(),
+            "data": data,
+            "input": input,
+            "values": decoded,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that signatures are split into name, inputs and outputs
+    #[test]
+    fn test_parse_signature() {
+        assert_eq!(
+            parse_signature("balanceOf(address)(uint256)").unwrap(),
+            Signature {
+                name: "balanceOf".to_string(),
+                inputs: vec!["address".to_string()],
+                outputs: Some(vec!["uint256".to_string()]),
+            }
+        );
+
+        let sig = parse_signature("swap((address,uint24)[], uint256 amount)").unwrap();
+        assert_eq!(sig.inputs, vec!["(address,uint24)[]", "uint256 amount"]);
+        assert_eq!(sig.outputs, None);
+
+        let sig = parse_signature("(uint256,bool)").unwrap();
+        assert_eq!(sig.name, "");
+        assert_eq!(sig.inputs.len(), 2);
+
+        assert!(parse_signature("f()").unwrap().inputs.is_empty());
+    }
+
+    /// Test that malformed signatures are rejected before running cast
+    #[test]
+    fn test_parse_signature_invalid() {
+        for sig in [
+            "transfer",
+            "transfer(address,uint256",
+            "transfer(address))",
+            "1transfer(address)",
+            "f(uint256)(bool)(bool)",
+            "f(uint256,)",
+            "f(uint256) extra",
+            "f(uint256;)",
+        ] {
+            assert!(
+                matches!(parse_signature(sig), Err(ToolError::InvalidArguments(_))),
+                "{}",
+                sig
+            );
+        }
+    }
+
+    /// Test that the argument count must match the signature
+    #[test]
+    fn test_check_arg_count() {
+        let sig = parse_signature("transfer(address,uint256)").unwrap();
+        let args = json!({"args": ["0x0000000000000000000000000000000000000001", 5]});
+        let values = arg_strings(args.as_object().unwrap());
+        assert_eq!(values[1], "5");
+        assert!(check_arg_count(&sig, &values).is_ok());
+        assert!(check_arg_count(&sig, &values[..1]).is_err());
+    }
+
+    /// Test that each ABI tool requires a signature
+    #[test]
+    fn test_get_abi_tools() {
+        let tools = get_abi_tools();
+        let names: Vec<&str> = tools.iter().map(|t| t.name.as_ref()).collect();
+        assert_eq!(names, vec!["abi_encode", "encode_calldata", "abi_decode"]);
+        for tool in &tools {
+            let required = tool.input_schema.get("required").unwrap();
+            assert!(required.as_array().unwrap().contains(&json!("signature")));
+        }
+    }
+}
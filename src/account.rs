@@ -0,0 +1,412 @@
+//! Account state lookups
+//!
+//! `cast_nonce` reads an account's transaction count with `cast nonce` and returns it as
+//! a number. `cast_balance` reads a native or ERC20 balance with `cast balance` and formats
+//! it with the currency's decimals: the chain's native currency from chainlist.org, or the
+//! token's entry in the token list, falling back to asking the token contract.
+
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::cast;
+use crate::chainlist;
+use crate::error::ToolError;
+use crate::foundry::FoundryExecutor;
+use crate::gas;
+use crate::tokenlist::{self, TokenInfo};
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// Get the account tools
+pub fn get_account_tools() -> Vec<Tool> {
+    let mut props = cast::endpoint_properties();
+    props.insert(
+        "address".to_string(),
+        json!({
+            "type": "string",
+            "description": "Account address (or ENS name)"
+        }),
+    );
+    props.insert(
+        "block".to_string(),
+        json!({
+            "type": "string",
+            "description": "Block number or tag such as 'latest' or 'pending' (default: latest)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props.clone()));
+    schema.insert("required".to_string(), json!(["address"]));
+
+    let mut balance_props = props.clone();
+    balance_props.insert(
+        "token".to_string(),
+        json!({
+            "type": "string",
+            "description": "ERC20 token address or symbol (e.g. 'USDC') to read instead of the native balance"
+        }),
+    );
+
+    let mut balance_schema = schema.clone();
+    balance_schema.insert("properties".to_string(), Value::Object(balance_props));
+
+    vec![
+        Tool::new(
+            "cast_nonce".to_string(),
+            "Get an account's nonce (transaction count), optionally at a block. Uses the configured default RPC, else the running Anvil session, unless an rpc_url or port is given.".to_string(),
+            Arc::new(schema),
+        ),
+        Tool::new(
+            "cast_balance".to_string(),
+            "Get an account's native or ERC20 token balance, both raw and formatted with the currency's decimals. Tokens are given by address or by symbol from the token list. Uses the configured default RPC, else the running Anvil session, unless an rpc_url or port is given.".to_string(),
+            Arc::new(balance_schema),
+        ),
+    ]
+}
+
+/// The currency a balance is in
+#[derive(Debug, Clone, PartialEq)]
+struct Currency {
+    /// Token contract, or `None` for the native currency
+    token: Option<String>,
+    symbol: Option<String>,
+    decimals: Option<u32>,
+}
+
+/// Whether a value is a 0x-prefixed 20-byte hex address
+pub fn is_address(value: &str) -> bool {
+    value
+        .strip_prefix("0x")
+        .is_some_and(|hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Pick the token with the given symbol on a chain from the token list
+fn token_by_symbol(
+    tokens: &[TokenInfo],
+    symbol: &str,
+    chain_id: u64,
+) -> Result<TokenInfo, ToolError> {
+    let matches: Vec<&TokenInfo> = tokens
+        .iter()
+        .filter(|t| t.chain_id == chain_id && t.symbol.eq_ignore_ascii_case(symbol))
+        .collect();
+    match matches.as_slice() {
+        [token] => Ok((*token).clone()),
+        [] => Err(ToolError::InvalidArguments(format!(
+            "No token '{}' in the token list for chain {}; pass the token address instead",
+            symbol, chain_id
+        ))),
+        several => Err(ToolError::InvalidArguments(format!(
+            "'{}' matches several tokens on chain {} ({}); pass the token address instead",
+            symbol,
+            chain_id,
+            several
+                .iter()
+                .map(|t| t.address.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+/// Read the chain ID of the endpoint
+fn read_chain_id(rpc_url: &str, executor: &FoundryExecutor) -> Result<u64, ToolError> {
+    let mut args = JsonObject::new();
+    args.insert("rpc-url".to_string(), json!(rpc_url));
+    let output = cast::run_tool(executor, "cast_chain_id", args, &[])?;
+    Ok(cast::parse_u128(&output, "chain ID")? as u64)
+}
+
+/// Ask a token contract for its decimals and symbol, for tokens missing from the token list
+fn read_token_metadata(token: &str, rpc_url: &str, executor: &FoundryExecutor) -> Currency {
+    let call = |signature: &str| {
+        let mut args = JsonObject::new();
+        args.insert("to".to_string(), json!(token));
+        args.insert("sig".to_string(), json!(signature));
+        args.insert("rpc-url".to_string(), json!(rpc_url));
+        cast::run_tool(executor, "cast_call", args, &[]).ok()
+    };
+
+    Currency {
+        token: Some(token.to_string()),
+        symbol: call("symbol()(string)").map(|s| s.trim_matches('"').to_string()),
+        decimals: call("decimals()(uint8)").and_then(|d| d.parse().ok()),
+    }
+}
+
+/// Read the raw balance in the smallest unit
+async fn read_balance(
+    args: &JsonObject,
+    address: &str,
+    token: Option<&str>,
+    executor: &FoundryExecutor,
+) -> Result<String, ToolError> {
+    let mut balance_args = read_args(args, address, executor).await?;
+    if let Some(token) = token {
+        balance_args.insert("erc20".to_string(), json!(token));
+    }
+    let output = cast::run_tool(executor, "cast_balance", balance_args, &[])?;
+
+    // Newer cast versions append a scientific-notation hint, e.g. "1000000 [1e6]"
+    let raw = output.split_whitespace().next().unwrap_or_default();
+    if raw.is_empty() || !raw.chars().all(|c| c.is_ascii_digit()) {
+        return Err(ToolError::execution_failed(format!(
+            "Unexpected balance from cast: '{}'",
+            output
+        )));
+    }
+    Ok(raw.to_string())
+}
+
+/// Work out the currency, then read the balance in it
+async fn query_balance(
+    args: &JsonObject,
+    address: &str,
+    executor: &FoundryExecutor,
+) -> Result<(Currency, String, u64), ToolError> {
+    let rpc_url = cast::resolve_endpoint(args, &executor.config()).await?;
+    let chain_id = read_chain_id(&rpc_url, executor)?;
+
+    let currency = match args.get("token").and_then(|v| v.as_str()).map(str::trim) {
+        None => {
+            // Formatting is still useful without chainlist, so fall back to ETH's decimals
+            let chains = chainlist::fetch_chainlist().await.unwrap_or_default();
+            let (symbol, decimals) = gas::native_currency(&chains, chain_id);
+            Currency {
+                token: None,
+                symbol: Some(symbol),
+                decimals: Some(decimals),
+            }
+        }
+        Some(token) if is_address(token) => {
+            let listed = tokenlist::fetch_tokenlist().await.ok().and_then(|list| {
+                tokenlist::find_token_by_address(&list.tokens, token, Some(chain_id))
+                    .first()
+                    .map(|t| (*t).clone())
+            });
+            match listed {
+                Some(info) => Currency {
+                    token: Some(info.address),
+                    symbol: Some(info.symbol),
+                    decimals: Some(u32::from(info.decimals)),
+                },
+                None => read_token_metadata(token, &rpc_url, executor),
+            }
+        }
+        Some(symbol) => {
+            let list = tokenlist::fetch_tokenlist().await.map_err(|e| {
+                ToolError::RpcUnavailable(format!(
+                    "Failed to fetch the token list to look up '{}': {:#}",
+                    symbol, e
+                ))
+            })?;
+            let info = token_by_symbol(&list.tokens, symbol, chain_id)?;
+            Currency {
+                token: Some(info.address),
+                symbol: Some(info.symbol),
+                decimals: Some(u32::from(info.decimals)),
+            }
+        }
+    };
+
+    let raw = read_balance(args, address, currency.token.as_deref(), executor).await?;
+    Ok((currency, raw, chain_id))
+}
+
+/// The arguments for a schema tool reading `address` at the requested endpoint and block
+async fn read_args(
+    args: &JsonObject,
+    address: &str,
+    executor: &FoundryExecutor,
+) -> Result<JsonObject, ToolError> {
+    let rpc_url = cast::resolve_endpoint(args, &executor.config()).await?;
+    let mut read_args = JsonObject::new();
+    read_args.insert("who".to_string(), json!(address));
+    read_args.insert("rpc-url".to_string(), json!(rpc_url));
+    if let Some(block) = args.get("block").and_then(|v| v.as_str()) {
+        read_args.insert("block".to_string(), json!(block));
+    }
+    Ok(read_args)
+}
+
+fn required_address(args: &Option<JsonObject>) -> Result<(&JsonObject, &str), rmcp::ErrorData> {
+    let args = args
+        .as_ref()
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing arguments", None))?;
+    let address = args
+        .get("address")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing 'address' parameter", None))?;
+    Ok((args, address))
+}
+
+async fn read_nonce(
+    args: &JsonObject,
+    address: &str,
+    executor: &FoundryExecutor,
+) -> Result<u64, ToolError> {
+    let nonce_args = read_args(args, address, executor).await?;
+    let output = cast::run_tool(executor, "cast_nonce", nonce_args, &[])?;
+    output.parse().map_err(|_| {
+        ToolError::execution_failed(format!("Unexpected nonce from cast: '{}'", output))
+    })
+}
+
+/// Handle cast_nonce tool call
+pub async fn handle_cast_nonce(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let (args, address) = required_address(arguments)?;
+    let block = args.get("block").and_then(|v| v.as_str());
+
+    let nonce = match read_nonce(args, address, executor).await {
+        Ok(nonce) => nonce,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut text = format!("Nonce of {}: {}", address, nonce);
+    if let Some(block) = block {
+        text.push_str(&format!(" (at block {})", block));
+    }
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(json!({
+        "address": address,
+        "nonce": nonce,
+        "block": block.unwrap_or("latest"),
+    }));
+    Ok(result)
+}
+
+/// Handle cast_balance tool call
+pub async fn handle_cast_balance(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let (args, address) = required_address(arguments)?;
+
+    let (currency, raw, chain_id) = match query_balance(args, address, executor).await {
+        Ok(values) => values,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    // Amounts beyond u128 are only returned raw
+    let formatted = currency.decimals.and_then(|decimals| {
+        raw.parse::<u128>()
+            .ok()
+            .map(|amount| cast::format_units(amount, decimals))
+    });
+    let text = match (&formatted, &currency.symbol) {
+        (Some(formatted), Some(symbol)) => {
+            format!(
+                "Balance of {}: {} {} ({} raw)",
+                address, formatted, symbol, raw
+            )
+        }
+        (Some(formatted), None) => format!("Balance of {}: {} ({} raw)", address, formatted, raw),
+        (None, _) => format!("Balance of {}: {} (raw, decimals unknown)", address, raw),
+    };
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(json!({
+        "address": address,
+        "token": currency.token,
+        "symbol": currency.symbol,
+        "decimals": currency.decimals,
+        "raw": raw,
+        "formatted": formatted,
+        "chain_id": chain_id,
+    }));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the endpoint and block are passed on under the schema argument names
+    #[tokio::test]
+    async fn test_read_args() {
+        let executor = FoundryExecutor::with_config(
+            crate::schema::SchemaFile { tools: vec![] },
+            crate::config::Config::default(),
+        );
+        let args = json!({"port": 8545, "block": "pending"});
+        let read = read_args(args.as_object().unwrap(), "0x01", &executor)
+            .await
+            .unwrap();
+        assert_eq!(read["who"], json!("0x01"));
+        assert_eq!(read["rpc-url"], json!("http://127.0.0.1:8545"));
+        assert_eq!(read["block"], json!("pending"));
+    }
+
+    /// Test that the configured default_rpc_url is used when no endpoint is given
+    #[tokio::test]
+    async fn test_read_args_default_rpc_url() {
+        let executor = FoundryExecutor::with_config(
+            crate::schema::SchemaFile { tools: vec![] },
+            crate::config::Config {
+                default_rpc_url: Some("https://rpc.example".to_string()),
+                ..Default::default()
+            },
+        );
+        let args = json!({});
+        let read = read_args(args.as_object().unwrap(), "0x01", &executor)
+            .await
+            .unwrap();
+        assert_eq!(read["rpc-url"], json!("https://rpc.example"));
+    }
+
+    fn token(symbol: &str, address: &str, chain_id: u64) -> TokenInfo {
+        serde_json::from_value(json!({
+            "chainId": chain_id,
+            "address": address,
+            "name": symbol,
+            "symbol": symbol,
+            "decimals": 6,
+        }))
+        .unwrap()
+    }
+
+    /// Test that symbols resolve to exactly one token on the chain
+    #[test]
+    fn test_token_by_symbol() {
+        let tokens = vec![
+            token("USDC", "0x01", 1),
+            token("USDC", "0x02", 10),
+            token("USDT", "0x03", 1),
+            token("USDT", "0x04", 1),
+        ];
+        assert_eq!(token_by_symbol(&tokens, "usdc", 1).unwrap().address, "0x01");
+        assert!(matches!(
+            token_by_symbol(&tokens, "DAI", 1),
+            Err(ToolError::InvalidArguments(_))
+        ));
+        let err = token_by_symbol(&tokens, "USDT", 1).unwrap_err();
+        assert!(err.to_string().contains("0x03, 0x04"));
+    }
+
+    /// Test that token addresses are told apart from symbols
+    #[test]
+    fn test_is_address() {
+        assert!(is_address(&format!("0x{}", "aB".repeat(20))));
+        assert!(!is_address("USDC"));
+        assert!(!is_address("0x1234"));
+    }
+
+    /// Test that an address is required
+    #[tokio::test]
+    async fn test_nonce_requires_address() {
+        let executor = FoundryExecutor::with_config(
+            crate::schema::SchemaFile { tools: vec![] },
+            crate::config::Config::default(),
+        );
+        let args = Some(json!({"port": 8545}).as_object().unwrap().clone());
+        assert!(handle_cast_nonce(&args, &executor).await.is_err());
+    }
+}
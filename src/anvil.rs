@@ -0,0 +1,836 @@
+//! Anvil node manipulation
+//!
+//! Typed tools for the test manipulations used most against a local node, such as moving
+//! time forward, fixing the next block's base fee or saving and restoring named
+//! snapshots. Each one is a JSON-RPC call sent with `cast_rpc` to the running Anvil
+//! session, and the node's errors are returned as tool errors.
+//!
+//! Impersonation and rewriting an account's balance, code or storage let a caller act as
+//! or reshape any account, so those tools need `allow_dangerous` (or an unlocked server).
+
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::account;
+use crate::cast;
+use crate::error::ToolError;
+use crate::foundry::FoundryExecutor;
+use crate::sessions::SessionManager;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// Get the Anvil node tools
+pub fn get_anvil_tools() -> Vec<Tool> {
+    vec![
+        anvil_set_time_tool(),
+        anvil_set_basefee_tool(),
+        anvil_impersonate_tool(),
+        anvil_stop_impersonate_tool(),
+        anvil_set_balance_tool(),
+        anvil_set_code_tool(),
+        anvil_set_storage_at_tool(),
+        anvil_snapshot_save_tool(),
+        anvil_snapshot_restore_tool(),
+    ]
+}
+
+fn tool(name: &str, description: &str, props: JsonObject, required: Value) -> Tool {
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+    schema.insert("required".to_string(), required);
+    Tool::new(name.to_string(), description.to_string(), Arc::new(schema))
+}
+
+fn anvil_set_time_tool() -> Tool {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "timestamp".to_string(),
+        json!({
+            "type": "integer",
+            "description": "Unix timestamp for the next block (evm_setNextBlockTimestamp)"
+        }),
+    );
+    props.insert(
+        "seconds".to_string(),
+        json!({
+            "type": "integer",
+            "description": "Seconds to move the clock forward instead (evm_increaseTime)"
+        }),
+    );
+    props.insert(
+        "mine".to_string(),
+        json!({
+            "type": "boolean",
+            "description": "Mine a block afterwards so the new time takes effect now (default: false)"
+        }),
+    );
+    tool(
+        "anvil_set_time",
+        "Set the running Anvil session's next block timestamp, or move its clock forward by a number of seconds. Give exactly one of timestamp or seconds.",
+        props,
+        json!([]),
+    )
+}
+
+fn anvil_set_basefee_tool() -> Tool {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "basefee".to_string(),
+        json!({
+            "type": "integer",
+            "description": "Base fee per gas of the next block, in wei"
+        }),
+    );
+    tool(
+        "anvil_set_basefee",
+        "Set the base fee per gas of the running Anvil session's next block (anvil_setNextBlockBaseFeePerGas).",
+        props,
+        json!(["basefee"]),
+    )
+}
+
+fn address_property(description: &str) -> JsonObject {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "address".to_string(),
+        json!({
+            "type": "string",
+            "description": description
+        }),
+    );
+    props
+}
+
+fn anvil_impersonate_tool() -> Tool {
+    tool(
+        "anvil_impersonate",
+        "Let the running Anvil session accept transactions from an account without its key (anvil_impersonateAccount), e.g. to act as a whale or a contract on a fork. Needs allow_dangerous or an unlocked server.",
+        address_property("Account to impersonate"),
+        json!(["address"]),
+    )
+}
+
+fn anvil_stop_impersonate_tool() -> Tool {
+    tool(
+        "anvil_stop_impersonate",
+        "Stop impersonating an account on the running Anvil session (anvil_stopImpersonatingAccount). Needs allow_dangerous or an unlocked server.",
+        address_property("Account to stop impersonating"),
+        json!(["address"]),
+    )
+}
+
+fn anvil_set_balance_tool() -> Tool {
+    let mut props = address_property("Account whose balance is set");
+    props.insert(
+        "balance".to_string(),
+        json!({
+            "type": "string",
+            "description": "New balance in wei, as a decimal or 0x-prefixed hex number"
+        }),
+    );
+    tool(
+        "anvil_set_balance",
+        "Set an account's native balance on the running Anvil session (anvil_setBalance). Needs allow_dangerous or an unlocked server.",
+        props,
+        json!(["address", "balance"]),
+    )
+}
+
+fn anvil_set_code_tool() -> Tool {
+    let mut props = address_property("Account whose code is replaced");
+    props.insert(
+        "code".to_string(),
+        json!({
+            "type": "string",
+            "description": "Runtime bytecode as hex; '0x' removes the code"
+        }),
+    );
+    tool(
+        "anvil_set_code",
+        "Replace an account's runtime bytecode on the running Anvil session (anvil_setCode). Needs allow_dangerous or an unlocked server.",
+        props,
+        json!(["address", "code"]),
+    )
+}
+
+fn anvil_set_storage_at_tool() -> Tool {
+    let mut props = address_property("Contract whose storage is written");
+    props.insert(
+        "slot".to_string(),
+        json!({
+            "type": "string",
+            "description": "Storage slot, as a decimal or 0x-prefixed hex number"
+        }),
+    );
+    props.insert(
+        "value".to_string(),
+        json!({
+            "type": "string",
+            "description": "32-byte word to store, as a decimal or 0x-prefixed hex number (left-padded with zeros)"
+        }),
+    );
+    tool(
+        "anvil_set_storage_at",
+        "Write a storage slot of a contract on the running Anvil session (anvil_setStorageAt). Needs allow_dangerous or an unlocked server.",
+        props,
+        json!(["address", "slot", "value"]),
+    )
+}
+
+fn snapshot_name_property(description: &str) -> JsonObject {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "name".to_string(),
+        json!({
+            "type": "string",
+            "description": description
+        }),
+    );
+    props
+}
+
+fn anvil_snapshot_save_tool() -> Tool {
+    tool(
+        "anvil_snapshot_save",
+        "Snapshot the running Anvil session's chain state (evm_snapshot) under a name, replacing an earlier snapshot of that name.",
+        snapshot_name_property("Name to save the snapshot under, e.g. 'after-deploy'"),
+        json!(["name"]),
+    )
+}
+
+fn anvil_snapshot_restore_tool() -> Tool {
+    tool(
+        "anvil_snapshot_restore",
+        "Revert the running Anvil session to a named snapshot (evm_revert). The snapshot and every snapshot saved after it are used up by the revert and forgotten; save again to return to the same state later.",
+        snapshot_name_property("Name of the snapshot to restore"),
+        json!(["name"]),
+    )
+}
+
+/// Refuse a tool that can act as or rewrite arbitrary accounts unless dangerous commands
+/// are allowed
+fn check_dangerous_allowed(executor: &FoundryExecutor, tool: &str) -> Result<(), ToolError> {
+    if executor.config().allow_dangerous || executor.is_elevated() {
+        return Ok(());
+    }
+    Err(ToolError::ForbiddenCommand(format!(
+        "{} can act on arbitrary accounts and needs allow_dangerous (or an unlocked server)",
+        tool
+    )))
+}
+
+/// Read the required `address` argument
+fn required_address(args: &JsonObject) -> Result<&str, ToolError> {
+    let address = args
+        .get("address")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .ok_or_else(|| ToolError::InvalidArguments("Missing 'address' parameter".to_string()))?;
+    if !account::is_address(address) {
+        return Err(ToolError::InvalidArguments(format!(
+            "'{}' is not an address",
+            address
+        )));
+    }
+    Ok(address)
+}
+
+/// Convert a decimal string to the hex digits of a 256-bit word, or `None` if it isn't
+/// a plain decimal number or doesn't fit
+fn decimal_to_u256_hex(decimal: &str) -> Option<String> {
+    if decimal.is_empty() || !decimal.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    // Little-endian 64-bit limbs, multiplied by ten and added to per digit
+    let mut limbs = [0u64; 4];
+    for digit in decimal.bytes() {
+        let mut carry = u128::from(digit - b'0');
+        for limb in limbs.iter_mut() {
+            let value = u128::from(*limb) * 10 + carry;
+            *limb = value as u64;
+            carry = value >> 64;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+
+    Some(
+        limbs
+            .iter()
+            .rev()
+            .map(|limb| format!("{:016x}", limb))
+            .collect(),
+    )
+}
+
+/// Read a required unsigned number given as an integer, a decimal string or 0x hex, as
+/// the hex digits of a 256-bit word (without the prefix or leading zeros)
+fn required_u256_hex(args: &JsonObject, name: &str) -> Result<String, ToolError> {
+    let invalid = || {
+        ToolError::InvalidArguments(format!(
+            "'{}' must be a non-negative decimal or 0x-prefixed hex number of at most 32 bytes",
+            name
+        ))
+    };
+    let hex = match args.get(name) {
+        None | Some(Value::Null) => {
+            return Err(ToolError::InvalidArguments(format!(
+                "Missing '{}' parameter",
+                name
+            )))
+        }
+        Some(Value::Number(n)) => format!("{:x}", n.as_u64().ok_or_else(invalid)?),
+        Some(Value::String(s)) => match s.trim().strip_prefix("0x") {
+            Some(hex) if !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+                hex.to_lowercase()
+            }
+            Some(_) => return Err(invalid()),
+            None => decimal_to_u256_hex(s.trim()).ok_or_else(invalid)?,
+        },
+        Some(_) => return Err(invalid()),
+    };
+
+    let hex = hex.trim_start_matches('0');
+    if hex.len() > 64 {
+        return Err(invalid());
+    }
+    Ok(if hex.is_empty() { "0" } else { hex }.to_string())
+}
+
+/// Read the required `code` argument as 0x-prefixed hex bytes
+fn required_code(args: &JsonObject) -> Result<String, ToolError> {
+    let code = args
+        .get("code")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .ok_or_else(|| ToolError::InvalidArguments("Missing 'code' parameter".to_string()))?;
+    let hex = code.strip_prefix("0x").unwrap_or(code);
+    if !hex.len().is_multiple_of(2) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ToolError::InvalidArguments(
+            "'code' is not valid hex".to_string(),
+        ));
+    }
+    Ok(format!("0x{}", hex))
+}
+
+/// Work out the RPC call for a state-setting tool: the method, its parameters and a
+/// description of the change
+fn state_call(tool: &str, args: &JsonObject) -> Result<(&'static str, Value, String), ToolError> {
+    let address = required_address(args)?;
+    match tool {
+        "anvil_set_balance" => {
+            let balance = required_u256_hex(args, "balance")?;
+            Ok((
+                "anvil_setBalance",
+                json!([address, format!("0x{}", balance)]),
+                format!("Balance of {} set to 0x{} wei", address, balance),
+            ))
+        }
+        "anvil_set_code" => {
+            let code = required_code(args)?;
+            let bytes = (code.len() - 2) / 2;
+            Ok((
+                "anvil_setCode",
+                json!([address, code]),
+                format!("Code of {} replaced ({} bytes)", address, bytes),
+            ))
+        }
+        "anvil_set_storage_at" => {
+            let slot = required_u256_hex(args, "slot")?;
+            let value = required_u256_hex(args, "value")?;
+            Ok((
+                "anvil_setStorageAt",
+                json!([address, format!("0x{}", slot), format!("0x{:0>64}", value)]),
+                format!("Slot 0x{} of {} set to 0x{:0>64}", slot, address, value),
+            ))
+        }
+        _ => Err(ToolError::ToolNotFound(format!(
+            "Tool '{}' not found",
+            tool
+        ))),
+    }
+}
+
+/// RPC URL of the running Anvil session
+pub fn session_rpc_url() -> Result<String, ToolError> {
+    let manager = SessionManager::global();
+    if let Some(note) = manager.check_anvil() {
+        eprintln!("⚠ {}", note);
+    }
+    manager
+        .anvil_rpc_url()
+        .ok_or_else(SessionManager::anvil_not_running)
+}
+
+/// Send a JSON-RPC call to a node with `cast_rpc` and return its result
+pub fn rpc(
+    executor: &FoundryExecutor,
+    rpc_url: &str,
+    method: &str,
+    params: Value,
+) -> Result<Value, ToolError> {
+    let mut args = JsonObject::new();
+    args.insert("method".to_string(), json!(method));
+    args.insert("params".to_string(), json!([params.to_string()]));
+    args.insert("raw".to_string(), json!(true));
+    args.insert("rpc-url".to_string(), json!(rpc_url));
+    let output = cast::run_tool(executor, "cast_rpc", args, &[])?;
+    Ok(serde_json::from_str(&output).unwrap_or(Value::String(output)))
+}
+
+/// Read an optional non-negative integer argument
+fn optional_u64(args: &JsonObject, name: &str) -> Result<Option<u64>, ToolError> {
+    match args.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value.as_u64().map(Some).ok_or_else(|| {
+            ToolError::InvalidArguments(format!("'{}' must be a non-negative integer", name))
+        }),
+    }
+}
+
+fn success(text: String, structured: Value) -> CallToolResult {
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(structured);
+    result
+}
+
+/// Work out the RPC call for anvil_set_time: the method, its parameters and a description
+fn time_call(args: &JsonObject) -> Result<(&'static str, Value, String), ToolError> {
+    match (
+        optional_u64(args, "timestamp")?,
+        optional_u64(args, "seconds")?,
+    ) {
+        (Some(timestamp), None) => Ok((
+            "evm_setNextBlockTimestamp",
+            json!([timestamp]),
+            format!("Next block timestamp set to {}", timestamp),
+        )),
+        (None, Some(seconds)) => Ok((
+            "evm_increaseTime",
+            json!([seconds]),
+            format!("Clock moved forward by {} s", seconds),
+        )),
+        _ => Err(ToolError::InvalidArguments(
+            "Give exactly one of 'timestamp' or 'seconds'".to_string(),
+        )),
+    }
+}
+
+/// Handle anvil_set_time tool call
+pub async fn handle_anvil_set_time(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let empty = JsonObject::new();
+    let args = arguments.as_ref().unwrap_or(&empty);
+    let (method, params, mut text) = time_call(args)?;
+    let mine = args.get("mine").and_then(|v| v.as_bool()).unwrap_or(false);
+    let rpc_url = session_rpc_url()?;
+
+    let result = rpc(executor, &rpc_url, method, params.clone()).and_then(|result| {
+        if mine {
+            rpc(executor, &rpc_url, "evm_mine", json!([]))?;
+        }
+        Ok(result)
+    });
+    let result = match result {
+        Ok(result) => result,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+    if mine {
+        text.push_str("; mined a block");
+    }
+
+    Ok(success(
+        text,
+        json!({
+            "method": method,
+            "params": params,
+            "mined": mine,
+            "result": result,
+        }),
+    ))
+}
+
+/// Handle anvil_set_basefee tool call
+pub async fn handle_anvil_set_basefee(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let empty = JsonObject::new();
+    let args = arguments.as_ref().unwrap_or(&empty);
+    let basefee = optional_u64(args, "basefee")?
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing 'basefee' parameter", None))?;
+    let rpc_url = session_rpc_url()?;
+
+    let method = "anvil_setNextBlockBaseFeePerGas";
+    let result = match rpc(
+        executor,
+        &rpc_url,
+        method,
+        json!([format!("{:#x}", basefee)]),
+    ) {
+        Ok(result) => result,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(success(
+        format!("Next block base fee set to {} wei", basefee),
+        json!({
+            "method": method,
+            "basefee": basefee,
+            "result": result,
+        }),
+    ))
+}
+
+/// Handle anvil_impersonate and anvil_stop_impersonate tool calls
+pub async fn handle_anvil_impersonate(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+    impersonate: bool,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let (tool, method) = if impersonate {
+        ("anvil_impersonate", "anvil_impersonateAccount")
+    } else {
+        ("anvil_stop_impersonate", "anvil_stopImpersonatingAccount")
+    };
+    check_dangerous_allowed(executor, tool)?;
+
+    let empty = JsonObject::new();
+    let address = required_address(arguments.as_ref().unwrap_or(&empty))?;
+    let rpc_url = session_rpc_url()?;
+
+    let result = match rpc(executor, &rpc_url, method, json!([address])) {
+        Ok(result) => result,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let text = if impersonate {
+        format!(
+            "Impersonating {}: transactions from it are accepted without a signature",
+            address
+        )
+    } else {
+        format!("Stopped impersonating {}", address)
+    };
+    Ok(success(
+        text,
+        json!({
+            "method": method,
+            "address": address,
+            "impersonating": impersonate,
+            "result": result,
+        }),
+    ))
+}
+
+/// Handle anvil_set_balance, anvil_set_code and anvil_set_storage_at tool calls
+pub async fn handle_anvil_set_state(
+    tool: &str,
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    check_dangerous_allowed(executor, tool)?;
+
+    let empty = JsonObject::new();
+    let (method, params, text) = state_call(tool, arguments.as_ref().unwrap_or(&empty))?;
+    let rpc_url = session_rpc_url()?;
+
+    let result = match rpc(executor, &rpc_url, method, params.clone()) {
+        Ok(result) => result,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(success(
+        text,
+        json!({
+            "method": method,
+            "params": params,
+            "result": result,
+        }),
+    ))
+}
+
+/// Read the required `name` of a snapshot
+fn required_name(args: &JsonObject) -> Result<&str, ToolError> {
+    args.get("name")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| ToolError::InvalidArguments("Missing 'name' parameter".to_string()))
+}
+
+/// Handle anvil_snapshot_save tool call
+pub async fn handle_anvil_snapshot_save(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let empty = JsonObject::new();
+    let name = required_name(arguments.as_ref().unwrap_or(&empty))?;
+    let rpc_url = session_rpc_url()?;
+
+    let id = match rpc(executor, &rpc_url, "evm_snapshot", json!([])) {
+        Ok(Value::String(id)) => id,
+        Ok(other) => {
+            return Ok(ToolError::execution_failed(format!(
+                "Unexpected evm_snapshot result: {}",
+                other
+            ))
+            .into_call_tool_result())
+        }
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+    let manager = SessionManager::global();
+    manager.save_anvil_snapshot(name, &id)?;
+
+    Ok(success(
+        format!("Saved snapshot '{}' (id {})", name, id),
+        json!({
+            "name": name,
+            "id": id,
+            "snapshots": manager.anvil_snapshot_names(),
+        }),
+    ))
+}
+
+/// Handle anvil_snapshot_restore tool call
+pub async fn handle_anvil_snapshot_restore(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let empty = JsonObject::new();
+    let name = required_name(arguments.as_ref().unwrap_or(&empty))?;
+    let rpc_url = session_rpc_url()?;
+    let manager = SessionManager::global();
+
+    let Some(id) = manager.anvil_snapshot_id(name)? else {
+        let saved = manager.anvil_snapshot_names();
+        return Err(ToolError::InvalidArguments(if saved.is_empty() {
+            format!("No snapshot named '{}'; none are saved", name)
+        } else {
+            format!(
+                "No snapshot named '{}'; saved snapshots: {}",
+                name,
+                saved.join(", ")
+            )
+        })
+        .into());
+    };
+
+    let reverted = match rpc(executor, &rpc_url, "evm_revert", json!([id])) {
+        Ok(result) => result.as_bool() == Some(true),
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+    // Either way the snapshot can't be used again
+    let pruned = manager.prune_anvil_snapshots(name);
+    if !reverted {
+        return Ok(ToolError::execution_failed(format!(
+            "Anvil no longer has snapshot '{}' (id {}), e.g. because the node was restarted; it has been forgotten",
+            name, id
+        ))
+        .into_call_tool_result());
+    }
+
+    let mut text = format!("Restored snapshot '{}'", name);
+    if !pruned.is_empty() {
+        text.push_str(&format!(
+            "\nWarning: later snapshots were invalidated by the revert and forgotten: {}",
+            pruned.join(", ")
+        ));
+    }
+    Ok(success(
+        text,
+        json!({
+            "name": name,
+            "id": id,
+            "invalidated": pruned,
+            "snapshots": manager.anvil_snapshot_names(),
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that exactly one of timestamp or seconds picks the RPC method
+    #[test]
+    fn test_time_call() {
+        let args = |value: Value| value.as_object().unwrap().clone();
+
+        let (method, params, _) = time_call(&args(json!({"timestamp": 1_700_000_000}))).unwrap();
+        assert_eq!(method, "evm_setNextBlockTimestamp");
+        assert_eq!(params, json!([1_700_000_000]));
+
+        let (method, params, _) = time_call(&args(json!({"seconds": 3600}))).unwrap();
+        assert_eq!(method, "evm_increaseTime");
+        assert_eq!(params, json!([3600]));
+
+        for invalid in [
+            json!({}),
+            json!({"timestamp": 1, "seconds": 2}),
+            json!({"seconds": -5}),
+            json!({"timestamp": "soon"}),
+        ] {
+            assert!(matches!(
+                time_call(&args(invalid)),
+                Err(ToolError::InvalidArguments(_))
+            ));
+        }
+    }
+
+    /// Test that impersonation is refused unless dangerous commands are allowed
+    #[tokio::test]
+    async fn test_impersonate_needs_dangerous() {
+        let executor = FoundryExecutor::with_config(
+            crate::schema::SchemaFile { tools: vec![] },
+            crate::config::Config::safe_default(),
+        );
+        let args = json!({"address": format!("0x{}", "ab".repeat(20))});
+        let args = args.as_object().cloned();
+        for impersonate in [true, false] {
+            let err = handle_anvil_impersonate(&args, &executor, impersonate)
+                .await
+                .unwrap_err();
+            assert!(err.message.contains("allow_dangerous"));
+        }
+    }
+
+    /// Test that numbers are accepted as integers, decimal strings or hex
+    #[test]
+    fn test_required_u256_hex() {
+        let args = |value: Value| json!({ "n": value }).as_object().unwrap().clone();
+        assert_eq!(required_u256_hex(&args(json!(255)), "n").unwrap(), "ff");
+        assert_eq!(
+            required_u256_hex(&args(json!("1000000000000000000")), "n").unwrap(),
+            "de0b6b3a7640000"
+        );
+        assert_eq!(
+            required_u256_hex(&args(json!("0x00FF")), "n").unwrap(),
+            "ff"
+        );
+        assert_eq!(required_u256_hex(&args(json!("0x0")), "n").unwrap(), "0");
+        // Decimals past u128::MAX, up to the largest 256-bit word
+        assert_eq!(
+            required_u256_hex(&args(json!("340282366920938463463374607431768211456")), "n")
+                .unwrap(),
+            format!("1{}", "0".repeat(32))
+        );
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(
+            required_u256_hex(&args(json!(max)), "n").unwrap(),
+            "f".repeat(64)
+        );
+        for invalid in [
+            json!(-1),
+            json!("0x"),
+            json!("12abc"),
+            json!(format!("0x1{}", "0".repeat(64))),
+            json!(max.replace("935", "936")),
+        ] {
+            assert!(required_u256_hex(&args(invalid), "n").is_err());
+        }
+        assert!(required_u256_hex(&JsonObject::new(), "n").is_err());
+    }
+
+    /// Test that each state tool builds its RPC call with checked arguments
+    #[test]
+    fn test_state_call() {
+        let address = format!("0x{}", "ab".repeat(20));
+        let args = |value: Value| {
+            let mut args = value.as_object().unwrap().clone();
+            args.insert("address".to_string(), json!(address));
+            args
+        };
+
+        let (method, params, _) =
+            state_call("anvil_set_balance", &args(json!({"balance": "1000"}))).unwrap();
+        assert_eq!(method, "anvil_setBalance");
+        assert_eq!(params, json!([address, "0x3e8"]));
+
+        let (method, params, _) =
+            state_call("anvil_set_code", &args(json!({"code": "6080"}))).unwrap();
+        assert_eq!(method, "anvil_setCode");
+        assert_eq!(params, json!([address, "0x6080"]));
+        assert!(state_call("anvil_set_code", &args(json!({"code": "0x608"}))).is_err());
+
+        let (method, params, _) = state_call(
+            "anvil_set_storage_at",
+            &args(json!({"slot": 2, "value": "0x1"})),
+        )
+        .unwrap();
+        assert_eq!(method, "anvil_setStorageAt");
+        assert_eq!(
+            params,
+            json!([address, "0x2", format!("0x{}1", "0".repeat(63))])
+        );
+    }
+
+    /// Test that rewriting account state is refused unless dangerous commands are allowed
+    #[tokio::test]
+    async fn test_set_state_needs_dangerous() {
+        let executor = FoundryExecutor::with_config(
+            crate::schema::SchemaFile { tools: vec![] },
+            crate::config::Config::safe_default(),
+        );
+        for tool in [
+            "anvil_set_balance",
+            "anvil_set_code",
+            "anvil_set_storage_at",
+        ] {
+            let err = handle_anvil_set_state(tool, &None, &executor)
+                .await
+                .unwrap_err();
+            assert!(err.message.contains("allow_dangerous"));
+        }
+    }
+
+    /// Test that the address argument is required and checked
+    #[test]
+    fn test_required_address() {
+        let args = |value: Value| value.as_object().unwrap().clone();
+        let address = format!("0x{}", "ab".repeat(20));
+        assert_eq!(
+            required_address(&args(json!({ "address": address }))).unwrap(),
+            address
+        );
+        assert!(required_address(&args(json!({}))).is_err());
+        assert!(required_address(&args(json!({"address": "vitalik.eth"}))).is_err());
+    }
+
+    /// Test that a snapshot name is required
+    #[test]
+    fn test_required_name() {
+        let args = |value: Value| value.as_object().unwrap().clone();
+        assert_eq!(
+            required_name(&args(json!({"name": " base "}))).unwrap(),
+            "base"
+        );
+        assert!(required_name(&args(json!({"name": ""}))).is_err());
+        assert!(required_name(&args(json!({}))).is_err());
+    }
+
+    /// Test that the tools refuse to run without an Anvil session
+    #[tokio::test]
+    async fn test_requires_session() {
+        if SessionManager::global().is_anvil_running() {
+            return;
+        }
+        let executor = FoundryExecutor::new(crate::schema::SchemaFile { tools: vec![] });
+        let args = json!({"basefee": 1}).as_object().cloned();
+        let err = handle_anvil_set_basefee(&args, &executor)
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("anvil_session_start"));
+    }
+}
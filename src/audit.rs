@@ -0,0 +1,158 @@
+//! Audit logging of executed commands
+//!
+//! When `Config.audit_log_path` is set, every tool call that runs a process (Foundry
+//! commands, conversions, session operations, and `unlock`/`lock`) is appended to the log
+//! as a JSON line.
+//! Secret-bearing arguments are always redacted, regardless of `allow_dangerous`.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::meta;
+use crate::redact::Redactor;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// Tools that don't run a process but are still audited (privilege changes)
+const AUDITED_TOOLS: &[&str] = &["unlock", "lock"];
+
+/// A single audit log record
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub timestamp_ms: u64,
+    pub tool: String,
+    pub arguments: Value,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+}
+
+impl AuditEntry {
+    /// Build an entry for a completed tool call, redacting sensitive arguments
+    pub fn new(
+        redactor: &Redactor,
+        tool: &str,
+        arguments: &Option<JsonObject>,
+        success: bool,
+        exit_code: Option<i32>,
+        duration: Duration,
+    ) -> Self {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+
+        Self {
+            timestamp_ms,
+            tool: tool.to_string(),
+            arguments: redactor.redact_arguments(arguments),
+            success,
+            exit_code,
+            duration_ms: duration.as_millis() as u64,
+        }
+    }
+}
+
+/// Append-only JSON-lines audit log
+#[derive(Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl AuditLog {
+    /// Create an audit log that appends to the given path
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Append an entry to the log
+    pub fn record(&self, entry: &AuditEntry) -> Result<()> {
+        let line = serde_json::to_string(entry).context("Failed to serialize audit entry")?;
+
+        let _guard = self.write_lock.lock().unwrap();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open audit log: {}", self.path.display()))?;
+        writeln!(file, "{}", line)
+            .with_context(|| format!("Failed to write audit log: {}", self.path.display()))
+    }
+}
+
+/// Whether calls to the given tool should be audited
+pub fn is_audited(tool: &str) -> bool {
+    AUDITED_TOOLS.contains(&tool) || meta::runs_process(tool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Test that only process-executing tools are audited
+    #[test]
+    fn test_is_audited() {
+        assert!(is_audited("forge_build"));
+        assert!(is_audited("cast_convert"));
+        assert!(is_audited("anvil_session_start"));
+        assert!(is_audited("unlock"));
+        assert!(!is_audited("search_chains"));
+        assert!(!is_audited("server_stats"));
+    }
+
+    /// Test that entries are appended as JSON lines without leaking secrets
+    #[test]
+    fn test_record_appends_json_lines() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("audit.jsonl");
+        let log = AuditLog::new(&path);
+
+        let mut args = JsonObject::new();
+        args.insert("mnemonic".to_string(), json!("test test test junk"));
+        let redactor = Redactor::default();
+        log.record(&AuditEntry::new(
+            &redactor,
+            "cast_wallet_address",
+            &Some(args),
+            true,
+            None,
+            Duration::from_millis(12),
+        ))
+        .unwrap();
+        log.record(&AuditEntry::new(
+            &redactor,
+            "forge_build",
+            &None,
+            false,
+            Some(1),
+            Duration::from_millis(5),
+        ))
+        .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("junk"));
+
+        let lines: Vec<Value> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["tool"], "cast_wallet_address");
+        assert_eq!(lines[0]["duration_ms"], 12);
+        assert!(lines[0].get("exit_code").is_none());
+        assert_eq!(lines[1]["success"], false);
+        assert_eq!(lines[1]["exit_code"], 1);
+    }
+}
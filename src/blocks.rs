@@ -0,0 +1,360 @@
+//! Block and transaction lookups
+//!
+//! `cast_block` and `cast_tx` run `cast block --json` / `cast tx --json` and parse the
+//! result into a few useful fields (number, hash, timestamp, gas and transactions for a
+//! block; from, to, value and status for a transaction). The transaction status comes
+//! from `cast receipt`.
+
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::cast;
+use crate::error::ToolError;
+use crate::foundry::FoundryExecutor;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// Get the block and transaction tools
+pub fn get_block_tools() -> Vec<Tool> {
+    let mut block_props = cast::endpoint_properties();
+    block_props.insert(
+        "block".to_string(),
+        json!({
+            "type": "string",
+            "description": "Block number, hash or tag such as 'latest', 'finalized' or 'earliest' (default: latest)"
+        }),
+    );
+    block_props.insert(
+        "full".to_string(),
+        json!({
+            "type": "boolean",
+            "description": "Fetch full transaction objects rather than hashes (only hashes are returned either way)"
+        }),
+    );
+
+    let mut block_schema = serde_json::Map::new();
+    block_schema.insert("type".to_string(), Value::String("object".to_string()));
+    block_schema.insert("properties".to_string(), Value::Object(block_props));
+
+    let mut tx_props = cast::endpoint_properties();
+    tx_props.insert(
+        "tx_hash".to_string(),
+        json!({
+            "type": "string",
+            "description": "Transaction hash"
+        }),
+    );
+
+    let mut tx_schema = serde_json::Map::new();
+    tx_schema.insert("type".to_string(), Value::String("object".to_string()));
+    tx_schema.insert("properties".to_string(), Value::Object(tx_props));
+    tx_schema.insert("required".to_string(), json!(["tx_hash"]));
+
+    vec![
+        Tool::new(
+            "cast_block".to_string(),
+            "Get a block's number, hash, timestamp, gas used and limit, base fee and transaction hashes. Uses the configured default RPC, else the running Anvil session, unless an rpc_url or port is given.".to_string(),
+            Arc::new(block_schema),
+        ),
+        Tool::new(
+            "cast_tx".to_string(),
+            "Get a transaction's sender, recipient, value, nonce and block, plus its status (success, reverted or pending) and gas used from the receipt. Uses the configured default RPC, else the running Anvil session, unless an rpc_url or port is given.".to_string(),
+            Arc::new(tx_schema),
+        ),
+    ]
+}
+
+/// Read an RPC quantity, which cast prints as hex (or, for some fields, decimal)
+fn quantity(value: Option<&Value>) -> Option<u128> {
+    match value? {
+        Value::Number(n) => n.as_u64().map(u128::from),
+        Value::String(s) => match s.strip_prefix("0x") {
+            Some(hex) => u128::from_str_radix(hex, 16).ok(),
+            None => s.parse().ok(),
+        },
+        _ => None,
+    }
+}
+
+/// A quantity as a JSON number, or a decimal string if it doesn't fit in a u64
+fn quantity_json(value: Option<&Value>) -> Value {
+    match quantity(value) {
+        Some(n) => u64::try_from(n).map_or_else(|_| json!(n.to_string()), |n| json!(n)),
+        None => Value::Null,
+    }
+}
+
+fn string_field(json: &Value, name: &str) -> Value {
+    json.get(name)
+        .and_then(|v| v.as_str())
+        .map_or(Value::Null, |s| json!(s))
+}
+
+/// Parse `cast block --json` output
+fn parse_block(json: &Value) -> Value {
+    let transactions: Vec<&str> = json
+        .get("transactions")
+        .and_then(|v| v.as_array())
+        .map(|txs| {
+            txs.iter()
+                .filter_map(|tx| tx.as_str().or_else(|| tx.get("hash")?.as_str()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    json!({
+        "number": quantity_json(json.get("number")),
+        "hash": string_field(json, "hash"),
+        "parent_hash": string_field(json, "parentHash"),
+        "timestamp": quantity_json(json.get("timestamp")),
+        "gas_used": quantity_json(json.get("gasUsed")),
+        "gas_limit": quantity_json(json.get("gasLimit")),
+        "base_fee_per_gas": quantity_json(json.get("baseFeePerGas")),
+        "miner": string_field(json, "miner"),
+        "transaction_count": transactions.len(),
+        "transactions": transactions,
+    })
+}
+
+/// Parse `cast tx --json` output, with the status and gas used from the receipt if mined
+fn parse_tx(tx: &Value, receipt: Option<&Value>) -> Value {
+    let status = match receipt.map(|r| quantity(r.get("status"))) {
+        None => "pending",
+        Some(Some(1)) => "success",
+        Some(Some(0)) => "reverted",
+        Some(_) => "unknown",
+    };
+    let value = quantity(tx.get("value"));
+
+    let mut info = json!({
+        "hash": string_field(tx, "hash"),
+        "from": string_field(tx, "from"),
+        "to": string_field(tx, "to"),
+        "value_wei": value.map_or(Value::Null, |v| json!(v.to_string())),
+        "value_eth": value.map_or(Value::Null, |v| json!(cast::format_units(v, 18))),
+        "nonce": quantity_json(tx.get("nonce")),
+        "block_number": quantity_json(tx.get("blockNumber")),
+        "gas_limit": quantity_json(tx.get("gas")),
+        "status": status,
+    });
+    if let Some(receipt) = receipt {
+        info["gas_used"] = quantity_json(receipt.get("gasUsed"));
+        info["effective_gas_price"] = quantity_json(receipt.get("effectiveGasPrice"));
+        if let Some(address) = receipt.get("contractAddress").and_then(|v| v.as_str()) {
+            info["contract_address"] = json!(address);
+        }
+    }
+    info
+}
+
+/// Run a schema tool with `--json` and parse its output
+fn run_json(
+    executor: &FoundryExecutor,
+    name: &str,
+    arguments: JsonObject,
+) -> Result<Value, ToolError> {
+    let output = cast::run_tool(executor, name, arguments, &["--json".to_string()])?;
+    serde_json::from_str(&output).map_err(|e| {
+        ToolError::execution_failed(format!("Could not parse {} output as JSON: {}", name, e))
+    })
+}
+
+async fn fetch_block(args: &JsonObject, executor: &FoundryExecutor) -> Result<Value, ToolError> {
+    let rpc_url = cast::resolve_endpoint(args, &executor.config()).await?;
+    let mut block_args = JsonObject::new();
+    block_args.insert("rpc-url".to_string(), json!(rpc_url));
+    if let Some(block) = args.get("block").and_then(|v| v.as_str()) {
+        block_args.insert("block".to_string(), json!(block));
+    }
+    if args.get("full").and_then(|v| v.as_bool()) == Some(true) {
+        block_args.insert("full".to_string(), json!(true));
+    }
+
+    let block = run_json(executor, "cast_block", block_args)?;
+    if block.is_null() {
+        return Err(ToolError::execution_failed("Block not found"));
+    }
+    Ok(parse_block(&block))
+}
+
+async fn fetch_tx(
+    args: &JsonObject,
+    tx_hash: &str,
+    executor: &FoundryExecutor,
+) -> Result<Value, ToolError> {
+    let rpc_url = cast::resolve_endpoint(args, &executor.config()).await?;
+
+    let mut tx_args = JsonObject::new();
+    tx_args.insert("tx_hash".to_string(), json!(tx_hash));
+    tx_args.insert("rpc-url".to_string(), json!(rpc_url));
+    let tx = run_json(executor, "cast_tx", tx_args)?;
+    if tx.is_null() {
+        return Err(ToolError::execution_failed(format!(
+            "Transaction {} not found",
+            tx_hash
+        )));
+    }
+
+    // A transaction without a block is still pending and has no receipt yet
+    let receipt = if tx.get("blockNumber").is_some_and(|n| !n.is_null()) {
+        let mut receipt_args = JsonObject::new();
+        receipt_args.insert("tx_hash".to_string(), json!(tx_hash));
+        receipt_args.insert("rpc-url".to_string(), json!(rpc_url));
+        receipt_args.insert("async".to_string(), json!(true));
+        Some(run_json(executor, "cast_receipt", receipt_args)?)
+    } else {
+        None
+    };
+
+    Ok(parse_tx(&tx, receipt.as_ref()))
+}
+
+fn block_text(block: &Value) -> String {
+    let mut text = format!(
+        "Block {} ({})\nTimestamp: {}\nGas used: {} / {}",
+        block["number"], block["hash"], block["timestamp"], block["gas_used"], block["gas_limit"]
+    );
+    if !block["base_fee_per_gas"].is_null() {
+        text.push_str(&format!("\nBase fee: {} wei", block["base_fee_per_gas"]));
+    }
+    text.push_str(&format!("\nTransactions: {}", block["transaction_count"]));
+    text.replace('"', "")
+}
+
+fn tx_text(tx: &Value) -> String {
+    let to = if tx["to"].is_null() {
+        "(contract creation)".to_string()
+    } else {
+        tx["to"].to_string()
+    };
+    let mut text = format!(
+        "Transaction {}\nStatus: {}\nFrom: {}\nTo: {}\nValue: {} ETH",
+        tx["hash"], tx["status"], tx["from"], to, tx["value_eth"]
+    );
+    if !tx["block_number"].is_null() {
+        text.push_str(&format!("\nBlock: {}", tx["block_number"]));
+    }
+    if let Some(gas_used) = tx.get("gas_used") {
+        text.push_str(&format!("\nGas used: {}", gas_used));
+    }
+    if let Some(address) = tx.get("contract_address") {
+        text.push_str(&format!("\nContract created: {}", address));
+    }
+    text.replace('"', "")
+}
+
+fn to_result(
+    result: Result<Value, ToolError>,
+    text: fn(&Value) -> String,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let structured = match result {
+        Ok(structured) => structured,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut result = CallToolResult::success(vec![Content::text(text(&structured))]);
+    result.structured_content = Some(structured);
+    Ok(result)
+}
+
+/// Handle cast_block tool call
+pub async fn handle_cast_block(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let empty = JsonObject::new();
+    let args = arguments.as_ref().unwrap_or(&empty);
+    to_result(fetch_block(args, executor).await, block_text)
+}
+
+/// Handle cast_tx tool call
+pub async fn handle_cast_tx(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let args = arguments
+        .as_ref()
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing arguments", None))?;
+    let tx_hash = args
+        .get("tx_hash")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing 'tx_hash' parameter", None))?;
+    to_result(fetch_tx(args, tx_hash, executor).await, tx_text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that hex and decimal quantities parse, and large ones become strings
+    #[test]
+    fn test_quantity() {
+        assert_eq!(quantity(Some(&json!("0x1a"))), Some(26));
+        assert_eq!(quantity(Some(&json!("26"))), Some(26));
+        assert_eq!(quantity(Some(&json!(26))), Some(26));
+        assert_eq!(quantity(Some(&json!("0xzz"))), None);
+        assert_eq!(quantity(None), None);
+
+        assert_eq!(quantity_json(Some(&json!("0x10"))), json!(16));
+        assert_eq!(
+            quantity_json(Some(&json!("0x56bc75e2d63100000"))),
+            json!("100000000000000000000")
+        );
+    }
+
+    /// Test that block fields are extracted, with full transaction objects reduced to hashes
+    #[test]
+    fn test_parse_block() {
+        let block = json!({
+            "number": "0x10",
+            "hash": "0xabc",
+            "parentHash": "0xdef",
+            "timestamp": "0x65000000",
+            "gasUsed": "0x5208",
+            "gasLimit": "0x1c9c380",
+            "baseFeePerGas": "0x3b9aca00",
+            "miner": "0x0000000000000000000000000000000000000000",
+            "transactions": [{"hash": "0x01"}, "0x02"]
+        });
+        let parsed = parse_block(&block);
+        assert_eq!(parsed["number"], json!(16));
+        assert_eq!(parsed["timestamp"], json!(0x65000000u64));
+        assert_eq!(parsed["gas_used"], json!(21000));
+        assert_eq!(parsed["base_fee_per_gas"], json!(1_000_000_000));
+        assert_eq!(parsed["transaction_count"], json!(2));
+        assert_eq!(parsed["transactions"], json!(["0x01", "0x02"]));
+        assert!(block_text(&parsed).contains("Block 16 (0xabc)"));
+    }
+
+    /// Test that the status comes from the receipt, and a missing receipt means pending
+    #[test]
+    fn test_parse_tx() {
+        let tx = json!({
+            "hash": "0x01",
+            "from": "0xaa",
+            "to": null,
+            "value": "0xde0b6b3a7640000",
+            "nonce": "0x2",
+            "blockNumber": "0x10",
+            "gas": "0x5208"
+        });
+
+        let pending = parse_tx(&tx, None);
+        assert_eq!(pending["status"], json!("pending"));
+        assert_eq!(pending["value_wei"], json!("1000000000000000000"));
+        assert_eq!(pending["value_eth"], json!("1"));
+        assert!(pending.get("gas_used").is_none());
+
+        let receipt = json!({"status": "0x0", "gasUsed": "0x5000", "contractAddress": "0xcc"});
+        let reverted = parse_tx(&tx, Some(&receipt));
+        assert_eq!(reverted["status"], json!("reverted"));
+        assert_eq!(reverted["gas_used"], json!(0x5000));
+        assert_eq!(reverted["contract_address"], json!("0xcc"));
+        assert!(tx_text(&reverted).contains("To: (contract creation)"));
+
+        let receipt = json!({"status": "0x1"});
+        assert_eq!(parse_tx(&tx, Some(&receipt))["status"], json!("success"));
+    }
+}
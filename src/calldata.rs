@@ -0,0 +1,293 @@
+//! Calldata decoding helper
+//!
+//! `decode_calldata` picks the right `cast` tool for what the client has: a known signature
+//! goes through `cast_decode_calldata`, full calldata without one through
+//! `cast_4byte_calldata`, and a bare selector through `cast_4byte`, which looks it up in the
+//! signature directory.
+
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::cast;
+use crate::error::ToolError;
+use crate::foundry::FoundryExecutor;
+
+/// How a piece of calldata will be decoded
+#[derive(Debug, Clone, PartialEq)]
+enum DecodeMode {
+    /// Decode the arguments against a caller-supplied signature
+    Signature(String),
+    /// Look the selector up in the signature directory and decode the arguments
+    Lookup,
+    /// Only a selector was given; list the matching signatures
+    Selector,
+}
+
+impl DecodeMode {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Signature(_) => "signature",
+            Self::Lookup => "lookup",
+            Self::Selector => "selector",
+        }
+    }
+}
+
+/// Get the calldata decoding tools
+pub fn get_calldata_tools() -> Vec<Tool> {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "calldata".to_string(),
+        json!({
+            "type": "string",
+            "description": "Hex calldata to decode, or just a 4-byte function selector (e.g. '0xa9059cbb')"
+        }),
+    );
+    props.insert(
+        "signature".to_string(),
+        json!({
+            "type": "string",
+            "description": "Function signature to decode against, e.g. 'transfer(address,uint256)' (optional; looked up in the 4byte directory when omitted)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+    schema.insert("required".to_string(), json!(["calldata"]));
+
+    vec![Tool::new(
+        "decode_calldata".to_string(),
+        "Decode raw calldata into the called function and its arguments. Uses the given signature if any, otherwise looks the selector up in the 4byte signature directory. A bare selector returns the matching function signatures.".to_string(),
+        Arc::new(schema),
+    )]
+}
+
+/// Validate calldata and normalize it to lowercase `0x`-prefixed hex
+fn normalize_calldata(calldata: &str) -> Result<String, ToolError> {
+    let trimmed = calldata.trim();
+    let hex = trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+        .unwrap_or(trimmed);
+
+    if hex.is_empty() || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ToolError::InvalidArguments(format!(
+            "'{}' is not hex calldata",
+            calldata
+        )));
+    }
+    if !hex.len().is_multiple_of(2) {
+        return Err(ToolError::InvalidArguments(
+            "Calldata has an odd number of hex digits".to_string(),
+        ));
+    }
+    if hex.len() < 8 {
+        return Err(ToolError::InvalidArguments(
+            "Calldata must include at least a 4-byte function selector".to_string(),
+        ));
+    }
+
+    Ok(format!("0x{}", hex.to_ascii_lowercase()))
+}
+
+/// Choose how to decode the calldata
+fn decode_mode(calldata: &str, signature: Option<&str>) -> DecodeMode {
+    match signature {
+        Some(sig) if !sig.trim().is_empty() => DecodeMode::Signature(sig.trim().to_string()),
+        _ if calldata.len() == 10 => DecodeMode::Selector,
+        _ => DecodeMode::Lookup,
+    }
+}
+
+/// The `cast` tool and arguments for a decode
+fn decode_args(
+    calldata: &str,
+    mode: &DecodeMode,
+) -> (&'static str, serde_json::Map<String, Value>) {
+    let mut args = serde_json::Map::new();
+    let name = match mode {
+        DecodeMode::Signature(sig) => {
+            args.insert("sig".to_string(), json!(sig));
+            args.insert("calldata".to_string(), json!(calldata));
+            "cast_decode_calldata"
+        }
+        DecodeMode::Lookup => {
+            args.insert("calldata".to_string(), json!(calldata));
+            "cast_4byte_calldata"
+        }
+        DecodeMode::Selector => {
+            args.insert("selector".to_string(), json!(calldata));
+            "cast_4byte"
+        }
+    };
+    (name, args)
+}
+
+/// Strip the `1) "sig"` numbering `cast 4byte-decode` puts on the matched signature
+fn parse_signature_line(line: &str) -> String {
+    let line = line.trim();
+    let line = match line.split_once(") ") {
+        Some((index, rest)) if index.chars().all(|c| c.is_ascii_digit()) => rest,
+        _ => line,
+    };
+    line.trim_matches('"').to_string()
+}
+
+/// Lines of `cast` output, without blank ones
+fn output_lines(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Handle decode_calldata tool call
+pub async fn handle_decode_calldata(
+    arguments: &Option<serde_json::Map<String, Value>>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let args = arguments
+        .as_ref()
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing arguments", None))?;
+
+    let calldata = args
+        .get("calldata")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing 'calldata' parameter", None))?;
+    let calldata = normalize_calldata(calldata)?;
+    let mode = decode_mode(&calldata, args.get("signature").and_then(|v| v.as_str()));
+
+    let (name, decode) = decode_args(&calldata, &mode);
+    let output = match cast::run_tool(executor, name, decode, &[]) {
+        Ok(output) => output,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+    let lines = output_lines(&output);
+
+    let (text, mut structured) = match &mode {
+        DecodeMode::Selector => {
+            let signatures: Vec<String> = lines.iter().map(|l| parse_signature_line(l)).collect();
+            let text = if signatures.is_empty() {
+                format!("No known signatures for selector {}", calldata)
+            } else {
+                format!(
+                    "Signatures matching selector {}:\n{}",
+                    calldata,
+                    signatures.join("\n")
+                )
+            };
+            (
+                text,
+                json!({ "selector": calldata, "signatures": signatures }),
+            )
+        }
+        DecodeMode::Signature(sig) => (
+            format!("Function: {}\nArguments:\n{}", sig, lines.join("\n")),
+            json!({ "function": sig, "arguments": lines }),
+        ),
+        DecodeMode::Lookup => {
+            let function = lines
+                .first()
+                .map(|line| parse_signature_line(line))
+                .unwrap_or_default();
+            let arguments = lines.get(1..).unwrap_or_default().to_vec();
+            (
+                format!(
+                    "Function: {}\nArguments:\n{}",
+                    function,
+                    arguments.join("\n")
+                ),
+                json!({ "function": function, "arguments": arguments }),
+            )
+        }
+    };
+
+    structured["mode"] = json!(mode.name());
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(structured);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSFER: &str = "0xa9059cbb000000000000000000000000000000000000000000000000000000000000dead0000000000000000000000000000000000000000000000000000000000000064";
+
+    /// Test that calldata is normalized and malformed input rejected
+    #[test]
+    fn test_normalize_calldata() {
+        assert_eq!(normalize_calldata("A9059CBB").unwrap(), "0xa9059cbb");
+        assert_eq!(normalize_calldata(" 0xa9059cbb ").unwrap(), "0xa9059cbb");
+        assert!(normalize_calldata("").is_err());
+        assert!(normalize_calldata("0x").is_err());
+        assert!(normalize_calldata("0xa9059c").is_err());
+        assert!(normalize_calldata("0xa9059cbb0").is_err());
+        assert!(normalize_calldata("0xzz059cbb").is_err());
+    }
+
+    /// Test that the decode mode follows the inputs
+    #[test]
+    fn test_decode_mode() {
+        assert_eq!(decode_mode("0xa9059cbb", None), DecodeMode::Selector);
+        assert_eq!(decode_mode(TRANSFER, None), DecodeMode::Lookup);
+        assert_eq!(decode_mode(TRANSFER, Some("  ")), DecodeMode::Lookup);
+        assert_eq!(
+            decode_mode(TRANSFER, Some("transfer(address,uint256)")),
+            DecodeMode::Signature("transfer(address,uint256)".to_string())
+        );
+    }
+
+    /// Test that each mode maps to the matching cast tool
+    #[test]
+    fn test_decode_args() {
+        let (name, args) = decode_args("0xa9059cbb", &DecodeMode::Selector);
+        assert_eq!(name, "cast_4byte");
+        assert_eq!(Value::Object(args), json!({"selector": "0xa9059cbb"}));
+
+        let (name, args) = decode_args(TRANSFER, &DecodeMode::Lookup);
+        assert_eq!(name, "cast_4byte_calldata");
+        assert_eq!(Value::Object(args), json!({"calldata": TRANSFER}));
+
+        let (name, args) = decode_args(
+            TRANSFER,
+            &DecodeMode::Signature("transfer(address,uint256)".to_string()),
+        );
+        assert_eq!(name, "cast_decode_calldata");
+        assert_eq!(
+            Value::Object(args),
+            json!({"sig": "transfer(address,uint256)", "calldata": TRANSFER})
+        );
+    }
+
+    /// Test that 4byte-decode's numbering and quotes are stripped from signatures
+    #[test]
+    fn test_parse_signature_line() {
+        assert_eq!(
+            parse_signature_line("1) \"transfer(address,uint256)\""),
+            "transfer(address,uint256)"
+        );
+        assert_eq!(
+            parse_signature_line("transfer(address,uint256)"),
+            "transfer(address,uint256)"
+        );
+    }
+
+    /// Test that the tool requires calldata
+    #[test]
+    fn test_get_calldata_tools() {
+        let tools = get_calldata_tools();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "decode_calldata");
+        assert_eq!(
+            tools[0].input_schema.get("required"),
+            Some(&json!(["calldata"]))
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Helpers for built-in tools that shell out to `cast`
+//!
+//! The schema-driven Foundry tools go through [`crate::foundry::FoundryExecutor`]; the
+//! convenience tools that stitch several `cast` calls together use these helpers instead.
+
+use std::process::Command;
+
+use crate::chainlist;
+use crate::config::Config;
+use crate::error::ToolError;
+use crate::foundry::FoundryExecutor;
+use crate::sessions::SessionManager;
+
+type JsonObject = serde_json::Map<String, serde_json::Value>;
+
+/// Path to the `cast` binary, given the detected Foundry bin directory
+pub fn cast_path(foundry_bin_path: &Option<String>) -> String {
+    crate::foundry::binary_path(foundry_bin_path.as_deref(), "cast")
+}
+
+/// Run `cast` with the given arguments and return its trimmed stdout
+pub fn run(cast_path: &str, args: &[String]) -> Result<String, ToolError> {
+    let output = Command::new(cast_path).args(args).output().map_err(|e| {
+        ToolError::execution_failed(format!("Failed to execute '{}': {}", cast_path, e))
+    })?;
+
+    if !output.status.success() {
+        return Err(ToolError::ExecutionFailed {
+            message: format!(
+                "cast {} failed: {}",
+                args.first().map(String::as_str).unwrap_or_default(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            exit_code: output.status.code(),
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Run a schema tool and return its trimmed stdout, reporting a tool the configuration
+/// filtered out as forbidden
+///
+/// Built-ins that replace a schema tool run it through here, so the allowlist and argument
+/// validation still apply. `extra_args` are passed on to [`FoundryExecutor::run_tool`].
+pub fn run_tool(
+    executor: &FoundryExecutor,
+    name: &str,
+    arguments: JsonObject,
+    extra_args: &[String],
+) -> Result<String, ToolError> {
+    let arguments = Some(arguments);
+    let output = match executor.run_tool(name, &arguments, extra_args, None) {
+        Err(ToolError::ToolNotFound(_)) => {
+            return Err(ToolError::ForbiddenCommand(format!(
+                "'{}' is disabled by the server configuration",
+                name
+            )))
+        }
+        result => result?,
+    };
+    if !output.success {
+        return Err(executor.output_error(&arguments, &output));
+    }
+    Ok(output.stdout.trim().to_string())
+}
+
+/// Parse a decimal integer printed by `cast`
+pub fn parse_u128(output: &str, what: &str) -> Result<u128, ToolError> {
+    output.trim().parse().map_err(|_| {
+        ToolError::execution_failed(format!("Unexpected {} from cast: '{}'", what, output))
+    })
+}
+
+/// Resolve the RPC URL for a call: the explicit `rpc_url`, else the configured
+/// `default_rpc_url` or `default_chain`, else the running Anvil session
+pub async fn resolve_rpc_url(rpc_url: Option<&str>, config: &Config) -> Result<String, ToolError> {
+    if let Some(url) = rpc_url {
+        return Ok(url.to_string());
+    }
+    if let Some(default) = config
+        .default_rpc_url
+        .as_deref()
+        .or(config.default_chain.as_deref())
+    {
+        if chainlist::looks_like_chain_name(default) {
+            return chainlist::resolve_chain_rpc_url(default).await;
+        }
+        return Ok(default.to_string());
+    }
+
+    let manager = SessionManager::global();
+    if let Some(note) = manager.check_anvil() {
+        eprintln!("⚠ {}", note);
+    }
+    manager.anvil_rpc_url().ok_or_else(|| {
+        ToolError::InvalidArguments(
+            "No 'rpc_url' given and no Anvil session is running. Pass an RPC URL (see search_rpc_url) or start one with anvil_session_start.".to_string(),
+        )
+    })
+}
+
+/// Tool input properties for choosing an endpoint: `rpc_url`, or the `port` of a local node
+pub fn endpoint_properties() -> JsonObject {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "rpc_url".to_string(),
+        serde_json::json!({
+            "type": "string",
+            "description": "RPC endpoint to query (default: the configured default_rpc_url or default_chain, else the running Anvil session)"
+        }),
+    );
+    props.insert(
+        "port".to_string(),
+        serde_json::json!({
+            "type": "integer",
+            "description": "Port of a local node on 127.0.0.1, instead of rpc_url"
+        }),
+    );
+    props
+}
+
+/// Resolve the endpoint from `rpc_url` or `port` (see [`endpoint_properties`]), else as in
+/// [`resolve_rpc_url`]
+pub async fn resolve_endpoint(args: &JsonObject, config: &Config) -> Result<String, ToolError> {
+    let rpc_url = args.get("rpc_url").and_then(|v| v.as_str());
+    match (rpc_url, args.get("port")) {
+        (Some(_), Some(_)) => Err(ToolError::InvalidArguments(
+            "Give either 'rpc_url' or 'port', not both".to_string(),
+        )),
+        (None, Some(port)) => match port.as_u64().filter(|p| (1..=65535).contains(p)) {
+            Some(port) => Ok(format!("http://127.0.0.1:{}", port)),
+            None => Err(ToolError::InvalidArguments(
+                "'port' must be a number between 1 and 65535".to_string(),
+            )),
+        },
+        (rpc_url, None) => resolve_rpc_url(rpc_url, config).await,
+    }
+}
+
+/// Format an integer amount with the given number of decimals (e.g. wei to ether),
+/// trimming trailing zeros
+pub fn format_units(amount: u128, decimals: u32) -> String {
+    let Some(scale) = 10u128.checked_pow(decimals) else {
+        return amount.to_string();
+    };
+
+    let whole = amount / scale;
+    let fraction = amount % scale;
+    if fraction == 0 {
+        return whole.to_string();
+    }
+
+    let fraction = format!("{:0width$}", fraction, width = decimals as usize);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that amounts are scaled and trailing zeros trimmed
+    #[test]
+    fn test_format_units() {
+        assert_eq!(format_units(0, 18), "0");
+        assert_eq!(format_units(1_000_000_000_000_000_000, 18), "1");
+        assert_eq!(format_units(31_500_000_000_000, 18), "0.0000315");
+        assert_eq!(format_units(1_500_000_000, 9), "1.5");
+        assert_eq!(format_units(42, 0), "42");
+    }
+
+    /// Test that the cast path follows the detected bin directory
+    #[test]
+    fn test_cast_path() {
+        assert_eq!(cast_path(&None), "cast");
+        assert_eq!(
+            cast_path(&Some("/opt/foundry/bin".to_string())),
+            "/opt/foundry/bin/cast"
+        );
+    }
+
+    /// Test that a port and an rpc_url can't both be given, and ports are range-checked
+    #[tokio::test]
+    async fn test_resolve_endpoint() {
+        let config = Config::default();
+        let args = serde_json::json!({"port": 8545});
+        assert_eq!(
+            resolve_endpoint(args.as_object().unwrap(), &config)
+                .await
+                .unwrap(),
+            "http://127.0.0.1:8545"
+        );
+        let args = serde_json::json!({"rpc_url": "http://localhost:8545"});
+        assert_eq!(
+            resolve_endpoint(args.as_object().unwrap(), &config)
+                .await
+                .unwrap(),
+            "http://localhost:8545"
+        );
+
+        for args in [
+            serde_json::json!({"rpc_url": "http://localhost:8545", "port": 8545}),
+            serde_json::json!({"port": 0}),
+            serde_json::json!({"port": "8545"}),
+        ] {
+            assert!(matches!(
+                resolve_endpoint(args.as_object().unwrap(), &config).await,
+                Err(ToolError::InvalidArguments(_))
+            ));
+        }
+    }
+
+    /// Test that an explicit RPC URL wins over the Anvil session
+    #[tokio::test]
+    async fn test_resolve_rpc_url_explicit() {
+        assert_eq!(
+            resolve_rpc_url(Some("http://localhost:9545"), &Config::default())
+                .await
+                .unwrap(),
+            "http://localhost:9545"
+        );
+    }
+
+    /// Test that the configured default_rpc_url stands in for a missing rpc_url
+    #[tokio::test]
+    async fn test_resolve_rpc_url_default() {
+        let config = Config {
+            default_rpc_url: Some("https://rpc.example".to_string()),
+            default_chain: Some("base".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve_rpc_url(None, &config).await.unwrap(),
+            "https://rpc.example"
+        );
+        assert_eq!(
+            resolve_rpc_url(Some("http://localhost:9545"), &config)
+                .await
+                .unwrap(),
+            "http://localhost:9545"
+        );
+    }
+}
@@ -9,7 +9,12 @@ use rmcp::model::{CallToolResult, Content, Tool};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::config::{self, Config};
+use crate::error::ToolError;
+use crate::format::{self, ListFormat};
 
 /// RPC endpoint information from chainlist.org
 /// Can be either a string URL or an object with metadata
@@ -104,23 +109,111 @@ pub struct ChainInfo {
     pub extra: HashMap<String, serde_json::Value>,
 }
 
+/// Name fragments that mark a chain as a test network
+const TESTNET_NAME_HINTS: &[&str] = &[
+    "testnet", "sepolia", "goerli", "holesky", "hoodi", "ropsten", "rinkeby", "kovan", "devnet",
+    "mumbai", "amoy", "fuji", "chiado",
+];
+
+impl ChainInfo {
+    /// Symbol of the chain's native currency (e.g. "POL" for Polygon), if listed
+    pub fn native_currency_symbol(&self) -> Option<&str> {
+        self.native_currency.as_ref()?.get("symbol")?.as_str()
+    }
+
+    /// Whether the chain is a testnet, as labeled by chainlist.org.
+    ///
+    /// Many chains are unlabeled; with `infer` set, those are classified by their name
+    /// instead (e.g. "Base Sepolia Testnet").
+    pub fn is_testnet(&self, infer: bool) -> Option<bool> {
+        self.testnet
+            .or_else(|| infer.then(|| looks_like_testnet(&self.name)))
+    }
+}
+
+/// Guess from a chain's name whether it is a test network
+fn looks_like_testnet(name: &str) -> bool {
+    let name = name.to_lowercase();
+    TESTNET_NAME_HINTS.iter().any(|hint| name.contains(hint))
+}
+
+/// Chains shown by `list_popular_chains` unless `Config.popular_chain_ids` is set
+pub const DEFAULT_POPULAR_CHAIN_IDS: &[u64] = &[
+    1, 10, 137, 42161, 8453, 43114, 56, 250, 100, 324, 1101, 59144, 534352,
+];
+
+/// Chainlist data and when it was downloaded
+type CachedChainlist = (Vec<ChainInfo>, Instant);
+
 /// Global cache for chainlist data
-static CHAINLIST_CACHE: Lazy<Mutex<Option<Vec<ChainInfo>>>> = Lazy::new(|| Mutex::new(None));
+static CHAINLIST_CACHE: Lazy<Mutex<Option<CachedChainlist>>> = Lazy::new(|| Mutex::new(None));
+
+/// Held while a fetch is in flight so concurrent callers on a cold cache share one download
+static CHAINLIST_FETCH: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mutex::new(()));
+
+/// The cached data, if it has been fetched
+fn cached() -> Option<Vec<ChainInfo>> {
+    CHAINLIST_CACHE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|(chains, _)| chains.clone())
+}
+
+/// How long ago the cached data was downloaded, if it is cached
+pub fn cache_age() -> Option<Duration> {
+    CHAINLIST_CACHE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|(_, fetched_at)| fetched_at.elapsed())
+}
+
+/// Snapshot of major chains bundled at compile time
+#[cfg(feature = "embedded-chainlist")]
+const EMBEDDED_CHAINLIST: &str = include_str!("../chainlist-snapshot.json");
+
+/// Parse the bundled snapshot of major chains
+#[cfg(feature = "embedded-chainlist")]
+pub fn embedded_chainlist() -> Result<Vec<ChainInfo>> {
+    serde_json::from_str(EMBEDDED_CHAINLIST).context("Failed to parse embedded chainlist snapshot")
+}
 
-/// Fetches and caches chain data from chainlist.org
+/// Fetches and caches chain data from chainlist.org.
+///
+/// With the `embedded-chainlist` feature, falls back to a bundled snapshot of major chains
+/// when chainlist.org can't be reached. The snapshot isn't cached, so later calls retry
+/// the network.
 pub async fn fetch_chainlist() -> Result<Vec<ChainInfo>> {
-    // Check cache first
-    {
-        let cache = CHAINLIST_CACHE.lock().unwrap();
-        if let Some(ref cached) = *cache {
-            return Ok(cached.clone());
+    crate::http::fetch_once(&CHAINLIST_FETCH, cached, || async {
+        let chains = match download_chainlist().await {
+            Ok(chains) => chains,
+            #[cfg(feature = "embedded-chainlist")]
+            Err(e) => {
+                eprintln!(
+                    "⚠ Warning: chainlist.org unavailable ({:#}); using the embedded snapshot",
+                    e
+                );
+                return embedded_chainlist();
+            }
+            #[cfg(not(feature = "embedded-chainlist"))]
+            Err(e) => return Err(e),
+        };
+
+        // Update cache
+        {
+            let mut cache = CHAINLIST_CACHE.lock().unwrap();
+            *cache = Some((chains.clone(), Instant::now()));
         }
-    }
 
-    // Fetch from API
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
-        .build()?;
+        Ok(chains)
+    })
+    .await
+}
+
+/// Download the full chain list from chainlist.org
+async fn download_chainlist() -> Result<Vec<ChainInfo>> {
+    let client = crate::http::client()?;
 
     let response = client.get("https://chainlist.org/rpcs.json").send().await?;
 
@@ -128,17 +221,8 @@ pub async fn fetch_chainlist() -> Result<Vec<ChainInfo>> {
     let text = response.text().await?;
 
     // Try to parse the JSON
-    let chains: Vec<ChainInfo> = serde_json::from_str(&text).context(
-        "Failed to parse chainlist.org response. This might be due to API format changes.",
-    )?;
-
-    // Update cache
-    {
-        let mut cache = CHAINLIST_CACHE.lock().unwrap();
-        *cache = Some(chains.clone());
-    }
-
-    Ok(chains)
+    serde_json::from_str(&text)
+        .context("Failed to parse chainlist.org response. This might be due to API format changes.")
 }
 
 /// Find RPC URLs for a specific chain by ID or name
@@ -169,7 +253,10 @@ pub fn find_chain_rpcs<'a>(chains: &'a [ChainInfo], query: &str) -> Option<&'a C
     })
 }
 
-/// Search for chains matching a query string
+/// Search for chains matching a query string.
+///
+/// Names and IDs match partially; the native currency symbol must match exactly
+/// (case-insensitively), since short symbols would otherwise match almost everything.
 pub fn search_chains<'a>(chains: &'a [ChainInfo], query: &str) -> Vec<&'a ChainInfo> {
     let query_lower = query.to_lowercase();
     chains
@@ -179,6 +266,8 @@ pub fn search_chains<'a>(chains: &'a [ChainInfo], query: &str) -> Vec<&'a ChainI
                 || c.short_name.to_lowercase().contains(&query_lower)
                 || c.chain.to_lowercase().contains(&query_lower)
                 || c.chain_id.to_string().contains(&query_lower)
+                || c.native_currency_symbol()
+                    .is_some_and(|symbol| symbol.eq_ignore_ascii_case(query))
         })
         .take(50) // Limit results
         .collect()
@@ -188,6 +277,7 @@ pub fn search_chains<'a>(chains: &'a [ChainInfo], query: &str) -> Vec<&'a ChainI
 pub fn clear_cache() {
     let mut cache = CHAINLIST_CACHE.lock().unwrap();
     *cache = None;
+    RESOLVED_RPCS.lock().unwrap().clear();
 }
 
 /// RPC filter options
@@ -240,6 +330,27 @@ pub fn filter_and_sort_rpcs(rpcs: &[RpcEntry], filter: &RpcFilter) -> Vec<RpcEnt
     filtered
 }
 
+/// Explain why no RPC endpoints are shown and what to try instead
+fn no_rpcs_hint(chain: &ChainInfo) -> String {
+    let mut hint = if chain.rpc.is_empty() {
+        String::from("  chainlist.org lists no RPC endpoints for this chain.\n")
+    } else {
+        format!(
+            "  None of the {} RPC endpoint(s) for this chain match the filters.\n  \
+             Try again without no_tracking, websocket_only, or http_only.\n",
+            chain.rpc.len()
+        )
+    };
+
+    if let Some(info_url) = &chain.info_url {
+        hint.push_str(&format!(
+            "  The project site may list official endpoints: {}\n",
+            info_url
+        ));
+    }
+    hint
+}
+
 /// Format chain information as a string
 pub fn format_chain_info(chain: &ChainInfo, rpcs: &[RpcEntry], limit: Option<usize>) -> String {
     let mut response = format!(
@@ -259,7 +370,7 @@ pub fn format_chain_info(chain: &ChainInfo, rpcs: &[RpcEntry], limit: Option<usi
     response.push_str("RPC Endpoints:\n");
 
     if rpcs.is_empty() {
-        response.push_str("  No RPC endpoints found matching the criteria.\n");
+        response.push_str(&no_rpcs_hint(chain));
     } else {
         let display_rpcs = if let Some(lim) = limit {
             &rpcs[..rpcs.len().min(lim)]
@@ -328,7 +439,7 @@ pub fn get_chainlist_tools() -> Vec<Tool> {
                 let mut props = serde_json::Map::new();
                 props.insert("chain".to_string(), serde_json::json!({
                     "type": "string",
-                    "description": "Chain ID or name (e.g., '1', 'ethereum', 'polygon')"
+                    "description": "Chain ID or name (e.g., '1', 'ethereum', 'polygon'). Defaults to the server's default_chain, if configured"
                 }));
                 props.insert("prefer_open_source".to_string(), serde_json::json!({
                     "type": "boolean",
@@ -354,7 +465,6 @@ pub fn get_chainlist_tools() -> Vec<Tool> {
                 let mut schema = serde_json::Map::new();
                 schema.insert("type".to_string(), Value::String("object".to_string()));
                 schema.insert("properties".to_string(), Value::Object(props));
-                schema.insert("required".to_string(), Value::Array(vec![Value::String("chain".to_string())]));
                 schema
             }),
         ),
@@ -376,6 +486,11 @@ pub fn get_chainlist_tools() -> Vec<Tool> {
                     "type": "boolean",
                     "description": "Only return mainnets (default: false)"
                 }));
+                props.insert("infer_testnet".to_string(), serde_json::json!({
+                    "type": "boolean",
+                    "description": "Classify chains chainlist.org doesn't label as testnet or mainnet by their name, e.g. 'Sepolia' or 'Testnet' (default: false)"
+                }));
+                props.insert("format".to_string(), format::format_property());
 
                 let mut schema = serde_json::Map::new();
                 schema.insert("type".to_string(), Value::String("object".to_string()));
@@ -395,6 +510,42 @@ pub fn get_chainlist_tools() -> Vec<Tool> {
                 schema
             }),
         ),
+        // chain_details tool
+        Tool::new(
+            "chain_details".to_string(),
+            "Get the complete chainlist.org record for a network as JSON, including fields the other tools omit (features, icon, slip44, ENS registry, parent chain, ...).".to_string(),
+            Arc::new({
+                let mut props = serde_json::Map::new();
+                props.insert("chain".to_string(), serde_json::json!({
+                    "type": "string",
+                    "description": "Chain ID or name (e.g., '1', 'ethereum', 'polygon')"
+                }));
+
+                let mut schema = serde_json::Map::new();
+                schema.insert("type".to_string(), Value::String("object".to_string()));
+                schema.insert("properties".to_string(), Value::Object(props));
+                schema.insert("required".to_string(), Value::Array(vec![Value::String("chain".to_string())]));
+                schema
+            }),
+        ),
+        // chain_gas_price tool
+        Tool::new(
+            "chain_gas_price".to_string(),
+            "Get the current gas price of a network. Looks up its public RPC endpoints on chainlist.org and queries eth_gasPrice, falling back to the next endpoint if one fails. Returns the price in gwei and wei.".to_string(),
+            Arc::new({
+                let mut props = serde_json::Map::new();
+                props.insert("chain".to_string(), serde_json::json!({
+                    "type": "string",
+                    "description": "Chain ID or name (e.g., '1', 'ethereum', 'polygon')"
+                }));
+
+                let mut schema = serde_json::Map::new();
+                schema.insert("type".to_string(), Value::String("object".to_string()));
+                schema.insert("properties".to_string(), Value::Object(props));
+                schema.insert("required".to_string(), Value::Array(vec![Value::String("chain".to_string())]));
+                schema
+            }),
+        ),
     ]
 }
 
@@ -432,7 +583,10 @@ pub async fn handle_search_rpc_url(
 
     // Fetch chain data
     let chains = fetch_chainlist().await.map_err(|e| {
-        rmcp::ErrorData::internal_error(format!("Failed to fetch chainlist data: {}", e), None)
+        rmcp::ErrorData::from(ToolError::RpcUnavailable(format!(
+            "Failed to fetch chainlist data: {}",
+            e
+        )))
     })?;
 
     // Find the requested chain
@@ -449,8 +603,17 @@ pub async fn handle_search_rpc_url(
     // Filter and sort RPCs
     let rpcs = filter_and_sort_rpcs(&chain_info.rpc, &filter);
 
-    // Format response
-    let response = format_chain_info(chain_info, &rpcs, limit);
+    // Format response, with the configured endpoint first. Its path is hidden like in
+    // `get_config`, since providers put API keys there.
+    let mut response = String::new();
+    if let Some(url) = rpc_override(&chains, chain_info) {
+        response.push_str(&format!(
+            "Configured RPC (rpc_overrides, used when '{}' is resolved as an rpc-url): {}\n\n",
+            chain,
+            config::redact_url_path(&url)
+        ));
+    }
+    response.push_str(&format_chain_info(chain_info, &rpcs, limit));
 
     Ok(CallToolResult::success(vec![Content::text(response)]))
 }
@@ -471,10 +634,18 @@ pub async fn handle_search_chains(
         .get("mainnet_only")
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
+    let infer_testnet = args
+        .get("infer_testnet")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let list_format = format::list_format(args)?;
 
     // Fetch chain data
     let chains = fetch_chainlist().await.map_err(|e| {
-        rmcp::ErrorData::internal_error(format!("Failed to fetch chainlist data: {}", e), None)
+        rmcp::ErrorData::from(ToolError::RpcUnavailable(format!(
+            "Failed to fetch chainlist data: {}",
+            e
+        )))
     })?;
 
     // Search chains
@@ -482,22 +653,50 @@ pub async fn handle_search_chains(
 
     // Apply filters
     if testnet_only {
-        results.retain(|c| c.testnet == Some(true));
+        results.retain(|c| c.is_testnet(infer_testnet) == Some(true));
     }
     if mainnet_only {
-        results.retain(|c| c.testnet != Some(true));
+        results.retain(|c| c.is_testnet(infer_testnet) != Some(true));
     }
 
     // Build response
     let mut response = format!("Found {} chains matching '{}'\n\n", results.len(), query);
 
+    if list_format == ListFormat::Table {
+        let rows: Vec<Vec<String>> = results
+            .iter()
+            .map(|chain| {
+                let kind = match (chain.is_testnet(infer_testnet), chain.testnet) {
+                    (Some(true), None) => "testnet (inferred)",
+                    (Some(true), _) => "testnet",
+                    _ => "",
+                };
+                vec![
+                    chain.name.clone(),
+                    chain.chain.clone(),
+                    chain.chain_id.to_string(),
+                    chain.short_name.clone(),
+                    kind.to_string(),
+                    chain.rpc.len().to_string(),
+                ]
+            })
+            .collect();
+        response.push_str(&format::render_table(
+            &["Name", "Chain", "Chain ID", "Short Name", "Type", "RPCs"],
+            &rows,
+        ));
+        return Ok(CallToolResult::success(vec![Content::text(response)]));
+    }
+
     for chain in results {
         response.push_str(&format!(
             "• {} ({})\n  Chain ID: {}\n  Short Name: {}\n",
             chain.name, chain.chain, chain.chain_id, chain.short_name
         ));
-        if let Some(testnet) = chain.testnet {
-            if testnet {
+        if chain.is_testnet(infer_testnet) == Some(true) {
+            if chain.testnet.is_none() {
+                response.push_str("  Type: Testnet (inferred from name)\n");
+            } else {
                 response.push_str("  Type: Testnet\n");
             }
         }
@@ -508,35 +707,795 @@ pub async fn handle_search_chains(
 }
 
 /// Handle list_popular_chains tool call
+///
+/// Lists `popular_ids` in the given order, or [`DEFAULT_POPULAR_CHAIN_IDS`] when empty.
 pub async fn handle_list_popular_chains(
     _args: &serde_json::Map<String, Value>,
+    popular_ids: &[u64],
 ) -> Result<CallToolResult, rmcp::ErrorData> {
     // Fetch chain data
     let chains = fetch_chainlist().await.map_err(|e| {
-        rmcp::ErrorData::internal_error(format!("Failed to fetch chainlist data: {}", e), None)
+        rmcp::ErrorData::from(ToolError::RpcUnavailable(format!(
+            "Failed to fetch chainlist data: {}",
+            e
+        )))
     })?;
 
-    // Popular chain IDs
-    let popular_ids = vec![
-        1, 10, 137, 42161, 8453, 43114, 56, 250, 100, 324, 1101, 59144, 534352,
-    ];
+    let popular_ids = if popular_ids.is_empty() {
+        DEFAULT_POPULAR_CHAIN_IDS
+    } else {
+        popular_ids
+    };
+
+    let (found, missing): (Vec<u64>, Vec<u64>) = popular_ids
+        .iter()
+        .partition(|&&id| chains.iter().any(|c| c.chain_id == id));
 
     let mut response = String::from("Popular Blockchain Networks:\n\n");
+    let mut listed = Vec::new();
+    for &id in &found {
+        let Some(chain) = chains.iter().find(|c| c.chain_id == id) else {
+            continue;
+        };
+        response.push_str(&format!(
+            "• {} ({})\n  Chain ID: {}\n  Short Name: {}\n  RPCs: {}\n\n",
+            chain.name,
+            chain.chain,
+            chain.chain_id,
+            chain.short_name,
+            chain.rpc.len()
+        ));
+        listed.push(serde_json::json!({
+            "name": chain.name,
+            "chain_id": chain.chain_id,
+            "short_name": chain.short_name,
+            "rpcs": chain.rpc.len(),
+        }));
+    }
 
-    for id in popular_ids {
-        if let Some(chain) = chains.iter().find(|c| c.chain_id == id) {
-            response.push_str(&format!(
-                "• {} ({})\n  Chain ID: {}\n  Short Name: {}\n  RPCs: {}\n\n",
-                chain.name,
-                chain.chain,
-                chain.chain_id,
-                chain.short_name,
-                chain.rpc.len()
-            ));
-        }
+    // A short list usually means chainlist.org served incomplete data, so say so
+    if !missing.is_empty() {
+        let ids: Vec<String> = missing.iter().map(u64::to_string).collect();
+        response.push_str(&format!(
+            "⚠ Found {} of {} popular chains; not listed on chainlist.org: {}. The chain data may be incomplete.\n\n",
+            found.len(),
+            popular_ids.len(),
+            ids.join(", ")
+        ));
     }
 
+    let cache_age = cache_age();
+    response.push_str(&match cache_age {
+        Some(age) => format!(
+            "Chain data downloaded from chainlist.org {} ago.\n",
+            format_age(age)
+        ),
+        None => {
+            "Chain data from the embedded snapshot; chainlist.org was unreachable.\n".to_string()
+        }
+    });
     response.push_str("Use 'search_chains' to find more networks or 'search_rpc_url' to get RPC endpoints for a specific chain.\n");
 
-    Ok(CallToolResult::success(vec![Content::text(response)]))
+    let mut result = CallToolResult::success(vec![Content::text(response)]);
+    result.structured_content = Some(serde_json::json!({
+        "chains": listed,
+        "found": found,
+        "missing": missing,
+        "complete": missing.is_empty(),
+        "cache_age_secs": cache_age.map(|age| age.as_secs()),
+    }));
+    Ok(result)
+}
+
+/// Render an age coarsely, e.g. "45s", "12m", "3h 5m"
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// Handle chain_details tool call
+pub async fn handle_chain_details(
+    args: &serde_json::Map<String, Value>,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let chain = args.get("chain").and_then(|v| v.as_str()).ok_or_else(|| {
+        rmcp::ErrorData::invalid_params("Missing or invalid 'chain' parameter", None)
+    })?;
+
+    let chains = fetch_chainlist().await.map_err(|e| {
+        rmcp::ErrorData::from(ToolError::RpcUnavailable(format!(
+            "Failed to fetch chainlist data: {}",
+            e
+        )))
+    })?;
+
+    let chain_info = find_chain_rpcs(&chains, chain).ok_or_else(|| {
+        rmcp::ErrorData::invalid_params(
+            format!(
+                "Chain '{}' not found. Try using chain ID (e.g., '1' for Ethereum) or common names like 'ethereum', 'polygon', 'arbitrum'",
+                chain
+            ),
+            None,
+        )
+    })?;
+
+    let details = serde_json::to_value(chain_info).map_err(|e| {
+        rmcp::ErrorData::internal_error(format!("Failed to serialize chain: {}", e), None)
+    })?;
+    let text = serde_json::to_string_pretty(&details).unwrap_or_default();
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(details);
+    Ok(result)
+}
+
+/// Most endpoints to try before giving up on a live query
+const MAX_RPC_ATTEMPTS: usize = 5;
+
+/// Per-endpoint timeout for live queries, so dead endpoints are skipped quickly
+const RPC_QUERY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// HTTP endpoints worth querying for a chain, best first.
+///
+/// Skips templated URLs that need an API key (e.g. `.../${INFURA_API_KEY}`).
+fn candidate_rpcs(chain: &ChainInfo) -> Vec<String> {
+    let filter = RpcFilter {
+        prefer_open_source: true,
+        http_only: true,
+        ..Default::default()
+    };
+    filter_and_sort_rpcs(&chain.rpc, &filter)
+        .iter()
+        .map(|rpc| rpc.url().to_string())
+        .filter(|url| !url.contains("${"))
+        .take(MAX_RPC_ATTEMPTS)
+        .collect()
+}
+
+/// Parse a JSON-RPC hex quantity such as "0x3b9aca00"
+fn parse_quantity(value: &str) -> Option<u128> {
+    let digits = value.strip_prefix("0x")?;
+    u128::from_str_radix(digits, 16).ok()
+}
+
+/// Query a parameterless method returning a quantity from a single JSON-RPC endpoint
+async fn query_quantity(client: &reqwest::Client, url: &str, method: &str) -> Result<u128> {
+    let response: Value = client
+        .post(url)
+        .timeout(RPC_QUERY_TIMEOUT)
+        .json(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": [],
+        }))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    if let Some(error) = response.get("error") {
+        anyhow::bail!("RPC error: {}", error);
+    }
+    response
+        .get("result")
+        .and_then(|v| v.as_str())
+        .and_then(parse_quantity)
+        .with_context(|| format!("Missing or malformed {} result", method))
+}
+
+/// Query `eth_gasPrice` from a single JSON-RPC endpoint
+async fn query_gas_price(client: &reqwest::Client, url: &str) -> Result<u128> {
+    query_quantity(client, url, "eth_gasPrice").await
+}
+
+/// RPC URLs already resolved from chain names, by the name as given
+static RESOLVED_RPCS: Lazy<Mutex<HashMap<String, String>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// `Config.rpc_overrides` sorted by key, so a chain matched by several keys picks the same
+/// URL every time. Held here like the HTTP settings, since resolution runs outside the
+/// executor.
+static RPC_OVERRIDES: Lazy<RwLock<Vec<(String, String)>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Use the given configuration's RPC overrides for subsequent resolution
+pub fn configure(config: &Config) {
+    let mut overrides: Vec<(String, String)> = config
+        .rpc_overrides
+        .iter()
+        .map(|(chain, url)| (chain.trim().to_string(), url.clone()))
+        .collect();
+    overrides.sort();
+    *RPC_OVERRIDES.write().unwrap() = overrides;
+}
+
+/// The override configured under `query` itself (e.g. "base" or "8453"), found without
+/// the chain data
+fn direct_rpc_override(query: &str) -> Option<String> {
+    RPC_OVERRIDES
+        .read()
+        .unwrap()
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(query.trim()))
+        .map(|(_, url)| url.clone())
+}
+
+/// The override configured for `chain`, keyed by its ID or any name it is found by
+pub fn rpc_override(chains: &[ChainInfo], chain: &ChainInfo) -> Option<String> {
+    RPC_OVERRIDES
+        .read()
+        .unwrap()
+        .iter()
+        .find(|(key, _)| {
+            find_chain_rpcs(chains, key).is_some_and(|found| found.chain_id == chain.chain_id)
+        })
+        .map(|(_, url)| url.clone())
+}
+
+/// Whether an `rpc-url` value is a chain name or ID rather than a URL or IPC path
+pub fn looks_like_chain_name(value: &str) -> bool {
+    let value = value.trim();
+    !value.is_empty()
+        && !value.contains("://")
+        && !value.ends_with(".ipc")
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+}
+
+/// HTTP endpoints to try when resolving a chain name, no-tracking ones first
+fn resolution_rpcs(chain: &ChainInfo) -> Vec<String> {
+    let mut rpcs = filter_and_sort_rpcs(
+        &chain.rpc,
+        &RpcFilter {
+            http_only: true,
+            ..Default::default()
+        },
+    );
+    rpcs.sort_by_key(|rpc| {
+        (
+            rpc.tracking().map_or(2, |t| match t.as_str() {
+                "none" => 0,
+                "limited" => 1,
+                _ => 2,
+            }),
+            !rpc.is_open_source().unwrap_or(false),
+        )
+    });
+    rpcs.iter()
+        .map(|rpc| rpc.url().to_string())
+        .filter(|url| !url.contains("${"))
+        .take(MAX_RPC_ATTEMPTS)
+        .collect()
+}
+
+/// Resolve a chain name or ID to a live RPC URL.
+///
+/// A URL configured in `rpc_overrides` for the chain is used as is. Otherwise candidates
+/// are tried no-tracking first, and the first one whose `eth_chainId` matches is used and
+/// remembered for later calls.
+pub async fn resolve_chain_rpc_url(query: &str) -> Result<String, ToolError> {
+    let query = query.trim();
+    if let Some(url) = direct_rpc_override(query) {
+        return Ok(url);
+    }
+    if let Some(url) = RESOLVED_RPCS.lock().unwrap().get(query) {
+        return Ok(url.clone());
+    }
+    if !crate::http::network_enabled() {
+        return Err(ToolError::ForbiddenCommand(format!(
+            "rpc-url '{}' names a chain, but looking it up needs network access, which is disabled (enable_network_tools); pass a URL or set it in rpc_overrides",
+            query
+        )));
+    }
+
+    let chains = fetch_chainlist().await.map_err(|e| {
+        ToolError::RpcUnavailable(format!("Failed to fetch chainlist data: {:#}", e))
+    })?;
+    let chain = find_chain_rpcs(&chains, query).ok_or_else(|| {
+        ToolError::InvalidArguments(format!(
+            "rpc-url '{}' is neither a URL nor a known chain name or ID",
+            query
+        ))
+    })?;
+    if let Some(url) = rpc_override(&chains, chain) {
+        return Ok(url);
+    }
+    let client = crate::http::client()
+        .map_err(|e| ToolError::RpcUnavailable(format!("Failed to create HTTP client: {:#}", e)))?;
+
+    let mut failures = Vec::new();
+    for url in resolution_rpcs(chain) {
+        match query_quantity(&client, &url, "eth_chainId").await {
+            Ok(id) if id == u128::from(chain.chain_id) => {
+                eprintln!("Resolved rpc-url '{}' to {} ({})", query, url, chain.name);
+                RESOLVED_RPCS
+                    .lock()
+                    .unwrap()
+                    .insert(query.to_string(), url.clone());
+                return Ok(url);
+            }
+            Ok(id) => failures.push(format!("{}: reports chain ID {}", url, id)),
+            Err(e) => failures.push(format!("{}: {:#}", url, e)),
+        }
+    }
+
+    Err(ToolError::RpcUnavailable(if failures.is_empty() {
+        format!(
+            "chainlist.org lists no public HTTP RPC endpoints for {}",
+            chain.name
+        )
+    } else {
+        format!(
+            "No RPC endpoint for {} answered eth_chainId:\n- {}",
+            chain.name,
+            failures.join("\n- ")
+        )
+    }))
+}
+
+/// Replace an `rpc-url` argument that names a chain with a live RPC URL for it
+pub async fn resolve_rpc_url_argument(
+    arguments: &mut Option<serde_json::Map<String, Value>>,
+) -> Result<(), ToolError> {
+    let Some(rpc_url) = arguments.as_mut().and_then(|args| args.get_mut("rpc-url")) else {
+        return Ok(());
+    };
+    let Some(query) = rpc_url.as_str().filter(|v| looks_like_chain_name(v)) else {
+        return Ok(());
+    };
+
+    *rpc_url = Value::String(resolve_chain_rpc_url(query).await?);
+    Ok(())
+}
+
+/// Fill in `default_chain` as the `key` argument when the call doesn't give one. Returns
+/// the chain applied, for [`default_chain_note`].
+pub fn apply_default_chain(
+    arguments: &mut Option<serde_json::Map<String, Value>>,
+    key: &str,
+    default_chain: Option<&str>,
+) -> Option<String> {
+    let default_chain = default_chain?;
+    let args = arguments.get_or_insert_with(serde_json::Map::new);
+    if args.contains_key(key) {
+        return None;
+    }
+    args.insert(key.to_string(), Value::String(default_chain.to_string()));
+    Some(default_chain.to_string())
+}
+
+/// Put a note first in a tool's result saying the configured default chain was used
+pub fn default_chain_note(
+    result: Result<CallToolResult, rmcp::ErrorData>,
+    chain: Option<String>,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    match (result, chain) {
+        (Ok(mut result), Some(chain)) => {
+            result.content.insert(
+                0,
+                Content::text(format!(
+                    "Note: no chain given, so the configured default_chain '{}' was used",
+                    chain
+                )),
+            );
+            Ok(result)
+        }
+        (result, _) => result,
+    }
+}
+
+/// Handle chain_gas_price tool call
+pub async fn handle_chain_gas_price(
+    args: &serde_json::Map<String, Value>,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let chain = args.get("chain").and_then(|v| v.as_str()).ok_or_else(|| {
+        rmcp::ErrorData::invalid_params("Missing or invalid 'chain' parameter", None)
+    })?;
+
+    let chains = fetch_chainlist().await.map_err(|e| {
+        rmcp::ErrorData::from(ToolError::RpcUnavailable(format!(
+            "Failed to fetch chainlist data: {}",
+            e
+        )))
+    })?;
+
+    let chain_info = find_chain_rpcs(&chains, chain).ok_or_else(|| {
+        rmcp::ErrorData::invalid_params(
+            format!(
+                "Chain '{}' not found. Try using chain ID (e.g., '1' for Ethereum) or common names like 'ethereum', 'polygon', 'arbitrum'",
+                chain
+            ),
+            None,
+        )
+    })?;
+
+    let candidates = candidate_rpcs(chain_info);
+    if candidates.is_empty() {
+        return Err(ToolError::RpcUnavailable(format!(
+            "chainlist.org lists no public HTTP RPC endpoints for {}",
+            chain_info.name
+        ))
+        .into());
+    }
+
+    let client = crate::http::client().map_err(|e| {
+        rmcp::ErrorData::from(ToolError::RpcUnavailable(format!(
+            "Failed to create HTTP client: {:#}",
+            e
+        )))
+    })?;
+
+    let mut failures = Vec::new();
+    for url in &candidates {
+        match query_gas_price(&client, url).await {
+            Ok(price) => {
+                let gwei = crate::cast::format_units(price, 9);
+                let text = format!(
+                    "Gas price on {} (chain ID {}): {} gwei ({} wei)\nRPC: {}",
+                    chain_info.name, chain_info.chain_id, gwei, price, url
+                );
+                let mut result = CallToolResult::success(vec![Content::text(text)]);
+                result.structured_content = Some(serde_json::json!({
+                    "chain_id": chain_info.chain_id,
+                    "gas_price_wei": price.to_string(),
+                    "gas_price_gwei": gwei,
+                    "rpc_url": url,
+                }));
+                return Ok(result);
+            }
+            Err(e) => failures.push(format!("{}: {:#}", url, e)),
+        }
+    }
+
+    Err(ToolError::RpcUnavailable(format!(
+        "No RPC endpoint for {} answered eth_gasPrice:\n- {}",
+        chain_info.name,
+        failures.join("\n- ")
+    ))
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(name: &str, chain_id: u64, symbol: &str) -> ChainInfo {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "chain": name,
+            "chainId": chain_id,
+            "shortName": name.to_lowercase(),
+            "nativeCurrency": {"name": symbol, "symbol": symbol, "decimals": 18},
+        }))
+        .unwrap()
+    }
+
+    /// Test that chains can be found by their native currency symbol
+    #[test]
+    fn test_search_chains_by_currency_symbol() {
+        let chains = vec![
+            chain("Ethereum Mainnet", 1, "ETH"),
+            chain("Polygon Mainnet", 137, "MATIC"),
+        ];
+
+        let results = search_chains(&chains, "matic");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chain_id, 137);
+        assert_eq!(results[0].native_currency_symbol(), Some("MATIC"));
+
+        // Symbols match exactly, not as substrings
+        assert!(search_chains(&chains, "MAT").is_empty());
+    }
+
+    /// Test that the embedded snapshot parses and covers the popular chains
+    #[cfg(feature = "embedded-chainlist")]
+    #[test]
+    fn test_embedded_chainlist() {
+        let chains = embedded_chainlist().unwrap();
+        for id in DEFAULT_POPULAR_CHAIN_IDS {
+            let chain = chains.iter().find(|c| c.chain_id == *id);
+            assert!(
+                chain.is_some_and(|c| !candidate_rpcs(c).is_empty()),
+                "chain {} missing from the snapshot or has no HTTP RPCs",
+                id
+            );
+        }
+    }
+
+    /// Test that a chain without any RPC endpoints says chainlist.org lists none
+    #[test]
+    fn test_no_rpcs_hint_none_listed() {
+        let hint = no_rpcs_hint(&chain("Quiet Chain", 424242, "QC"));
+        assert!(hint.contains("chainlist.org lists no RPC endpoints"));
+        assert!(!hint.contains("match the filters"));
+        assert!(!hint.contains("project site"));
+    }
+
+    /// Test that endpoints hidden by the filters are counted and the filters named
+    #[test]
+    fn test_no_rpcs_hint_all_filtered() {
+        let mut filtered = chain("Tracked Chain", 424242, "TC");
+        filtered.rpc = vec![
+            RpcEntry::String("wss://ws.example".to_string()),
+            RpcEntry::Object {
+                url: "https://rpc.example".to_string(),
+                tracking: Some("yes".to_string()),
+                is_open_source: None,
+            },
+        ];
+        let hint = no_rpcs_hint(&filtered);
+        assert!(hint.contains("None of the 2 RPC endpoint(s) for this chain match the filters"));
+        assert!(hint.contains("without no_tracking, websocket_only, or http_only"));
+        assert!(!hint.contains("lists no RPC endpoints"));
+    }
+
+    /// Test that the chain's info URL is suggested when it has one
+    #[test]
+    fn test_no_rpcs_hint_suggests_info_url() {
+        let mut with_site = chain("Quiet Chain", 424242, "QC");
+        with_site.info_url = Some("https://quiet.example".to_string());
+        let hint = no_rpcs_hint(&with_site);
+        assert!(hint.contains("lists no RPC endpoints"));
+        assert!(
+            hint.contains("The project site may list official endpoints: https://quiet.example")
+        );
+    }
+
+    /// Test that fields ChainInfo doesn't model survive a round trip through it
+    #[test]
+    fn test_chain_info_keeps_extra_fields() {
+        let chain: ChainInfo = serde_json::from_value(serde_json::json!({
+            "name": "Ethereum Mainnet",
+            "chain": "ETH",
+            "chainId": 1,
+            "shortName": "eth",
+            "slip44": 60,
+            "ens": {"registry": "0x00000000000C2E074eC69A0dFb2997BA6C7d2e1e"},
+            "features": [{"name": "EIP1559"}],
+        }))
+        .unwrap();
+
+        let details = serde_json::to_value(&chain).unwrap();
+        assert_eq!(details["chainId"], 1);
+        assert_eq!(details["slip44"], 60);
+        assert_eq!(
+            details["ens"]["registry"],
+            "0x00000000000C2E074eC69A0dFb2997BA6C7d2e1e"
+        );
+        assert_eq!(details["features"][0]["name"], "EIP1559");
+    }
+
+    /// Test that live queries only use plain HTTP endpoints
+    #[test]
+    fn test_candidate_rpcs() {
+        let mut ethereum = chain("Ethereum Mainnet", 1, "ETH");
+        ethereum.rpc = serde_json::from_value(serde_json::json!([
+            "https://mainnet.infura.io/v3/${INFURA_API_KEY}",
+            "wss://ethereum-rpc.publicnode.com",
+            {"url": "https://eth.llamarpc.com", "tracking": "yes"},
+            {"url": "https://ethereum-rpc.publicnode.com", "tracking": "none", "isOpenSource": true},
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            candidate_rpcs(&ethereum),
+            vec![
+                "https://ethereum-rpc.publicnode.com",
+                "https://eth.llamarpc.com"
+            ]
+        );
+    }
+
+    /// Test that chain names and IDs are told apart from URLs and IPC paths
+    #[test]
+    fn test_looks_like_chain_name() {
+        for value in ["1", "mainnet", "arbitrum one", "base-sepolia", "op_mainnet"] {
+            assert!(looks_like_chain_name(value), "{}", value);
+        }
+        for value in [
+            "",
+            "http://localhost:8545",
+            "wss://eth.example.com",
+            "/tmp/anvil.ipc",
+            "geth.ipc",
+            "localhost:8545",
+        ] {
+            assert!(!looks_like_chain_name(value), "{}", value);
+        }
+    }
+
+    /// Test that name resolution tries no-tracking endpoints first
+    #[test]
+    fn test_resolution_rpcs() {
+        let mut ethereum = chain("Ethereum Mainnet", 1, "ETH");
+        ethereum.rpc = serde_json::from_value(serde_json::json!([
+            {"url": "https://eth.llamarpc.com", "tracking": "yes", "isOpenSource": true},
+            "https://plain.example.com",
+            "https://mainnet.infura.io/v3/${INFURA_API_KEY}",
+            {"url": "https://limited.example.com", "tracking": "limited"},
+            {"url": "https://ethereum-rpc.publicnode.com", "tracking": "none"},
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            resolution_rpcs(&ethereum),
+            vec![
+                "https://ethereum-rpc.publicnode.com",
+                "https://limited.example.com",
+                "https://eth.llamarpc.com",
+                "https://plain.example.com",
+            ]
+        );
+    }
+
+    /// Test that URLs pass through untouched and no lookup happens without an rpc-url
+    #[tokio::test]
+    async fn test_resolve_rpc_url_argument_leaves_urls() {
+        let mut arguments = Some(
+            serde_json::json!({"rpc-url": "http://localhost:8545"})
+                .as_object()
+                .unwrap()
+                .clone(),
+        );
+        resolve_rpc_url_argument(&mut arguments).await.unwrap();
+        assert_eq!(
+            arguments.unwrap()["rpc-url"],
+            serde_json::json!("http://localhost:8545")
+        );
+
+        let mut arguments = None;
+        resolve_rpc_url_argument(&mut arguments).await.unwrap();
+        assert!(arguments.is_none());
+    }
+
+    /// Test that configured overrides win, matched by the name given or the chain's ID
+    #[tokio::test]
+    async fn test_rpc_overrides() {
+        configure(&Config {
+            rpc_overrides: HashMap::from([
+                (
+                    "Base".to_string(),
+                    "https://base.example/v2/KEY".to_string(),
+                ),
+                ("10".to_string(), "https://op.example".to_string()),
+            ]),
+            ..Config::default()
+        });
+
+        // Matched by name without fetching the chain data
+        let mut arguments = serde_json::json!({"rpc-url": "base"}).as_object().cloned();
+        resolve_rpc_url_argument(&mut arguments).await.unwrap();
+        assert_eq!(arguments.unwrap()["rpc-url"], "https://base.example/v2/KEY");
+
+        // Matched through the chain data, whatever the chain was called
+        let chains = vec![chain("OP Mainnet", 10, "ETH"), chain("Base", 8453, "ETH")];
+        assert_eq!(
+            rpc_override(&chains, &chains[0]).as_deref(),
+            Some("https://op.example")
+        );
+        assert_eq!(
+            rpc_override(&chains, &chains[1]).as_deref(),
+            Some("https://base.example/v2/KEY")
+        );
+        assert_eq!(rpc_override(&chains, &chain("Gnosis", 100, "xDAI")), None);
+
+        configure(&Config::default());
+    }
+
+    /// Test that JSON-RPC hex quantities are parsed
+    #[test]
+    fn test_parse_quantity() {
+        assert_eq!(parse_quantity("0x3b9aca00"), Some(1_000_000_000));
+        assert_eq!(parse_quantity("0x0"), Some(0));
+        assert_eq!(parse_quantity("1000"), None);
+        assert_eq!(parse_quantity("0xzz"), None);
+    }
+
+    /// Test that common testnet names are recognized
+    #[test]
+    fn test_looks_like_testnet() {
+        for name in [
+            "Sepolia",
+            "Base Sepolia Testnet",
+            "Goerli",
+            "Holesky",
+            "Polygon Amoy",
+            "Avalanche Fuji Testnet",
+            "Gnosis Chiado Testnet",
+        ] {
+            assert!(looks_like_testnet(name), "{} should be a testnet", name);
+        }
+        for name in ["Ethereum Mainnet", "OP Mainnet", "Polygon Mainnet", "Base"] {
+            assert!(
+                !looks_like_testnet(name),
+                "{} should not be a testnet",
+                name
+            );
+        }
+    }
+
+    /// Test that the name heuristic only applies when opted in and the chain is unlabeled
+    #[test]
+    fn test_is_testnet_inference() {
+        let mut sepolia = chain("Sepolia", 11155111, "ETH");
+        assert_eq!(sepolia.is_testnet(false), None);
+        assert_eq!(sepolia.is_testnet(true), Some(true));
+        assert_eq!(
+            chain("Ethereum Mainnet", 1, "ETH").is_testnet(true),
+            Some(false)
+        );
+
+        // An explicit label always wins
+        sepolia.testnet = Some(false);
+        assert_eq!(sepolia.is_testnet(true), Some(false));
+    }
+
+    /// Test that the default chain fills in only a missing chain, and is noted first
+    #[test]
+    fn test_apply_default_chain() {
+        let mut arguments = None;
+        let applied = apply_default_chain(&mut arguments, "chain", Some("base"));
+        assert_eq!(applied.as_deref(), Some("base"));
+        assert_eq!(arguments.as_ref().unwrap()["chain"], "base");
+
+        let result = default_chain_note(
+            Ok(CallToolResult::success(vec![Content::text("rpcs")])),
+            applied,
+        )
+        .unwrap();
+        assert_eq!(result.content.len(), 2);
+        assert!(result.content[0]
+            .as_text()
+            .unwrap()
+            .text
+            .contains("default_chain 'base'"));
+
+        let mut arguments = serde_json::json!({"chain": "optimism"})
+            .as_object()
+            .cloned();
+        assert_eq!(
+            apply_default_chain(&mut arguments, "chain", Some("base")),
+            None
+        );
+        assert_eq!(arguments.unwrap()["chain"], "optimism");
+
+        let mut arguments = None;
+        assert_eq!(apply_default_chain(&mut arguments, "chain", None), None);
+        assert!(arguments.is_none());
+    }
+
+    /// Test that popular chains missing from the data are reported along with the cache age
+    #[tokio::test]
+    async fn test_list_popular_chains_reports_missing() {
+        *CHAINLIST_CACHE.lock().unwrap() = Some((
+            vec![chain("Ethereum Mainnet", 1, "ETH")],
+            Instant::now() - Duration::from_secs(90),
+        ));
+        let result = handle_list_popular_chains(&serde_json::Map::new(), &[1, 8453])
+            .await
+            .unwrap();
+        clear_cache();
+
+        let text = &result.content[0].as_text().unwrap().text;
+        assert!(text.contains("Found 1 of 2 popular chains"));
+        assert!(text.contains("downloaded from chainlist.org 1m ago"));
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["found"], serde_json::json!([1]));
+        assert_eq!(structured["missing"], serde_json::json!([8453]));
+        assert_eq!(structured["complete"], false);
+        assert_eq!(structured["cache_age_secs"], 90);
+    }
+
+    /// Test that ages are rendered coarsely
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::from_secs(45)), "45s");
+        assert_eq!(format_age(Duration::from_secs(750)), "12m");
+        assert_eq!(format_age(Duration::from_secs(3 * 3600 + 5 * 60)), "3h 5m");
+    }
 }
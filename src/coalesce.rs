@@ -0,0 +1,200 @@
+//! Coalescing of identical concurrent read calls
+//!
+//! When `coalesce_read_calls` is enabled, a read-only RPC tool called with the same
+//! arguments as a call still in progress waits for that call's result instead of running
+//! `cast` again. Only calls that overlap are shared; nothing is cached once a call ends.
+//! If the first call is cancelled, the ones waiting on it run on their own.
+
+use rmcp::model::CallToolResult;
+use rmcp::ErrorData as McpError;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+type Outcome = Result<CallToolResult, McpError>;
+
+/// Tools that only read chain state, so identical concurrent calls can share one result
+const READ_TOOLS: &[&str] = &[
+    "cast_access_list",
+    "cast_admin",
+    "cast_age",
+    "cast_balance",
+    "cast_base_fee",
+    "cast_block",
+    "cast_block_number",
+    "cast_call",
+    "cast_chain",
+    "cast_chain_id",
+    "cast_client",
+    "cast_code",
+    "cast_codehash",
+    "cast_codesize",
+    "cast_estimate",
+    "cast_find_block",
+    "cast_gas_price",
+    "cast_implementation",
+    "cast_logs",
+    "cast_nonce",
+    "cast_proof",
+    "cast_receipt",
+    "cast_storage",
+    "cast_storage_root",
+    "cast_tx",
+];
+
+/// Whether calls to a tool may be coalesced
+pub fn is_coalescable(tool: &str) -> bool {
+    READ_TOOLS.contains(&tool)
+}
+
+/// Key identifying identical calls: the tool name and its arguments
+pub fn call_key(tool: &str, arguments: &Option<JsonObject>) -> String {
+    // serde_json keeps object keys sorted, so equal arguments serialize identically
+    let arguments = arguments
+        .as_ref()
+        .map(|args| Value::Object(args.clone()).to_string())
+        .unwrap_or_default();
+    format!("{}\n{}", tool, arguments)
+}
+
+type InFlight = Arc<Mutex<HashMap<String, watch::Receiver<Option<Outcome>>>>>;
+
+/// Calls in progress, by [`call_key`]
+#[derive(Default)]
+pub struct Coalescer {
+    in_flight: InFlight,
+}
+
+/// Removes a finished (or cancelled) call from the in-flight map
+struct Leader {
+    in_flight: InFlight,
+    key: String,
+}
+
+impl Drop for Leader {
+    fn drop(&mut self) {
+        self.in_flight.lock().unwrap().remove(&self.key);
+    }
+}
+
+impl Coalescer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `work`, or wait for the identical call already running and share its outcome
+    pub async fn run(&self, key: String, work: impl Future<Output = Outcome>) -> Outcome {
+        let sender = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get(&key) {
+                Some(receiver) => Err(receiver.clone()),
+                None => {
+                    let (sender, receiver) = watch::channel(None);
+                    in_flight.insert(key.clone(), receiver);
+                    Ok(sender)
+                }
+            }
+        };
+
+        match sender {
+            Ok(sender) => {
+                let _leader = Leader {
+                    in_flight: self.in_flight.clone(),
+                    key,
+                };
+                let outcome = work.await;
+                let _ = sender.send(Some(outcome.clone()));
+                outcome
+            }
+            Err(mut receiver) => {
+                if let Ok(outcome) = receiver.wait_for(Option::is_some).await {
+                    if let Some(outcome) = outcome.clone() {
+                        return outcome;
+                    }
+                }
+                // The first call was cancelled before finishing
+                work.await
+            }
+        }
+    }
+
+    /// Number of distinct calls in progress
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::Content;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Test that equal arguments give equal keys regardless of order
+    #[test]
+    fn test_call_key() {
+        let a = json!({"to": "0x1", "sig": "name()"}).as_object().cloned();
+        let b = json!({"sig": "name()", "to": "0x1"}).as_object().cloned();
+        assert_eq!(call_key("cast_call", &a), call_key("cast_call", &b));
+        assert_ne!(call_key("cast_call", &a), call_key("cast_code", &a));
+        assert_ne!(call_key("cast_call", &a), call_key("cast_call", &None));
+        assert!(is_coalescable("cast_call"));
+        assert!(!is_coalescable("cast_send"));
+    }
+
+    /// Test that overlapping identical calls run once and share the result
+    #[tokio::test]
+    async fn test_run_coalesces_overlapping_calls() {
+        let coalescer = Coalescer::new();
+        let runs = AtomicUsize::new(0);
+        let work = || async {
+            runs.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok(CallToolResult::success(vec![Content::text("42")]))
+        };
+
+        let (first, second) = tokio::join!(
+            coalescer.run("key".to_string(), work()),
+            coalescer.run("key".to_string(), work()),
+        );
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(first.unwrap(), second.unwrap());
+        assert_eq!(coalescer.in_flight(), 0);
+
+        // Once finished, the next call runs again
+        coalescer.run("key".to_string(), work()).await.unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    /// Test that a waiting call runs itself when the first one is cancelled
+    #[tokio::test]
+    async fn test_run_after_cancelled_leader() {
+        let coalescer = Coalescer::new();
+        let leader = async {
+            let work = coalescer.run("key".to_string(), std::future::pending());
+            let _ = tokio::time::timeout(Duration::from_millis(30), work).await;
+        };
+        let follower = async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            assert_eq!(coalescer.in_flight(), 1);
+            coalescer
+                .run("key".to_string(), async {
+                    Ok(CallToolResult::success(vec![Content::text("own")]))
+                })
+                .await
+        };
+
+        let ((), result) = tokio::join!(leader, follower);
+        assert_eq!(
+            result.unwrap(),
+            CallToolResult::success(vec![Content::text("own")])
+        );
+        assert_eq!(coalescer.in_flight(), 0);
+    }
+}
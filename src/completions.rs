@@ -0,0 +1,135 @@
+//! Argument completion for the MCP `completion/complete` request
+//!
+//! Completes well-known tool arguments against live data: `chain` against chainlist.org
+//! network names, `conversion_type` against the cast conversion types, and `symbol`
+//! against the Optimism token list.
+
+use anyhow::Result;
+use rmcp::model::{ArgumentInfo, CompletionInfo};
+use std::collections::BTreeSet;
+
+use crate::chainlist;
+use crate::conversion::CONVERSION_TYPES;
+use crate::tokenlist;
+
+/// Complete the value of a tool argument.
+///
+/// Unknown argument names yield an empty completion rather than an error, so clients
+/// can request completions for any argument without special-casing. Without
+/// `network_enabled`, arguments completed from downloaded lists get no candidates.
+pub async fn complete_argument(
+    argument: &ArgumentInfo,
+    network_enabled: bool,
+) -> Result<CompletionInfo> {
+    let candidates: Vec<String> = match argument.name.as_str() {
+        "chain" | "symbol" if !network_enabled => Vec::new(),
+        "chain" => chainlist::fetch_chainlist()
+            .await?
+            .into_iter()
+            .map(|c| c.name)
+            .collect(),
+        "conversion_type" => CONVERSION_TYPES.iter().map(|s| s.to_string()).collect(),
+        "symbol" => tokenlist::fetch_tokenlist()
+            .await?
+            .tokens
+            .into_iter()
+            .map(|t| t.symbol)
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    Ok(rank_candidates(candidates, &argument.value))
+}
+
+/// Filter candidates by case-insensitive prefix, deduplicate, and sort them.
+///
+/// The result is capped at the MCP maximum of 100 values, with `total` and
+/// `has_more` describing the full match set.
+fn rank_candidates(candidates: Vec<String>, prefix: &str) -> CompletionInfo {
+    let prefix_lower = prefix.to_lowercase();
+    let matches: BTreeSet<String> = candidates
+        .into_iter()
+        .filter(|c| c.to_lowercase().starts_with(&prefix_lower))
+        .collect();
+
+    let total = matches.len();
+    let values: Vec<String> = matches
+        .into_iter()
+        .take(CompletionInfo::MAX_VALUES)
+        .collect();
+
+    CompletionInfo {
+        has_more: Some(total > values.len()),
+        total: Some(total as u32),
+        values,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argument(name: &str, value: &str) -> ArgumentInfo {
+        ArgumentInfo {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    /// Test that conversion_type completes against the conversion enum by prefix
+    #[tokio::test]
+    async fn test_complete_conversion_type() {
+        let completion = complete_argument(&argument("conversion_type", "to-h"), true)
+            .await
+            .unwrap();
+        assert_eq!(completion.values, vec!["to-hex", "to-hexdata"]);
+        assert_eq!(completion.has_more, Some(false));
+    }
+
+    /// Test that unknown arguments return an empty completion
+    #[tokio::test]
+    async fn test_complete_unknown_argument_is_empty() {
+        let completion = complete_argument(&argument("nonexistent", ""), true)
+            .await
+            .unwrap();
+        assert!(completion.values.is_empty());
+        assert_eq!(completion.total, Some(0));
+    }
+
+    /// Test that nothing is downloaded for completions when network access is disabled
+    #[tokio::test]
+    async fn test_complete_without_network() {
+        for name in ["chain", "symbol"] {
+            let completion = complete_argument(&argument(name, ""), false).await.unwrap();
+            assert!(completion.values.is_empty());
+        }
+        let completion = complete_argument(&argument("conversion_type", "to-h"), false)
+            .await
+            .unwrap();
+        assert_eq!(completion.values, vec!["to-hex", "to-hexdata"]);
+    }
+
+    /// Test that ranking is case-insensitive and deduplicates candidates
+    #[test]
+    fn test_rank_candidates_case_insensitive_dedup() {
+        let candidates = vec![
+            "USDC".to_string(),
+            "USDC".to_string(),
+            "USDT".to_string(),
+            "WETH".to_string(),
+        ];
+        let completion = rank_candidates(candidates, "us");
+        assert_eq!(completion.values, vec!["USDC", "USDT"]);
+        assert_eq!(completion.total, Some(2));
+    }
+
+    /// Test that results are capped at the MCP maximum
+    #[test]
+    fn test_rank_candidates_caps_results() {
+        let candidates: Vec<String> = (0..250).map(|i| format!("chain{:03}", i)).collect();
+        let completion = rank_candidates(candidates, "chain");
+        assert_eq!(completion.values.len(), CompletionInfo::MAX_VALUES);
+        assert_eq!(completion.total, Some(250));
+        assert_eq!(completion.has_more, Some(true));
+    }
+}
@@ -1,13 +1,23 @@
 //! Unified conversion tool for all cast conversion operations
 //!
-//! This module provides a single MCP tool that wraps all cast conversion CLI subcommands.
+//! This module provides a single MCP tool that wraps all cast conversion CLI subcommands,
+//! plus `addresses_checksum`, which checksums a batch of addresses.
+//!
+//! Checksumming (EIP-55, or EIP-1191 with a chain ID) is computed natively, so it needs no
+//! `cast` process and works without Foundry installed; `to-check-sum-address` only falls
+//! back to cast for input that isn't a plain hex address.
+//!
+//! Older cast releases lack some conversions. `conversion_capabilities` probes the installed
+//! cast once per binary path, and `cast_convert` then rejects the missing ones up front.
 
-use anyhow::{Context, Result};
 use rmcp::model::{CallToolResult, Content, Tool};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::process::Command;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::error::ToolError;
 
 /// All supported conversion types
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -118,7 +128,9 @@ impl ConversionType {
             Self::ToHex => "Convert number to hexadecimal",
             Self::ToDec => "Convert number to decimal",
             Self::ToBase => "Convert number to arbitrary base",
-            Self::ToCheckSumAddress => "Convert address to EIP-55 checksummed format",
+            Self::ToCheckSumAddress => {
+                "Convert address to EIP-55 checksummed format (EIP-1191 with chain_id)"
+            }
             Self::ToBytes32 => "Right-pad hex data to 32 bytes",
             Self::ToUint256 => "Convert number to hex-encoded uint256",
             Self::ToInt256 => "Convert number to hex-encoded int256",
@@ -178,6 +190,39 @@ pub struct ConversionParams {
     pub as_int: Option<bool>,
 }
 
+/// Names of all supported conversion types, as accepted by `conversion_type`
+pub const CONVERSION_TYPES: &[&str] = &[
+    "max-int",
+    "min-int",
+    "max-uint",
+    "address-zero",
+    "hash-zero",
+    "from-utf8",
+    "to-ascii",
+    "to-utf8",
+    "from-bin",
+    "concat-hex",
+    "to-hexdata",
+    "to-hex",
+    "to-dec",
+    "to-base",
+    "to-check-sum-address",
+    "to-bytes32",
+    "to-uint256",
+    "to-int256",
+    "from-fixed-point",
+    "to-fixed-point",
+    "shl",
+    "shr",
+    "to-unit",
+    "parse-units",
+    "format-units",
+    "to-wei",
+    "from-wei",
+    "to-rlp",
+    "from-rlp",
+];
+
 /// Get the unified cast conversion tool definition
 pub fn get_conversion_tool() -> Tool {
     let input_schema = json!({
@@ -186,18 +231,7 @@ pub fn get_conversion_tool() -> Tool {
             "conversion_type": {
                 "type": "string",
                 "description": "The type of conversion to perform",
-                "enum": [
-                    "max-int", "min-int", "max-uint",
-                    "address-zero", "hash-zero",
-                    "from-utf8", "to-ascii", "to-utf8", "from-bin",
-                    "concat-hex", "to-hexdata", "to-hex", "to-dec", "to-base",
-                    "to-check-sum-address", "to-bytes32",
-                    "to-uint256", "to-int256",
-                    "from-fixed-point", "to-fixed-point",
-                    "shl", "shr",
-                    "to-unit", "parse-units", "format-units", "to-wei", "from-wei",
-                    "to-rlp", "from-rlp"
-                ]
+                "enum": CONVERSION_TYPES
             },
             "value": {
                 "type": "string",
@@ -274,20 +308,54 @@ pub async fn handle_cast_convert(
     let params: ConversionParams = serde_json::from_value(Value::Object(args.clone()))
         .map_err(|e| rmcp::ErrorData::invalid_params(format!("Invalid parameters: {}", e), None))?;
 
-    match execute_conversion(params, cast_path) {
-        Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
-        Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
-    }
+    let conversion_type = params.conversion_type.clone();
+    let output = match execute_conversion(params, cast_path) {
+        Ok(output) => output,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut result = CallToolResult::success(vec![Content::text(output.clone())]);
+    result.structured_content = Some(json!({
+        "conversion_type": conversion_type,
+        "input": args,
+        "output": output,
+    }));
+    Ok(result)
 }
 
 /// Execute a cast conversion
-pub fn execute_conversion(params: ConversionParams, cast_path: &str) -> Result<String> {
+///
+/// An unknown conversion type is an invalid argument; a `cast` that can't be started, or
+/// that rejects the input, is an execution failure carrying its exit code.
+pub fn execute_conversion(params: ConversionParams, cast_path: &str) -> Result<String, ToolError> {
     let conversion_type: ConversionType =
-        serde_json::from_str(&format!("\"{}\"", params.conversion_type))
-            .with_context(|| format!("Invalid conversion type: {}", params.conversion_type))?;
+        serde_json::from_str(&format!("\"{}\"", params.conversion_type)).map_err(|_| {
+            ToolError::InvalidArguments(format!(
+                "Invalid conversion type: {}. Valid types: {}",
+                params.conversion_type,
+                CONVERSION_TYPES.join(", ")
+            ))
+        })?;
+
+    // Checksumming a hex address needs no cast process
+    if let (ConversionType::ToCheckSumAddress, Some(value)) = (&conversion_type, &params.value) {
+        if let Some(checksummed) = checksum_address(value.trim(), params.chain_id) {
+            return Ok(checksummed);
+        }
+    }
+
+    // Fail fast on a conversion an earlier probe found this cast lacks
+    let subcommand = conversion_type.subcommand();
+    if cached_capabilities(cast_path).is_some_and(|supported| !supported.contains(&subcommand)) {
+        return Err(ToolError::execution_failed(format!(
+            "The installed cast ({}) has no '{}' conversion; upgrade Foundry with foundryup",
+            cast_path, subcommand
+        )));
+    }
 
     let mut cmd = Command::new(cast_path);
-    cmd.arg(conversion_type.subcommand());
+    cmd.arg(subcommand);
 
     // Add positional arguments based on conversion type
     match conversion_type {
@@ -389,9 +457,12 @@ pub fn execute_conversion(params: ConversionParams, cast_path: &str) -> Result<S
         }
     }
 
-    let output = cmd
-        .output()
-        .with_context(|| format!("Failed to execute cast {}", conversion_type.subcommand()))?;
+    let output = cmd.output().map_err(|e| {
+        ToolError::execution_failed(format!(
+            "Failed to execute '{}': {}. Install Foundry from https://getfoundry.sh/",
+            cast_path, e
+        ))
+    })?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -400,8 +471,325 @@ pub fn execute_conversion(params: ConversionParams, cast_path: &str) -> Result<S
     if output.status.success() {
         Ok(combined)
     } else {
-        anyhow::bail!("Conversion failed: {}", combined)
+        Err(ToolError::ExecutionFailed {
+            message: format!("Conversion failed: {}", combined),
+            exit_code: output.status.code(),
+        })
+    }
+}
+
+/// Conversions found supported by the last probe, with the cast binary it ran
+static CAPABILITIES: Mutex<Option<(String, Vec<&'static str>)>> = Mutex::new(None);
+
+/// Most `cast <conversion> --help` processes a probe runs at once
+const MAX_PARALLEL_PROBES: usize = 4;
+
+/// Run `cast <conversion> --help` for every conversion type, at most
+/// `MAX_PARALLEL_PROBES` at a time, and keep those cast accepts
+fn probe_conversions(cast_path: &str) -> Result<Vec<&'static str>, ToolError> {
+    // A cast that can't start says nothing about which conversions it has
+    Command::new(cast_path)
+        .arg("--version")
+        .output()
+        .map_err(|e| {
+            ToolError::execution_failed(format!(
+                "Failed to execute '{}': {}. Install Foundry from https://getfoundry.sh/",
+                cast_path, e
+            ))
+        })?;
+
+    Ok(CONVERSION_TYPES
+        .chunks(MAX_PARALLEL_PROBES)
+        .flat_map(|chunk| {
+            std::thread::scope(|scope| {
+                let probes: Vec<_> = chunk
+                    .iter()
+                    .map(|&conversion| {
+                        scope.spawn(move || {
+                            let supported = Command::new(cast_path)
+                                .args([conversion, "--help"])
+                                .output()
+                                .is_ok_and(|output| output.status.success());
+                            supported.then_some(conversion)
+                        })
+                    })
+                    .collect();
+                probes
+                    .into_iter()
+                    .filter_map(|probe| probe.join().ok().flatten())
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect())
+}
+
+/// The probed conversions, if the last probe ran this cast binary
+fn cached_capabilities(cast_path: &str) -> Option<Vec<&'static str>> {
+    match &*CAPABILITIES.lock().unwrap() {
+        Some((probed, supported)) if probed == cast_path => Some(supported.clone()),
+        _ => None,
+    }
+}
+
+/// Conversions the cast at `cast_path` supports, probed on first use (or with `refresh`)
+/// and cached for that binary. Also returns whether the answer came from the cache.
+pub fn conversion_capabilities(
+    cast_path: &str,
+    refresh: bool,
+) -> Result<(Vec<&'static str>, bool), ToolError> {
+    if !refresh {
+        if let Some(supported) = cached_capabilities(cast_path) {
+            return Ok((supported, true));
+        }
+    }
+    let supported = probe_conversions(cast_path)?;
+    *CAPABILITIES.lock().unwrap() = Some((cast_path.to_string(), supported.clone()));
+    Ok((supported, false))
+}
+
+/// Get the conversion_capabilities tool definition
+pub fn get_conversion_capabilities_tool() -> Tool {
+    let input_schema = json!({
+        "type": "object",
+        "properties": {
+            "refresh": {
+                "type": "boolean",
+                "description": "Probe cast again instead of using the cached result, e.g. after upgrading Foundry (default: false)"
+            }
+        }
+    });
+
+    Tool::new(
+        "conversion_capabilities".to_string(),
+        "List which cast_convert conversion types the installed cast supports. Older cast releases lack some; cast is probed once and the result cached.".to_string(),
+        Arc::new(input_schema.as_object().unwrap().clone()),
+    )
+}
+
+/// Handle the conversion_capabilities tool call
+pub async fn handle_conversion_capabilities(
+    arguments: &Option<serde_json::Map<String, Value>>,
+    cast_path: String,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let refresh = arguments
+        .as_ref()
+        .and_then(|args| args.get("refresh"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    // Probing runs a cast process per conversion, so keep it off the async workers
+    let path = cast_path.clone();
+    let probed = tokio::task::spawn_blocking(move || conversion_capabilities(&path, refresh))
+        .await
+        .unwrap_or_else(|e| {
+            Err(ToolError::execution_failed(format!(
+                "Probing cast did not complete: {}",
+                e
+            )))
+        });
+    let (supported, cached) = match probed {
+        Ok(probed) => probed,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+    let unsupported: Vec<&str> = CONVERSION_TYPES
+        .iter()
+        .copied()
+        .filter(|conversion| !supported.contains(conversion))
+        .collect();
+
+    let mut text = format!(
+        "{} supports {} of {} conversion types{}\n",
+        cast_path,
+        supported.len(),
+        CONVERSION_TYPES.len(),
+        if cached { " (cached)" } else { "" }
+    );
+    if unsupported.is_empty() {
+        text.push_str("All conversion types are available.\n");
+    } else {
+        text.push_str(&format!("Unsupported: {}\n", unsupported.join(", ")));
+        if unsupported.contains(&"to-check-sum-address") {
+            text.push_str("to-check-sum-address still works for hex addresses, which are checksummed without cast.\n");
+        }
+    }
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(json!({
+        "cast_path": cast_path,
+        "supported": supported,
+        "unsupported": unsupported,
+        "cached": cached,
+    }));
+    Ok(result)
+}
+
+/// Most addresses `addresses_checksum` takes in one call
+const MAX_CHECKSUM_ADDRESSES: usize = 1000;
+
+/// EIP-55 checksummed form of a 20-byte hex address, or `None` if it isn't one. With a
+/// chain ID the checksum is the chain-specific one from EIP-1191.
+pub fn checksum_address(address: &str, chain_id: Option<u64>) -> Option<String> {
+    let hex = address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+        .unwrap_or(address);
+    if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let lower = hex.to_ascii_lowercase();
+    let mut hash = [0u8; 32];
+    let mut keccak = Keccak::v256();
+    if let Some(chain_id) = chain_id {
+        keccak.update(format!("{}0x", chain_id).as_bytes());
+    }
+    keccak.update(lower.as_bytes());
+    keccak.finalize(&mut hash);
+
+    // A letter is uppercased when the matching nibble of the hash is 8 or more
+    let checksummed: String = lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = if i % 2 == 0 {
+                hash[i / 2] >> 4
+            } else {
+                hash[i / 2] & 0x0f
+            };
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    Some(format!("0x{}", checksummed))
+}
+
+/// Check one address for `addresses_checksum`
+///
+/// An all-lowercase or all-uppercase address carries no checksum and is valid as long as
+/// it is 20 bytes of hex; a mixed-case one must match its checksum, so typos are caught.
+fn check_address(input: &str, chain_id: Option<u64>) -> Value {
+    let address = input.trim();
+    let Some(checksummed) = checksum_address(address, chain_id) else {
+        return json!({
+            "input": input,
+            "valid": false,
+            "checksummed": null,
+            "error": "not a 20-byte hex address",
+        });
+    };
+
+    let hex = &address[address.len() - 40..];
+    let mixed_case =
+        hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase());
+    if mixed_case && hex != &checksummed[2..] {
+        return json!({
+            "input": input,
+            "valid": false,
+            "checksummed": checksummed,
+            "error": "mixed-case address with an invalid checksum",
+        });
+    }
+    json!({
+        "input": input,
+        "valid": true,
+        "checksummed": checksummed,
+    })
+}
+
+/// Get the addresses_checksum tool definition
+pub fn get_addresses_checksum_tool() -> Tool {
+    let input_schema = json!({
+        "type": "object",
+        "properties": {
+            "addresses": {
+                "type": "array",
+                "items": {"type": "string"},
+                "maxItems": MAX_CHECKSUM_ADDRESSES,
+                "description": "Addresses to validate and checksum"
+            },
+            "chain_id": {
+                "type": "number",
+                "description": "Chain ID for EIP-1191 checksums (default: plain EIP-55)"
+            }
+        },
+        "required": ["addresses"]
+    });
+
+    Tool::new(
+        "addresses_checksum".to_string(),
+        format!(
+            "Validate and EIP-55 checksum up to {} addresses in one call, e.g. an allowlist. Each address is returned checksummed with a validity flag; mixed-case addresses whose checksum doesn't match are invalid.",
+            MAX_CHECKSUM_ADDRESSES
+        ),
+        Arc::new(input_schema.as_object().unwrap().clone()),
+    )
+}
+
+/// Handle the addresses_checksum tool call
+pub fn handle_addresses_checksum(
+    arguments: &Option<serde_json::Map<String, Value>>,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let addresses = arguments
+        .as_ref()
+        .and_then(|args| args.get("addresses"))
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| {
+            ToolError::InvalidArguments("'addresses' must be an array of strings".to_string())
+        })?;
+    if addresses.len() > MAX_CHECKSUM_ADDRESSES {
+        return Err(ToolError::InvalidArguments(format!(
+            "At most {} addresses can be checked in one call, got {}",
+            MAX_CHECKSUM_ADDRESSES,
+            addresses.len()
+        ))
+        .into());
     }
+    let addresses = addresses
+        .iter()
+        .map(|v| v.as_str())
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| {
+            ToolError::InvalidArguments("'addresses' must be an array of strings".to_string())
+        })?;
+
+    let chain_id = arguments
+        .as_ref()
+        .and_then(|args| args.get("chain_id"))
+        .and_then(|v| v.as_u64());
+
+    let results: Vec<Value> = addresses
+        .iter()
+        .map(|a| check_address(a, chain_id))
+        .collect();
+    let valid = results.iter().filter(|r| r["valid"] == json!(true)).count();
+
+    let mut text = format!("{} of {} addresses valid\n", valid, results.len());
+    for result in &results {
+        match result["valid"].as_bool() {
+            Some(true) => text.push_str(&format!(
+                "\n{}",
+                result["checksummed"].as_str().unwrap_or_default()
+            )),
+            _ => text.push_str(&format!(
+                "\n{}: invalid, {}",
+                result["input"].as_str().unwrap_or_default(),
+                result["error"].as_str().unwrap_or_default()
+            )),
+        }
+    }
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(json!({
+        "results": results,
+        "valid": valid,
+        "invalid": addresses.len() - valid,
+        "chain_id": chain_id,
+    }));
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -424,6 +812,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_conversion_types_match_enum() {
+        for name in CONVERSION_TYPES {
+            let parsed: ConversionType = serde_json::from_str(&format!("\"{}\"", name)).unwrap();
+            assert_eq!(parsed.subcommand(), *name);
+        }
+    }
+
     #[test]
     fn test_subcommand_names() {
         assert_eq!(ConversionType::MaxInt.subcommand(), "max-int");
@@ -434,4 +830,166 @@ mod tests {
         );
         assert_eq!(ConversionType::Shl.subcommand(), "shl");
     }
+
+    #[test]
+    fn test_execute_conversion_errors() {
+        let params = |conversion_type: &str| ConversionParams {
+            conversion_type: conversion_type.to_string(),
+            ..Default::default()
+        };
+
+        let err = execute_conversion(params("to-nothing"), "cast").unwrap_err();
+        assert!(matches!(err, ToolError::InvalidArguments(_)));
+        assert!(err.message().contains("to-hex"));
+
+        let err = execute_conversion(params("address-zero"), "/nonexistent/cast").unwrap_err();
+        assert!(matches!(
+            err,
+            ToolError::ExecutionFailed {
+                exit_code: None,
+                ..
+            }
+        ));
+        assert!(err.message().contains("getfoundry.sh"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_conversion_capabilities() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // A cast that only knows to-hex and to-dec
+        let dir = tempfile::tempdir().unwrap();
+        let cast = dir.path().join("cast");
+        std::fs::write(
+            &cast,
+            "#!/bin/sh\ncase \"$1\" in --version|to-hex|to-dec) exit 0 ;; *) exit 2 ;; esac\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&cast, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let cast_path = cast.to_string_lossy().to_string();
+
+        let (supported, cached) = conversion_capabilities(&cast_path, false).unwrap();
+        assert_eq!(supported, vec!["to-hex", "to-dec"]);
+        assert!(!cached);
+        let (_, cached) = conversion_capabilities(&cast_path, false).unwrap();
+        assert!(cached);
+
+        // Conversions the probe didn't find are rejected without running cast
+        let params = ConversionParams {
+            conversion_type: "to-rlp".to_string(),
+            value: Some("0x01".to_string()),
+            ..Default::default()
+        };
+        let err = execute_conversion(params, &cast_path).unwrap_err();
+        assert!(err.message().contains("no 'to-rlp' conversion"));
+
+        // Another binary isn't answered from this cache
+        assert!(cached_capabilities("/nonexistent/cast").is_none());
+        assert!(conversion_capabilities("/nonexistent/cast", false).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle_cast_convert_errors() {
+        let mut args = serde_json::Map::new();
+        args.insert("conversion_type".to_string(), json!("to-nothing"));
+        let err = handle_cast_convert(&Some(args.clone()), "cast")
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, rmcp::model::ErrorCode::INVALID_PARAMS);
+
+        args.insert("conversion_type".to_string(), json!("address-zero"));
+        let result = handle_cast_convert(&Some(args), "/nonexistent/cast")
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, Some(true));
+        assert_eq!(
+            result.structured_content.unwrap()["error"]["kind"],
+            "execution_failed"
+        );
+    }
+
+    #[test]
+    fn test_checksum_address() {
+        // Test vectors from EIP-55
+        for address in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            assert_eq!(
+                checksum_address(&address.to_lowercase(), None).as_deref(),
+                Some(address)
+            );
+        }
+        assert_eq!(checksum_address("0x1234", None), None);
+        assert_eq!(
+            checksum_address("0xzzAeb6053F3E94C9b9A09f33669435E7Ef1BeAed", None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_checksum_address_eip1191() {
+        // Test vectors from EIP-1191 for RSK mainnet (30) and testnet (31)
+        assert_eq!(
+            checksum_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed", Some(30)).as_deref(),
+            Some("0x5aaEB6053f3e94c9b9a09f33669435E7ef1bEAeD")
+        );
+        assert_eq!(
+            checksum_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed", Some(31)).as_deref(),
+            Some("0x5aAeb6053F3e94c9b9A09F33669435E7EF1BEaEd")
+        );
+    }
+
+    #[test]
+    fn test_to_check_sum_address_without_cast() {
+        let params = ConversionParams {
+            conversion_type: "to-check-sum-address".to_string(),
+            value: Some("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            execute_conversion(params, "/nonexistent/cast").unwrap(),
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+        );
+
+        // Anything else still goes to cast
+        let params = ConversionParams {
+            conversion_type: "to-check-sum-address".to_string(),
+            value: Some("vitalik.eth".to_string()),
+            ..Default::default()
+        };
+        assert!(execute_conversion(params, "/nonexistent/cast").is_err());
+    }
+
+    #[test]
+    fn test_handle_addresses_checksum() {
+        let args = json!({"addresses": [
+            "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+            "0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED",
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD",
+            "not an address",
+        ]});
+        let result = handle_addresses_checksum(&args.as_object().cloned()).unwrap();
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["valid"], json!(2));
+        assert_eq!(structured["invalid"], json!(2));
+        let results = structured["results"].as_array().unwrap();
+        assert_eq!(
+            results[1]["checksummed"],
+            json!("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")
+        );
+        assert_eq!(results[2]["valid"], json!(false));
+        assert_eq!(
+            results[2]["checksummed"],
+            json!("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")
+        );
+        assert_eq!(results[3]["checksummed"], Value::Null);
+
+        let err =
+            handle_addresses_checksum(&json!({"addresses": [1]}).as_object().cloned()).unwrap_err();
+        assert_eq!(err.code, rmcp::model::ErrorCode::INVALID_PARAMS);
+    }
 }
@@ -0,0 +1,346 @@
+//! Deployment address prediction
+//!
+//! `cast_compute_address` predicts where a contract will be deployed: from the deployer and
+//! its nonce for CREATE, or from the deployer, a salt and the init code (or its hash) for
+//! CREATE2. Inputs are validated before `cast compute-address` runs.
+
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::cast;
+use crate::error::ToolError;
+use crate::foundry::FoundryExecutor;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// How the contract is deployed
+#[derive(Debug, Clone, PartialEq)]
+enum Scheme {
+    /// CREATE with the given nonce, or the deployer's current nonce when `None`
+    Create {
+        nonce: Option<u64>,
+    },
+    Create2 {
+        salt: String,
+        init_code: InitCode,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum InitCode {
+    Code(String),
+    Hash(String),
+}
+
+/// Get the deployment tools
+pub fn get_deployment_tools() -> Vec<Tool> {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "deployer".to_string(),
+        json!({
+            "type": "string",
+            "description": "Deploying address (for CREATE2, usually a factory such as 0x4e59b44847b379578588920cA78FbF26c0B4956C)"
+        }),
+    );
+    props.insert(
+        "nonce".to_string(),
+        json!({
+            "type": "integer",
+            "description": "CREATE: deployer nonce (default: its current nonce, read over RPC)"
+        }),
+    );
+    props.insert(
+        "salt".to_string(),
+        json!({
+            "type": "string",
+            "description": "CREATE2: salt, up to 32 bytes of hex (left-padded)"
+        }),
+    );
+    props.insert(
+        "init_code".to_string(),
+        json!({
+            "type": "string",
+            "description": "CREATE2: contract creation code including constructor arguments"
+        }),
+    );
+    props.insert(
+        "init_code_hash".to_string(),
+        json!({
+            "type": "string",
+            "description": "CREATE2: keccak256 hash of the init code, instead of init_code"
+        }),
+    );
+    props.insert(
+        "rpc_url".to_string(),
+        json!({
+            "type": "string",
+            "description": "RPC endpoint to read the nonce from when it isn't given (default: the configured default_rpc_url or default_chain, else the running Anvil session)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+    schema.insert("required".to_string(), json!(["deployer"]));
+
+    vec![Tool::new(
+        "cast_compute_address".to_string(),
+        "Predict a contract's deployment address: from the deployer and nonce (CREATE), or from the deployer, salt and init code or its hash (CREATE2). Read-only; nothing is deployed.".to_string(),
+        Arc::new(schema),
+    )]
+}
+
+/// Check that `value` is 0x-prefixed hex of `bytes` bytes (any whole number of bytes if `None`)
+fn check_hex(name: &str, value: &str, bytes: Option<usize>) -> Result<(), ToolError> {
+    let hex = value.strip_prefix("0x").ok_or_else(|| {
+        ToolError::InvalidArguments(format!("'{}' must be 0x-prefixed hex", name))
+    })?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) || !hex.len().is_multiple_of(2) {
+        return Err(ToolError::InvalidArguments(format!(
+            "'{}' is not valid hex",
+            name
+        )));
+    }
+    match bytes {
+        Some(bytes) if hex.len() != bytes * 2 => Err(ToolError::InvalidArguments(format!(
+            "'{}' must be {} bytes, got {}",
+            name,
+            bytes,
+            hex.len() / 2
+        ))),
+        None if hex.is_empty() => Err(ToolError::InvalidArguments(format!("'{}' is empty", name))),
+        _ => Ok(()),
+    }
+}
+
+/// Left-pad a salt to 32 bytes
+fn normalize_salt(salt: &str) -> Result<String, ToolError> {
+    let hex = salt.trim().strip_prefix("0x").unwrap_or(salt.trim());
+    if hex.is_empty() || hex.len() > 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ToolError::InvalidArguments(
+            "'salt' must be hex of at most 32 bytes".to_string(),
+        ));
+    }
+    Ok(format!("0x{:0>64}", hex.to_ascii_lowercase()))
+}
+
+/// Work out the deployment scheme from the arguments
+fn scheme(args: &JsonObject) -> Result<Scheme, ToolError> {
+    let get = |name: &str| args.get(name).and_then(|v| v.as_str()).map(str::trim);
+    let nonce = match args.get("nonce") {
+        None => None,
+        Some(nonce) => Some(nonce.as_u64().ok_or_else(|| {
+            ToolError::InvalidArguments("'nonce' must be a non-negative integer".to_string())
+        })?),
+    };
+
+    let init_code = match (get("init_code"), get("init_code_hash")) {
+        (Some(_), Some(_)) => {
+            return Err(ToolError::InvalidArguments(
+                "Give either 'init_code' or 'init_code_hash', not both".to_string(),
+            ))
+        }
+        (Some(code), None) => {
+            check_hex("init_code", code, None)?;
+            Some(InitCode::Code(code.to_string()))
+        }
+        (None, Some(hash)) => {
+            check_hex("init_code_hash", hash, Some(32))?;
+            Some(InitCode::Hash(hash.to_string()))
+        }
+        (None, None) => None,
+    };
+
+    match (nonce, get("salt"), init_code) {
+        (Some(_), Some(_), _) => Err(ToolError::InvalidArguments(
+            "'nonce' is for CREATE and 'salt' for CREATE2; give one or the other".to_string(),
+        )),
+        (nonce, None, None) => Ok(Scheme::Create { nonce }),
+        (_, None, Some(_)) => Err(ToolError::InvalidArguments(
+            "CREATE2 needs a 'salt' along with the init code".to_string(),
+        )),
+        (None, Some(_), None) => Err(ToolError::InvalidArguments(
+            "CREATE2 needs 'init_code' or 'init_code_hash' along with the salt".to_string(),
+        )),
+        (None, Some(salt), Some(init_code)) => Ok(Scheme::Create2 {
+            salt: normalize_salt(salt)?,
+            init_code,
+        }),
+    }
+}
+
+/// Pull the address out of `cast compute-address` output ("Computed Address: 0x...")
+fn parse_address(output: &str) -> Result<String, ToolError> {
+    output
+        .split_whitespace()
+        .rev()
+        .find(|word| word.starts_with("0x") && word.len() == 42)
+        .map(str::to_string)
+        .ok_or_else(|| {
+            ToolError::execution_failed(format!(
+                "Unexpected output from cast compute-address: '{}'",
+                output
+            ))
+        })
+}
+
+async fn compute(
+    args: &JsonObject,
+    deployer: &str,
+    scheme: &Scheme,
+    executor: &FoundryExecutor,
+) -> Result<String, ToolError> {
+    let mut compute_args = JsonObject::new();
+    compute_args.insert("address".to_string(), json!(deployer));
+    match scheme {
+        Scheme::Create { nonce: Some(nonce) } => {
+            compute_args.insert("nonce".to_string(), json!(nonce));
+        }
+        Scheme::Create { nonce: None } => {
+            let rpc_url = cast::resolve_rpc_url(
+                args.get("rpc_url").and_then(|v| v.as_str()),
+                &executor.config(),
+            )
+            .await?;
+            compute_args.insert("rpc-url".to_string(), json!(rpc_url));
+        }
+        Scheme::Create2 { salt, init_code } => {
+            compute_args.insert("salt".to_string(), json!(salt));
+            match init_code {
+                InitCode::Code(code) => compute_args.insert("init-code".to_string(), json!(code)),
+                InitCode::Hash(hash) => {
+                    compute_args.insert("init-code-hash".to_string(), json!(hash))
+                }
+            };
+        }
+    }
+
+    let output = cast::run_tool(executor, "cast_compute_address", compute_args, &[])?;
+    parse_address(&output)
+}
+
+/// Handle cast_compute_address tool call
+pub async fn handle_cast_compute_address(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let args = arguments
+        .as_ref()
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing arguments", None))?;
+    let deployer = args
+        .get("deployer")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing 'deployer' parameter", None))?;
+    check_hex("deployer", deployer, Some(20))?;
+    let scheme = scheme(args)?;
+
+    let address = match compute(args, deployer, &scheme, executor).await {
+        Ok(address) => address,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut structured = json!({ "address": address, "deployer": deployer });
+    let text = match &scheme {
+        Scheme::Create { nonce } => {
+            structured["scheme"] = json!("create");
+            structured["nonce"] = json!(nonce);
+            match nonce {
+                Some(nonce) => format!("CREATE from {} at nonce {}: {}", deployer, nonce, address),
+                None => format!("CREATE from {} at its current nonce: {}", deployer, address),
+            }
+        }
+        Scheme::Create2 { salt, .. } => {
+            structured["scheme"] = json!("create2");
+            structured["salt"] = json!(salt);
+            format!("CREATE2 from {} with salt {}: {}", deployer, salt, address)
+        }
+    };
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(structured);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheme_of(args: Value) -> Result<Scheme, ToolError> {
+        scheme(args.as_object().unwrap())
+    }
+
+    /// Test that the scheme follows from the arguments given
+    #[test]
+    fn test_scheme() {
+        assert_eq!(
+            scheme_of(json!({"nonce": 5})).unwrap(),
+            Scheme::Create { nonce: Some(5) }
+        );
+        assert_eq!(
+            scheme_of(json!({})).unwrap(),
+            Scheme::Create { nonce: None }
+        );
+
+        let hash = format!("0x{}", "ab".repeat(32));
+        assert_eq!(
+            scheme_of(json!({"salt": "0x1", "init_code_hash": hash})).unwrap(),
+            Scheme::Create2 {
+                salt: format!("0x{:0>64}", "1"),
+                init_code: InitCode::Hash(hash.clone()),
+            }
+        );
+        assert!(matches!(
+            scheme_of(json!({"salt": "0x1", "init_code": "0x6080"})).unwrap(),
+            Scheme::Create2 {
+                init_code: InitCode::Code(_),
+                ..
+            }
+        ));
+    }
+
+    /// Test that mixed or incomplete inputs are rejected
+    #[test]
+    fn test_scheme_invalid() {
+        let hash = format!("0x{}", "ab".repeat(32));
+        for args in [
+            json!({"nonce": -1}),
+            json!({"nonce": 1, "salt": "0x1", "init_code_hash": hash}),
+            json!({"salt": "0x1"}),
+            json!({"init_code": "0x6080"}),
+            json!({"salt": "0x1", "init_code": "0x6080", "init_code_hash": hash}),
+            json!({"salt": "0x1", "init_code_hash": "0x1234"}),
+            json!({"salt": "0x1", "init_code": "6080"}),
+            json!({"salt": "0x1", "init_code": "0x"}),
+            json!({"salt": "zz", "init_code": "0x6080"}),
+            json!({"salt": format!("0x{}", "1".repeat(65)), "init_code": "0x6080"}),
+        ] {
+            assert!(
+                matches!(scheme_of(args.clone()), Err(ToolError::InvalidArguments(_))),
+                "{}",
+                args
+            );
+        }
+    }
+
+    /// Test that the deployer must be a 20-byte address
+    #[test]
+    fn test_check_deployer() {
+        assert!(check_hex("deployer", &format!("0x{}", "aa".repeat(20)), Some(20)).is_ok());
+        assert!(check_hex("deployer", "0x1234", Some(20)).is_err());
+        assert!(check_hex("deployer", &"aa".repeat(20), Some(20)).is_err());
+    }
+
+    /// Test that the address is read from cast's output
+    #[test]
+    fn test_parse_address() {
+        let address = "0x5FbDB2315678afecb367f032d93F642f64180aa3";
+        assert_eq!(
+            parse_address(&format!("Computed Address: {}", address)).unwrap(),
+            address
+        );
+        assert!(parse_address("error").is_err());
+    }
+}
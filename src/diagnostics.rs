@@ -0,0 +1,188 @@
+//! Compiler diagnostics from failed runs
+//!
+//! When `forge build` (or anything else that compiles) fails, the Solidity errors are
+//! buried in the command's output. This module picks them out as structured diagnostics,
+//! with file, line, column, severity and message, and attaches them to the error result
+//! next to the raw text, so a client can go straight to the offending line.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rmcp::model::CallToolResult;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::error::ToolError;
+
+/// A compiler error or warning
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+    /// `error`, `warning` or `info`
+    pub severity: String,
+    /// Solc error code, e.g. "7576"
+    pub code: Option<String>,
+    pub message: String,
+}
+
+/// Header of a solc message, e.g. "Error (7576): Undeclared identifier." or
+/// "TypeError: Type uint256 is not implicitly convertible"
+static HEADER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(?:Error: )?(?P<kind>[A-Za-z]*(?:Error|Warning|Info))(?: \((?P<code>\d+)\))?: (?P<message>.+)$",
+    )
+    .unwrap()
+});
+
+/// Source location under a header, e.g. "  --> src/Counter.sol:10:9:"
+static LOCATION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*--> (?P<file>.+?):(?P<line>\d+):(?P<column>\d+):?\s*$").unwrap());
+
+fn severity(kind: &str) -> &'static str {
+    if kind.ends_with("Warning") {
+        "warning"
+    } else if kind.ends_with("Info") {
+        "info"
+    } else {
+        "error"
+    }
+}
+
+/// Extract the diagnostics from compiler output.
+///
+/// Only messages with a source location are kept, which leaves out wrappers such as
+/// "Compiler run failed" and errors that aren't about the code.
+pub fn parse_diagnostics(output: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut header: Option<(String, Option<String>, String)> = None;
+
+    for line in output.lines() {
+        let line = line.trim_end();
+        if let Some(caps) = HEADER.captures(line) {
+            header = Some((
+                severity(&caps["kind"]).to_string(),
+                caps.name("code").map(|c| c.as_str().to_string()),
+                caps["message"].trim().to_string(),
+            ));
+        } else if let Some(caps) = LOCATION.captures(line) {
+            // A location belongs to the nearest header above it
+            if let Some((severity, code, message)) = header.take() {
+                diagnostics.push(Diagnostic {
+                    file: caps["file"].to_string(),
+                    line: caps["line"].parse().unwrap_or_default(),
+                    column: caps["column"].parse().unwrap_or_default(),
+                    severity,
+                    code,
+                    message,
+                });
+            }
+        }
+    }
+    diagnostics
+}
+
+/// Turn an execution error into a tool result, with any compiler diagnostics in its
+/// output added to the structured content
+pub fn error_result(error: ToolError) -> CallToolResult {
+    let diagnostics = parse_diagnostics(error.message());
+    let mut result = error.into_call_tool_result();
+    if !diagnostics.is_empty() {
+        if let Some(structured) = result.structured_content.as_mut() {
+            structured["diagnostics"] = json!(diagnostics);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUILD_OUTPUT: &str = "\
+Compiling 2 files with Solc 0.8.24
+Solc 0.8.24 finished in 12.34ms
+Error: Compiler run failed:
+Error (7576): Undeclared identifier. Did you mean \"count\"?
+  --> src/Counter.sol:10:9:
+   |
+10 |         coutn = 1;
+   |         ^^^^^
+Warning (2072): Unused local variable.
+  --> test/Counter.t.sol:22:9:
+   |
+22 |         uint256 unused;
+   |         ^^^^^^^^^^^^^^
+";
+
+    /// Test that errors and warnings are read with their locations
+    #[test]
+    fn test_parse_diagnostics() {
+        let diagnostics = parse_diagnostics(BUILD_OUTPUT);
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic {
+                    file: "src/Counter.sol".to_string(),
+                    line: 10,
+                    column: 9,
+                    severity: "error".to_string(),
+                    code: Some("7576".to_string()),
+                    message: "Undeclared identifier. Did you mean \"count\"?".to_string(),
+                },
+                Diagnostic {
+                    file: "test/Counter.t.sol".to_string(),
+                    line: 22,
+                    column: 9,
+                    severity: "warning".to_string(),
+                    code: Some("2072".to_string()),
+                    message: "Unused local variable.".to_string(),
+                },
+            ]
+        );
+    }
+
+    /// Test that solc's typed error names are understood
+    #[test]
+    fn test_parse_typed_errors() {
+        let output = "ParserError: Expected ';' but got '}'\n --> src/A.sol:5:1:\n";
+        let diagnostics = parse_diagnostics(output);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, "error");
+        assert_eq!(diagnostics[0].code, None);
+        assert_eq!(diagnostics[0].message, "Expected ';' but got '}'");
+    }
+
+    /// Test that errors without a source location are not diagnostics
+    #[test]
+    fn test_parse_ignores_unlocated_errors() {
+        assert!(
+            parse_diagnostics("Error: server returned an error response: error code -32000")
+                .is_empty()
+        );
+        assert!(parse_diagnostics("Error: Compiler run failed:").is_empty());
+    }
+
+    /// Test that diagnostics are added to the error result alongside the raw output
+    #[test]
+    fn test_error_result() {
+        let result = error_result(ToolError::ExecutionFailed {
+            message: BUILD_OUTPUT.to_string(),
+            exit_code: Some(1),
+        });
+        assert_eq!(result.is_error, Some(true));
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["error"]["message"], json!(BUILD_OUTPUT));
+        assert_eq!(
+            structured["diagnostics"][0]["file"],
+            json!("src/Counter.sol")
+        );
+
+        let result = error_result(ToolError::execution_failed("boom"));
+        assert!(result
+            .structured_content
+            .unwrap()
+            .get("diagnostics")
+            .is_none());
+    }
+}
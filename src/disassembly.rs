@@ -0,0 +1,267 @@
+//! Bytecode disassembly
+//!
+//! `cast_disassemble` turns runtime bytecode, given directly or fetched from a deployed
+//! contract with `cast code`, into an opcode listing with `cast disassemble`. Each
+//! instruction is returned with its program counter, and listings beyond
+//! `max_output_bytes` are cut off at an instruction boundary.
+
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::cast;
+use crate::error::ToolError;
+use crate::foundry::FoundryExecutor;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// One disassembled instruction
+#[derive(Debug, Clone, PartialEq)]
+struct Instruction {
+    pc: u64,
+    opcode: String,
+    /// Immediate operand of a PUSH
+    immediate: Option<String>,
+}
+
+/// Get the disassembly tools
+pub fn get_disassembly_tools() -> Vec<Tool> {
+    let mut props = cast::endpoint_properties();
+    props.insert(
+        "bytecode".to_string(),
+        json!({
+            "type": "string",
+            "description": "Runtime bytecode as hex"
+        }),
+    );
+    props.insert(
+        "address".to_string(),
+        json!({
+            "type": "string",
+            "description": "Deployed contract whose code is fetched over RPC, instead of bytecode"
+        }),
+    );
+    props.insert(
+        "block".to_string(),
+        json!({
+            "type": "string",
+            "description": "Block number or tag to fetch the code at (default: latest)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+
+    vec![Tool::new(
+        "cast_disassemble".to_string(),
+        "Disassemble EVM bytecode, given as hex or fetched from a contract address, into opcodes with their program counters. Long listings are truncated to the server's output limit.".to_string(),
+        Arc::new(schema),
+    )]
+}
+
+/// Check that `bytecode` is non-empty hex
+fn check_bytecode(bytecode: &str) -> Result<(), ToolError> {
+    let hex = bytecode.strip_prefix("0x").unwrap_or(bytecode);
+    if hex.is_empty() {
+        return Err(ToolError::InvalidArguments(
+            "'bytecode' is empty".to_string(),
+        ));
+    }
+    if !hex.len().is_multiple_of(2) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ToolError::InvalidArguments(
+            "'bytecode' is not valid hex".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Get the bytecode to disassemble, fetching it when an address is given
+async fn read_bytecode(args: &JsonObject, executor: &FoundryExecutor) -> Result<String, ToolError> {
+    let get = |name: &str| args.get(name).and_then(|v| v.as_str()).map(str::trim);
+    let bytecode = match (get("bytecode"), get("address")) {
+        (Some(bytecode), None) => bytecode.to_string(),
+        (None, Some(address)) => {
+            let mut code_args = JsonObject::new();
+            code_args.insert("who".to_string(), json!(address));
+            let rpc_url = cast::resolve_endpoint(args, &executor.config()).await?;
+            code_args.insert("rpc-url".to_string(), json!(rpc_url));
+            if let Some(block) = get("block") {
+                code_args.insert("block".to_string(), json!(block));
+            }
+            let code = cast::run_tool(executor, "cast_code", code_args, &[])?;
+            if code.trim_start_matches("0x").is_empty() {
+                return Err(ToolError::InvalidArguments(format!(
+                    "{} has no code",
+                    address
+                )));
+            }
+            code
+        }
+        _ => {
+            return Err(ToolError::InvalidArguments(
+                "Give exactly one of 'bytecode' or 'address'".to_string(),
+            ))
+        }
+    };
+    check_bytecode(&bytecode)?;
+    Ok(bytecode)
+}
+
+/// Parse a `cast disassemble` line such as "0000000a: PUSH1 0x80"
+fn parse_instruction(line: &str) -> Option<Instruction> {
+    let (pc, rest) = line.split_once(':')?;
+    let pc = u64::from_str_radix(pc.trim(), 16).ok()?;
+    let mut parts = rest.split_whitespace();
+    let opcode = parts.next()?.to_string();
+    Some(Instruction {
+        pc,
+        opcode,
+        immediate: parts.next().map(str::to_string),
+    })
+}
+
+/// Keep as many listing lines as fit in `limit` bytes
+fn fit_lines(lines: &[&str], limit: Option<usize>) -> usize {
+    let Some(limit) = limit else {
+        return lines.len();
+    };
+
+    let mut size = 0;
+    lines
+        .iter()
+        .take_while(|line| {
+            size += line.len() + 1;
+            size <= limit
+        })
+        .count()
+}
+
+/// Handle cast_disassemble tool call
+pub async fn handle_cast_disassemble(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let empty = JsonObject::new();
+    let args = arguments.as_ref().unwrap_or(&empty);
+
+    let listing = read_bytecode(args, executor).await.and_then(|bytecode| {
+        let mut disassemble_args = JsonObject::new();
+        disassemble_args.insert("bytecode".to_string(), json!(bytecode));
+        let listing = cast::run_tool(executor, "cast_disassemble", disassemble_args, &[])?;
+        Ok((bytecode, listing))
+    });
+    let (bytecode, listing) = match listing {
+        Ok(values) => values,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let lines: Vec<&str> = listing.lines().filter(|l| !l.trim().is_empty()).collect();
+    let shown = fit_lines(
+        &lines,
+        executor.config().max_output_bytes_for("cast_disassemble"),
+    );
+    let truncated = shown < lines.len();
+
+    // Fall back to the plain listing if cast's format isn't the one we know
+    let instructions: Option<Vec<Instruction>> = lines[..shown]
+        .iter()
+        .map(|l| parse_instruction(l))
+        .collect();
+    let instructions = instructions.map(|instructions| {
+        instructions
+            .into_iter()
+            .map(|i| json!({ "pc": i.pc, "opcode": i.opcode, "immediate": i.immediate }))
+            .collect::<Vec<_>>()
+    });
+
+    let mut text = lines[..shown].join("\n");
+    if truncated {
+        text.push_str(&format!(
+            "\n... [listing truncated: {} of {} instructions shown]",
+            shown,
+            lines.len()
+        ));
+    }
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(json!({
+        "address": args.get("address"),
+        "bytecode_bytes": bytecode.trim_start_matches("0x").len() / 2,
+        "instruction_count": lines.len(),
+        "instructions": instructions,
+        "truncated": truncated,
+    }));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that instructions are read with their program counter and PUSH immediate
+    #[test]
+    fn test_parse_instruction() {
+        assert_eq!(
+            parse_instruction("00000000: PUSH1 0x80"),
+            Some(Instruction {
+                pc: 0,
+                opcode: "PUSH1".to_string(),
+                immediate: Some("0x80".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_instruction("0000001a: MSTORE"),
+            Some(Instruction {
+                pc: 26,
+                opcode: "MSTORE".to_string(),
+                immediate: None,
+            })
+        );
+        assert_eq!(parse_instruction("PUSH1 0x80"), None);
+    }
+
+    /// Test that the listing is cut at a line boundary within the limit
+    #[test]
+    fn test_fit_lines() {
+        let lines = [
+            "00000000: PUSH1 0x80",
+            "00000002: PUSH1 0x40",
+            "00000004: MSTORE",
+        ];
+        assert_eq!(fit_lines(&lines, None), 3);
+        assert_eq!(fit_lines(&lines, Some(50)), 2);
+        assert_eq!(fit_lines(&lines, Some(5)), 0);
+    }
+
+    /// Test that exactly one valid source of bytecode is required
+    #[tokio::test]
+    async fn test_read_bytecode_validation() {
+        let executor = FoundryExecutor::with_config(
+            crate::schema::SchemaFile { tools: vec![] },
+            crate::config::Config::default(),
+        );
+        for args in [
+            json!({}),
+            json!({"bytecode": "0x6080", "address": "0x01"}),
+            json!({"bytecode": "0x"}),
+            json!({"bytecode": "0x608"}),
+            json!({"bytecode": "0xzz"}),
+        ] {
+            assert!(matches!(
+                read_bytecode(args.as_object().unwrap(), &executor).await,
+                Err(ToolError::InvalidArguments(_))
+            ));
+        }
+        assert_eq!(
+            read_bytecode(
+                json!({"bytecode": "0x6080"}).as_object().unwrap(),
+                &executor
+            )
+            .await
+            .unwrap(),
+            "0x6080"
+        );
+    }
+}
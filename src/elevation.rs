@@ -0,0 +1,125 @@
+//! Temporary elevation of the session's privileges
+//!
+//! `allow_dangerous` is all-or-nothing for the lifetime of the server. When
+//! `Config.unlock_passphrase` is set, the `unlock` tool lifts every command and flag
+//! restriction for a bounded time instead; `lock` (or the timer running out) restores
+//! them. The server talks to a single client over stdio, so the elevation is held by the
+//! handler and applies to that client's session.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Tracks whether, and until when, the session is elevated
+#[derive(Debug, Default)]
+pub struct Elevation {
+    until: Mutex<Option<Instant>>,
+}
+
+impl Elevation {
+    /// Create a non-elevated state
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Elevate for the given duration, replacing any current elevation
+    pub fn unlock(&self, duration: Duration) {
+        self.unlock_at(Instant::now(), duration);
+    }
+
+    fn unlock_at(&self, now: Instant, duration: Duration) {
+        *self.until.lock().unwrap() = Some(now + duration);
+    }
+
+    /// End the elevation. Returns whether the session was elevated.
+    pub fn lock(&self) -> bool {
+        self.until.lock().unwrap().take().is_some()
+    }
+
+    /// Time left on the current elevation, if any
+    pub fn remaining(&self) -> Option<Duration> {
+        self.remaining_at(Instant::now())
+    }
+
+    fn remaining_at(&self, now: Instant) -> Option<Duration> {
+        self.until
+            .lock()
+            .unwrap()
+            .and_then(|until| until.checked_duration_since(now))
+            .filter(|left| !left.is_zero())
+    }
+
+    /// Clear an elevation whose time has run out. Returns whether one was cleared.
+    pub fn expire(&self) -> bool {
+        self.expire_at(Instant::now())
+    }
+
+    fn expire_at(&self, now: Instant) -> bool {
+        let mut until = self.until.lock().unwrap();
+        match *until {
+            Some(deadline) if deadline <= now => {
+                *until = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Compare a supplied passphrase with the configured one without short-circuiting on
+/// the first differing byte
+pub fn passphrase_matches(expected: &str, given: &str) -> bool {
+    let (expected, given) = (expected.as_bytes(), given.as_bytes());
+    if expected.len() != given.len() {
+        return false;
+    }
+    expected
+        .iter()
+        .zip(given)
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that an elevation lasts for its duration and then expires
+    #[test]
+    fn test_unlock_expires() {
+        let elevation = Elevation::new();
+        let start = Instant::now();
+        assert!(elevation.remaining_at(start).is_none());
+
+        elevation.unlock_at(start, Duration::from_secs(60));
+        assert_eq!(
+            elevation.remaining_at(start + Duration::from_secs(20)),
+            Some(Duration::from_secs(40))
+        );
+        assert!(!elevation.expire_at(start + Duration::from_secs(59)));
+
+        let later = start + Duration::from_secs(60);
+        assert!(elevation.remaining_at(later).is_none());
+        assert!(elevation.expire_at(later));
+        assert!(!elevation.expire_at(later));
+    }
+
+    /// Test that lock ends an elevation early
+    #[test]
+    fn test_lock() {
+        let elevation = Elevation::new();
+        assert!(!elevation.lock());
+
+        elevation.unlock(Duration::from_secs(60));
+        assert!(elevation.lock());
+        assert!(elevation.remaining().is_none());
+    }
+
+    /// Test that passphrases must match exactly
+    #[test]
+    fn test_passphrase_matches() {
+        assert!(passphrase_matches("correct horse", "correct horse"));
+        assert!(!passphrase_matches("correct horse", "correct horsf"));
+        assert!(!passphrase_matches("correct horse", "correct"));
+        assert!(!passphrase_matches("correct horse", ""));
+    }
+}
@@ -0,0 +1,219 @@
+//! ENS name resolution
+//!
+//! `cast_resolve_ens` resolves an ENS name to its address with `cast resolve-name`, or an
+//! address to its primary name with `cast lookup-address`. ENS lives on Ethereum mainnet,
+//! so without an `rpc_url` a live mainnet endpoint is picked from chainlist.org.
+
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::cast;
+use crate::chainlist;
+use crate::error::ToolError;
+use crate::foundry::FoundryExecutor;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// Which way to resolve
+#[derive(Debug, Clone, PartialEq)]
+enum Lookup {
+    /// Name to address
+    Forward(String),
+    /// Address to name
+    Reverse(String),
+}
+
+/// Get the ENS tools
+pub fn get_ens_tools() -> Vec<Tool> {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "name".to_string(),
+        json!({
+            "type": "string",
+            "description": "ENS name to resolve to an address, e.g. 'vitalik.eth'"
+        }),
+    );
+    props.insert(
+        "address".to_string(),
+        json!({
+            "type": "string",
+            "description": "Address to look up the primary ENS name of, instead of name"
+        }),
+    );
+    props.insert(
+        "verify".to_string(),
+        json!({
+            "type": "boolean",
+            "description": "Check that the result resolves back to the input (default: false)"
+        }),
+    );
+    props.insert(
+        "rpc_url".to_string(),
+        json!({
+            "type": "string",
+            "description": "Ethereum mainnet RPC endpoint (default: a live endpoint from chainlist.org)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+
+    vec![Tool::new(
+        "cast_resolve_ens".to_string(),
+        "Resolve an ENS name to its address, or an address to its primary ENS name. Uses an Ethereum mainnet endpoint from chainlist.org unless an rpc_url is given.".to_string(),
+        Arc::new(schema),
+    )]
+}
+
+/// Work out the lookup direction from the arguments
+fn lookup(args: &JsonObject) -> Result<Lookup, ToolError> {
+    let get = |name: &str| args.get(name).and_then(|v| v.as_str()).map(str::trim);
+    match (get("name"), get("address")) {
+        (Some(_), Some(_)) => Err(ToolError::InvalidArguments(
+            "Give either 'name' or 'address', not both".to_string(),
+        )),
+        (None, None) => Err(ToolError::InvalidArguments(
+            "Give a 'name' to resolve or an 'address' to look up".to_string(),
+        )),
+        (Some(name), None) => {
+            if !name.contains('.') || name.starts_with('.') || name.ends_with('.') {
+                return Err(ToolError::InvalidArguments(format!(
+                    "'{}' is not an ENS name such as 'vitalik.eth'",
+                    name
+                )));
+            }
+            Ok(Lookup::Forward(name.to_string()))
+        }
+        (None, Some(address)) => {
+            let hex = address.strip_prefix("0x").unwrap_or_default();
+            if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(ToolError::InvalidArguments(format!(
+                    "'{}' is not an address",
+                    address
+                )));
+            }
+            Ok(Lookup::Reverse(address.to_string()))
+        }
+    }
+}
+
+/// Run the lookup against `rpc_url`
+fn resolve(
+    lookup: &Lookup,
+    verify: bool,
+    rpc_url: &str,
+    executor: &FoundryExecutor,
+) -> Result<String, ToolError> {
+    let (tool, who) = match lookup {
+        Lookup::Forward(name) => ("cast_resolve_name", name),
+        Lookup::Reverse(address) => ("cast_lookup_address", address),
+    };
+
+    let mut args = JsonObject::new();
+    args.insert("who".to_string(), json!(who));
+    args.insert("rpc-url".to_string(), json!(rpc_url));
+    if verify {
+        args.insert("verify".to_string(), json!(true));
+    }
+    let output = cast::run_tool(executor, tool, args, &[])?;
+
+    // A name without an address record resolves to the zero address
+    if output.is_empty() || output == format!("0x{}", "0".repeat(40)) {
+        return Err(ToolError::execution_failed(format!(
+            "{} has no ENS {}",
+            who,
+            match lookup {
+                Lookup::Forward(_) => "address record",
+                Lookup::Reverse(_) => "primary name",
+            }
+        )));
+    }
+    Ok(output)
+}
+
+/// Handle cast_resolve_ens tool call
+pub async fn handle_cast_resolve_ens(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let empty = JsonObject::new();
+    let args = arguments.as_ref().unwrap_or(&empty);
+    let lookup = lookup(args)?;
+    let verify = args.get("verify").and_then(|v| v.as_bool()) == Some(true);
+
+    let rpc_url = match args.get("rpc_url").and_then(|v| v.as_str()) {
+        Some(url) => Ok(url.to_string()),
+        None => chainlist::resolve_chain_rpc_url("1").await,
+    };
+    let resolved = match rpc_url.and_then(|url| resolve(&lookup, verify, &url, executor)) {
+        Ok(resolved) => resolved,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let (name, address) = match &lookup {
+        Lookup::Forward(name) => (name.clone(), resolved),
+        Lookup::Reverse(address) => (resolved, address.clone()),
+    };
+    let mut result =
+        CallToolResult::success(vec![Content::text(format!("{} => {}", name, address))]);
+    result.structured_content = Some(json!({
+        "name": name,
+        "address": address,
+        "verified": verify,
+    }));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup_of(args: Value) -> Result<Lookup, ToolError> {
+        lookup(args.as_object().unwrap())
+    }
+
+    /// Test that the direction follows from whether a name or an address is given
+    #[test]
+    fn test_lookup() {
+        assert_eq!(
+            lookup_of(json!({"name": "vitalik.eth"})).unwrap(),
+            Lookup::Forward("vitalik.eth".to_string())
+        );
+        let address = format!("0x{}", "d8".repeat(20));
+        assert_eq!(
+            lookup_of(json!({ "address": address })).unwrap(),
+            Lookup::Reverse(address)
+        );
+
+        for args in [
+            json!({}),
+            json!({"name": "vitalik.eth", "address": "0x01"}),
+            json!({"name": "vitalik"}),
+            json!({"name": "vitalik.eth."}),
+            json!({"address": "0x1234"}),
+            json!({"address": "vitalik.eth"}),
+        ] {
+            assert!(matches!(
+                lookup_of(args),
+                Err(ToolError::InvalidArguments(_))
+            ));
+        }
+    }
+
+    /// Test that the lookup runs through the schema tools, so a filtered one is forbidden
+    #[test]
+    fn test_resolve_needs_schema_tool() {
+        let executor = FoundryExecutor::with_config(
+            crate::schema::SchemaFile { tools: vec![] },
+            crate::config::Config::default(),
+        );
+        let lookup = Lookup::Forward("vitalik.eth".to_string());
+        assert!(matches!(
+            resolve(&lookup, false, "http://127.0.0.1:1", &executor),
+            Err(ToolError::ForbiddenCommand(_))
+        ));
+    }
+}
@@ -0,0 +1,212 @@
+//! Typed tool errors with stable MCP error codes
+//!
+//! Each error kind maps to a distinct JSON-RPC error code and a machine-readable `kind`
+//! string, so clients can branch on the failure type instead of parsing messages.
+
+use rmcp::model::{CallToolResult, Content, ErrorCode};
+use serde_json::json;
+use std::fmt;
+
+/// Error raised while resolving or executing a tool
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolError {
+    /// The requested tool does not exist (or was filtered out by configuration)
+    ToolNotFound(String),
+    /// The command or flag is forbidden by the server configuration
+    ForbiddenCommand(String),
+    /// The provided arguments are missing or malformed
+    InvalidArguments(String),
+    /// The command did not finish within its time limit
+    Timeout(String),
+    /// The command ran but failed, or could not be spawned
+    ExecutionFailed {
+        message: String,
+        exit_code: Option<i32>,
+    },
+    /// A remote data source or RPC endpoint could not be reached
+    RpcUnavailable(String),
+    /// The tool was called too often and must not be retried until the delay passes
+    RateLimited {
+        message: String,
+        retry_after_secs: u64,
+    },
+}
+
+impl ToolError {
+    /// Create an execution failure without an exit code
+    pub fn execution_failed(message: impl Into<String>) -> Self {
+        Self::ExecutionFailed {
+            message: message.into(),
+            exit_code: None,
+        }
+    }
+
+    /// Machine-readable error kind
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ToolNotFound(_) => "tool_not_found",
+            Self::ForbiddenCommand(_) => "forbidden_command",
+            Self::InvalidArguments(_) => "invalid_arguments",
+            Self::Timeout(_) => "timeout",
+            Self::ExecutionFailed { .. } => "execution_failed",
+            Self::RpcUnavailable(_) => "rpc_unavailable",
+            Self::RateLimited { .. } => "rate_limited",
+        }
+    }
+
+    /// JSON-RPC error code for this kind
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::ToolNotFound(_) => ErrorCode(-32010),
+            Self::ForbiddenCommand(_) => ErrorCode(-32011),
+            Self::InvalidArguments(_) => ErrorCode::INVALID_PARAMS,
+            Self::Timeout(_) => ErrorCode(-32012),
+            Self::ExecutionFailed { .. } => ErrorCode(-32013),
+            Self::RpcUnavailable(_) => ErrorCode(-32014),
+            Self::RateLimited { .. } => ErrorCode(-32015),
+        }
+    }
+
+    /// Human-readable error message
+    pub fn message(&self) -> &str {
+        match self {
+            Self::ToolNotFound(m)
+            | Self::ForbiddenCommand(m)
+            | Self::InvalidArguments(m)
+            | Self::Timeout(m)
+            | Self::RpcUnavailable(m) => m,
+            Self::ExecutionFailed { message, .. } | Self::RateLimited { message, .. } => message,
+        }
+    }
+
+    /// Whether this error happened while running the tool (as opposed to resolving it).
+    ///
+    /// Execution errors are reported as tool results with `is_error` set so the model
+    /// can read them; resolution errors are reported as protocol errors.
+    pub fn is_execution_error(&self) -> bool {
+        matches!(
+            self,
+            Self::Timeout(_)
+                | Self::ExecutionFailed { .. }
+                | Self::RpcUnavailable(_)
+                | Self::RateLimited { .. }
+        )
+    }
+
+    /// Structured error payload shared by protocol errors and tool results
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut data = json!({
+            "kind": self.kind(),
+            "code": self.code().0,
+            "message": self.message(),
+        });
+        if let Self::ExecutionFailed {
+            exit_code: Some(code),
+            ..
+        } = self
+        {
+            data["exit_code"] = json!(code);
+        }
+        if let Self::RateLimited {
+            retry_after_secs, ..
+        } = self
+        {
+            data["retry_after_secs"] = json!(retry_after_secs);
+        }
+        data
+    }
+
+    /// Convert into an error tool result carrying the structured payload
+    pub fn into_call_tool_result(self) -> CallToolResult {
+        let mut result = CallToolResult::error(vec![Content::text(self.message().to_string())]);
+        result.structured_content = Some(json!({ "error": self.to_json() }));
+        result
+    }
+}
+
+impl fmt::Display for ToolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for ToolError {}
+
+impl From<anyhow::Error> for ToolError {
+    fn from(err: anyhow::Error) -> Self {
+        match err.downcast::<ToolError>() {
+            Ok(tool_error) => tool_error,
+            Err(err) => Self::execution_failed(err.to_string()),
+        }
+    }
+}
+
+impl From<ToolError> for rmcp::ErrorData {
+    fn from(err: ToolError) -> Self {
+        rmcp::ErrorData::new(err.code(), err.message().to_string(), Some(err.to_json()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that every kind maps to a distinct error code
+    #[test]
+    fn test_error_codes_are_distinct() {
+        let errors = [
+            ToolError::ToolNotFound("x".into()),
+            ToolError::ForbiddenCommand("x".into()),
+            ToolError::InvalidArguments("x".into()),
+            ToolError::Timeout("x".into()),
+            ToolError::execution_failed("x"),
+            ToolError::RpcUnavailable("x".into()),
+            ToolError::RateLimited {
+                message: "x".into(),
+                retry_after_secs: 1,
+            },
+        ];
+
+        let mut codes: Vec<i32> = errors.iter().map(|e| e.code().0).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+    }
+
+    /// Test that conversion to an MCP error keeps the message and kind
+    #[test]
+    fn test_into_mcp_error_preserves_kind() {
+        let err: rmcp::ErrorData = ToolError::ToolNotFound("Tool 'x' not found".into()).into();
+        assert_eq!(err.code, ErrorCode(-32010));
+        assert_eq!(err.message, "Tool 'x' not found");
+        assert_eq!(err.data.unwrap()["kind"], "tool_not_found");
+    }
+
+    /// Test that execution failures become error tool results with structured content
+    #[test]
+    fn test_into_call_tool_result_includes_exit_code() {
+        let result = ToolError::ExecutionFailed {
+            message: "boom".into(),
+            exit_code: Some(2),
+        }
+        .into_call_tool_result();
+
+        assert_eq!(result.is_error, Some(true));
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["error"]["kind"], "execution_failed");
+        assert_eq!(structured["error"]["exit_code"], 2);
+    }
+
+    /// Test that typed errors survive a round trip through anyhow
+    #[test]
+    fn test_from_anyhow_downcasts_tool_error() {
+        let err: anyhow::Error = ToolError::Timeout("too slow".into()).into();
+        assert_eq!(ToolError::from(err), ToolError::Timeout("too slow".into()));
+
+        let err = anyhow::anyhow!("plain failure");
+        assert_eq!(
+            ToolError::from(err),
+            ToolError::execution_failed("plain failure")
+        );
+    }
+}
@@ -0,0 +1,962 @@
+//! Block explorer source verification lookups
+//!
+//! `contract_verified` asks a chain's block explorer whether a contract's source is
+//! verified and returns its ABI if so, ready for the decode and call tools. The explorers
+//! come from the chain's chainlist.org record: Blockscout instances are queried through
+//! their keyless Etherscan-compatible API, and Etherscan-family explorers through the
+//! Etherscan V2 API, which needs an API key.
+//!
+//! `fetch_abi` returns just a verified contract's ABI, with its functions and events as
+//! signatures the decode, calldata and call tools accept. ABIs are cached by chain and
+//! address for the life of the server, so repeated lookups don't hit the explorer.
+//!
+//! `cast_interface` turns an ABI into a Solidity interface with `cast interface`. Given an
+//! address, the ABI comes from the same explorers and cache, so Blockscout chains work
+//! without a key and cast needs no explorer configuration of its own.
+
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::cast;
+use crate::chainlist::{self, ChainInfo};
+use crate::error::ToolError;
+use crate::foundry::FoundryExecutor;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// Etherscan's multichain API, covering every Etherscan-family explorer
+const ETHERSCAN_V2_API: &str = "https://api.etherscan.io/v2/api";
+
+/// Environment variable read when `etherscan_api_key` isn't configured, as Foundry does
+const ETHERSCAN_API_KEY_ENV: &str = "ETHERSCAN_API_KEY";
+
+/// Answer Etherscan-compatible APIs give for a contract without verified source
+const NOT_VERIFIED_ABI: &str = "Contract source code not verified";
+
+/// A verified contract's ABI, as fetched from an explorer
+#[derive(Debug, Clone, PartialEq)]
+struct CachedAbi {
+    abi: Value,
+    contract_name: Option<String>,
+    explorer: String,
+}
+
+/// Verified ABIs by chain ID and lowercased address. Verified source doesn't change, so
+/// entries are kept until the server stops or a refresh is asked for.
+static ABI_CACHE: Lazy<Mutex<HashMap<(u64, String), CachedAbi>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn abi_cache_key(chain_id: u64, address: &str) -> (u64, String) {
+    (chain_id, address.to_lowercase())
+}
+
+/// An explorer API that can answer `getsourcecode`
+#[derive(Debug, Clone, PartialEq)]
+enum ExplorerApi {
+    /// Etherscan V2, selected by chain ID
+    Etherscan { chain_id: u64 },
+    /// A Blockscout instance's Etherscan-compatible API
+    Blockscout { api_url: String },
+}
+
+/// The explorer's answer for one contract
+#[derive(Debug, Clone, PartialEq)]
+struct Verification {
+    verified: bool,
+    contract_name: Option<String>,
+    compiler_version: Option<String>,
+    abi: Option<Value>,
+    /// Implementation address when the explorer recognizes a proxy
+    implementation: Option<String>,
+}
+
+/// Get the explorer tools
+pub fn get_explorer_tools() -> Vec<Tool> {
+    vec![
+        contract_verified_tool(),
+        fetch_abi_tool(),
+        cast_interface_tool(),
+    ]
+}
+
+/// Properties naming a contract on a chain, shared by the explorer tools
+fn contract_properties() -> JsonObject {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "address".to_string(),
+        json!({
+            "type": "string",
+            "description": "Contract address"
+        }),
+    );
+    props.insert(
+        "chain".to_string(),
+        json!({
+            "type": "string",
+            "description": "Chain name or ID, e.g. 'base' or '10'"
+        }),
+    );
+    props
+}
+
+fn contract_verified_tool() -> Tool {
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert(
+        "properties".to_string(),
+        Value::Object(contract_properties()),
+    );
+    schema.insert("required".to_string(), json!(["address", "chain"]));
+
+    Tool::new(
+        "contract_verified".to_string(),
+        "Check whether a contract's source is verified on the chain's block explorer (from chainlist.org) and return its ABI, name and compiler version if so. Blockscout explorers need no key; Etherscan-family explorers need etherscan_api_key or ETHERSCAN_API_KEY.".to_string(),
+        Arc::new(schema),
+    )
+}
+
+fn fetch_abi_tool() -> Tool {
+    let mut props = contract_properties();
+    props.insert(
+        "refresh".to_string(),
+        json!({
+            "type": "boolean",
+            "description": "Fetch from the explorer even if the ABI is cached (default: false)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+    schema.insert("required".to_string(), json!(["address", "chain"]));
+
+    Tool::new(
+        "fetch_abi".to_string(),
+        "Fetch a verified contract's ABI from the chain's block explorer and cache it by chain and address. Also lists its functions and events as signatures ready for abi_decode, encode_calldata, cast_call and cast_logs. Fails if the source isn't verified.".to_string(),
+        Arc::new(schema),
+    )
+}
+
+fn cast_interface_tool() -> Tool {
+    let mut props = contract_properties();
+    props.insert(
+        "abi".to_string(),
+        json!({
+            "description": "ABI as JSON (array or string), or the path to an ABI JSON file, instead of address and chain"
+        }),
+    );
+    props.insert(
+        "name".to_string(),
+        json!({
+            "type": "string",
+            "description": "Name of the generated interface (default: 'I' + the verified contract name, or 'Interface')"
+        }),
+    );
+    props.insert(
+        "pragma".to_string(),
+        json!({
+            "type": "string",
+            "description": "Solidity pragma version (default: ^0.8.4)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+
+    Tool::new(
+        "cast_interface".to_string(),
+        "Generate a Solidity interface from a verified contract (address and chain, with the ABI fetched from the chain's block explorer found on chainlist.org) or from an ABI given as JSON or a file path. Returns the interface source.".to_string(),
+        Arc::new(schema),
+    )
+}
+
+/// The API key to use for Etherscan, from the config or the environment
+fn etherscan_api_key(configured: Option<&str>) -> Option<String> {
+    let usable = |key: &String| !key.trim().is_empty();
+    configured
+        .map(str::to_string)
+        .filter(usable)
+        .or_else(|| std::env::var(ETHERSCAN_API_KEY_ENV).ok().filter(usable))
+}
+
+/// Work out which explorer APIs can be asked about the chain, in the order to try them.
+///
+/// Etherscan comes first when there is a key for it, since it usually has the most
+/// verified contracts. Fails with a message naming the missing key when Etherscan is the
+/// only option.
+fn explorer_apis(chain: &ChainInfo, has_api_key: bool) -> Result<Vec<ExplorerApi>, ToolError> {
+    let mut etherscan = false;
+    let mut apis = Vec::new();
+    for explorer in &chain.explorers {
+        let name = explorer["name"].as_str().unwrap_or_default().to_lowercase();
+        let Some(url) = explorer["url"].as_str() else {
+            continue;
+        };
+        let host = url
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        if name.contains("blockscout") || host.contains("blockscout") {
+            apis.push(ExplorerApi::Blockscout {
+                api_url: format!("{}/api", url.trim_end_matches('/')),
+            });
+        } else if name.contains("etherscan") || host.contains("scan.") {
+            etherscan = true;
+        }
+    }
+
+    if etherscan && has_api_key {
+        apis.insert(
+            0,
+            ExplorerApi::Etherscan {
+                chain_id: chain.chain_id,
+            },
+        );
+    }
+    if !apis.is_empty() {
+        return Ok(apis);
+    }
+
+    Err(ToolError::execution_failed(if etherscan {
+        format!(
+            "{}'s explorer is Etherscan, which needs an API key: set etherscan_api_key in the config or the {} environment variable",
+            chain.name, ETHERSCAN_API_KEY_ENV
+        )
+    } else {
+        format!(
+            "chainlist.org lists no Etherscan or Blockscout explorer for {}",
+            chain.name
+        )
+    }))
+}
+
+/// Read a `getsourcecode` response
+fn parse_source_response(response: &Value) -> Result<Verification> {
+    let entry = match &response["result"] {
+        Value::Array(results) if !results.is_empty() => &results[0],
+        // Errors such as a bad API key come back as a bare message
+        Value::String(message) => anyhow::bail!("{}", message),
+        _ => anyhow::bail!("Unexpected response: {}", response),
+    };
+    let field = |name: &str| {
+        entry[name]
+            .as_str()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+
+    let abi = field("ABI").filter(|abi| abi != NOT_VERIFIED_ABI);
+    if field("SourceCode").is_none() || abi.is_none() {
+        return Ok(Verification {
+            verified: false,
+            contract_name: None,
+            compiler_version: None,
+            abi: None,
+            implementation: None,
+        });
+    }
+
+    let abi = abi
+        .map(|abi| serde_json::from_str(&abi).context("Explorer returned a malformed ABI"))
+        .transpose()?;
+    Ok(Verification {
+        verified: true,
+        contract_name: field("ContractName"),
+        compiler_version: field("CompilerVersion"),
+        abi,
+        implementation: field("Implementation"),
+    })
+}
+
+/// Ask one explorer API about a contract
+async fn query_explorer(
+    client: &reqwest::Client,
+    api: &ExplorerApi,
+    address: &str,
+    api_key: Option<&str>,
+) -> Result<Verification> {
+    let mut params = vec![
+        ("module", "contract".to_string()),
+        ("action", "getsourcecode".to_string()),
+        ("address", address.to_string()),
+    ];
+    let url = match api {
+        ExplorerApi::Etherscan { chain_id } => {
+            params.push(("chainid", chain_id.to_string()));
+            params.push(("apikey", api_key.unwrap_or_default().to_string()));
+            ETHERSCAN_V2_API
+        }
+        ExplorerApi::Blockscout { api_url } => api_url.as_str(),
+    };
+
+    // Errors carry the request URL, which would include the API key
+    let response: Value = client
+        .get(url)
+        .query(&params)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.without_url())?
+        .json()
+        .await
+        .map_err(|e| e.without_url())?;
+    parse_source_response(&response)
+}
+
+fn api_label(api: &ExplorerApi) -> String {
+    match api {
+        ExplorerApi::Etherscan { .. } => "etherscan".to_string(),
+        ExplorerApi::Blockscout { api_url } => api_url.clone(),
+    }
+}
+
+/// Check the address against each explorer until one answers
+async fn check_verified(
+    chain: &ChainInfo,
+    address: &str,
+    api_key: Option<&str>,
+) -> Result<(Verification, ExplorerApi), ToolError> {
+    let apis = explorer_apis(chain, api_key.is_some())?;
+    let client = crate::http::client()
+        .map_err(|e| ToolError::RpcUnavailable(format!("Failed to create HTTP client: {:#}", e)))?;
+
+    let mut failures = Vec::new();
+    for api in apis {
+        match query_explorer(&client, &api, address, api_key).await {
+            Ok(verification) => return Ok((verification, api)),
+            Err(e) => failures.push(format!("{}: {:#}", api_label(&api), e)),
+        }
+    }
+    Err(ToolError::RpcUnavailable(format!(
+        "No explorer for {} answered:\n- {}",
+        chain.name,
+        failures.join("\n- ")
+    )))
+}
+
+/// An ABI parameter's type as it appears in a signature, with tuples spelled out
+fn param_type(param: &Value) -> String {
+    let ty = param["type"].as_str().unwrap_or_default();
+    match ty.strip_prefix("tuple") {
+        Some(suffix) => {
+            let components = param["components"]
+                .as_array()
+                .map(|c| c.iter().map(param_type).collect::<Vec<_>>().join(","))
+                .unwrap_or_default();
+            format!("({}){}", components, suffix)
+        }
+        None => ty.to_string(),
+    }
+}
+
+fn param_types(params: &Value) -> String {
+    params
+        .as_array()
+        .map(|p| p.iter().map(param_type).collect::<Vec<_>>().join(","))
+        .unwrap_or_default()
+}
+
+/// List an ABI's functions, as `name(inputs)(outputs)` signatures with their state
+/// mutability, and its events, as signatures with `indexed` markers and parameter names
+fn abi_signatures(abi: &Value) -> (Vec<Value>, Vec<String>) {
+    let mut functions = Vec::new();
+    let mut events = Vec::new();
+    for item in abi.as_array().into_iter().flatten() {
+        let name = item["name"].as_str().unwrap_or_default();
+        match item["type"].as_str() {
+            Some("function") => {
+                let mut signature = format!("{}({})", name, param_types(&item["inputs"]));
+                let outputs = param_types(&item["outputs"]);
+                if !outputs.is_empty() {
+                    signature.push_str(&format!("({})", outputs));
+                }
+                functions.push(json!({
+                    "name": name,
+                    "signature": signature,
+                    "state_mutability": item["stateMutability"],
+                }));
+            }
+            Some("event") => {
+                let params: Vec<String> = item["inputs"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|input| {
+                        let mut param = param_type(input);
+                        if input["indexed"].as_bool() == Some(true) {
+                            param.push_str(" indexed");
+                        }
+                        match input["name"].as_str().filter(|n| !n.is_empty()) {
+                            Some(name) => format!("{} {}", param, name),
+                            None => param,
+                        }
+                    })
+                    .collect();
+                events.push(format!("{}({})", name, params.join(", ")));
+            }
+            _ => {}
+        }
+    }
+    (functions, events)
+}
+
+/// Read and check the `address` and `chain` arguments
+fn contract_arguments(arguments: &Option<JsonObject>) -> Result<(&str, &str), rmcp::ErrorData> {
+    let args = arguments
+        .as_ref()
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing arguments", None))?;
+    let get = |name: &str| {
+        args.get(name)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .ok_or_else(|| {
+                rmcp::ErrorData::invalid_params(format!("Missing '{}' parameter", name), None)
+            })
+    };
+    let address = get("address")?;
+    let query = get("chain")?;
+
+    let hex = address.strip_prefix("0x").unwrap_or_default();
+    if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ToolError::InvalidArguments(format!("'{}' is not an address", address)).into());
+    }
+    Ok((address, query))
+}
+
+/// Look up a chain by name or ID in chainlist.org
+async fn find_chain(query: &str) -> Result<ChainInfo, ToolError> {
+    let chains = chainlist::fetch_chainlist().await.map_err(|e| {
+        ToolError::RpcUnavailable(format!("Failed to fetch chainlist data: {:#}", e))
+    })?;
+    chainlist::find_chain_rpcs(&chains, query)
+        .cloned()
+        .ok_or_else(|| {
+            ToolError::InvalidArguments(format!("Chain '{}' not found in chainlist.org", query))
+        })
+}
+
+/// Remember a verified contract's ABI, returning the cached entry
+fn cache_abi(
+    chain_id: u64,
+    address: &str,
+    verification: &Verification,
+    api: &ExplorerApi,
+) -> Option<CachedAbi> {
+    let entry = CachedAbi {
+        abi: verification.abi.clone()?,
+        contract_name: verification.contract_name.clone(),
+        explorer: api_label(api),
+    };
+    ABI_CACHE
+        .lock()
+        .unwrap()
+        .insert(abi_cache_key(chain_id, address), entry.clone());
+    Some(entry)
+}
+
+/// Get a verified contract's ABI from the cache, or from the explorer when it isn't cached
+/// or `refresh` is set. Also says whether it came from the cache.
+async fn verified_abi(
+    chain: &ChainInfo,
+    address: &str,
+    api_key: Option<&str>,
+    refresh: bool,
+) -> Result<(CachedAbi, bool), ToolError> {
+    let cached = ABI_CACHE
+        .lock()
+        .unwrap()
+        .get(&abi_cache_key(chain.chain_id, address))
+        .filter(|_| !refresh)
+        .cloned();
+    if let Some(entry) = cached {
+        return Ok((entry, true));
+    }
+
+    let (verification, api) = check_verified(chain, address, api_key).await?;
+    if !verification.verified {
+        return Err(ToolError::execution_failed(format!(
+            "{} on {} has no verified source on {}, so its ABI isn't available",
+            address,
+            chain.name,
+            api_label(&api)
+        )));
+    }
+    let entry = cache_abi(chain.chain_id, address, &verification, &api).ok_or_else(|| {
+        ToolError::execution_failed("The explorer reported verified source without an ABI")
+    })?;
+    Ok((entry, false))
+}
+
+/// Handle contract_verified tool call
+pub async fn handle_contract_verified(
+    arguments: &Option<JsonObject>,
+    configured_api_key: Option<&str>,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let (address, query) = contract_arguments(arguments)?;
+    let chain = &find_chain(query).await?;
+
+    let api_key = etherscan_api_key(configured_api_key);
+    let (verification, api) = match check_verified(chain, address, api_key.as_deref()).await {
+        Ok(answer) => answer,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+    cache_abi(chain.chain_id, address, &verification, &api);
+
+    let mut text = if verification.verified {
+        format!(
+            "{} on {} is verified as {} ({})",
+            address,
+            chain.name,
+            verification.contract_name.as_deref().unwrap_or("unnamed"),
+            verification
+                .compiler_version
+                .as_deref()
+                .unwrap_or("unknown compiler")
+        )
+    } else {
+        format!("{} on {} has no verified source", address, chain.name)
+    };
+    if let Some(implementation) = &verification.implementation {
+        text.push_str(&format!("\nProxy for implementation {}", implementation));
+    }
+    if let Some(abi) = &verification.abi {
+        text.push_str(&format!("\n\nABI:\n{}", abi));
+    }
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(json!({
+        "address": address,
+        "chain": chain.name,
+        "chain_id": chain.chain_id,
+        "verified": verification.verified,
+        "contract_name": verification.contract_name,
+        "compiler_version": verification.compiler_version,
+        "implementation": verification.implementation,
+        "abi": verification.abi,
+        "explorer": api_label(&api),
+    }));
+    Ok(result)
+}
+
+/// Handle fetch_abi tool call
+pub async fn handle_fetch_abi(
+    arguments: &Option<JsonObject>,
+    configured_api_key: Option<&str>,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let (address, query) = contract_arguments(arguments)?;
+    let refresh = arguments
+        .as_ref()
+        .and_then(|args| args.get("refresh"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let chain = &find_chain(query).await?;
+
+    let api_key = etherscan_api_key(configured_api_key);
+    let (entry, from_cache) = match verified_abi(chain, address, api_key.as_deref(), refresh).await
+    {
+        Ok(answer) => answer,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let (functions, events) = abi_signatures(&entry.abi);
+    let mut text = format!(
+        "ABI of {} ({}) on {}{}: {} functions, {} events",
+        address,
+        entry.contract_name.as_deref().unwrap_or("unnamed"),
+        chain.name,
+        if from_cache { ", cached" } else { "" },
+        functions.len(),
+        events.len()
+    );
+    for function in &functions {
+        text.push_str(&format!(
+            "\n  {}",
+            function["signature"].as_str().unwrap_or_default()
+        ));
+    }
+    for event in &events {
+        text.push_str(&format!("\n  event {}", event));
+    }
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(json!({
+        "address": address,
+        "chain": chain.name,
+        "chain_id": chain.chain_id,
+        "contract_name": entry.contract_name,
+        "abi": entry.abi,
+        "functions": functions,
+        "events": events,
+        "explorer": entry.explorer,
+        "cached": from_cache,
+    }));
+    Ok(result)
+}
+
+/// Where `cast_interface` takes its ABI from
+#[derive(Debug, Clone, PartialEq)]
+enum AbiSource {
+    /// ABI JSON, written to a temporary file for cast
+    Json(Value),
+    /// Path to an ABI JSON file
+    File(String),
+}
+
+/// Read the `abi` argument: a JSON array, a JSON string, or a file path
+fn abi_source(abi: &Value) -> Result<AbiSource, ToolError> {
+    match abi {
+        Value::Array(_) | Value::Object(_) => Ok(AbiSource::Json(abi.clone())),
+        Value::String(text) => {
+            let text = text.trim();
+            if text.starts_with('[') || text.starts_with('{') {
+                serde_json::from_str(text)
+                    .map(AbiSource::Json)
+                    .map_err(|e| {
+                        ToolError::InvalidArguments(format!("'abi' is not valid JSON: {}", e))
+                    })
+            } else if text.is_empty() {
+                Err(ToolError::InvalidArguments("'abi' is empty".to_string()))
+            } else {
+                Ok(AbiSource::File(text.to_string()))
+            }
+        }
+        _ => Err(ToolError::InvalidArguments(
+            "'abi' must be ABI JSON or a file path".to_string(),
+        )),
+    }
+}
+
+/// Run `cast interface` on an ABI, returning the interface source
+fn generate_interface(
+    executor: &FoundryExecutor,
+    source: &AbiSource,
+    name: Option<&str>,
+    pragma: Option<&str>,
+) -> Result<String, ToolError> {
+    use std::io::Write;
+
+    // Kept until cast has read it
+    let mut abi_file = None;
+    let path = match source {
+        AbiSource::File(path) => path.clone(),
+        AbiSource::Json(abi) => {
+            let file = tempfile::Builder::new()
+                .prefix("abi-")
+                .suffix(".json")
+                .tempfile()
+                .and_then(|mut file| {
+                    file.write_all(abi.to_string().as_bytes())?;
+                    Ok(file)
+                })
+                .map_err(|e| {
+                    ToolError::execution_failed(format!("Failed to write the ABI file: {}", e))
+                })?;
+            let path = file.path().to_string_lossy().into_owned();
+            abi_file = Some(file);
+            path
+        }
+    };
+
+    let mut interface_args = JsonObject::new();
+    interface_args.insert("contract".to_string(), json!(path));
+    if let Some(name) = name {
+        interface_args.insert("name".to_string(), json!(name));
+    }
+    if let Some(pragma) = pragma {
+        interface_args.insert("pragma".to_string(), json!(pragma));
+    }
+    let interface = cast::run_tool(executor, "cast_interface", interface_args, &[]);
+    drop(abi_file);
+    interface
+}
+
+/// Handle cast_interface tool call
+pub async fn handle_cast_interface(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+    configured_api_key: Option<&str>,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let empty = JsonObject::new();
+    let args = arguments.as_ref().unwrap_or(&empty);
+    let get = |name: &str| {
+        args.get(name)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+
+    let (source, default_name, origin) = match (args.get("abi"), args.get("address")) {
+        (Some(abi), None) => (abi_source(abi)?, None, json!({ "source": "abi" })),
+        (None, Some(_)) => {
+            let (address, query) = contract_arguments(arguments)?;
+            let chain = &find_chain(query).await?;
+            let api_key = etherscan_api_key(configured_api_key);
+            let (entry, from_cache) =
+                match verified_abi(chain, address, api_key.as_deref(), false).await {
+                    Ok(answer) => answer,
+                    Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+                    Err(e) => return Err(e.into()),
+                };
+            let origin = json!({
+                "source": "explorer",
+                "address": address,
+                "chain": chain.name,
+                "chain_id": chain.chain_id,
+                "contract_name": entry.contract_name,
+                "explorer": entry.explorer,
+                "cached": from_cache,
+            });
+            let default_name = entry.contract_name.as_ref().map(|n| format!("I{}", n));
+            (AbiSource::Json(entry.abi), default_name, origin)
+        }
+        _ => {
+            return Err(ToolError::InvalidArguments(
+                "Give either 'abi', or 'address' and 'chain'".to_string(),
+            )
+            .into())
+        }
+    };
+
+    let name = get("name").map(str::to_string).or(default_name);
+    let interface = match generate_interface(executor, &source, name.as_deref(), get("pragma")) {
+        Ok(interface) => interface,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut structured = origin;
+    structured["name"] = json!(name);
+    structured["interface"] = json!(interface);
+    let mut result = CallToolResult::success(vec![Content::text(interface)]);
+    result.structured_content = Some(structured);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(explorers: Value) -> ChainInfo {
+        serde_json::from_value(json!({
+            "name": "Test Chain",
+            "chain": "TEST",
+            "chainId": 8453,
+            "shortName": "test",
+            "explorers": explorers,
+        }))
+        .unwrap()
+    }
+
+    /// Test that Etherscan is preferred with a key and Blockscout is used without one
+    #[test]
+    fn test_explorer_apis() {
+        let both = chain(json!([
+            {"name": "basescan", "url": "https://basescan.org", "standard": "EIP3091"},
+            {"name": "blockscout", "url": "https://base.blockscout.com/", "standard": "EIP3091"}
+        ]));
+        let blockscout = ExplorerApi::Blockscout {
+            api_url: "https://base.blockscout.com/api".to_string(),
+        };
+        assert_eq!(
+            explorer_apis(&both, true).unwrap(),
+            vec![
+                ExplorerApi::Etherscan { chain_id: 8453 },
+                blockscout.clone()
+            ]
+        );
+        assert_eq!(explorer_apis(&both, false).unwrap(), vec![blockscout]);
+    }
+
+    /// Test that a missing key or explorer is reported clearly
+    #[test]
+    fn test_explorer_apis_unavailable() {
+        let etherscan = chain(json!([{"name": "etherscan", "url": "https://etherscan.io"}]));
+        let err = explorer_apis(&etherscan, false).unwrap_err();
+        assert!(err.is_execution_error());
+        assert!(err.to_string().contains(ETHERSCAN_API_KEY_ENV));
+
+        let other = chain(json!([{"name": "otherexplorer", "url": "https://explorer.example"}]));
+        assert!(explorer_apis(&other, true)
+            .unwrap_err()
+            .to_string()
+            .contains("no Etherscan or Blockscout"));
+    }
+
+    /// Test that verified and unverified answers are told apart
+    #[test]
+    fn test_parse_source_response() {
+        let verified = parse_source_response(&json!({
+            "status": "1",
+            "message": "OK",
+            "result": [{
+                "SourceCode": "contract Token {}",
+                "ABI": "[{\"type\":\"function\",\"name\":\"totalSupply\"}]",
+                "ContractName": "Token",
+                "CompilerVersion": "v0.8.24+commit.e11b9ed9",
+                "Proxy": "0",
+                "Implementation": ""
+            }]
+        }))
+        .unwrap();
+        assert!(verified.verified);
+        assert_eq!(verified.contract_name.as_deref(), Some("Token"));
+        assert_eq!(verified.abi.unwrap()[0]["name"], json!("totalSupply"));
+        assert_eq!(verified.implementation, None);
+
+        let unverified = parse_source_response(&json!({
+            "status": "1",
+            "message": "OK",
+            "result": [{"SourceCode": "", "ABI": NOT_VERIFIED_ABI, "ContractName": ""}]
+        }))
+        .unwrap();
+        assert!(!unverified.verified);
+        assert_eq!(unverified.abi, None);
+
+        let error = parse_source_response(&json!({
+            "status": "0",
+            "message": "NOTOK",
+            "result": "Invalid API Key"
+        }))
+        .unwrap_err();
+        assert_eq!(error.to_string(), "Invalid API Key");
+    }
+
+    /// Test that functions and events are listed as signatures, tuples spelled out
+    #[test]
+    fn test_abi_signatures() {
+        let abi = json!([
+            {
+                "type": "function",
+                "name": "balanceOf",
+                "inputs": [{"name": "owner", "type": "address"}],
+                "outputs": [{"name": "", "type": "uint256"}],
+                "stateMutability": "view"
+            },
+            {
+                "type": "function",
+                "name": "submit",
+                "inputs": [{
+                    "name": "orders",
+                    "type": "tuple[]",
+                    "components": [{"name": "to", "type": "address"}, {"name": "amount", "type": "uint256"}]
+                }],
+                "outputs": [],
+                "stateMutability": "nonpayable"
+            },
+            {
+                "type": "event",
+                "name": "Transfer",
+                "inputs": [
+                    {"name": "from", "type": "address", "indexed": true},
+                    {"name": "to", "type": "address", "indexed": true},
+                    {"name": "value", "type": "uint256", "indexed": false}
+                ]
+            },
+            {"type": "constructor", "inputs": []}
+        ]);
+
+        let (functions, events) = abi_signatures(&abi);
+        assert_eq!(
+            functions[0]["signature"],
+            json!("balanceOf(address)(uint256)")
+        );
+        assert_eq!(functions[0]["state_mutability"], json!("view"));
+        assert_eq!(
+            functions[1]["signature"],
+            json!("submit((address,uint256)[])")
+        );
+        assert_eq!(functions.len(), 2);
+        assert_eq!(
+            events,
+            vec!["Transfer(address indexed from, address indexed to, uint256 value)"]
+        );
+    }
+
+    /// Test that ABIs are cached per chain regardless of address case
+    #[test]
+    fn test_cache_abi() {
+        let address = "0x00000000000000000000000000000000000000Ab";
+        let verification = Verification {
+            verified: true,
+            contract_name: Some("Token".to_string()),
+            compiler_version: None,
+            abi: Some(json!([])),
+            implementation: None,
+        };
+        let api = ExplorerApi::Etherscan { chain_id: 999_001 };
+        cache_abi(999_001, address, &verification, &api);
+
+        let cache = ABI_CACHE.lock().unwrap();
+        let entry = cache
+            .get(&abi_cache_key(
+                999_001,
+                &address.to_uppercase().replace('X', "x"),
+            ))
+            .unwrap();
+        assert_eq!(entry.contract_name.as_deref(), Some("Token"));
+        assert_eq!(entry.explorer, "etherscan");
+        assert!(!cache.contains_key(&abi_cache_key(999_002, address)));
+    }
+
+    /// Test that a configured key wins and blank keys count as missing
+    #[test]
+    fn test_etherscan_api_key() {
+        assert_eq!(etherscan_api_key(Some("KEY")), Some("KEY".to_string()));
+        if std::env::var(ETHERSCAN_API_KEY_ENV).is_err() {
+            assert_eq!(etherscan_api_key(Some(" ")), None);
+            assert_eq!(etherscan_api_key(None), None);
+        }
+    }
+
+    /// Test that the ABI is taken as JSON when it looks like JSON and as a path otherwise
+    #[test]
+    fn test_abi_source() {
+        let abi = json!([{"type": "function", "name": "totalSupply"}]);
+        assert_eq!(abi_source(&abi).unwrap(), AbiSource::Json(abi.clone()));
+        assert_eq!(
+            abi_source(&json!(abi.to_string())).unwrap(),
+            AbiSource::Json(abi)
+        );
+        assert_eq!(
+            abi_source(&json!("out/Token.sol/Token.json")).unwrap(),
+            AbiSource::File("out/Token.sol/Token.json".to_string())
+        );
+        for bad in [json!("[{"), json!(" "), json!(42)] {
+            assert!(matches!(
+                abi_source(&bad),
+                Err(ToolError::InvalidArguments(_))
+            ));
+        }
+    }
+
+    /// Test that cast_interface needs exactly one of an ABI or a contract
+    #[tokio::test]
+    async fn test_cast_interface_arguments() {
+        let executor = FoundryExecutor::with_config(
+            crate::schema::SchemaFile { tools: vec![] },
+            crate::config::Config::default(),
+        );
+        for args in [
+            json!({}),
+            json!({"abi": "[]", "address": "0x0000000000000000000000000000000000000001"}),
+            json!({"address": "0x01", "chain": "1"}),
+        ] {
+            let err = handle_cast_interface(&args.as_object().cloned(), &executor, None)
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, rmcp::model::ErrorCode::INVALID_PARAMS);
+        }
+    }
+}
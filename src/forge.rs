@@ -0,0 +1,1310 @@
+//! Forge workflow helpers
+//!
+//! Built-in tools that run a schema-driven `forge` command through
+//! [`FoundryExecutor`] and turn its output into something a client can act on without
+//! reading the raw log. The underlying command stays subject to the tool allowlist, so
+//! forbidding `forge_test` also disables the helpers built on it.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::diagnostics;
+use crate::error::ToolError;
+use crate::foundry::FoundryExecutor;
+use crate::project;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// A test that did not pass
+#[derive(Debug, Clone, PartialEq)]
+struct TestFailure {
+    /// Test suite, as `path:Contract`
+    suite: String,
+    test: String,
+    reason: Option<String>,
+}
+
+/// Pass/fail counts from a `forge test --json` run
+#[derive(Debug, Default, PartialEq)]
+struct TestSummary {
+    suites: usize,
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+    failures: Vec<TestFailure>,
+}
+
+/// One column of a coverage report, e.g. `85.71% (6/7)`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct CoverageMetric {
+    percent: f64,
+    covered: u64,
+    total: u64,
+}
+
+/// A row of the `forge coverage --report summary` table
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct FileCoverage {
+    file: String,
+    /// Keyed by metric: `lines`, `statements`, `branches`, `functions`
+    #[serde(flatten)]
+    metrics: BTreeMap<String, CoverageMetric>,
+}
+
+impl FileCoverage {
+    /// Line coverage, which files are ranked and checked against the threshold by
+    fn line_percent(&self) -> f64 {
+        self.metrics.get("lines").map_or(0.0, |m| m.percent)
+    }
+
+    fn describe(&self) -> String {
+        self.metrics
+            .iter()
+            .map(|(name, m)| format!("{:.2}% {} ({}/{})", m.percent, name, m.covered, m.total))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// An import remapping, `[context:]from=to`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct Remapping {
+    /// Directory the remapping is limited to, if any
+    context: Option<String>,
+    from: String,
+    to: String,
+    /// `foundry.toml`, `remappings.txt` or `auto-detected`
+    source: &'static str,
+}
+
+/// Highest verbosity forge accepts (`-vvvvv`)
+const MAX_VERBOSITY: u64 = 5;
+
+/// How long a coverage run may take unless the caller says otherwise
+const DEFAULT_COVERAGE_TIMEOUT_SECS: u64 = 600;
+
+/// Artifact fields `forge_inspect` accepts, as forge names them
+const INSPECT_FIELDS: &[&str] = &[
+    "abi",
+    "bytecode",
+    "deployedBytecode",
+    "assembly",
+    "assemblyOptimized",
+    "methodIdentifiers",
+    "gasEstimates",
+    "storageLayout",
+    "devdoc",
+    "userdoc",
+    "metadata",
+    "ir",
+    "irOptimized",
+    "errors",
+    "events",
+];
+
+/// Fields forge prints as text rather than JSON
+const INSPECT_TEXT_FIELDS: &[&str] = &[
+    "bytecode",
+    "deployedBytecode",
+    "assembly",
+    "assemblyOptimized",
+    "ir",
+    "irOptimized",
+];
+
+/// Shorthand field names, mapped to the field forge is asked for
+const INSPECT_FIELD_ALIASES: &[(&str, &str)] = &[("methods", "methodIdentifiers")];
+
+/// A contract identifier, `(<path>:)?<ContractName>`
+static CONTRACT_ID: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:[^:\s]+:)?[A-Za-z_$][A-Za-z0-9_$]*$").unwrap());
+
+/// Get the forge helper tools
+pub fn get_forge_tools() -> Vec<Tool> {
+    vec![
+        test_summary_tool(),
+        test_focused_tool(),
+        coverage_summary_tool(),
+        fmt_check_tool(),
+        remappings_tool(),
+        inspect_tool(),
+    ]
+}
+
+fn test_summary_tool() -> Tool {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "root".to_string(),
+        json!({
+            "type": "string",
+            "description": "Project root directory (default: the server's working directory)"
+        }),
+    );
+    props.insert(
+        "match_test".to_string(),
+        json!({
+            "type": "string",
+            "description": "Only run test functions matching this regex (optional)"
+        }),
+    );
+    props.insert(
+        "match_contract".to_string(),
+        json!({
+            "type": "string",
+            "description": "Only run tests in contracts matching this regex (optional)"
+        }),
+    );
+    props.insert(
+        "match_path".to_string(),
+        json!({
+            "type": "string",
+            "description": "Only run tests in source files matching this glob (optional)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+
+    Tool::new(
+        "forge_test_summary".to_string(),
+        "Run forge test and return the number of passed, failed and skipped tests, plus each failing test with its revert reason, instead of the full test log.".to_string(),
+        Arc::new(schema),
+    )
+}
+
+fn test_focused_tool() -> Tool {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "contract".to_string(),
+        json!({
+            "type": "string",
+            "description": "Test contract name, as a regex (e.g. 'TokenTest' or '^TokenTest$')"
+        }),
+    );
+    props.insert(
+        "test".to_string(),
+        json!({
+            "type": "string",
+            "description": "Test function name, as a regex (e.g. 'testTransfer' or '^testTransfer$')"
+        }),
+    );
+    props.insert(
+        "path".to_string(),
+        json!({
+            "type": "string",
+            "description": "Glob for the test source files (e.g. 'test/Token.t.sol')"
+        }),
+    );
+    props.insert(
+        "verbosity".to_string(),
+        json!({
+            "type": "integer",
+            "minimum": 0,
+            "maximum": MAX_VERBOSITY,
+            "description": "Trace verbosity, as the number of -v flags: 2 shows logs, 3 traces of failing tests, 4 all traces, 5 setup traces too (default: 0)"
+        }),
+    );
+    props.insert(
+        "root".to_string(),
+        json!({
+            "type": "string",
+            "description": "Project root directory (default: the server's working directory)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+
+    Tool::new(
+        "forge_test_focused".to_string(),
+        "Run only the tests matching a contract, test function and/or file path, without needing forge's --match-* flag names. At least one of contract, test or path is required.".to_string(),
+        Arc::new(schema),
+    )
+}
+
+fn coverage_summary_tool() -> Tool {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "root".to_string(),
+        json!({
+            "type": "string",
+            "description": "Project root directory (default: the server's working directory)"
+        }),
+    );
+    props.insert(
+        "match_test".to_string(),
+        json!({
+            "type": "string",
+            "description": "Only run test functions matching this regex (optional)"
+        }),
+    );
+    props.insert(
+        "match_contract".to_string(),
+        json!({
+            "type": "string",
+            "description": "Only run tests in contracts matching this regex (optional)"
+        }),
+    );
+    props.insert(
+        "match_path".to_string(),
+        json!({
+            "type": "string",
+            "description": "Only run tests in source files matching this glob (optional)"
+        }),
+    );
+    props.insert(
+        "min_threshold".to_string(),
+        json!({
+            "type": "number",
+            "minimum": 0,
+            "maximum": 100,
+            "description": "Flag files whose line coverage is below this percentage (optional)"
+        }),
+    );
+    props.insert(
+        "timeout_secs".to_string(),
+        json!({
+            "type": "integer",
+            "minimum": 1,
+            "description": format!("Stop the coverage run after this many seconds (default: {})", DEFAULT_COVERAGE_TIMEOUT_SECS)
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+
+    Tool::new(
+        "forge_coverage_summary".to_string(),
+        "Run forge coverage and return per-file line, statement, branch and function coverage, lowest line coverage first. Files below min_threshold are flagged.".to_string(),
+        Arc::new(schema),
+    )
+}
+
+fn fmt_check_tool() -> Tool {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "paths".to_string(),
+        json!({
+            "type": "array",
+            "items": {"type": "string"},
+            "description": "Files or directories to check (default: the project's configured sources)"
+        }),
+    );
+    props.insert(
+        "root".to_string(),
+        json!({
+            "type": "string",
+            "description": "Project root directory (default: the server's working directory)"
+        }),
+    );
+    props.insert(
+        "write".to_string(),
+        json!({
+            "type": "boolean",
+            "description": "Format the files that need it instead of only reporting them (default: false)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+
+    Tool::new(
+        "forge_fmt_check".to_string(),
+        "Check whether Solidity files are formatted with forge fmt --check, listing the files that need formatting and the diff. With write: true, formats those files.".to_string(),
+        Arc::new(schema),
+    )
+}
+
+fn remappings_tool() -> Tool {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "root".to_string(),
+        json!({
+            "type": "string",
+            "description": "Project root directory (default: the server's working directory)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+
+    Tool::new(
+        "forge_remappings".to_string(),
+        "List the project's import remappings as from/to pairs, each marked as coming from foundry.toml, remappings.txt or auto-detection from the installed libraries.".to_string(),
+        Arc::new(schema),
+    )
+}
+
+fn inspect_tool() -> Tool {
+    let fields: Vec<&str> = INSPECT_FIELDS
+        .iter()
+        .copied()
+        .chain(INSPECT_FIELD_ALIASES.iter().map(|(alias, _)| *alias))
+        .collect();
+
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "contract".to_string(),
+        json!({
+            "type": "string",
+            "description": "Contract to inspect, as 'ContractName' or 'path/to/File.sol:ContractName'"
+        }),
+    );
+    props.insert(
+        "field".to_string(),
+        json!({
+            "type": "string",
+            "enum": fields,
+            "description": "Artifact field to return ('methods' is short for methodIdentifiers)"
+        }),
+    );
+    props.insert(
+        "root".to_string(),
+        json!({
+            "type": "string",
+            "description": "Project root directory (default: the server's working directory)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+    schema.insert("required".to_string(), json!(["contract", "field"]));
+
+    Tool::new(
+        "forge_inspect".to_string(),
+        "Compile the project if needed and return one field of a contract's artifact with forge inspect: abi, bytecode, methodIdentifiers, storageLayout, gasEstimates and more. JSON fields are returned parsed in the structured content.".to_string(),
+        Arc::new(schema),
+    )
+}
+
+/// Copy string arguments across to the schema tool under its option names
+fn map_string_args(args: &JsonObject, mapping: &[(&str, &str)]) -> JsonObject {
+    let mut mapped = JsonObject::new();
+    for (from, to) in mapping {
+        if let Some(value) = args.get(*from).and_then(|v| v.as_str()) {
+            mapped.insert(to.to_string(), Value::String(value.to_string()));
+        }
+    }
+    mapped
+}
+
+/// Build the `forge_test` arguments for a summary run
+fn test_summary_args(args: &JsonObject) -> JsonObject {
+    let mut mapped = map_string_args(
+        args,
+        &[
+            ("root", "root"),
+            ("match_test", "match-test"),
+            ("match_contract", "match-contract"),
+            ("match_path", "match-path"),
+        ],
+    );
+    mapped.insert("json".to_string(), Value::Bool(true));
+    mapped
+}
+
+/// Build the `forge_test` arguments and trailing `-v` flag for a focused run
+fn test_focused_args(args: &JsonObject) -> Result<(JsonObject, Vec<String>), ToolError> {
+    let mapped = map_string_args(
+        args,
+        &[
+            ("root", "root"),
+            ("contract", "match-contract"),
+            ("test", "match-test"),
+            ("path", "match-path"),
+        ],
+    );
+    let has_target = ["match-contract", "match-test", "match-path"]
+        .iter()
+        .any(|key| mapped.contains_key(*key));
+    if !has_target {
+        return Err(ToolError::InvalidArguments(
+            "Specify at least one of 'contract', 'test' or 'path'".to_string(),
+        ));
+    }
+
+    let verbosity = match args.get("verbosity") {
+        None | Some(Value::Null) => 0,
+        Some(value) => value
+            .as_u64()
+            .filter(|v| *v <= MAX_VERBOSITY)
+            .ok_or_else(|| {
+                ToolError::InvalidArguments(format!(
+                    "'verbosity' must be an integer from 0 to {}",
+                    MAX_VERBOSITY
+                ))
+            })?,
+    };
+    let extra_args = if verbosity > 0 {
+        vec![format!("-{}", "v".repeat(verbosity as usize))]
+    } else {
+        Vec::new()
+    };
+
+    Ok((mapped, extra_args))
+}
+
+/// Build the `forge_coverage` arguments, threshold and timeout for a summary run
+fn coverage_args(args: &JsonObject) -> Result<(JsonObject, Option<f64>, Duration), ToolError> {
+    let mut mapped = map_string_args(
+        args,
+        &[
+            ("root", "root"),
+            ("match_test", "match-test"),
+            ("match_contract", "match-contract"),
+            ("match_path", "match-path"),
+        ],
+    );
+    mapped.insert("report".to_string(), Value::String("summary".to_string()));
+
+    let threshold = match args.get("min_threshold") {
+        None | Some(Value::Null) => None,
+        Some(value) => Some(
+            value
+                .as_f64()
+                .filter(|t| (0.0..=100.0).contains(t))
+                .ok_or_else(|| {
+                    ToolError::InvalidArguments(
+                        "'min_threshold' must be a percentage from 0 to 100".to_string(),
+                    )
+                })?,
+        ),
+    };
+
+    let timeout_secs = match args.get("timeout_secs") {
+        None | Some(Value::Null) => DEFAULT_COVERAGE_TIMEOUT_SECS,
+        Some(value) => value.as_u64().filter(|t| *t > 0).ok_or_else(|| {
+            ToolError::InvalidArguments("'timeout_secs' must be a positive integer".to_string())
+        })?,
+    };
+
+    Ok((mapped, threshold, Duration::from_secs(timeout_secs)))
+}
+
+/// Parse a coverage cell such as `85.71% (6/7)`
+fn parse_coverage_metric(cell: &str) -> Option<CoverageMetric> {
+    let (percent, counts) = cell.split_once('%')?;
+    let counts = counts.trim().strip_prefix('(')?.strip_suffix(')')?;
+    let (covered, total) = counts.split_once('/')?;
+    Some(CoverageMetric {
+        percent: percent.trim().parse().ok()?,
+        covered: covered.trim().parse().ok()?,
+        total: total.trim().parse().ok()?,
+    })
+}
+
+/// Metric key for a coverage table header such as `% Funcs`
+fn coverage_metric_name(header: &str) -> String {
+    match header
+        .trim_start_matches('%')
+        .trim()
+        .to_lowercase()
+        .as_str()
+    {
+        "funcs" => "functions".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Parse the `forge coverage --report summary` table into per-file rows and the total.
+///
+/// Works on both the markdown and the box-drawn table layouts forge has used, by keying
+/// off the `File` header row and skipping any row whose cells aren't coverage figures.
+fn parse_coverage_report(output: &str) -> (Vec<FileCoverage>, Option<FileCoverage>) {
+    let mut headers: Option<Vec<String>> = None;
+    let mut files = Vec::new();
+    let mut total = None;
+
+    for line in output.lines() {
+        let line = line.trim();
+        if !line.starts_with('|') {
+            continue;
+        }
+        let cells: Vec<&str> = line.trim_matches('|').split('|').map(str::trim).collect();
+        if cells.first() == Some(&"File") {
+            headers = Some(cells[1..].iter().map(|h| coverage_metric_name(h)).collect());
+            continue;
+        }
+        let Some(headers) = &headers else {
+            continue;
+        };
+        if cells.len() != headers.len() + 1 {
+            continue;
+        }
+
+        let metrics: Option<BTreeMap<String, CoverageMetric>> = headers
+            .iter()
+            .zip(&cells[1..])
+            .map(|(name, cell)| Some((name.clone(), parse_coverage_metric(cell)?)))
+            .collect();
+        let Some(metrics) = metrics else {
+            continue;
+        };
+
+        let row = FileCoverage {
+            file: cells[0].to_string(),
+            metrics,
+        };
+        if row.file == "Total" {
+            total = Some(row);
+        } else {
+            files.push(row);
+        }
+    }
+
+    files.sort_by(|a, b| a.line_percent().total_cmp(&b.line_percent()));
+    (files, total)
+}
+
+/// Build the `forge_fmt` arguments for a check or a write
+fn fmt_args(args: &JsonObject, check: bool) -> JsonObject {
+    let mut mapped = map_string_args(args, &[("root", "root")]);
+    if let Some(paths) = args.get("paths").and_then(|v| v.as_array()) {
+        mapped.insert("path".to_string(), Value::Array(paths.clone()));
+    }
+    if check {
+        mapped.insert("check".to_string(), Value::Bool(true));
+    }
+    mapped
+}
+
+/// Files named in the `Diff in <file>:` headers of `forge fmt --check` output
+fn parse_unformatted_files(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("Diff in ")?.strip_suffix(':'))
+        .map(String::from)
+        .collect()
+}
+
+/// Split a remapping line into context, prefix and target
+fn split_remapping(line: &str) -> Option<(Option<String>, String, String)> {
+    let (lhs, to) = line.trim().split_once('=')?;
+    let (context, from) = match lhs.split_once(':') {
+        Some((context, from)) => (Some(context.to_string()), from),
+        None => (None, lhs),
+    };
+    if from.is_empty() || to.is_empty() {
+        return None;
+    }
+    Some((context, from.to_string(), to.to_string()))
+}
+
+/// Parse `forge remappings` output, attributing each line to the file that configures it.
+///
+/// Forge normalizes trailing slashes, so entries are compared without them.
+fn parse_remappings(output: &str, configured: &[(String, &'static str)]) -> Vec<Remapping> {
+    let key = |context: &Option<String>, from: &str, to: &str| {
+        (
+            context.clone().unwrap_or_default(),
+            from.trim_end_matches('/').to_string(),
+            to.trim_end_matches('/').to_string(),
+        )
+    };
+    let configured: Vec<_> = configured
+        .iter()
+        .filter_map(|(line, source)| {
+            let (context, from, to) = split_remapping(line)?;
+            Some((key(&context, &from, &to), *source))
+        })
+        .collect();
+
+    output
+        .lines()
+        .filter_map(split_remapping)
+        .map(|(context, from, to)| {
+            let wanted = key(&context, &from, &to);
+            let source = configured
+                .iter()
+                .find(|(k, _)| *k == wanted)
+                .map_or("auto-detected", |(_, source)| *source);
+            Remapping {
+                context,
+                from,
+                to,
+                source,
+            }
+        })
+        .collect()
+}
+
+/// Build the `forge_inspect` arguments, checking the contract identifier and field
+fn inspect_args(args: &JsonObject) -> Result<(JsonObject, &'static str), ToolError> {
+    let contract = args
+        .get("contract")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .ok_or_else(|| ToolError::InvalidArguments("Missing 'contract' parameter".to_string()))?;
+    if !CONTRACT_ID.is_match(contract) {
+        return Err(ToolError::InvalidArguments(format!(
+            "'{}' is not a contract identifier; use 'ContractName' or 'path/to/File.sol:ContractName'",
+            contract
+        )));
+    }
+
+    let requested = args
+        .get("field")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ToolError::InvalidArguments("Missing 'field' parameter".to_string()))?;
+    let field = INSPECT_FIELD_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == requested)
+        .map(|(_, field)| *field)
+        .or_else(|| INSPECT_FIELDS.iter().copied().find(|f| *f == requested))
+        .ok_or_else(|| {
+            ToolError::InvalidArguments(format!(
+                "Unknown field '{}'. Valid fields: {}",
+                requested,
+                INSPECT_FIELDS.join(", ")
+            ))
+        })?;
+
+    let mut mapped = map_string_args(args, &[("root", "root")]);
+    mapped.insert("contract".to_string(), json!(contract));
+    mapped.insert("field".to_string(), json!(field));
+    Ok((mapped, field))
+}
+
+/// Find the JSON document in forge's stdout, skipping anything printed before it
+fn extract_json(stdout: &str) -> Option<Value> {
+    let start = stdout.find(['{', '['])?;
+    serde_json::Deserializer::from_str(&stdout[start..])
+        .into_iter::<Value>()
+        .next()?
+        .ok()
+}
+
+/// Tally the results in `forge test --json` output, keyed by suite then test name
+fn parse_test_results(results: &Value) -> Option<TestSummary> {
+    let suites = results.as_object()?;
+    let mut summary = TestSummary {
+        suites: suites.len(),
+        ..Default::default()
+    };
+
+    for (suite, suite_result) in suites {
+        let Some(tests) = suite_result.get("test_results").and_then(|t| t.as_object()) else {
+            continue;
+        };
+        for (test, result) in tests {
+            match result.get("status").and_then(|s| s.as_str()) {
+                Some("Success") => summary.passed += 1,
+                Some("Skipped") => summary.skipped += 1,
+                _ => {
+                    summary.failed += 1;
+                    summary.failures.push(TestFailure {
+                        suite: suite.clone(),
+                        test: test.clone(),
+                        reason: result
+                            .get("reason")
+                            .and_then(|r| r.as_str())
+                            .filter(|r| !r.is_empty())
+                            .map(String::from),
+                    });
+                }
+            }
+        }
+    }
+
+    Some(summary)
+}
+
+/// Handle forge_test_summary tool call
+pub fn handle_forge_test_summary(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let empty = JsonObject::new();
+    let test_args = Some(test_summary_args(arguments.as_ref().unwrap_or(&empty)));
+
+    let output = match executor.run_tool("forge_test", &test_args, &[], None) {
+        Ok(output) => output,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    // forge exits non-zero when a test fails but still prints the results
+    let Some(summary) = extract_json(&output.stdout)
+        .as_ref()
+        .and_then(parse_test_results)
+    else {
+        return Ok(diagnostics::error_result(
+            executor.output_error(&test_args, &output),
+        ));
+    };
+
+    let mut text = format!(
+        "{} passed, {} failed, {} skipped ({} suites)",
+        summary.passed, summary.failed, summary.skipped, summary.suites
+    );
+    if !summary.failures.is_empty() {
+        text.push_str("\n\nFailing tests:");
+        for failure in &summary.failures {
+            text.push_str(&format!("\n  {} {}", failure.suite, failure.test));
+            if let Some(reason) = &failure.reason {
+                text.push_str(&format!(": {}", reason));
+            }
+        }
+    }
+
+    let failures: Vec<Value> = summary
+        .failures
+        .iter()
+        .map(|f| json!({ "suite": f.suite, "test": f.test, "reason": f.reason }))
+        .collect();
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(json!({
+        "passed": summary.passed,
+        "failed": summary.failed,
+        "skipped": summary.skipped,
+        "suites": summary.suites,
+        "failures": failures,
+    }));
+    Ok(result)
+}
+
+/// Handle forge_test_focused tool call
+pub fn handle_forge_test_focused(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let empty = JsonObject::new();
+    let (test_args, extra_args) = test_focused_args(arguments.as_ref().unwrap_or(&empty))?;
+    let test_args = Some(test_args);
+
+    let output = match executor.run_tool("forge_test", &test_args, &extra_args, None) {
+        Ok(output) => output,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    if !output.success {
+        return Ok(diagnostics::error_result(
+            executor.output_error(&test_args, &output),
+        ));
+    }
+    Ok(CallToolResult::success(vec![Content::text(format!(
+        "{}{}",
+        output.stdout, output.stderr
+    ))]))
+}
+
+/// Handle forge_coverage_summary tool call
+pub fn handle_forge_coverage_summary(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let empty = JsonObject::new();
+    let (coverage_args, threshold, timeout) = coverage_args(arguments.as_ref().unwrap_or(&empty))?;
+    let coverage_args = Some(coverage_args);
+
+    let output = match executor.run_tool("forge_coverage", &coverage_args, &[], Some(timeout)) {
+        Ok(output) => output,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let (files, total) = parse_coverage_report(&output.stdout);
+    if !output.success || (files.is_empty() && total.is_none()) {
+        return Ok(diagnostics::error_result(
+            executor.output_error(&coverage_args, &output),
+        ));
+    }
+
+    let below = |file: &FileCoverage| threshold.is_some_and(|t| file.line_percent() < t);
+    let below_count = files.iter().filter(|f| below(f)).count();
+
+    let mut text = String::from("Coverage by file, lowest line coverage first:");
+    for file in &files {
+        text.push_str(&format!("\n  {}: {}", file.file, file.describe()));
+        if below(file) {
+            text.push_str(&format!(" [below {}%]", threshold.unwrap_or_default()));
+        }
+    }
+    if let Some(total) = &total {
+        text.push_str(&format!("\n\nTotal: {}", total.describe()));
+    }
+    if let Some(threshold) = threshold {
+        text.push_str(&format!(
+            "\n{} of {} files below {}% line coverage",
+            below_count,
+            files.len(),
+            threshold
+        ));
+    }
+
+    let file_values: Vec<Value> = files
+        .iter()
+        .map(|file| {
+            let mut value = json!(file);
+            if threshold.is_some() {
+                value["below_threshold"] = json!(below(file));
+            }
+            value
+        })
+        .collect();
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(json!({
+        "files": file_values,
+        "total": total,
+        "min_threshold": threshold,
+        "below_threshold": below_count,
+    }));
+    Ok(result)
+}
+
+/// Handle forge_fmt_check tool call
+pub fn handle_forge_fmt_check(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let empty = JsonObject::new();
+    let args = arguments.as_ref().unwrap_or(&empty);
+    let write = args.get("write").and_then(|v| v.as_bool()).unwrap_or(false);
+
+    let run = |check: bool| {
+        let fmt_args = Some(fmt_args(args, check));
+        executor
+            .run_tool("forge_fmt", &fmt_args, &[], None)
+            .map(|output| (output, fmt_args))
+    };
+
+    // Always check first, so a write can report which files it changed
+    let (output, check_args) = match run(true) {
+        Ok(result) => result,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+    let files = parse_unformatted_files(&output.stdout);
+    if !output.success && files.is_empty() {
+        return Ok(executor
+            .output_error(&check_args, &output)
+            .into_call_tool_result());
+    }
+
+    let written = write && !files.is_empty();
+    if written {
+        match run(false) {
+            Ok((output, _)) if output.success => {}
+            Ok((output, write_args)) => {
+                return Ok(executor
+                    .output_error(&write_args, &output)
+                    .into_call_tool_result());
+            }
+            Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let text = if files.is_empty() {
+        "All files are formatted".to_string()
+    } else if written {
+        format!("Formatted {} files:\n{}", files.len(), files.join("\n"))
+    } else {
+        format!(
+            "{} files need formatting:\n{}\n\n{}",
+            files.len(),
+            files.join("\n"),
+            output.stdout.trim_end()
+        )
+    };
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(json!({
+        "formatted": files.is_empty() || written,
+        "files": files,
+        "written": written,
+    }));
+    Ok(result)
+}
+
+/// Handle forge_remappings tool call
+pub fn handle_forge_remappings(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let empty = JsonObject::new();
+    let args = arguments.as_ref().unwrap_or(&empty);
+    let remappings_args = Some(map_string_args(args, &[("root", "root")]));
+
+    let output = match executor.run_tool("forge_remappings", &remappings_args, &[], None) {
+        Ok(output) if output.success => output,
+        Ok(output) => {
+            return Ok(executor
+                .output_error(&remappings_args, &output)
+                .into_call_tool_result())
+        }
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let root = args.get("root").and_then(|v| v.as_str()).unwrap_or(".");
+    let configured = project::configured_remappings(Path::new(root));
+    let remappings = parse_remappings(&output.stdout, &configured);
+
+    let text = if remappings.is_empty() {
+        "No remappings".to_string()
+    } else {
+        remappings
+            .iter()
+            .map(|r| {
+                let context = r
+                    .context
+                    .as_deref()
+                    .map(|c| format!("{}:", c))
+                    .unwrap_or_default();
+                format!("{}{} → {} ({})", context, r.from, r.to, r.source)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(json!({ "remappings": remappings }));
+    Ok(result)
+}
+
+/// Handle forge_inspect tool call
+pub fn handle_forge_inspect(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let empty = JsonObject::new();
+    let args = arguments.as_ref().unwrap_or(&empty);
+    let (inspect_args, field) = inspect_args(args)?;
+    let contract = inspect_args["contract"].clone();
+    let inspect_args = Some(inspect_args);
+
+    let output = match executor.run_tool("forge_inspect", &inspect_args, &[], None) {
+        Ok(output) if output.success => output,
+        Ok(output) => {
+            return Ok(diagnostics::error_result(
+                executor.output_error(&inspect_args, &output),
+            ))
+        }
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    // Bytecode, assembly and IR come back as plain text, everything else as JSON
+    let value = if INSPECT_TEXT_FIELDS.contains(&field) {
+        None
+    } else {
+        extract_json(&output.stdout)
+    };
+    let text = match &value {
+        Some(value) => serde_json::to_string_pretty(value).unwrap_or_default(),
+        None => output.stdout.trim().to_string(),
+    };
+
+    let mut result = CallToolResult::success(vec![Content::text(text.clone())]);
+    result.structured_content = Some(json!({
+        "contract": contract,
+        "field": field,
+        "value": value.unwrap_or(Value::String(text)),
+    }));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that summary arguments map onto forge_test options and force JSON output
+    #[test]
+    fn test_test_summary_args() {
+        let args = json!({
+            "root": "/tmp/project",
+            "match_test": "testTransfer",
+            "match_contract": "Token",
+            "unknown": "ignored"
+        });
+        let mapped = test_summary_args(args.as_object().unwrap());
+
+        assert_eq!(mapped["root"], "/tmp/project");
+        assert_eq!(mapped["match-test"], "testTransfer");
+        assert_eq!(mapped["match-contract"], "Token");
+        assert_eq!(mapped["json"], true);
+        assert!(!mapped.contains_key("match-path"));
+        assert!(!mapped.contains_key("unknown"));
+    }
+
+    /// Test that focused arguments map onto the match flags with verbosity as -v flags
+    #[test]
+    fn test_test_focused_args() {
+        let args = json!({
+            "contract": "TokenTest",
+            "test": "^testTransfer$",
+            "path": "test/Token.t.sol",
+            "verbosity": 3
+        });
+        let (mapped, extra) = test_focused_args(args.as_object().unwrap()).unwrap();
+
+        assert_eq!(mapped["match-contract"], "TokenTest");
+        assert_eq!(mapped["match-test"], "^testTransfer$");
+        assert_eq!(mapped["match-path"], "test/Token.t.sol");
+        assert_eq!(extra, vec!["-vvv"]);
+
+        let args = json!({"test": "testMint"});
+        let (_, extra) = test_focused_args(args.as_object().unwrap()).unwrap();
+        assert!(extra.is_empty());
+    }
+
+    /// Test that a focused run needs a target and a valid verbosity
+    #[test]
+    fn test_test_focused_args_invalid() {
+        for args in [
+            json!({}),
+            json!({"root": "/tmp/project"}),
+            json!({"test": "testMint", "verbosity": 6}),
+            json!({"test": "testMint", "verbosity": "vvv"}),
+        ] {
+            assert!(matches!(
+                test_focused_args(args.as_object().unwrap()),
+                Err(ToolError::InvalidArguments(_))
+            ));
+        }
+    }
+
+    /// Test that coverage arguments request the summary report and validate the limits
+    #[test]
+    fn test_coverage_args() {
+        let args = json!({"match_contract": "Token", "min_threshold": 80});
+        let (mapped, threshold, timeout) = coverage_args(args.as_object().unwrap()).unwrap();
+        assert_eq!(mapped["report"], "summary");
+        assert_eq!(mapped["match-contract"], "Token");
+        assert_eq!(threshold, Some(80.0));
+        assert_eq!(timeout, Duration::from_secs(DEFAULT_COVERAGE_TIMEOUT_SECS));
+
+        let args = json!({"timeout_secs": 30});
+        let (_, threshold, timeout) = coverage_args(args.as_object().unwrap()).unwrap();
+        assert_eq!(threshold, None);
+        assert_eq!(timeout, Duration::from_secs(30));
+
+        for args in [
+            json!({"min_threshold": 101}),
+            json!({"min_threshold": "high"}),
+            json!({"timeout_secs": 0}),
+        ] {
+            assert!(matches!(
+                coverage_args(args.as_object().unwrap()),
+                Err(ToolError::InvalidArguments(_))
+            ));
+        }
+    }
+
+    /// Test that coverage cells parse into percentage and counts
+    #[test]
+    fn test_parse_coverage_metric() {
+        assert_eq!(
+            parse_coverage_metric("85.71% (6/7)"),
+            Some(CoverageMetric {
+                percent: 85.71,
+                covered: 6,
+                total: 7
+            })
+        );
+        assert_eq!(parse_coverage_metric("-------"), None);
+        assert_eq!(parse_coverage_metric("85.71%"), None);
+    }
+
+    /// Test that both table layouts parse, sorted by line coverage with the total separate
+    #[test]
+    fn test_parse_coverage_report() {
+        let markdown = "\
+Analysing contracts...
+| File              | % Lines        | % Statements   | % Branches    | % Funcs       |
+|-------------------|----------------|----------------|---------------|---------------|
+| src/Counter.sol   | 100.00% (4/4)  | 100.00% (2/2)  | 100.00% (0/0) | 100.00% (2/2) |
+| src/Vault.sol     | 50.00% (5/10)  | 40.00% (4/10)  | 25.00% (1/4)  | 66.67% (2/3)  |
+| Total             | 64.29% (9/14)  | 50.00% (6/12)  | 25.00% (1/4)  | 80.00% (4/5)  |
+";
+        let boxed = "\
+╭-----------------+---------------+---------------+---------------+---------------╮
+| File            | % Lines       | % Statements  | % Branches    | % Funcs       |
++=================================================================================+
+| src/Counter.sol | 100.00% (4/4) | 100.00% (2/2) | 100.00% (0/0) | 100.00% (2/2) |
+|-----------------+---------------+---------------+---------------+---------------|
+| src/Vault.sol   | 50.00% (5/10) | 40.00% (4/10) | 25.00% (1/4)  | 66.67% (2/3)  |
+|-----------------+---------------+---------------+---------------+---------------|
+| Total           | 64.29% (9/14) | 50.00% (6/12) | 25.00% (1/4)  | 80.00% (4/5)  |
+╰-----------------+---------------+---------------+---------------+---------------╯
+";
+        for output in [markdown, boxed] {
+            let (files, total) = parse_coverage_report(output);
+            assert_eq!(files.len(), 2);
+            assert_eq!(files[0].file, "src/Vault.sol");
+            assert_eq!(files[1].file, "src/Counter.sol");
+            assert_eq!(files[0].metrics["functions"].covered, 2);
+            assert_eq!(files[0].metrics.len(), 4);
+            assert_eq!(total.unwrap().metrics["lines"].percent, 64.29);
+        }
+
+        assert_eq!(
+            parse_coverage_report("Error: compilation failed"),
+            (vec![], None)
+        );
+    }
+
+    /// Test that fmt arguments pass paths through and only add --check when checking
+    #[test]
+    fn test_fmt_args() {
+        let args = json!({"paths": ["src", "test/Token.t.sol"], "root": "/tmp/project"});
+        let args = args.as_object().unwrap();
+
+        let check = fmt_args(args, true);
+        assert_eq!(check["path"], json!(["src", "test/Token.t.sol"]));
+        assert_eq!(check["root"], "/tmp/project");
+        assert_eq!(check["check"], true);
+        assert!(!fmt_args(args, false).contains_key("check"));
+    }
+
+    /// Test that unformatted files are read from the diff headers
+    #[test]
+    fn test_parse_unformatted_files() {
+        let output = "\
+Diff in src/Counter.sol:
+4 4    |     uint256 public number;
+5    |-    function setNumber(uint256 newNumber) public { number = newNumber; }
+Diff in /tmp/project/test/Counter.t.sol:
+1 1    | // SPDX-License-Identifier: UNLICENSED
+";
+        assert_eq!(
+            parse_unformatted_files(output),
+            vec!["src/Counter.sol", "/tmp/project/test/Counter.t.sol"]
+        );
+        assert!(parse_unformatted_files("").is_empty());
+    }
+
+    /// Test that the JSON document is found after leading log lines
+    #[test]
+    fn test_extract_json() {
+        let stdout = "Compiling 3 files\n{\"a\": {\"test_results\": {}}}\ntrailing";
+        assert_eq!(
+            extract_json(stdout),
+            Some(json!({"a": {"test_results": {}}}))
+        );
+        assert_eq!(extract_json("no json here"), None);
+    }
+
+    /// Test that statuses are tallied and failures keep their reasons
+    #[test]
+    fn test_parse_test_results() {
+        let results = json!({
+            "test/Token.t.sol:TokenTest": {
+                "duration": "1ms",
+                "test_results": {
+                    "testMint()": {"status": "Success", "reason": null},
+                    "testBurn()": {"status": "Failure", "reason": "revert: insufficient balance"},
+                    "testSkip()": {"status": "Skipped", "reason": null}
+                }
+            },
+            "test/Vault.t.sol:VaultTest": {
+                "test_results": {
+                    "testDeposit()": {"status": "Success"},
+                    "testFuzz(uint256)": {"status": "Failure", "reason": ""}
+                }
+            }
+        });
+        let summary = parse_test_results(&results).unwrap();
+
+        assert_eq!(summary.suites, 2);
+        assert_eq!(summary.passed, 2);
+        assert_eq!(summary.failed, 2);
+        assert_eq!(summary.skipped, 1);
+        assert!(summary.failures.contains(&TestFailure {
+            suite: "test/Token.t.sol:TokenTest".to_string(),
+            test: "testBurn()".to_string(),
+            reason: Some("revert: insufficient balance".to_string()),
+        }));
+        assert!(summary.failures.contains(&TestFailure {
+            suite: "test/Vault.t.sol:VaultTest".to_string(),
+            test: "testFuzz(uint256)".to_string(),
+            reason: None,
+        }));
+    }
+
+    /// Test that output that isn't a results map is rejected
+    #[test]
+    fn test_parse_test_results_invalid() {
+        assert_eq!(parse_test_results(&json!([1, 2])), None);
+        assert_eq!(
+            parse_test_results(&json!({})).unwrap(),
+            TestSummary::default()
+        );
+    }
+
+    /// Test that remappings are split and attributed to the file that configures them
+    #[test]
+    fn test_parse_remappings() {
+        let output = "forge-std/=lib/forge-std/src/\n@oz/=lib/openzeppelin-contracts/contracts\nsrc/:utils/=src/utils/\nds-test/=lib/forge-std/lib/ds-test/src/\n";
+        let configured = vec![
+            (
+                "@oz/=lib/openzeppelin-contracts/contracts/".to_string(),
+                "foundry.toml",
+            ),
+            (
+                "forge-std/=lib/forge-std/src/".to_string(),
+                "remappings.txt",
+            ),
+            ("src/:utils/=src/utils/".to_string(), "remappings.txt"),
+        ];
+        let remappings = parse_remappings(output, &configured);
+
+        assert_eq!(remappings.len(), 4);
+        assert_eq!(remappings[0].from, "forge-std/");
+        assert_eq!(remappings[0].to, "lib/forge-std/src/");
+        assert_eq!(remappings[0].source, "remappings.txt");
+        assert_eq!(remappings[1].source, "foundry.toml");
+        assert_eq!(remappings[2].context.as_deref(), Some("src/"));
+        assert_eq!(remappings[2].from, "utils/");
+        assert_eq!(remappings[2].source, "remappings.txt");
+        assert_eq!(remappings[3].source, "auto-detected");
+        assert!(parse_remappings("Compiling...\n", &[]).is_empty());
+    }
+
+    /// Test that forge_inspect arguments are checked and field aliases resolved
+    #[test]
+    fn test_inspect_args() {
+        let args = json!({"contract": "src/Token.sol:Token", "field": "methods", "root": "/tmp/p"});
+        let (mapped, field) = inspect_args(args.as_object().unwrap()).unwrap();
+        assert_eq!(field, "methodIdentifiers");
+        assert_eq!(mapped["contract"], "src/Token.sol:Token");
+        assert_eq!(mapped["field"], "methodIdentifiers");
+        assert_eq!(mapped["root"], "/tmp/p");
+
+        let args = json!({"contract": "Token", "field": "storageLayout"});
+        assert_eq!(
+            inspect_args(args.as_object().unwrap()).unwrap().1,
+            "storageLayout"
+        );
+
+        for args in [
+            json!({"contract": "Token", "field": "sourceCode"}),
+            json!({"contract": "1Token", "field": "abi"}),
+            json!({"contract": "src/Token.sol:", "field": "abi"}),
+            json!({"contract": "Token --force", "field": "abi"}),
+            json!({"field": "abi"}),
+        ] {
+            assert!(matches!(
+                inspect_args(args.as_object().unwrap()),
+                Err(ToolError::InvalidArguments(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Text layouts for list-style tool output
+//!
+//! List tools print one bullet per item with its details on indented lines, which stays
+//! compact for agents. With `format: "table"` they print an aligned table instead, one row
+//! per item, which is easier to scan in a chat client. The tools build their rows and
+//! leave the layout to [`render_table`].
+
+use serde_json::{json, Value};
+
+use crate::error::ToolError;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// How a list tool lays out its items
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListFormat {
+    /// Bullets with indented details
+    #[default]
+    List,
+    /// Aligned text table
+    Table,
+}
+
+/// Schema of the `format` argument shared by list tools
+pub fn format_property() -> Value {
+    json!({
+        "type": "string",
+        "enum": ["list", "table"],
+        "description": "Layout of the results: 'list' (default, compact) or 'table' (aligned columns)"
+    })
+}
+
+/// Read the `format` argument, defaulting to a list
+pub fn list_format(args: &JsonObject) -> Result<ListFormat, ToolError> {
+    match args.get("format").and_then(|v| v.as_str()) {
+        None | Some("list") => Ok(ListFormat::List),
+        Some("table") => Ok(ListFormat::Table),
+        Some(other) => Err(ToolError::InvalidArguments(format!(
+            "Unknown format '{}', expected 'list' or 'table'",
+            other
+        ))),
+    }
+}
+
+/// Render rows as a table with a header, columns padded to their widest cell
+pub fn render_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: &[&str]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        format!("{}\n", padded.join("  ").trim_end())
+    };
+
+    let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    let mut table = line(headers);
+    table.push_str(&line(&rule.iter().map(String::as_str).collect::<Vec<_>>()));
+    for row in rows {
+        table.push_str(&line(&row.iter().map(String::as_str).collect::<Vec<_>>()));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that columns are aligned to the widest cell, counting characters not bytes
+    #[test]
+    fn test_render_table() {
+        let table = render_table(
+            &["Name", "ID"],
+            &[
+                vec!["Ethereum".to_string(), "1".to_string()],
+                vec!["Zoë".to_string(), "8453".to_string()],
+            ],
+        );
+        assert_eq!(
+            table,
+            "Name      ID\n--------  ----\nEthereum  1\nZoë       8453\n"
+        );
+    }
+
+    /// Test that the format defaults to a list and rejects unknown layouts
+    #[test]
+    fn test_list_format() {
+        let args = |v: Value| v.as_object().cloned().unwrap();
+        assert_eq!(list_format(&args(json!({}))).unwrap(), ListFormat::List);
+        assert_eq!(
+            list_format(&args(json!({"format": "table"}))).unwrap(),
+            ListFormat::Table
+        );
+        assert!(matches!(
+            list_format(&args(json!({"format": "csv"}))),
+            Err(ToolError::InvalidArguments(_))
+        ));
+    }
+}
@@ -1,18 +1,144 @@
 //! Foundry CLI tool execution and schema conversion
 
-use anyhow::{Context, Result};
+use anyhow::Result;
+use base64::Engine;
 use rmcp::model::*;
 use serde_json::Value;
 use std::collections::HashMap;
-use std::process::Command;
-use std::sync::Arc;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 use crate::config::Config;
 use crate::context::ContextConfig;
-use crate::schema::{SchemaFile, ToolSchema};
+use crate::error::ToolError;
+use crate::redact::{self, Redactor};
+use crate::schema::{OptionSchema, SchemaFile, ToolSchema};
+use regex::Regex;
 
 type JsonObject = serde_json::Map<String, Value>;
 
+/// Schema tools taken over by a built-in tool of the same name.
+///
+/// Only the built-in is advertised. The schema tool stays executable, and the built-in runs
+/// it through [`crate::cast::run_tool`] rather than invoking `cast` itself, so the command
+/// allowlist, forbidden flags and argument validation still apply.
+pub const BUILTIN_OVERRIDES: &[&str] = &[
+    "cast_storage",
+    "cast_wallet_address",
+    "cast_block",
+    "cast_tx",
+    "cast_logs",
+    "cast_compute_address",
+    "cast_selectors",
+    "cast_nonce",
+    "cast_balance",
+    "cast_disassemble",
+    "cast_interface",
+    "cast_run",
+    "forge_remappings",
+    "forge_inspect",
+];
+
+/// Path to a Foundry binary: `<bin_dir>/<name>`, or `<bin_dir>/<name>.exe` when only that
+/// exists, as on Windows. Without a directory the bare name is looked up on PATH.
+pub fn binary_path(bin_dir: Option<&str>, name: &str) -> String {
+    let Some(dir) = bin_dir else {
+        return name.to_string();
+    };
+    let plain = Path::new(dir).join(name);
+    let exe = Path::new(dir).join(format!("{}.exe", name));
+    let path = if !plain.exists() && exe.exists() {
+        exe
+    } else {
+        plain
+    };
+    path.to_string_lossy().to_string()
+}
+
+/// Options naming a project directory or a directory inside one, which are resolved
+/// against the server's working directory (or for `out`, the given root) before the
+/// command runs
+const PROJECT_PATH_OPTIONS: &[&str] = &["root", "out"];
+
+/// Argument any Foundry tool accepts to choose how its output is returned: `"text"`
+/// (default, invalid UTF-8 replaced) or `"base64"` (stdout's raw bytes)
+pub const OUTPUT_ENCODING_ARG: &str = "__output_encoding";
+
+/// Largest integer an f64 holds exactly (2^53); bigger bare numbers may have been rounded
+const MAX_EXACT_FLOAT_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// Raw output of a Foundry CLI invocation
+#[derive(Debug, Clone)]
+pub struct ToolOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// Raw stdout when it isn't valid UTF-8, in which case `stdout` is a lossy copy
+    pub binary_stdout: Option<Vec<u8>>,
+    /// Whether the command exited with status 0
+    pub success: bool,
+    pub exit_code: Option<i32>,
+}
+
+/// Cut `output` down to at most `limit` bytes (on a character boundary), noting how much
+/// was dropped
+pub fn truncate_output(output: String, limit: Option<usize>) -> String {
+    let Some(limit) = limit.filter(|limit| output.len() > *limit) else {
+        return output;
+    };
+
+    let mut end = limit;
+    while !output.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!(
+        "{}\n... [output truncated: {} of {} bytes shown]",
+        &output[..end],
+        end,
+        output.len()
+    )
+}
+
+/// Run a command to completion, or kill it and return `None` once `timeout` passes
+fn output_with_timeout(cmd: &mut Command, timeout: Duration) -> std::io::Result<Option<Output>> {
+    let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+
+    // Drain the pipes while waiting so a chatty process can't block on a full buffer
+    fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        })
+    }
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if start.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(None);
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    };
+
+    Ok(Some(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    }))
+}
+
 /// Parse tool name parts into command components (handles triple underscore pattern)
 fn parse_subcommand_parts(parts: &[&str]) -> (Vec<String>, bool) {
     let mut subcommand_parts = Vec::new();
@@ -47,17 +173,32 @@ fn parse_subcommand_parts(parts: &[&str]) -> (Vec<String>, bool) {
     (result, found_triple_underscore)
 }
 
+/// The tools exposed under a particular configuration.
+///
+/// Replaced as a whole when the configuration is reloaded, so a call always sees a
+/// consistent tool map, tool list, and policy.
+struct ToolSet {
+    tools: HashMap<String, ToolSchema>,
+    /// Parameter patterns of those tools, compiled once and keyed by their source
+    patterns: HashMap<String, Regex>,
+    tool_list: Vec<Tool>,
+    config: Arc<Config>,
+    redactor: Arc<Redactor>,
+    /// `cast_send` is forbidden but listed, and runs as `cast_call`
+    simulates_send: bool,
+}
+
 /// Foundry tool executor with security configuration support.
 ///
 /// This executor manages Foundry CLI tools, filters out forbidden commands/flags
 /// based on configuration, and handles command execution.
 pub struct FoundryExecutor {
-    tools: HashMap<String, ToolSchema>,
-    tool_list: Vec<Tool>,
+    /// Every tool in the schema, before filtering
+    schema_tools: Vec<ToolSchema>,
+    tool_set: RwLock<Arc<ToolSet>>,
+    /// Unrestricted tool set, present while the session is elevated via `unlock`
+    elevated: RwLock<Option<Arc<ToolSet>>>,
     foundry_bin_path: Option<String>,
-    #[allow(dead_code)]
-    config: Config,
-    #[allow(dead_code)]
     context: Arc<ContextConfig>,
 }
 
@@ -73,36 +214,154 @@ impl FoundryExecutor {
     pub fn with_config(schema_file: SchemaFile, config: Config) -> Self {
         let context = Arc::new(ContextConfig::load());
 
-        let filtered_tools: Vec<ToolSchema> = schema_file
-            .tools
-            .into_iter()
-            .filter(|tool| Self::is_tool_allowed(tool, &config))
-            .collect();
+        // Surface schema-generation bugs instead of silently dropping or misordering arguments
+        for problem in schema_file.validate() {
+            eprintln!("⚠ Schema warning: {}", problem);
+        }
+
+        let schema_tools = schema_file.tools;
+        let tool_set = Self::build_tool_set(&schema_tools, config, &context);
+        let foundry_bin_path = Self::detect_foundry_path();
+
+        Self {
+            schema_tools,
+            tool_set: RwLock::new(Arc::new(tool_set)),
+            elevated: RwLock::new(None),
+            foundry_bin_path,
+            context,
+        }
+    }
 
-        let tools: HashMap<String, ToolSchema> = filtered_tools
+    /// Filter the schema by the configuration and build the advertised tool list.
+    fn build_tool_set(
+        schema_tools: &[ToolSchema],
+        config: Config,
+        context: &ContextConfig,
+    ) -> ToolSet {
+        let tools: HashMap<String, ToolSchema> = schema_tools
             .iter()
+            .filter(|tool| Self::is_tool_allowed(tool, &config))
             .map(|tool| (tool.name.clone(), tool.clone()))
             .collect();
 
-        let tool_list: Vec<Tool> = filtered_tools
+        // Invalid patterns are reported by schema validation at startup and skipped here
+        let patterns: HashMap<String, Regex> = tools
+            .values()
+            .flat_map(|tool| {
+                let positionals = tool.positionals.iter().map(|p| p.effective_pattern());
+                let options = tool.options.iter().map(|o| o.effective_pattern());
+                positionals.chain(options).flatten()
+            })
+            .filter_map(|pattern| Some((pattern.to_string(), Regex::new(pattern).ok()?)))
+            .collect();
+
+        // Hidden tools stay executable but aren't advertised to clients; keep schema order
+        let tool_list: Vec<Tool> = schema_tools
             .iter()
-            .map(|tool| Self::schema_to_tool(tool, &config, &context))
+            .filter(|tool| !tool.hidden && !BUILTIN_OVERRIDES.contains(&tool.name.as_str()))
+            .filter(|tool| tools.contains_key(&tool.name))
+            .map(|tool| Self::schema_to_tool(tool, &config, context))
             .collect();
 
-        let foundry_bin_path = Self::detect_foundry_path();
+        let mut tool_list = tool_list;
+
+        // Keep advertising a forbidden send when it can be simulated with a call instead
+        let send_schema = schema_tools.iter().find(|tool| tool.name == "cast_send");
+        let simulates_send = config.simulate_forbidden_sends
+            && !tools.contains_key("cast_send")
+            && tools.contains_key("cast_call")
+            && send_schema.is_some();
+        if let (true, Some(send)) = (simulates_send, send_schema) {
+            let mut tool = Self::schema_to_tool(send, &config, context);
+            tool.description = Some(
+                format!(
+                    "SIMULATION ONLY: sending is forbidden on this server, so this runs `cast call` with the same arguments and nothing is broadcast. {}",
+                    tool.description.unwrap_or_default()
+                )
+                .into(),
+            );
+            tool_list.push(tool);
+        }
 
-        Self {
+        let redactor = Redactor::new(&config.sensitive_arguments);
+
+        ToolSet {
             tools,
+            patterns,
             tool_list,
-            foundry_bin_path,
-            config,
-            context,
+            config: Arc::new(config),
+            redactor: Arc::new(redactor),
+            simulates_send,
+        }
+    }
+
+    /// Replace the active configuration, re-filtering the exposed tools.
+    ///
+    /// Calls already in progress finish under the previous configuration.
+    pub fn reload_config(&self, config: Config) {
+        let elevated = self.is_elevated().then(|| config.elevated());
+        let tool_set = Self::build_tool_set(&self.schema_tools, config, &self.context);
+        *self.tool_set.write().unwrap() = Arc::new(tool_set);
+
+        if let Some(config) = elevated {
+            let tool_set = Self::build_tool_set(&self.schema_tools, config, &self.context);
+            *self.elevated.write().unwrap() = Some(Arc::new(tool_set));
+        }
+    }
+
+    /// Lift (or restore) the configured command and flag restrictions.
+    pub fn set_elevated(&self, elevated: bool) {
+        let tool_set = elevated.then(|| {
+            let config = self.config().elevated();
+            Arc::new(Self::build_tool_set(
+                &self.schema_tools,
+                config,
+                &self.context,
+            ))
+        });
+        *self.elevated.write().unwrap() = tool_set;
+    }
+
+    /// Whether restrictions are currently lifted.
+    pub fn is_elevated(&self) -> bool {
+        self.elevated.read().unwrap().is_some()
+    }
+
+    /// The tool set calls should use: the elevated one while unlocked, else the base one.
+    fn tool_set(&self) -> Arc<ToolSet> {
+        if let Some(elevated) = self.elevated.read().unwrap().as_ref() {
+            return elevated.clone();
         }
+        self.tool_set.read().unwrap().clone()
     }
 
     /// Get the list of available tools (after filtering).
-    pub fn tool_list(&self) -> &[Tool] {
-        &self.tool_list
+    pub fn tool_list(&self) -> Vec<Tool> {
+        self.tool_set().tool_list.clone()
+    }
+
+    /// Whether a forbidden `cast_send` is run as a `cast_call` simulation, per
+    /// `simulate_forbidden_sends`.
+    pub fn simulates_send(&self) -> bool {
+        self.tool_set().simulates_send
+    }
+
+    /// Get the names of every tool in the schema, including filtered ones.
+    pub fn schema_tool_names(&self) -> Vec<String> {
+        self.schema_tools
+            .iter()
+            .map(|tool| tool.name.clone())
+            .collect()
+    }
+
+    /// Get the active configuration (without any elevation applied).
+    pub fn config(&self) -> Arc<Config> {
+        self.tool_set.read().unwrap().config.clone()
+    }
+
+    /// Get the redactor used to mask sensitive argument values.
+    pub fn redactor(&self) -> Arc<Redactor> {
+        self.tool_set().redactor.clone()
     }
 
     /// Get the detected Foundry binary path, if found.
@@ -148,7 +407,8 @@ impl FoundryExecutor {
 
     /// Check if a tool is allowed based on configuration.
     ///
-    /// Returns `false` if the tool name or its base command is forbidden.
+    /// With an allowlist, returns `true` only if the tool name or its base command is
+    /// listed. Otherwise returns `false` if the tool name or its base command is forbidden.
     fn is_tool_allowed(tool: &ToolSchema, config: &Config) -> bool {
         // Filter out individual cast conversion commands (replaced by unified cast_convert)
         if Self::is_cast_conversion_command(&tool.name) {
@@ -159,6 +419,15 @@ impl FoundryExecutor {
             return false;
         }
 
+        // An allowlist, when configured, takes precedence over the forbidden lists
+        if config.allowed_commands.is_some() {
+            let allowed = config.is_tool_allowlisted(&tool.name);
+            if !allowed {
+                eprintln!("🚫 Filtering out command not in allowlist: {}", tool.name);
+            }
+            return allowed;
+        }
+
         // Check if the full tool name is forbidden
         if config.is_command_forbidden(&tool.name) {
             eprintln!("🚫 Filtering out forbidden command: {}", tool.name);
@@ -176,34 +445,47 @@ impl FoundryExecutor {
     }
 
     fn get_command_path(&self, command_name: &str) -> String {
-        if let Some(bin_path) = &self.foundry_bin_path {
-            format!("{}/{}", bin_path, command_name)
-        } else {
-            command_name.to_string()
+        binary_path(self.foundry_bin_path.as_deref(), command_name)
+    }
+
+    /// Directories Foundry is commonly installed in, most likely first.
+    ///
+    /// `foundryup` installs into `.foundry/bin` under the home directory (`HOME`, or
+    /// `USERPROFILE` on Windows); package managers use the usual Unix prefixes.
+    fn foundry_search_paths(windows: bool, home: Option<&str>) -> Vec<std::path::PathBuf> {
+        let mut paths = Vec::new();
+        if let Some(home) = home {
+            paths.push(Path::new(home).join(".foundry").join("bin"));
         }
+        if !windows {
+            paths.push("/usr/local/bin".into());
+            paths.push("/opt/homebrew/bin".into());
+        }
+        paths
     }
 
-    fn detect_foundry_path() -> Option<String> {
-        // Common installation paths for Foundry
-        let home = std::env::var("HOME").ok()?;
-        let common_paths = vec![
-            format!("{}/.foundry/bin", home),
-            "/usr/local/bin".to_string(),
-            "/opt/homebrew/bin".to_string(),
-        ];
+    /// Detect the directory containing the Foundry binaries.
+    pub fn detect_foundry_path() -> Option<String> {
+        let windows = cfg!(windows);
+        let (home_var, locator) = if windows {
+            ("USERPROFILE", "where")
+        } else {
+            ("HOME", "which")
+        };
 
-        for path in common_paths {
-            let forge_path = format!("{}/forge", path);
-            if std::path::Path::new(&forge_path).exists() {
-                return Some(path);
+        let home = std::env::var(home_var).ok();
+        for path in Self::foundry_search_paths(windows, home.as_deref()) {
+            if path.join("forge").exists() || path.join("forge.exe").exists() {
+                return Some(path.to_string_lossy().to_string());
             }
         }
 
-        // Try to find via which command
-        if let Ok(output) = Command::new("which").arg("forge").output() {
+        // Fall back to searching PATH; `where` lists every match, so take the first
+        if let Ok(output) = Command::new(locator).arg("forge").output() {
             if output.status.success() {
-                let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                if let Some(parent) = std::path::Path::new(&path).parent() {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let path = stdout.lines().next().unwrap_or_default().trim();
+                if let Some(parent) = Path::new(path).parent() {
                     return Some(parent.to_string_lossy().to_string());
                 }
             }
@@ -213,21 +495,31 @@ impl FoundryExecutor {
     }
 
     /// Convert a ToolSchema to an MCP Tool, filtering out forbidden flags.
+    ///
+    /// Flags are matched by long name or short alias, globally or scoped to this tool.
     fn schema_to_tool(tool: &ToolSchema, config: &Config, context: &ContextConfig) -> Tool {
         let mut properties = serde_json::Map::new();
         let mut required = Vec::new();
 
         // Add positional arguments
-        for pos in &tool.positionals {
-            let description = context.positional_description(&pos.name, &pos.description);
-            let param_name = pos.name.to_lowercase();
-            properties.insert(
-                param_name.clone(),
-                serde_json::json!({
-                    "type": Self::map_type(&pos.param_type),
-                    "description": description,
-                }),
+        for pos in tool.positionals.iter().filter(|p| !p.hidden) {
+            let description = Self::with_deprecation_note(
+                context.positional_description(&pos.name, &pos.description),
+                pos.deprecated,
             );
+            let param_name = pos.name.to_lowercase();
+            let mut prop = serde_json::json!({
+                "type": Self::map_type(&pos.param_type),
+                "description": description,
+            });
+            if let Some(default) = &pos.default {
+                prop.as_object_mut()
+                    .unwrap()
+                    .insert("default".to_string(), default.clone());
+            }
+            Self::add_numeric_bounds(&mut prop, &pos.param_type, pos.minimum, pos.maximum);
+            Self::add_pattern(&mut prop, &pos.param_type, pos.effective_pattern());
+            properties.insert(param_name.clone(), prop);
             if pos.required {
                 required.push(Value::String(param_name));
             }
@@ -235,11 +527,14 @@ impl FoundryExecutor {
 
         // Add options (flags with values) - filter out forbidden flags
         for opt in &tool.options {
-            if config.forbidden_flags.contains(&opt.name) {
+            if opt.hidden || config.is_flag_forbidden(&tool.name, &opt.name, opt.short.as_deref()) {
                 continue;
             }
 
-            let description = context.flag_description(&opt.name, &opt.description);
+            let description = Self::with_deprecation_note(
+                context.flag_description(&opt.name, &opt.description),
+                opt.deprecated,
+            );
             let param_name = opt.name.to_lowercase();
             let mut prop = serde_json::json!({
                 "type": Self::map_type(&opt.param_type),
@@ -250,6 +545,19 @@ impl FoundryExecutor {
                     .unwrap()
                     .insert("default".to_string(), default.clone());
             }
+            if let Some(values) = &opt.enum_values {
+                let prop = prop.as_object_mut().unwrap();
+                if opt.param_type == "array" {
+                    prop.insert(
+                        "items".to_string(),
+                        serde_json::json!({ "type": "string", "enum": values }),
+                    );
+                } else {
+                    prop.insert("enum".to_string(), serde_json::json!(values));
+                }
+            }
+            Self::add_numeric_bounds(&mut prop, &opt.param_type, opt.minimum, opt.maximum);
+            Self::add_pattern(&mut prop, &opt.param_type, opt.effective_pattern());
             properties.insert(param_name.clone(), prop);
             if opt.required {
                 required.push(Value::String(param_name));
@@ -258,11 +566,16 @@ impl FoundryExecutor {
 
         // Add flags (boolean) - filter out forbidden flags
         for flag in &tool.flags {
-            if config.forbidden_flags.contains(&flag.name) {
+            if flag.hidden
+                || config.is_flag_forbidden(&tool.name, &flag.name, flag.short.as_deref())
+            {
                 continue;
             }
 
-            let description = context.flag_description(&flag.name, &flag.description);
+            let description = Self::with_deprecation_note(
+                context.flag_description(&flag.name, &flag.description),
+                flag.deprecated,
+            );
             let param_name = flag.name.to_lowercase();
             properties.insert(
                 param_name.clone(),
@@ -283,11 +596,79 @@ impl FoundryExecutor {
             input_schema.insert("required".to_string(), Value::Array(required));
         }
 
-        let tool_description = context.tool_description(&tool.name, &tool.description);
+        // A configured override replaces the schema's description; context still appends
+        let description = config
+            .tool_description_overrides
+            .get(&tool.name)
+            .unwrap_or(&tool.description);
+        let tool_description = Self::with_deprecation_note(
+            context.tool_description(&tool.name, description),
+            tool.deprecated,
+        );
 
         Tool::new(tool.name.clone(), tool_description, Arc::new(input_schema))
     }
 
+    /// Append a deprecation note to a description if the tool or parameter is deprecated.
+    fn with_deprecation_note(description: String, deprecated: bool) -> String {
+        if deprecated {
+            format!(
+                "{} (Deprecated: may be removed in a future Foundry release)",
+                description
+            )
+        } else {
+            description
+        }
+    }
+
+    /// Add `minimum`/`maximum` to a generated property for numeric parameters.
+    ///
+    /// Bounds on non-numeric parameters are ignored, since JSON Schema only applies
+    /// them to numbers.
+    fn add_numeric_bounds(
+        prop: &mut Value,
+        param_type: &str,
+        minimum: Option<f64>,
+        maximum: Option<f64>,
+    ) {
+        if Self::map_type(param_type) != "number" {
+            return;
+        }
+
+        let prop = prop.as_object_mut().unwrap();
+        if let Some(min) = minimum {
+            prop.insert("minimum".to_string(), serde_json::json!(min));
+        }
+        if let Some(max) = maximum {
+            prop.insert("maximum".to_string(), serde_json::json!(max));
+        }
+    }
+
+    /// Add a regex `pattern` to a generated property for string parameters.
+    ///
+    /// For array parameters the pattern applies to each item.
+    fn add_pattern(prop: &mut Value, param_type: &str, pattern: Option<&str>) {
+        let Some(pattern) = pattern else {
+            return;
+        };
+
+        let prop = prop.as_object_mut().unwrap();
+        match Self::map_type(param_type) {
+            "string" => {
+                prop.insert("pattern".to_string(), serde_json::json!(pattern));
+            }
+            "array" => {
+                let items = prop
+                    .entry("items")
+                    .or_insert_with(|| serde_json::json!({ "type": "string" }));
+                if let Some(items) = items.as_object_mut() {
+                    items.insert("pattern".to_string(), serde_json::json!(pattern));
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// Map Foundry parameter types to JSON schema types.
     fn map_type(param_type: &str) -> &str {
         match param_type {
@@ -312,91 +693,474 @@ impl FoundryExecutor {
     ///
     /// # Errors
     ///
-    /// Returns an error if the tool is not found, arguments are invalid,
+    /// Returns a [`ToolError`] if the tool is not found, arguments are invalid,
     /// or command execution fails.
-    pub fn execute_tool(&self, name: &str, arguments: &Option<JsonObject>) -> Result<String> {
-        let tool = self
+    pub fn execute_tool(
+        &self,
+        name: &str,
+        arguments: &Option<JsonObject>,
+    ) -> Result<String, ToolError> {
+        let base64 = Self::wants_base64(arguments)?;
+        let output = self.run_tool(name, arguments, &[], None)?;
+
+        if output.success {
+            let text = if base64 {
+                let bytes = match &output.binary_stdout {
+                    Some(bytes) => bytes.as_slice(),
+                    None => output.stdout.as_bytes(),
+                };
+                base64::engine::general_purpose::STANDARD.encode(bytes)
+            } else if output.binary_stdout.is_some() {
+                format!(
+                    "{}{}\n[output was not valid UTF-8 and invalid bytes were replaced; pass {}: \"base64\" for the raw bytes]",
+                    output.stdout, output.stderr, OUTPUT_ENCODING_ARG
+                )
+            } else {
+                format!("{}{}", output.stdout, output.stderr)
+            };
+            Ok(truncate_output(
+                text,
+                self.config().max_output_bytes_for(name),
+            ))
+        } else {
+            Err(self.output_error(arguments, &output))
+        }
+    }
+
+    /// Fill in `default_rpc_url` for a tool taking `--rpc-url` when the call doesn't give
+    /// one. Returns whether the default was applied.
+    pub fn apply_default_rpc_url(&self, name: &str, arguments: &mut Option<JsonObject>) -> bool {
+        let Some(default) = self.config().default_rpc_url.clone() else {
+            return false;
+        };
+        if !self.lacks_rpc_url(name, arguments) {
+            return false;
+        }
+
+        arguments
+            .get_or_insert_with(JsonObject::new)
+            .insert("rpc-url".to_string(), Value::String(default));
+        true
+    }
+
+    /// Fill in `default_chain` as the `rpc-url` of a tool taking one when the call doesn't
+    /// give it, to be resolved to a live endpoint. Returns the chain applied.
+    pub fn apply_default_chain(
+        &self,
+        name: &str,
+        arguments: &mut Option<JsonObject>,
+    ) -> Option<String> {
+        let default = self.config().default_chain.clone()?;
+        if !self.lacks_rpc_url(name, arguments) {
+            return None;
+        }
+
+        arguments
+            .get_or_insert_with(JsonObject::new)
+            .insert("rpc-url".to_string(), Value::String(default.clone()));
+        Some(default)
+    }
+
+    /// Whether the tool takes `--rpc-url` and the call doesn't give one
+    fn lacks_rpc_url(&self, name: &str, arguments: &Option<JsonObject>) -> bool {
+        let takes_rpc_url = self
+            .tool_set()
+            .tools
+            .get(name)
+            .is_some_and(|tool| tool.options.iter().any(|opt| opt.name == "rpc-url"));
+        takes_rpc_url
+            && !arguments
+                .as_ref()
+                .is_some_and(|args| args.contains_key("rpc-url"))
+    }
+
+    /// Whether the caller asked for base64 output with `__output_encoding`
+    fn wants_base64(arguments: &Option<JsonObject>) -> Result<bool, ToolError> {
+        let encoding = arguments
+            .as_ref()
+            .and_then(|args| args.get(OUTPUT_ENCODING_ARG));
+        match encoding.map(|v| v.as_str()) {
+            None | Some(Some("text")) => Ok(false),
+            Some(Some("base64")) => Ok(true),
+            _ => Err(ToolError::InvalidArguments(format!(
+                "'{}' must be \"text\" or \"base64\"",
+                OUTPUT_ENCODING_ARG
+            ))),
+        }
+    }
+
+    /// The error for a run that failed, with its output scrubbed of secret arguments
+    pub fn output_error(&self, arguments: &Option<JsonObject>, output: &ToolOutput) -> ToolError {
+        let combined = format!("{}{}", output.stdout, output.stderr);
+        // Foundry sometimes echoes bad inputs back, including keys and mnemonics
+        let secrets = self.redactor().secret_values(arguments);
+        ToolError::ExecutionFailed {
+            message: redact::scrub(&combined, &secrets),
+            exit_code: output.exit_code,
+        }
+    }
+
+    /// Run a Foundry CLI tool and return its raw output, whatever the exit status.
+    ///
+    /// For built-in tools that parse the output themselves, such as `forge test --json`,
+    /// which still prints its results when tests fail. The output is not scrubbed.
+    /// `extra_args` are appended after the schema-derived arguments, for flags the schema
+    /// can't express (e.g. a repeated `-v`). With a `timeout` the process is killed once
+    /// it runs longer.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ToolError`] if the tool is not found, arguments are invalid,
+    /// the command could not be started, or it timed out.
+    pub fn run_tool(
+        &self,
+        name: &str,
+        arguments: &Option<JsonObject>,
+        extra_args: &[String],
+        timeout: Option<Duration>,
+    ) -> Result<ToolOutput, ToolError> {
+        let mut cmd = self.build_command(name, arguments)?;
+        cmd.args(extra_args);
+        let parts: Vec<&str> = name.split('_').collect();
+        let command_path = self.get_command_path(parts[0]);
+
+        // Execute the command
+        let output = match timeout {
+            Some(timeout) => output_with_timeout(&mut cmd, timeout),
+            None => cmd.output().map(Some),
+        };
+        let output = output.map_err(|e| {
+            ToolError::execution_failed(if self.foundry_bin_path.is_some() {
+                format!(
+                    "Failed to execute '{}' at '{}': {}. Try running '{} --version'",
+                    parts[0], command_path, e, command_path
+                )
+            } else {
+                format!(
+                    "Failed to execute '{}': {}. Install Foundry from https://getfoundry.sh/",
+                    parts[0], e
+                )
+            })
+        })?;
+        let Some(output) = output else {
+            return Err(ToolError::Timeout(format!(
+                "'{}' timed out after {} seconds",
+                name,
+                timeout.unwrap_or_default().as_secs()
+            )));
+        };
+
+        let binary_stdout = std::str::from_utf8(&output.stdout)
+            .is_err()
+            .then(|| output.stdout.clone());
+        Ok(ToolOutput {
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            binary_stdout,
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            success: output.status.success(),
+            exit_code: output.status.code(),
+        })
+    }
+
+    /// Build the command line for a tool invocation without running it.
+    ///
+    /// Validates the provided arguments against the tool schema and fills in
+    /// defaults for absent optional positionals.
+    fn build_command(
+        &self,
+        name: &str,
+        arguments: &Option<JsonObject>,
+    ) -> Result<Command, ToolError> {
+        let tool_set = self.tool_set();
+        let tool = tool_set
             .tools
             .get(name)
-            .context(format!("Tool '{}' not found", name))?;
+            .ok_or_else(|| ToolError::ToolNotFound(format!("Tool '{}' not found", name)))?;
         let parts: Vec<&str> = name.split('_').collect();
-        anyhow::ensure!(!parts.is_empty(), "Invalid tool name: {}", name);
 
         let command_path = self.get_command_path(parts[0]);
         let mut cmd = Command::new(&command_path);
 
         // Add subcommands/flags from tool name
         let (subcommands, _) = parse_subcommand_parts(&parts);
-        eprintln!(
-            "[DEBUG] Tool: {} -> Command: {} {}",
-            name,
-            parts[0],
-            subcommands.join(" ")
-        );
         for subcommand in subcommands {
             cmd.arg(subcommand);
         }
 
-        // Build command arguments from the schema and provided values
-        if let Some(args) = arguments {
-            // Add positional arguments first (sorted by index)
-            let mut positionals: Vec<_> = tool.positionals.iter().collect();
-            positionals.sort_by_key(|p| p.index.unwrap_or(0));
+        // Build command arguments from the schema and provided values; no arguments at
+        // all still gets positional defaults and the required checks
+        let empty = JsonObject::new();
+        let args = arguments.as_ref().unwrap_or(&empty);
+        // Add positional arguments first (sorted by index)
+        let mut positionals: Vec<_> = tool.positionals.iter().collect();
+        positionals.sort_by_key(|p| p.index.unwrap_or(0));
 
-            for pos in positionals {
-                let param_name = pos.name.to_lowercase();
-                if let Some(value) = args.get(&param_name) {
-                    Self::add_positional_argument(&mut cmd, value, &pos.param_type)?;
-                } else if pos.required {
-                    anyhow::bail!("Required positional argument '{}' not provided", pos.name);
-                }
+        for pos in positionals {
+            let param_name = pos.name.to_lowercase();
+            if let Some(value) = args.get(&param_name) {
+                Self::check_exact_number(&pos.name, value)?;
+                Self::check_numeric_bounds(
+                    &pos.name,
+                    &pos.param_type,
+                    value,
+                    pos.minimum,
+                    pos.maximum,
+                )?;
+                let pattern = pos
+                    .effective_pattern()
+                    .and_then(|p| tool_set.patterns.get(p));
+                Self::check_pattern(&pos.name, value, pattern)?;
+                Self::add_positional_argument(&mut cmd, value, &pos.param_type)?;
+            } else if let (false, Some(default)) = (pos.required, &pos.default) {
+                Self::add_positional_argument(&mut cmd, default, &pos.param_type)?;
+            } else if pos.required {
+                return Err(ToolError::InvalidArguments(format!(
+                    "Required positional argument '{}' not provided",
+                    pos.name
+                )));
             }
+        }
 
-            // Add flags (boolean options)
-            for flag in &tool.flags {
-                let param_name = flag.name.to_lowercase();
-                if let Some(value) = args.get(&param_name) {
-                    if let Some(true) = value.as_bool() {
-                        cmd.arg(format!("--{}", flag.name));
-                    }
+        // Add flags (boolean options)
+        for flag in &tool.flags {
+            let param_name = flag.name.to_lowercase();
+            if let Some(value) = args.get(&param_name) {
+                if let Some(true) = value.as_bool() {
+                    Self::check_flag_allowed(&tool_set.config, name, &flag.name, &flag.short)?;
+                    cmd.arg(format!("--{}", flag.name));
                 }
             }
+        }
 
-            // Add options (flags with values)
-            for opt in &tool.options {
-                let param_name = opt.name.to_lowercase();
-                if let Some(value) = args.get(&param_name) {
-                    Self::add_option_argument(&mut cmd, &opt.name, value, &opt.param_type)?;
-                } else if opt.required {
-                    anyhow::bail!("Required option '{}' not provided", opt.name);
-                }
+        // Add options (flags with values)
+        for opt in &tool.options {
+            let param_name = opt.name.to_lowercase();
+            if let Some(value) = args.get(&param_name) {
+                Self::check_flag_allowed(&tool_set.config, name, &opt.name, &opt.short)?;
+                let resolved;
+                let value = if PROJECT_PATH_OPTIONS.contains(&opt.name.as_str()) {
+                    let cwd = std::env::current_dir().map_err(|e| {
+                        ToolError::execution_failed(format!(
+                            "Could not read the working directory: {}",
+                            e
+                        ))
+                    })?;
+                    // Forge reads `--out` relative to the project root, not the cwd
+                    let root = args.get("root").and_then(Value::as_str);
+                    let base = match (opt.name.as_str(), root) {
+                        ("out", Some(root)) => cwd.join(root),
+                        _ => cwd,
+                    };
+                    resolved = Self::resolve_project_path(&opt.name, value, &base)?;
+                    &resolved
+                } else {
+                    value
+                };
+                Self::check_enum_value(opt, value)?;
+                Self::check_exact_number(&opt.name, value)?;
+                Self::check_numeric_bounds(
+                    &opt.name,
+                    &opt.param_type,
+                    value,
+                    opt.minimum,
+                    opt.maximum,
+                )?;
+                let pattern = opt
+                    .effective_pattern()
+                    .and_then(|p| tool_set.patterns.get(p));
+                Self::check_pattern(&opt.name, value, pattern)?;
+                Self::add_option_argument(&mut cmd, &opt.name, value, &opt.param_type)?;
+            } else if opt.required {
+                return Err(ToolError::InvalidArguments(format!(
+                    "Required option '{}' not provided",
+                    opt.name
+                )));
             }
         }
 
-        // Execute the command
-        let output = cmd.output().with_context(|| {
-            if self.foundry_bin_path.is_some() {
-                format!(
-                    "Failed to execute '{}' at '{}'. Try running '{} --version'",
-                    parts[0], command_path, command_path
-                )
-            } else {
-                format!(
-                    "Failed to execute '{}'. Install Foundry from https://getfoundry.sh/",
-                    parts[0]
-                )
+        eprintln!(
+            "[DEBUG] Tool: {} -> Command: {}",
+            name,
+            self.render_command(parts[0], &cmd, arguments)
+        );
+
+        Ok(cmd)
+    }
+
+    /// Render a built command for logging, with sensitive values redacted.
+    fn render_command(
+        &self,
+        program: &str,
+        cmd: &Command,
+        arguments: &Option<JsonObject>,
+    ) -> String {
+        let args: Vec<String> = cmd
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+        let redactor = self.redactor();
+        let line = redactor.redact_command_line(program, &args);
+        redact::scrub(&line, &redactor.secret_values(arguments))
+    }
+
+    /// Reject a flag or option the configuration forbids for this tool.
+    ///
+    /// Forbidden flags are left out of the advertised schema, but a client can still
+    /// pass them, so they are checked again before the command is built.
+    fn check_flag_allowed(
+        config: &Config,
+        tool: &str,
+        flag: &str,
+        short: &Option<String>,
+    ) -> Result<(), ToolError> {
+        if config.is_flag_forbidden(tool, flag, short.as_deref()) {
+            return Err(ToolError::ForbiddenCommand(format!(
+                "Flag '--{}' is forbidden for '{}' by the server configuration",
+                flag, tool
+            )));
+        }
+        Ok(())
+    }
+
+    /// Reject option values outside the option's allowed set, before spawning anything.
+    fn check_enum_value(opt: &OptionSchema, value: &Value) -> Result<(), ToolError> {
+        let Some(allowed) = &opt.enum_values else {
+            return Ok(());
+        };
+
+        let values = match value.as_array() {
+            Some(items) => items.iter().collect(),
+            None => vec![value],
+        };
+        for value in values {
+            let provided = Self::value_to_string(value).unwrap_or_else(|| value.to_string());
+            if !allowed.contains(&provided) {
+                return Err(ToolError::InvalidArguments(format!(
+                    "Invalid value '{}' for option '{}'. Allowed values: {}",
+                    provided,
+                    opt.name,
+                    allowed.join(", ")
+                )));
             }
-        })?;
+        }
+        Ok(())
+    }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let combined = format!("{}{}", stdout, stderr);
+    /// Reject bare JSON integers too large to have been read exactly.
+    ///
+    /// serde_json reads integers beyond the u64 range as f64, which would silently corrupt
+    /// a uint256 such as a token amount, so those must be passed as strings.
+    fn check_exact_number(name: &str, value: &Value) -> Result<(), ToolError> {
+        let values = match value.as_array() {
+            Some(items) => items.iter().collect(),
+            None => vec![value],
+        };
+        for value in values {
+            let Some(number) = value.as_f64().filter(|_| value.is_f64()) else {
+                continue;
+            };
+            if number.fract() == 0.0 && number.abs() > MAX_EXACT_FLOAT_INTEGER {
+                return Err(ToolError::InvalidArguments(format!(
+                    "Parameter '{}' is too large to pass as a JSON number without losing precision; pass it as a decimal or hex string instead",
+                    name
+                )));
+            }
+        }
+        Ok(())
+    }
 
-        if output.status.success() {
-            Ok(combined)
-        } else {
-            anyhow::bail!(combined)
+    /// Reject numeric values outside the parameter's `minimum`/`maximum` bounds.
+    fn check_numeric_bounds(
+        name: &str,
+        param_type: &str,
+        value: &Value,
+        minimum: Option<f64>,
+        maximum: Option<f64>,
+    ) -> Result<(), ToolError> {
+        if param_type != "number" || (minimum.is_none() && maximum.is_none()) {
+            return Ok(());
+        }
+
+        let number = value
+            .as_f64()
+            .or_else(|| value.as_str().and_then(|s| s.trim().parse::<f64>().ok()))
+            .ok_or_else(|| {
+                ToolError::InvalidArguments(format!(
+                    "Parameter '{}' expects a number, got {}",
+                    name, value
+                ))
+            })?;
+
+        let below = minimum.is_some_and(|min| number < min);
+        let above = maximum.is_some_and(|max| number > max);
+        if below || above {
+            let range = match (minimum, maximum) {
+                (Some(min), Some(max)) => format!("between {} and {}", min, max),
+                (Some(min), None) => format!("at least {}", min),
+                (None, Some(max)) => format!("at most {}", max),
+                (None, None) => unreachable!(),
+            };
+            return Err(ToolError::InvalidArguments(format!(
+                "Parameter '{}' must be {}, got {}",
+                name, range, number
+            )));
+        }
+        Ok(())
+    }
+
+    /// Make a relative `--root` or `--out` absolute against `base`, so forge doesn't
+    /// resolve it against something else. That is the directory the command runs in,
+    /// or for `--out` alongside a `--root`, the project root.
+    ///
+    /// A root must be an existing Foundry project (with a `foundry.toml`); forge's own
+    /// error for a missing root is a confusing "not found" about some file inside it.
+    fn resolve_project_path(name: &str, value: &Value, base: &Path) -> Result<Value, ToolError> {
+        let Some(path) = value.as_str() else {
+            return Ok(value.clone());
+        };
+        let resolved = base.join(path);
+
+        if name == "root" {
+            if !resolved.is_dir() {
+                return Err(ToolError::InvalidArguments(format!(
+                    "Project root '{}' does not exist",
+                    resolved.display()
+                )));
+            }
+            if !resolved.join("foundry.toml").is_file() {
+                return Err(ToolError::InvalidArguments(format!(
+                    "'{}' is not a Foundry project (no foundry.toml)",
+                    resolved.display()
+                )));
+            }
+        }
+        Ok(Value::String(resolved.display().to_string()))
+    }
+
+    /// Reject string values that don't match the parameter's compiled pattern.
+    fn check_pattern(name: &str, value: &Value, pattern: Option<&Regex>) -> Result<(), ToolError> {
+        let Some(re) = pattern else {
+            return Ok(());
+        };
+
+        let values = match value.as_array() {
+            Some(items) => items.iter().collect(),
+            None => vec![value],
+        };
+        for value in values {
+            let Some(s) = Self::value_to_string(value) else {
+                continue;
+            };
+            if !re.is_match(&s) {
+                return Err(ToolError::InvalidArguments(format!(
+                    "Invalid value '{}' for parameter '{}': must match pattern {}",
+                    s,
+                    name,
+                    re.as_str()
+                )));
+            }
         }
+        Ok(())
     }
 
     fn value_to_string(value: &Value) -> Option<String> {
@@ -449,7 +1213,7 @@ impl FoundryExecutor {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::schema::{FlagSchema, OptionSchema, PositionalSchema};
+    use crate::schema::{FlagSchema, PositionalSchema};
 
     fn create_test_schema() -> SchemaFile {
         SchemaFile {
@@ -460,6 +1224,7 @@ mod tests {
                     positionals: vec![],
                     options: vec![],
                     flags: vec![],
+                    ..Default::default()
                 },
                 ToolSchema {
                     name: "cast_call".to_string(),
@@ -470,6 +1235,7 @@ mod tests {
                         description: "Contract address".to_string(),
                         required: true,
                         index: Some(0),
+                        ..Default::default()
                     }],
                     options: vec![
                         OptionSchema {
@@ -480,6 +1246,7 @@ mod tests {
                             short: None,
                             value_name: None,
                             default: None,
+                            ..Default::default()
                         },
                         OptionSchema {
                             name: "private-key".to_string(),
@@ -489,6 +1256,7 @@ mod tests {
                             short: None,
                             value_name: None,
                             default: None,
+                            ..Default::default()
                         },
                     ],
                     flags: vec![
@@ -498,6 +1266,7 @@ mod tests {
                             description: "Output as JSON".to_string(),
                             required: false,
                             short: None,
+                            ..Default::default()
                         },
                         FlagSchema {
                             name: "broadcast".to_string(),
@@ -505,8 +1274,10 @@ mod tests {
                             description: "Broadcast transaction".to_string(),
                             required: false,
                             short: None,
+                            ..Default::default()
                         },
                     ],
+                    ..Default::default()
                 },
                 ToolSchema {
                     name: "anvil".to_string(),
@@ -514,6 +1285,7 @@ mod tests {
                     positionals: vec![],
                     options: vec![],
                     flags: vec![],
+                    ..Default::default()
                 },
                 ToolSchema {
                     name: "forge_script".to_string(),
@@ -527,8 +1299,10 @@ mod tests {
                         short: None,
                         value_name: None,
                         default: None,
+                        ..Default::default()
                     }],
                     flags: vec![],
+                    ..Default::default()
                 },
             ],
         }
@@ -579,11 +1353,11 @@ mod tests {
         let executor = FoundryExecutor::with_config(schema, config);
 
         // anvil should be filtered out by default
-        assert!(executor.tools.get("anvil").is_none());
+        assert!(!executor.tool_set().tools.contains_key("anvil"));
 
         // Safe tools should be present
-        assert!(executor.tools.get("forge_build").is_some());
-        assert!(executor.tools.get("cast_call").is_some());
+        assert!(executor.tool_set().tools.contains_key("forge_build"));
+        assert!(executor.tool_set().tools.contains_key("cast_call"));
     }
 
     /// Test that custom config can forbid specific commands while allowing dangerous ones
@@ -594,18 +1368,19 @@ mod tests {
             forbidden_commands: vec!["forge_build".to_string()],
             forbidden_flags: vec![],
             allow_dangerous: true, // Allow anvil but not forge_build
+            ..Default::default()
         };
 
         let executor = FoundryExecutor::with_config(schema, config);
 
         // forge_build should be filtered out
-        assert!(executor.tools.get("forge_build").is_none());
+        assert!(!executor.tool_set().tools.contains_key("forge_build"));
 
         // anvil should be present (allow_dangerous = true)
-        assert!(executor.tools.get("anvil").is_some());
+        assert!(executor.tool_set().tools.contains_key("anvil"));
 
         // Other tools should be present
-        assert!(executor.tools.get("cast_call").is_some());
+        assert!(executor.tool_set().tools.contains_key("cast_call"));
     }
 
     /// Test that forbidden flags are properly filtered from tool schemas
@@ -616,6 +1391,7 @@ mod tests {
             forbidden_commands: vec![],
             forbidden_flags: vec!["broadcast".to_string(), "private-key".to_string()],
             allow_dangerous: true,
+            ..Default::default()
         };
 
         let executor = FoundryExecutor::with_config(schema, config);
@@ -641,21 +1417,95 @@ mod tests {
         assert!(!properties.contains_key("private-key"));
     }
 
-    /// Test that tools with forbidden base commands are filtered (e.g. anvil_fork when anvil is forbidden)
+    /// Test that a scoped forbidden flag is removed only from its tool
     #[test]
-    fn test_is_tool_allowed_filters_base_command() {
+    fn test_executor_filters_scoped_forbidden_flags() {
+        let schema = create_test_schema();
         let config = Config {
-            forbidden_commands: vec!["anvil".to_string()],
-            forbidden_flags: vec![],
+            forbidden_commands: vec![],
+            forbidden_flags: vec!["forge_script:broadcast".to_string()],
             allow_dangerous: true,
+            ..Default::default()
         };
 
-        let tool = ToolSchema {
-            name: "anvil_fork".to_string(),
-            description: "Fork with anvil".to_string(),
-            positionals: vec![],
-            options: vec![],
-            flags: vec![],
+        let executor = FoundryExecutor::with_config(schema, config);
+        let properties = |name: &str| {
+            executor
+                .tool_list()
+                .iter()
+                .find(|t| t.name == name)
+                .unwrap()
+                .input_schema["properties"]
+                .as_object()
+                .unwrap()
+                .clone()
+        };
+
+        assert!(!properties("forge_script").contains_key("broadcast"));
+        assert!(properties("cast_call").contains_key("broadcast"));
+    }
+
+    /// Test that an allowlist exposes only the listed tools, by name or base command
+    #[test]
+    fn test_with_config_allowlist_filtering() {
+        let config = Config {
+            allowed_commands: Some(vec!["forge_build".to_string(), "anvil".to_string()]),
+            ..Default::default()
+        };
+
+        let executor = FoundryExecutor::with_config(create_test_schema(), config);
+        let tool_list = executor.tool_list();
+        let mut names: Vec<&str> = tool_list.iter().map(|t| &*t.name).collect();
+        names.sort();
+
+        assert_eq!(names, vec!["anvil", "forge_build"]);
+    }
+
+    /// Test that the allowlist overrides the forbidden command list
+    #[test]
+    fn test_allowlist_overrides_forbidden_commands() {
+        let config = Config {
+            forbidden_commands: vec!["anvil".to_string()],
+            allowed_commands: Some(vec!["anvil".to_string()]),
+            ..Default::default()
+        };
+
+        let executor = FoundryExecutor::with_config(create_test_schema(), config);
+        assert!(executor.tool_set().tools.contains_key("anvil"));
+        assert!(!executor.tool_set().tools.contains_key("cast_call"));
+    }
+
+    /// Test that glob patterns in forbidden_commands filter matching tools only
+    #[test]
+    fn test_is_tool_allowed_glob_pattern() {
+        let config = Config {
+            forbidden_commands: vec!["forge_*".to_string()],
+            ..Default::default()
+        };
+
+        let executor = FoundryExecutor::with_config(create_test_schema(), config);
+        assert!(!executor.tool_set().tools.contains_key("forge_build"));
+        assert!(!executor.tool_set().tools.contains_key("forge_script"));
+        assert!(executor.tool_set().tools.contains_key("cast_call"));
+    }
+
+    /// Test that tools with forbidden base commands are filtered (e.g. anvil_fork when anvil is forbidden)
+    #[test]
+    fn test_is_tool_allowed_filters_base_command() {
+        let config = Config {
+            forbidden_commands: vec!["anvil".to_string()],
+            forbidden_flags: vec![],
+            allow_dangerous: true,
+            ..Default::default()
+        };
+
+        let tool = ToolSchema {
+            name: "anvil_fork".to_string(),
+            description: "Fork with anvil".to_string(),
+            positionals: vec![],
+            options: vec![],
+            flags: vec![],
+            ..Default::default()
         };
 
         // Should be filtered because base command "anvil" is forbidden
@@ -669,6 +1519,7 @@ mod tests {
             forbidden_commands: vec!["forge_script".to_string()],
             forbidden_flags: vec![],
             allow_dangerous: true,
+            ..Default::default()
         };
 
         let tool = ToolSchema {
@@ -677,12 +1528,105 @@ mod tests {
             positionals: vec![],
             options: vec![],
             flags: vec![],
+            ..Default::default()
         };
 
         // Should be filtered by exact name match
         assert!(!FoundryExecutor::is_tool_allowed(&tool, &config));
     }
 
+    /// Test that read-only mode still blocks mutations when an allowlist is configured
+    #[test]
+    fn test_read_only_applies_with_allowlist() {
+        let config = Config {
+            read_only: true,
+            allowed_commands: Some(vec!["cast".to_string()]),
+            ..Default::default()
+        };
+        let tool = |name: &str| ToolSchema {
+            name: name.to_string(),
+            description: "Cast".to_string(),
+            ..Default::default()
+        };
+
+        assert!(FoundryExecutor::is_tool_allowed(
+            &tool("cast_call"),
+            &config
+        ));
+        assert!(!FoundryExecutor::is_tool_allowed(
+            &tool("cast_send"),
+            &config
+        ));
+    }
+
+    /// Test that a forbidden send stays listed as a simulation only when enabled
+    #[test]
+    fn test_simulate_forbidden_sends() {
+        let schema = || SchemaFile {
+            tools: ["cast_call", "cast_send"]
+                .into_iter()
+                .map(|name| ToolSchema {
+                    name: name.to_string(),
+                    description: "Cast".to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        };
+        let send = |executor: &FoundryExecutor| {
+            executor
+                .tool_list()
+                .into_iter()
+                .find(|tool| tool.name == "cast_send")
+        };
+
+        let executor = FoundryExecutor::with_config(schema(), Config::read_only());
+        assert!(!executor.simulates_send());
+        assert!(send(&executor).is_none());
+
+        let config = Config {
+            simulate_forbidden_sends: true,
+            ..Config::read_only()
+        };
+        let executor = FoundryExecutor::with_config(schema(), config);
+        assert!(executor.simulates_send());
+        let description = send(&executor).unwrap().description.unwrap();
+        assert!(description.starts_with("SIMULATION ONLY"));
+        assert!(matches!(
+            executor.run_tool("cast_send", &None, &[], None),
+            Err(ToolError::ToolNotFound(_))
+        ));
+
+        executor.set_elevated(true);
+        assert!(!executor.simulates_send());
+    }
+
+    /// Test that elevation exposes forbidden tools until it is revoked
+    #[test]
+    fn test_set_elevated_lifts_restrictions() {
+        let schema = SchemaFile {
+            tools: vec![ToolSchema {
+                name: "forge_script".to_string(),
+                description: "Run script".to_string(),
+                ..Default::default()
+            }],
+        };
+        let executor = FoundryExecutor::with_config(schema, Config::read_only());
+        assert!(executor.tool_list().is_empty());
+
+        executor.set_elevated(true);
+        assert!(executor.is_elevated());
+        assert_eq!(executor.tool_list().len(), 1);
+        assert!(executor.config().read_only);
+
+        // Reloading while elevated keeps the session elevated
+        executor.reload_config(Config::read_only());
+        assert_eq!(executor.tool_list().len(), 1);
+
+        executor.set_elevated(false);
+        assert!(!executor.is_elevated());
+        assert!(executor.tool_list().is_empty());
+    }
+
     /// Test that parameter types are correctly mapped to JSON schema types
     #[test]
     fn test_map_type_conversions() {
@@ -708,8 +1652,8 @@ mod tests {
             Some("42".to_string())
         );
         assert_eq!(
-            FoundryExecutor::value_to_string(&json!(3.14)),
-            Some("3.14".to_string())
+            FoundryExecutor::value_to_string(&json!(2.5)),
+            Some("2.5".to_string())
         );
         assert_eq!(
             FoundryExecutor::value_to_string(&json!(100u64)),
@@ -740,6 +1684,7 @@ mod tests {
                 description: "First arg".to_string(),
                 required: true,
                 index: Some(0),
+                ..Default::default()
             }],
             options: vec![OptionSchema {
                 name: "option1".to_string(),
@@ -749,6 +1694,7 @@ mod tests {
                 short: None,
                 value_name: None,
                 default: Some(serde_json::json!("default_value")),
+                ..Default::default()
             }],
             flags: vec![FlagSchema {
                 name: "flag1".to_string(),
@@ -756,7 +1702,9 @@ mod tests {
                 description: "Flag 1".to_string(),
                 required: false,
                 short: None,
+                ..Default::default()
             }],
+            ..Default::default()
         };
 
         let context = ContextConfig::default();
@@ -801,7 +1749,611 @@ mod tests {
 
         let result = executor.execute_tool("nonexistent_tool", &None);
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("not found"));
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("not found"));
+        assert_eq!(err.kind(), "tool_not_found");
+    }
+
+    /// Test that a missing required positional is reported as invalid arguments
+    #[test]
+    fn test_execute_tool_missing_required_positional() {
+        let schema = create_test_schema();
+        let executor = FoundryExecutor::new(schema);
+
+        let result = executor.execute_tool("cast_call", &Some(serde_json::Map::new()));
+        assert!(matches!(result, Err(ToolError::InvalidArguments(_))));
+    }
+
+    fn evm_version_option() -> OptionSchema {
+        OptionSchema {
+            name: "evm-version".to_string(),
+            param_type: "string".to_string(),
+            description: "The target EVM version".to_string(),
+            required: false,
+            short: None,
+            value_name: None,
+            default: None,
+            enum_values: Some(vec!["paris".to_string(), "shanghai".to_string()]),
+            ..Default::default()
+        }
+    }
+
+    /// Test that a description override replaces the schema's text and context appends to it
+    #[test]
+    fn test_schema_to_tool_description_override() {
+        let schema = ToolSchema {
+            name: "forge_build".to_string(),
+            description: "Build".to_string(),
+            ..Default::default()
+        };
+        let config = Config {
+            tool_description_overrides: [(
+                "forge_build".to_string(),
+                "Compile the project's contracts".to_string(),
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+        let context: ContextConfig = serde_json::from_value(serde_json::json!({
+            "tools": {"forge_build": "Run from the project root."}
+        }))
+        .unwrap();
+
+        let tool = FoundryExecutor::schema_to_tool(&schema, &config, &ContextConfig::default());
+        assert_eq!(
+            tool.description.as_deref(),
+            Some("Compile the project's contracts")
+        );
+
+        let tool = FoundryExecutor::schema_to_tool(&schema, &config, &context);
+        assert_eq!(
+            tool.description.as_deref(),
+            Some("Compile the project's contracts\n\nRun from the project root.")
+        );
+
+        let tool = FoundryExecutor::schema_to_tool(&schema, &Config::default(), &context);
+        assert_eq!(
+            tool.description.as_deref(),
+            Some("Build\n\nRun from the project root.")
+        );
+    }
+
+    /// Test that enum_values are emitted as a JSON Schema enum
+    #[test]
+    fn test_schema_to_tool_emits_enum() {
+        let schema = ToolSchema {
+            name: "forge_build".to_string(),
+            description: "Build".to_string(),
+            positionals: vec![],
+            options: vec![evm_version_option()],
+            flags: vec![],
+            ..Default::default()
+        };
+
+        let tool =
+            FoundryExecutor::schema_to_tool(&schema, &Config::default(), &ContextConfig::default());
+        let prop = &tool.input_schema["properties"]["evm-version"];
+        assert_eq!(prop["enum"], serde_json::json!(["paris", "shanghai"]));
+    }
+
+    /// Test that option values outside the enum are rejected before spawning
+    #[test]
+    fn test_execute_tool_rejects_value_outside_enum() {
+        let schema = SchemaFile {
+            tools: vec![ToolSchema {
+                name: "forge_build".to_string(),
+                description: "Build".to_string(),
+                positionals: vec![],
+                options: vec![evm_version_option()],
+                flags: vec![],
+                ..Default::default()
+            }],
+        };
+        let executor = FoundryExecutor::new(schema);
+
+        let mut args = serde_json::Map::new();
+        args.insert("evm-version".to_string(), serde_json::json!("shangai"));
+        let err = executor
+            .execute_tool("forge_build", &Some(args))
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidArguments(_)));
+        assert!(err.message().contains("paris, shanghai"));
+    }
+
+    fn accounts_option() -> OptionSchema {
+        OptionSchema {
+            name: "accounts".to_string(),
+            param_type: "number".to_string(),
+            description: "Number of dev accounts".to_string(),
+            required: false,
+            minimum: Some(1.0),
+            maximum: Some(255.0),
+            ..Default::default()
+        }
+    }
+
+    /// Test that numeric bounds are emitted as JSON Schema minimum/maximum
+    #[test]
+    fn test_schema_to_tool_emits_numeric_bounds() {
+        let schema = ToolSchema {
+            name: "anvil".to_string(),
+            description: "Anvil".to_string(),
+            options: vec![accounts_option()],
+            ..Default::default()
+        };
+
+        let tool =
+            FoundryExecutor::schema_to_tool(&schema, &Config::default(), &ContextConfig::default());
+        let prop = &tool.input_schema["properties"]["accounts"];
+        assert_eq!(prop["minimum"], serde_json::json!(1.0));
+        assert_eq!(prop["maximum"], serde_json::json!(255.0));
+    }
+
+    /// Test that out-of-range and non-numeric values are rejected
+    #[test]
+    fn test_check_numeric_bounds() {
+        use serde_json::json;
+
+        let check = |value: Value| {
+            FoundryExecutor::check_numeric_bounds(
+                "accounts",
+                "number",
+                &value,
+                Some(1.0),
+                Some(255.0),
+            )
+        };
+        assert!(check(json!(10)).is_ok());
+        assert!(check(json!("255")).is_ok());
+
+        let err = check(json!(0)).unwrap_err();
+        assert_eq!(
+            err.message(),
+            "Parameter 'accounts' must be between 1 and 255, got 0"
+        );
+        assert!(check(json!(256)).is_err());
+        assert!(check(json!("many")).is_err());
+    }
+
+    /// Test that integers too large for an exact JSON number are refused, while the same
+    /// values as strings reach the command unchanged
+    #[test]
+    fn test_large_numbers() {
+        use serde_json::json;
+
+        const MAX_UINT256: &str =
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+
+        let schema = SchemaFile {
+            tools: vec![ToolSchema {
+                name: "cast_call".to_string(),
+                description: "Call".to_string(),
+                positionals: vec![PositionalSchema {
+                    name: "value".to_string(),
+                    param_type: "string".to_string(),
+                    required: true,
+                    index: Some(0),
+                    ..Default::default()
+                }],
+                options: vec![OptionSchema {
+                    name: "values".to_string(),
+                    param_type: "array".to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+        let executor = FoundryExecutor::with_config(schema, Config::default());
+        let build = |args: Value| executor.build_command("cast_call", &args.as_object().cloned());
+
+        let cmd = build(json!({"value": MAX_UINT256})).unwrap();
+        let args: Vec<_> = cmd.get_args().collect();
+        assert_eq!(args.last().unwrap().to_str(), Some(MAX_UINT256));
+
+        // u64::MAX is still read exactly as a bare number
+        let cmd = build(json!({"value": u64::MAX})).unwrap();
+        let args: Vec<_> = cmd.get_args().collect();
+        assert_eq!(
+            args.last().unwrap().to_str(),
+            Some(u64::MAX.to_string().as_str())
+        );
+        assert!(build(json!({"value": 2.5})).is_ok());
+
+        let max_uint256: Value = serde_json::from_str(MAX_UINT256).unwrap();
+        let err = build(json!({ "value": max_uint256 })).unwrap_err();
+        assert!(matches!(err, ToolError::InvalidArguments(_)));
+        assert!(err.message().contains("as a decimal or hex string"));
+
+        let too_big: Value = serde_json::from_str("18446744073709551616").unwrap();
+        assert!(build(json!({"value": "1", "values": ["1", too_big]})).is_err());
+        assert!(build(json!({"value": 1e300})).is_err());
+    }
+
+    /// Test that semantic types produce a JSON Schema pattern for string parameters
+    #[test]
+    fn test_schema_to_tool_emits_pattern() {
+        let schema = ToolSchema {
+            name: "cast_code".to_string(),
+            description: "Get code".to_string(),
+            positionals: vec![PositionalSchema {
+                name: "who".to_string(),
+                param_type: "string".to_string(),
+                required: true,
+                index: Some(0),
+                semantic_type: Some("address".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let tool =
+            FoundryExecutor::schema_to_tool(&schema, &Config::default(), &ContextConfig::default());
+        assert_eq!(
+            tool.input_schema["properties"]["who"]["pattern"],
+            "^0x[0-9a-fA-F]{40}$"
+        );
+    }
+
+    /// Test that values not matching the pattern are rejected, including array items
+    #[test]
+    fn test_check_pattern() {
+        use serde_json::json;
+
+        let re = Regex::new("^0x[0-9a-fA-F]{40}$").unwrap();
+        let pattern = Some(&re);
+        let address = "0x000000000000000000000000000000000000dEaD";
+        assert!(FoundryExecutor::check_pattern("who", &json!(address), pattern).is_ok());
+        assert!(FoundryExecutor::check_pattern("who", &json!(address), None).is_ok());
+
+        let err = FoundryExecutor::check_pattern("who", &json!("0xdead"), pattern).unwrap_err();
+        assert!(matches!(err, ToolError::InvalidArguments(_)));
+        assert!(FoundryExecutor::check_pattern("who", &json!([address, "nope"]), pattern).is_err());
+    }
+
+    /// Test that relative root/out paths are made absolute and a root must be a project
+    #[test]
+    fn test_resolve_project_path() {
+        use serde_json::json;
+
+        let cwd = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(cwd.path().join("app")).unwrap();
+        std::fs::create_dir_all(cwd.path().join("docs")).unwrap();
+        std::fs::write(cwd.path().join("app/foundry.toml"), "[profile.default]\n").unwrap();
+
+        let root = FoundryExecutor::resolve_project_path("root", &json!("app"), cwd.path());
+        assert_eq!(
+            root.unwrap(),
+            json!(cwd.path().join("app").display().to_string())
+        );
+        let out = FoundryExecutor::resolve_project_path("out", &json!("build"), cwd.path());
+        assert_eq!(
+            out.unwrap(),
+            json!(cwd.path().join("build").display().to_string())
+        );
+
+        for missing in ["missing", "docs"] {
+            let err = FoundryExecutor::resolve_project_path("root", &json!(missing), cwd.path())
+                .unwrap_err();
+            assert!(matches!(err, ToolError::InvalidArguments(_)));
+        }
+        let err =
+            FoundryExecutor::resolve_project_path("root", &json!("docs"), cwd.path()).unwrap_err();
+        assert!(err.message().contains("is not a Foundry project"));
+    }
+
+    /// Test that a relative out is resolved inside the given root rather than the cwd
+    #[test]
+    fn test_resolve_out_against_root() {
+        let project = tempfile::tempdir().unwrap();
+        std::fs::write(project.path().join("foundry.toml"), "[profile.default]\n").unwrap();
+        let schema = SchemaFile {
+            tools: vec![ToolSchema {
+                name: "forge_build".to_string(),
+                description: "Build the project".to_string(),
+                options: ["root", "out"]
+                    .into_iter()
+                    .map(|name| crate::schema::OptionSchema {
+                        name: name.to_string(),
+                        param_type: "string".to_string(),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            }],
+        };
+        let executor = FoundryExecutor::new(schema);
+        let args = serde_json::json!({
+            "root": project.path().display().to_string(),
+            "out": "build",
+        });
+
+        let cmd = executor
+            .build_command("forge_build", &args.as_object().cloned())
+            .unwrap();
+        let out = project.path().join("build").display().to_string();
+        assert!(command_args(&cmd).contains(&out));
+    }
+
+    fn init_schema() -> SchemaFile {
+        SchemaFile {
+            tools: vec![ToolSchema {
+                name: "forge_init".to_string(),
+                description: "Create a new project".to_string(),
+                positionals: vec![
+                    PositionalSchema {
+                        name: "TEMPLATE".to_string(),
+                        param_type: "string".to_string(),
+                        required: true,
+                        index: Some(0),
+                        ..Default::default()
+                    },
+                    PositionalSchema {
+                        name: "PATH".to_string(),
+                        param_type: "string".to_string(),
+                        required: false,
+                        index: Some(1),
+                        default: Some(serde_json::json!(".")),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }],
+        }
+    }
+
+    fn command_args(cmd: &Command) -> Vec<String> {
+        cmd.get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect()
+    }
+
+    /// Test that a provided positional value takes precedence over its default
+    #[test]
+    fn test_positional_default_present() {
+        let executor = FoundryExecutor::new(init_schema());
+        let mut args = serde_json::Map::new();
+        args.insert("template".to_string(), serde_json::json!("t"));
+        args.insert("path".to_string(), serde_json::json!("my-project"));
+
+        let cmd = executor.build_command("forge_init", &Some(args)).unwrap();
+        assert_eq!(command_args(&cmd), vec!["init", "t", "my-project"]);
+    }
+
+    /// Test that an absent optional positional falls back to its default
+    #[test]
+    fn test_positional_default_absent() {
+        let executor = FoundryExecutor::new(init_schema());
+        let mut args = serde_json::Map::new();
+        args.insert("template".to_string(), serde_json::json!("t"));
+
+        let cmd = executor.build_command("forge_init", &Some(args)).unwrap();
+        assert_eq!(command_args(&cmd), vec!["init", "t", "."]);
+    }
+
+    /// Test that an absent required positional is still an error despite other defaults
+    #[test]
+    fn test_positional_default_absent_required() {
+        let executor = FoundryExecutor::new(init_schema());
+        let mut args = serde_json::Map::new();
+        args.insert("path".to_string(), serde_json::json!("my-project"));
+
+        let err = executor
+            .build_command("forge_init", &Some(args))
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidArguments(_)));
+    }
+
+    /// Test that a call without any arguments still checks required positionals
+    #[test]
+    fn test_positional_required_without_arguments() {
+        let executor = FoundryExecutor::new(init_schema());
+        let err = executor.build_command("forge_init", &None).unwrap_err();
+        assert!(matches!(err, ToolError::InvalidArguments(_)));
+        assert!(err.message().contains("TEMPLATE"));
+    }
+
+    /// Test that a call without any arguments still fills in positional defaults
+    #[test]
+    fn test_positional_default_without_arguments() {
+        let mut schema = init_schema();
+        schema.tools[0].positionals.remove(0);
+        let executor = FoundryExecutor::new(schema);
+
+        let cmd = executor.build_command("forge_init", &None).unwrap();
+        assert_eq!(command_args(&cmd), vec!["init", "."]);
+    }
+
+    /// Test that positional defaults are surfaced in the generated schema
+    #[test]
+    fn test_schema_to_tool_positional_default() {
+        let schema = init_schema();
+        let tool = FoundryExecutor::schema_to_tool(
+            &schema.tools[0],
+            &Config::default(),
+            &ContextConfig::default(),
+        );
+        assert_eq!(tool.input_schema["properties"]["path"]["default"], ".");
+    }
+
+    /// Test that sensitive option and positional values never reach the rendered command line
+    #[test]
+    fn test_render_command_redacts_secrets() {
+        let key = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+        let schema = SchemaFile {
+            tools: vec![ToolSchema {
+                name: "cast_wallet".to_string(),
+                description: "Wallet management".to_string(),
+                positionals: vec![PositionalSchema {
+                    name: "SEED".to_string(),
+                    param_type: "string".to_string(),
+                    index: Some(0),
+                    ..Default::default()
+                }],
+                options: vec![OptionSchema {
+                    name: "private-key".to_string(),
+                    param_type: "string".to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+        let config = Config {
+            sensitive_arguments: vec!["seed".to_string()],
+            ..Default::default()
+        };
+        let executor = FoundryExecutor::with_config(schema, config);
+
+        let mut args = serde_json::Map::new();
+        args.insert(
+            "seed".to_string(),
+            serde_json::json!("correct horse battery"),
+        );
+        args.insert("private-key".to_string(), serde_json::json!(key));
+        let args = Some(args);
+
+        let cmd = executor.build_command("cast_wallet", &args).unwrap();
+        let line = executor.render_command("cast", &cmd, &args);
+        assert!(!line.contains(key));
+        assert!(!line.contains("horse"));
+        assert_eq!(
+            line,
+            "cast wallet ***redacted*** --private-key ***redacted***"
+        );
+    }
+
+    /// Test that hidden tools are omitted from the tool list but remain executable
+    #[test]
+    fn test_hidden_tool_not_listed_but_executable() {
+        let schema = SchemaFile {
+            tools: vec![ToolSchema {
+                name: "forge_legacy".to_string(),
+                description: "Old command".to_string(),
+                hidden: true,
+                ..Default::default()
+            }],
+        };
+        let executor = FoundryExecutor::with_config(schema, Config::default());
+
+        assert!(executor.tool_list().is_empty());
+        assert!(executor.build_command("forge_legacy", &None).is_ok());
+    }
+
+    /// Test that schema tools overridden by a built-in are not listed but remain executable
+    #[test]
+    fn test_builtin_override_not_listed_but_executable() {
+        let schema = SchemaFile {
+            tools: vec![ToolSchema {
+                name: "cast_storage".to_string(),
+                description: "Get the raw value of a contract's storage slot".to_string(),
+                ..Default::default()
+            }],
+        };
+        let executor = FoundryExecutor::with_config(schema, Config::default());
+
+        assert!(executor.tool_list().is_empty());
+        assert!(executor.build_command("cast_storage", &None).is_ok());
+    }
+
+    /// Test that hidden parameters are omitted and deprecated ones are annotated
+    #[test]
+    fn test_schema_to_tool_hidden_and_deprecated_params() {
+        let schema = ToolSchema {
+            name: "forge_build".to_string(),
+            description: "Build".to_string(),
+            options: vec![OptionSchema {
+                name: "old-opt".to_string(),
+                param_type: "string".to_string(),
+                description: "Old option".to_string(),
+                deprecated: true,
+                ..Default::default()
+            }],
+            flags: vec![FlagSchema {
+                name: "secret".to_string(),
+                param_type: "boolean".to_string(),
+                description: "Internal".to_string(),
+                hidden: true,
+                ..Default::default()
+            }],
+            deprecated: true,
+            ..Default::default()
+        };
+
+        let tool =
+            FoundryExecutor::schema_to_tool(&schema, &Config::default(), &ContextConfig::default());
+        let properties = tool.input_schema["properties"].as_object().unwrap();
+        assert!(!properties.contains_key("secret"));
+        assert!(properties["old-opt"]["description"]
+            .as_str()
+            .unwrap()
+            .contains("Deprecated"));
+        assert!(tool.description.unwrap().contains("Deprecated"));
+    }
+
+    /// Test that hidden parameters are still accepted at execution
+    #[test]
+    fn test_hidden_flag_still_executable() {
+        let schema = SchemaFile {
+            tools: vec![ToolSchema {
+                name: "forge_build".to_string(),
+                description: "Build".to_string(),
+                flags: vec![FlagSchema {
+                    name: "secret".to_string(),
+                    param_type: "boolean".to_string(),
+                    hidden: true,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+        let executor = FoundryExecutor::new(schema);
+        let mut args = serde_json::Map::new();
+        args.insert("secret".to_string(), serde_json::json!(true));
+
+        let cmd = executor.build_command("forge_build", &Some(args)).unwrap();
+        assert_eq!(command_args(&cmd), vec!["build", "--secret"]);
+    }
+
+    /// Test that a binary's .exe is used only when the plain name doesn't exist
+    #[test]
+    fn test_binary_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path().to_str().unwrap();
+        std::fs::write(dir.path().join("forge.exe"), "").unwrap();
+        std::fs::write(dir.path().join("cast"), "").unwrap();
+        std::fs::write(dir.path().join("cast.exe"), "").unwrap();
+
+        assert_eq!(binary_path(None, "forge"), "forge");
+        assert_eq!(
+            binary_path(Some(bin), "forge"),
+            dir.path().join("forge.exe").to_string_lossy()
+        );
+        assert_eq!(
+            binary_path(Some(bin), "cast"),
+            dir.path().join("cast").to_string_lossy()
+        );
+        assert_eq!(
+            binary_path(Some(bin), "anvil"),
+            dir.path().join("anvil").to_string_lossy()
+        );
+    }
+
+    /// Test that Windows searches only under USERPROFILE and Unix adds the usual prefixes
+    #[test]
+    fn test_foundry_search_paths() {
+        let paths = FoundryExecutor::foundry_search_paths(true, Some(r"C:\Users\dev"));
+        assert_eq!(
+            paths,
+            vec![Path::new(r"C:\Users\dev").join(".foundry").join("bin")]
+        );
+
+        let paths = FoundryExecutor::foundry_search_paths(false, Some("/home/dev"));
+        assert_eq!(paths[0], Path::new("/home/dev/.foundry/bin"));
+        assert!(paths.contains(&"/usr/local/bin".into()));
+
+        // Without a home directory the other locations are still searched
+        assert!(FoundryExecutor::foundry_search_paths(true, None).is_empty());
+        assert_eq!(FoundryExecutor::foundry_search_paths(false, None).len(), 2);
     }
 
     /// Test that command path includes bin directory when foundry bin path is set
@@ -837,7 +2389,7 @@ mod tests {
         let executor = FoundryExecutor::with_config(schema, config);
 
         // Verify dangerous commands are filtered
-        assert!(executor.tools.get("anvil").is_none());
+        assert!(!executor.tool_set().tools.contains_key("anvil"));
 
         // Verify dangerous flags are filtered from the MCP tool list
         let tool_list = executor.tool_list();
@@ -883,6 +2435,7 @@ mod tests {
             forbidden_commands: vec!["cast_call".to_string()],
             forbidden_flags: vec![],
             allow_dangerous: true,
+            ..Default::default()
         };
 
         let executor = FoundryExecutor::with_config(schema, config);
@@ -907,6 +2460,7 @@ mod tests {
                     positionals: vec![],
                     options: vec![],
                     flags: vec![],
+                    ..Default::default()
                 },
                 ToolSchema {
                     name: "cast_to_wei".to_string(),
@@ -914,6 +2468,7 @@ mod tests {
                     positionals: vec![],
                     options: vec![],
                     flags: vec![],
+                    ..Default::default()
                 },
                 ToolSchema {
                     name: "cast_max_int".to_string(),
@@ -921,6 +2476,7 @@ mod tests {
                     positionals: vec![],
                     options: vec![],
                     flags: vec![],
+                    ..Default::default()
                 },
                 ToolSchema {
                     name: "cast_call".to_string(), // Not a conversion command
@@ -928,6 +2484,7 @@ mod tests {
                     positionals: vec![],
                     options: vec![],
                     flags: vec![],
+                    ..Default::default()
                 },
             ],
         };
@@ -963,4 +2520,198 @@ mod tests {
         assert!(!FoundryExecutor::is_cast_conversion_command("forge_build"));
         assert!(!FoundryExecutor::is_cast_conversion_command("anvil"));
     }
+
+    /// Test that forbidden flags and options passed anyway are rejected, not emitted
+    #[test]
+    fn test_build_command_rejects_forbidden_flags() {
+        let config = Config {
+            forbidden_flags: vec!["broadcast".to_string(), "cast_call:private-key".to_string()],
+            ..Default::default()
+        };
+        let executor = FoundryExecutor::with_config(create_test_schema(), config);
+        let build = |args: Value| executor.build_command("cast_call", &args.as_object().cloned());
+
+        let err = build(serde_json::json!({"address": "0x1", "broadcast": true})).unwrap_err();
+        assert!(matches!(err, ToolError::ForbiddenCommand(_)));
+        let err = build(serde_json::json!({"address": "0x1", "private-key": "0xabc"})).unwrap_err();
+        assert!(matches!(err, ToolError::ForbiddenCommand(_)));
+        assert!(!err.message().contains("0xabc"));
+
+        // An unset flag, or one that isn't forbidden, is fine
+        assert!(
+            build(serde_json::json!({"address": "0x1", "broadcast": false, "json": true})).is_ok()
+        );
+    }
+
+    /// Test that parameter patterns are compiled with the tool set and enforced from there
+    #[test]
+    fn test_tool_set_compiles_patterns() {
+        let schema = SchemaFile {
+            tools: vec![ToolSchema {
+                name: "cast_code".to_string(),
+                description: "Get code".to_string(),
+                positionals: vec![PositionalSchema {
+                    name: "who".to_string(),
+                    param_type: "string".to_string(),
+                    required: true,
+                    index: Some(0),
+                    semantic_type: Some("address".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+        let executor = FoundryExecutor::with_config(schema, Config::default());
+        assert!(executor
+            .tool_set()
+            .patterns
+            .contains_key("^0x[0-9a-fA-F]{40}$"));
+
+        let build = |args: Value| executor.build_command("cast_code", &args.as_object().cloned());
+        let address = "0x000000000000000000000000000000000000dEaD";
+        assert!(build(serde_json::json!({ "who": address })).is_ok());
+        let err = build(serde_json::json!({"who": "0xdead"})).unwrap_err();
+        assert!(matches!(err, ToolError::InvalidArguments(_)));
+    }
+
+    /// Test that the default RPC URL fills in only a missing rpc-url on tools that take one
+    #[test]
+    fn test_apply_default_rpc_url() {
+        let config = Config {
+            default_rpc_url: Some("https://rpc.example".to_string()),
+            ..Default::default()
+        };
+        let executor = FoundryExecutor::with_config(create_test_schema(), config);
+
+        let mut arguments = None;
+        assert!(executor.apply_default_rpc_url("cast_call", &mut arguments));
+        assert_eq!(arguments.unwrap()["rpc-url"], "https://rpc.example");
+
+        let mut arguments = serde_json::json!({"rpc-url": "http://localhost:8545"})
+            .as_object()
+            .cloned();
+        assert!(!executor.apply_default_rpc_url("cast_call", &mut arguments));
+        assert_eq!(arguments.unwrap()["rpc-url"], "http://localhost:8545");
+
+        let mut arguments = None;
+        assert!(!executor.apply_default_rpc_url("forge_build", &mut arguments));
+        assert!(arguments.is_none());
+    }
+
+    /// Test that the default chain fills in a missing rpc-url as a chain name
+    #[test]
+    fn test_apply_default_chain() {
+        let config = Config {
+            default_chain: Some("base".to_string()),
+            ..Default::default()
+        };
+        let executor = FoundryExecutor::with_config(create_test_schema(), config);
+
+        let mut arguments = None;
+        assert_eq!(
+            executor.apply_default_chain("cast_call", &mut arguments),
+            Some("base".to_string())
+        );
+        assert_eq!(arguments.unwrap()["rpc-url"], "base");
+
+        let mut arguments = serde_json::json!({"rpc-url": "optimism"})
+            .as_object()
+            .cloned();
+        assert_eq!(
+            executor.apply_default_chain("cast_call", &mut arguments),
+            None
+        );
+        assert_eq!(arguments.unwrap()["rpc-url"], "optimism");
+
+        let mut arguments = None;
+        assert_eq!(
+            executor.apply_default_chain("forge_build", &mut arguments),
+            None
+        );
+        assert!(arguments.is_none());
+    }
+
+    /// Test that the output encoding argument accepts only text or base64
+    #[test]
+    fn test_wants_base64() {
+        let args = |value: Value| {
+            Some(
+                serde_json::json!({ OUTPUT_ENCODING_ARG: value })
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            )
+        };
+        assert!(!FoundryExecutor::wants_base64(&None).unwrap());
+        assert!(!FoundryExecutor::wants_base64(&args(serde_json::json!("text"))).unwrap());
+        assert!(FoundryExecutor::wants_base64(&args(serde_json::json!("base64"))).unwrap());
+        assert!(matches!(
+            FoundryExecutor::wants_base64(&args(serde_json::json!("hex"))),
+            Err(ToolError::InvalidArguments(_))
+        ));
+    }
+
+    /// Test that binary output is flagged as text and returned intact as base64
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_tool_binary_output() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let cast = dir.path().join("cast");
+        std::fs::write(&cast, "#!/bin/sh\nprintf 'ok\\377'\n").unwrap();
+        std::fs::set_permissions(&cast, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let schema = SchemaFile {
+            tools: vec![ToolSchema {
+                name: "cast_keccak".to_string(),
+                description: "Hash".to_string(),
+                ..Default::default()
+            }],
+        };
+        let mut executor = FoundryExecutor::with_config(schema, Config::default());
+        executor.foundry_bin_path = Some(dir.path().to_string_lossy().to_string());
+
+        let text = executor.execute_tool("cast_keccak", &None).unwrap();
+        assert!(text.starts_with("ok\u{fffd}"));
+        assert!(text.contains(OUTPUT_ENCODING_ARG));
+
+        let args = serde_json::json!({ OUTPUT_ENCODING_ARG: "base64" });
+        let encoded = executor
+            .execute_tool("cast_keccak", &args.as_object().cloned())
+            .unwrap();
+        assert_eq!(encoded, "b2v/");
+    }
+
+    /// Test that a command outliving its timeout is killed and a quick one returns output
+    #[test]
+    fn test_output_with_timeout() {
+        let output = output_with_timeout(
+            Command::new("sh").args(["-c", "echo out; echo err >&2"]),
+            Duration::from_secs(10),
+        )
+        .unwrap()
+        .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "out\n");
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "err\n");
+
+        let started = Instant::now();
+        let output =
+            output_with_timeout(Command::new("sleep").arg("10"), Duration::from_millis(200))
+                .unwrap();
+        assert!(output.is_none());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    /// Test that output over the limit is cut on a character boundary with a note
+    #[test]
+    fn test_truncate_output() {
+        assert_eq!(truncate_output("short".to_string(), Some(10)), "short");
+        assert_eq!(truncate_output("short".to_string(), None), "short");
+
+        let truncated = truncate_output("héllo world".to_string(), Some(2));
+        assert!(truncated.starts_with("h\n"));
+        assert!(truncated.ends_with("[output truncated: 1 of 12 bytes shown]"));
+    }
 }
@@ -0,0 +1,278 @@
+//! Gas estimation helper
+//!
+//! `estimate_gas` combines `cast estimate` with the network's current gas price and the
+//! chain's native currency from chainlist.org, so a client gets the expected fee in one call.
+
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::cast;
+use crate::chainlist::{self, ChainInfo};
+use crate::error::ToolError;
+use crate::foundry::FoundryExecutor;
+
+/// Anvil's default chain ID, which chainlist.org assigns to an unrelated network
+const ANVIL_CHAIN_ID: u64 = 31337;
+
+/// Get the gas estimation tools
+pub fn get_gas_tools() -> Vec<Tool> {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "to".to_string(),
+        json!({
+            "type": "string",
+            "description": "Destination address (or ENS name) of the transaction"
+        }),
+    );
+    props.insert(
+        "sig".to_string(),
+        json!({
+            "type": "string",
+            "description": "Function signature, e.g. 'transfer(address,uint256)', or raw calldata (optional)"
+        }),
+    );
+    props.insert(
+        "args".to_string(),
+        json!({
+            "type": "array",
+            "items": {"type": "string"},
+            "description": "Arguments for the function signature (optional)"
+        }),
+    );
+    props.insert(
+        "value".to_string(),
+        json!({
+            "type": "string",
+            "description": "Ether to send with the transaction, e.g. '1ether' or '100' wei (optional)"
+        }),
+    );
+    props.insert(
+        "from".to_string(),
+        json!({
+            "type": "string",
+            "description": "Sender address to estimate from (optional)"
+        }),
+    );
+    props.insert(
+        "rpc_url".to_string(),
+        json!({
+            "type": "string",
+            "description": "RPC endpoint to estimate against (default: the configured default_rpc_url or default_chain, else the running Anvil session)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+    schema.insert("required".to_string(), json!(["to"]));
+
+    vec![Tool::new(
+        "estimate_gas".to_string(),
+        "Estimate the gas a transaction would use and what it would cost at the current gas price, in the chain's native currency. Uses the configured default RPC, else the running Anvil session, unless an rpc_url is given.".to_string(),
+        Arc::new(schema),
+    )]
+}
+
+/// Build the `cast_estimate` arguments for a request
+fn estimate_args(
+    args: &serde_json::Map<String, Value>,
+    rpc_url: &str,
+) -> Result<serde_json::Map<String, Value>, ToolError> {
+    let to = args
+        .get("to")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ToolError::InvalidArguments("Missing 'to' parameter".to_string()))?;
+
+    let mut estimate = serde_json::Map::new();
+    estimate.insert("to".to_string(), json!(to));
+    for key in ["from", "value"] {
+        if let Some(value) = args.get(key).and_then(|v| v.as_str()) {
+            estimate.insert(key.to_string(), json!(value));
+        }
+    }
+    estimate.insert("rpc-url".to_string(), json!(rpc_url));
+
+    if let Some(sig) = args.get("sig").and_then(|v| v.as_str()) {
+        estimate.insert("sig".to_string(), json!(sig));
+        if let Some(call_args) = args.get("args").and_then(|v| v.as_array()) {
+            let call_args: Vec<String> = call_args
+                .iter()
+                .map(|arg| match arg {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect();
+            estimate.insert("args".to_string(), json!(call_args));
+        }
+    }
+
+    Ok(estimate)
+}
+
+/// Symbol and decimals of a chain's native currency, defaulting to ETH
+pub fn native_currency(chains: &[ChainInfo], chain_id: u64) -> (String, u32) {
+    let fallback = ("ETH".to_string(), 18);
+    if chain_id == ANVIL_CHAIN_ID {
+        return fallback;
+    }
+
+    chains
+        .iter()
+        .find(|chain| chain.chain_id == chain_id)
+        .and_then(|chain| chain.native_currency.as_ref())
+        .and_then(|currency| {
+            let symbol = currency.get("symbol")?.as_str()?.to_string();
+            let decimals = currency.get("decimals")?.as_u64()? as u32;
+            Some((symbol, decimals))
+        })
+        .unwrap_or(fallback)
+}
+
+/// Run `cast_estimate`, `cast_gas_price` and `cast_chain_id` for the gas estimate, gas
+/// price and chain ID
+async fn query_chain(
+    args: &serde_json::Map<String, Value>,
+    executor: &FoundryExecutor,
+) -> Result<(u128, u128, u64), ToolError> {
+    let rpc_url = cast::resolve_rpc_url(
+        args.get("rpc_url").and_then(|v| v.as_str()),
+        &executor.config(),
+    )
+    .await?;
+    let estimate = estimate_args(args, &rpc_url)?;
+    let query = |name: &str| {
+        let mut query_args = serde_json::Map::new();
+        query_args.insert("rpc-url".to_string(), json!(rpc_url));
+        cast::run_tool(executor, name, query_args, &[])
+    };
+
+    let gas = cast::parse_u128(
+        &cast::run_tool(executor, "cast_estimate", estimate, &[])?,
+        "gas estimate",
+    )?;
+    let price = cast::parse_u128(&query("cast_gas_price")?, "gas price")?;
+    let chain_id = cast::parse_u128(&query("cast_chain_id")?, "chain ID")? as u64;
+    Ok((gas, price, chain_id))
+}
+
+/// Handle estimate_gas tool call
+pub async fn handle_estimate_gas(
+    arguments: &Option<serde_json::Map<String, Value>>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let args = arguments
+        .as_ref()
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing arguments", None))?;
+
+    let (gas, price, chain_id) = match query_chain(args, executor).await {
+        Ok(values) => values,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    // The fee is still useful without chainlist, so fall back to ETH if it's unreachable
+    let chains = chainlist::fetch_chainlist().await.unwrap_or_default();
+    let (symbol, decimals) = native_currency(&chains, chain_id);
+
+    let fee = gas.saturating_mul(price);
+    let fee_formatted = cast::format_units(fee, decimals);
+
+    let text = format!(
+        "Estimated gas: {} units\nGas price: {} gwei\nEstimated fee: {} {} ({} wei)\nChain ID: {}",
+        gas,
+        cast::format_units(price, 9),
+        fee_formatted,
+        symbol,
+        fee,
+        chain_id
+    );
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(json!({
+        "gas_units": gas.to_string(),
+        "gas_price_wei": price.to_string(),
+        "fee_wei": fee.to_string(),
+        "fee": fee_formatted,
+        "symbol": symbol,
+        "chain_id": chain_id,
+    }));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(chain_id: u64, currency: Value) -> ChainInfo {
+        serde_json::from_value(json!({
+            "name": "Test",
+            "chain": "TST",
+            "chainId": chain_id,
+            "shortName": "tst",
+            "nativeCurrency": currency,
+        }))
+        .unwrap()
+    }
+
+    /// Test that the estimate_gas tool requires a destination
+    #[test]
+    fn test_get_gas_tools() {
+        let tools = get_gas_tools();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "estimate_gas");
+        assert_eq!(tools[0].input_schema.get("required"), Some(&json!(["to"])));
+    }
+
+    /// Test that cast_estimate arguments are keyed by the schema's option and positional names
+    #[test]
+    fn test_estimate_args() {
+        let args = json!({
+            "to": "0x0000000000000000000000000000000000000001",
+            "sig": "transfer(address,uint256)",
+            "args": ["0x0000000000000000000000000000000000000002", 100],
+            "from": "0x0000000000000000000000000000000000000003",
+            "value": "1ether"
+        });
+        let estimate = estimate_args(args.as_object().unwrap(), "http://localhost:8545").unwrap();
+        assert_eq!(
+            Value::Object(estimate),
+            json!({
+                "to": "0x0000000000000000000000000000000000000001",
+                "sig": "transfer(address,uint256)",
+                "args": ["0x0000000000000000000000000000000000000002", "100"],
+                "from": "0x0000000000000000000000000000000000000003",
+                "value": "1ether",
+                "rpc-url": "http://localhost:8545"
+            })
+        );
+    }
+
+    /// Test that a missing destination is rejected
+    #[test]
+    fn test_estimate_args_requires_to() {
+        let args = json!({"sig": "foo()"});
+        let result = estimate_args(args.as_object().unwrap(), "http://localhost:8545");
+        assert!(matches!(result, Err(ToolError::InvalidArguments(_))));
+    }
+
+    /// Test that the native currency comes from chainlist with an ETH fallback
+    #[test]
+    fn test_native_currency() {
+        let chains = vec![
+            chain(137, json!({"name": "POL", "symbol": "POL", "decimals": 18})),
+            chain(
+                ANVIL_CHAIN_ID,
+                json!({"name": "GO", "symbol": "GO", "decimals": 18}),
+            ),
+        ];
+
+        assert_eq!(native_currency(&chains, 137), ("POL".to_string(), 18));
+        assert_eq!(native_currency(&chains, 999), ("ETH".to_string(), 18));
+        assert_eq!(
+            native_currency(&chains, ANVIL_CHAIN_ID),
+            ("ETH".to_string(), 18)
+        );
+        assert_eq!(native_currency(&[], 1), ("ETH".to_string(), 18));
+    }
+}
@@ -0,0 +1,520 @@
+//! Schema generation from installed Foundry `--help` output
+//!
+//! The embedded `schemas.json` can drift from the Foundry version installed on a machine.
+//! This module walks `forge`, `cast`, `anvil`, and `chisel` recursively via `--help`,
+//! parses clap's help format into [`ToolSchema`] entries, and produces a [`SchemaFile`].
+//!
+//! Tool names follow the existing convention: the binary and subcommand path joined with
+//! underscores, with hyphens mapped to underscores. Flag-style subcommands such as
+//! `cast send --create` therefore become `cast_send___create`.
+
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde_json::Value;
+use std::process::Command;
+
+use crate::foundry::binary_path;
+use crate::schema::{FlagSchema, OptionSchema, PositionalSchema, SchemaFile, ToolSchema};
+
+/// Binaries walked when generating a schema
+pub const FOUNDRY_BINARIES: &[&str] = &["forge", "cast", "anvil", "chisel"];
+
+/// Maximum subcommand nesting depth to follow
+const MAX_DEPTH: usize = 4;
+
+/// Value names that indicate a numeric option
+const NUMERIC_VALUE_NAMES: &[&str] = &["NUM", "N", "NUMBER", "COUNT", "THREADS", "PORT"];
+
+/// Parsed contents of a single `--help` page
+#[derive(Debug, Default)]
+struct HelpPage {
+    about: String,
+    commands: Vec<String>,
+    positionals: Vec<PositionalSchema>,
+    options: Vec<OptionSchema>,
+    flags: Vec<FlagSchema>,
+}
+
+impl HelpPage {
+    fn has_parameters(&self) -> bool {
+        !self.positionals.is_empty() || !self.options.is_empty() || !self.flags.is_empty()
+    }
+}
+
+/// Which section of the help output an entry belongs to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Preamble,
+    Usage,
+    Commands,
+    Arguments,
+    Options,
+}
+
+/// Generate a schema by running `--help` on the installed Foundry binaries.
+pub fn generate_schema(foundry_bin_path: Option<&str>) -> Result<SchemaFile> {
+    generate_schema_with(FOUNDRY_BINARIES, |binary, path| {
+        run_help(foundry_bin_path, binary, path)
+    })
+}
+
+/// Generate a schema using a caller-supplied help provider.
+///
+/// `help` receives the binary name and subcommand path and returns the help text.
+fn generate_schema_with<F>(binaries: &[&str], mut help: F) -> Result<SchemaFile>
+where
+    F: FnMut(&str, &[String]) -> Result<String>,
+{
+    let mut tools = Vec::new();
+
+    for binary in binaries {
+        let mut stack: Vec<Vec<String>> = vec![vec![]];
+        while let Some(path) = stack.pop() {
+            let text = help(binary, &path)?;
+            let page = parse_help(&text);
+
+            // Pure grouping commands (e.g. `forge cache`) only exist to hold subcommands
+            if path.is_empty() || page.commands.is_empty() || page.has_parameters() {
+                tools.push(ToolSchema {
+                    name: tool_name(binary, &path),
+                    description: page.about.clone(),
+                    positionals: page.positionals,
+                    options: page.options,
+                    flags: page.flags,
+                    ..Default::default()
+                });
+            }
+
+            if path.len() < MAX_DEPTH {
+                // Push in reverse so subcommands are emitted in help order
+                for command in page.commands.iter().rev() {
+                    let mut child = path.clone();
+                    child.push(command.clone());
+                    stack.push(child);
+                }
+            }
+        }
+    }
+
+    Ok(SchemaFile { tools })
+}
+
+/// Build the tool name for a binary and subcommand path
+fn tool_name(binary: &str, path: &[String]) -> String {
+    std::iter::once(binary.to_string())
+        .chain(path.iter().map(|p| p.replace('-', "_")))
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Run `<binary> <path...> --help` and return its output
+fn run_help(foundry_bin_path: Option<&str>, binary: &str, path: &[String]) -> Result<String> {
+    let command_path = binary_path(foundry_bin_path, binary);
+
+    let output = Command::new(&command_path)
+        .args(path)
+        .arg("--help")
+        .env("NO_COLOR", "1")
+        .output()
+        .with_context(|| format!("Failed to execute '{}'", command_path))?;
+
+    if !output.status.success() {
+        bail!(
+            "'{} {} --help' failed: {}",
+            binary,
+            path.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Parse clap-formatted help text (short `-h` or long `--help` layout)
+fn parse_help(text: &str) -> HelpPage {
+    let option_re = Regex::new(
+        r"^(?:-(?P<short>\w), )?--(?P<long>[\w-]+)(?:[ =]\[?<(?P<value>[^>]+)>\]?(?P<multi>\.\.\.)?)?(?:\s{2,}(?P<desc>.*))?$",
+    )
+    .unwrap();
+    let short_only_re = Regex::new(r"^-(?P<short>\w)(?:\s{2,}(?P<desc>.*))?$").unwrap();
+    let positional_re = Regex::new(
+        r"^(?P<open>[<\[])(?P<name>[^>\]]+)[>\]](?P<multi>\.\.\.)?(?:\s{2,}(?P<desc>.*))?$",
+    )
+    .unwrap();
+    let command_re = Regex::new(r"^(?P<name>-{0,2}[\w-]+)(?:\s{2,}(?P<desc>.*))?$").unwrap();
+
+    let mut page = HelpPage::default();
+    let mut about_lines: Vec<&str> = Vec::new();
+    let mut section = Section::Preamble;
+
+    let lines: Vec<&str> = text.lines().collect();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim_end();
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = line.trim_start();
+        i += 1;
+
+        if indent == 0 && !trimmed.is_empty() {
+            if trimmed.starts_with("Usage:") {
+                section = Section::Usage;
+                continue;
+            }
+            if trimmed.ends_with(':') {
+                section = match trimmed {
+                    "Commands:" | "Subcommands:" => Section::Commands,
+                    "Arguments:" => Section::Arguments,
+                    _ => Section::Options,
+                };
+                continue;
+            }
+            if section == Section::Preamble {
+                about_lines.push(trimmed);
+                continue;
+            }
+        }
+
+        if section == Section::Preamble {
+            if trimmed.is_empty() && !about_lines.is_empty() {
+                // Only the first paragraph is used as the description
+                section = Section::Usage;
+            }
+            continue;
+        }
+
+        // Entries are indented by at most 6 columns; deeper lines are continuations
+        if trimmed.is_empty() || indent > 6 || section == Section::Usage {
+            continue;
+        }
+
+        // Gather continuation lines belonging to this entry
+        let mut details = Vec::new();
+        while i < lines.len() {
+            let next = lines[i].trim_end();
+            let next_indent = next.len() - next.trim_start().len();
+            if !next.trim().is_empty() && next_indent <= 6 {
+                break;
+            }
+            details.push(next.trim());
+            i += 1;
+        }
+
+        match section {
+            Section::Commands => {
+                if let Some(caps) = command_re.captures(trimmed) {
+                    let name = caps["name"].to_string();
+                    if name != "help" {
+                        page.commands.push(name);
+                    }
+                }
+            }
+            Section::Arguments => {
+                if let Some(caps) = positional_re.captures(trimmed) {
+                    let (description, _, _) =
+                        parse_details(caps.name("desc").map(|m| m.as_str()), &details);
+                    page.positionals.push(PositionalSchema {
+                        name: caps["name"].to_string(),
+                        param_type: if caps.name("multi").is_some() {
+                            "array".to_string()
+                        } else {
+                            "string".to_string()
+                        },
+                        description,
+                        required: &caps["open"] == "<",
+                        index: Some(page.positionals.len()),
+                        ..Default::default()
+                    });
+                }
+            }
+            Section::Options => {
+                let (short, long, value, multi, inline) =
+                    if let Some(caps) = option_re.captures(trimmed) {
+                        (
+                            caps.name("short").map(|m| m.as_str().to_string()),
+                            caps["long"].to_string(),
+                            caps.name("value").map(|m| m.as_str().to_string()),
+                            caps.name("multi").is_some(),
+                            caps.name("desc").map(|m| m.as_str()),
+                        )
+                    } else if let Some(caps) = short_only_re.captures(trimmed) {
+                        (
+                            Some(caps["short"].to_string()),
+                            caps["short"].to_string(),
+                            None,
+                            false,
+                            caps.name("desc").map(|m| m.as_str()),
+                        )
+                    } else {
+                        continue;
+                    };
+
+                if long == "help" || long == "version" {
+                    continue;
+                }
+
+                let (description, default, possible_values) = parse_details(inline, &details);
+                match value {
+                    Some(value_name) => {
+                        let param_type = if multi {
+                            "array"
+                        } else if NUMERIC_VALUE_NAMES.contains(&value_name.as_str()) {
+                            "number"
+                        } else {
+                            "string"
+                        };
+                        page.options.push(OptionSchema {
+                            name: long,
+                            param_type: param_type.to_string(),
+                            description,
+                            required: false,
+                            short,
+                            value_name: Some(value_name),
+                            default: default.map(Value::String),
+                            enum_values: possible_values,
+                            ..Default::default()
+                        });
+                    }
+                    None => page.flags.push(FlagSchema {
+                        name: long,
+                        param_type: "boolean".to_string(),
+                        description,
+                        required: false,
+                        short,
+                        ..Default::default()
+                    }),
+                }
+            }
+            Section::Preamble | Section::Usage => {}
+        }
+    }
+
+    page.about = about_lines.join(" ");
+    page
+}
+
+/// Extract the description, default value, and possible values for an entry.
+///
+/// Handles both the short layout (`[default: x] [possible values: a, b]` inline) and the
+/// long layout (description paragraphs followed by a `Possible values:` list).
+fn parse_details(
+    inline: Option<&str>,
+    details: &[&str],
+) -> (String, Option<String>, Option<Vec<String>>) {
+    let bracket_re = Regex::new(r"\[(?P<key>[a-z ]+): (?P<value>[^\]]*)\]").unwrap();
+
+    let mut description = Vec::new();
+    let mut default = None;
+    let mut possible_values: Option<Vec<String>> = None;
+    let mut in_description = true;
+    let mut in_possible_values = false;
+
+    for line in inline.into_iter().chain(details.iter().copied()) {
+        if line.is_empty() {
+            if !description.is_empty() {
+                in_description = false;
+            }
+            in_possible_values = false;
+            continue;
+        }
+
+        if line == "Possible values:" {
+            in_possible_values = true;
+            continue;
+        }
+        if in_possible_values {
+            if let Some(item) = line.strip_prefix("- ") {
+                let value = item.split(':').next().unwrap_or(item).trim();
+                possible_values
+                    .get_or_insert_with(Vec::new)
+                    .push(value.to_string());
+            }
+            continue;
+        }
+
+        let mut text = line.to_string();
+        for caps in bracket_re.captures_iter(line) {
+            match &caps["key"] {
+                "default" => default = Some(caps["value"].to_string()),
+                "possible values" => {
+                    possible_values = Some(
+                        caps["value"]
+                            .split(',')
+                            .map(|v| v.trim().to_string())
+                            .collect(),
+                    )
+                }
+                _ => {}
+            }
+            text = text.replace(&caps[0], "");
+        }
+
+        let text = text.trim();
+        if in_description && !text.is_empty() {
+            description.push(text.to_string());
+        }
+    }
+
+    (description.join(" "), default, possible_values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAST_SEND_HELP: &str = "\
+Sign and publish a transaction
+
+Usage: cast send [OPTIONS] [TO] [SIG] [ARGS]... [COMMAND]
+
+Commands:
+  --create  Use to deploy raw contract bytecode
+  help      Print this message or the help of the given subcommand(s)
+
+Arguments:
+  [TO]
+          The destination of the transaction
+
+  [ARGS]...
+          The arguments of the function to call
+
+Options:
+      --async
+          Only print the transaction hash and exit immediately
+
+  -r, --rpc-url <URL>
+          The RPC endpoint
+
+          [env: ETH_RPC_URL=]
+
+      --color <COLOR>
+          The color of the log messages
+
+          Possible values:
+          - auto:   Intelligently guess whether to use color output
+          - always: Force color output
+          - never:  Force disable color output
+
+  -j, --threads <THREADS>
+          Number of threads to use
+
+  -h, --help
+          Print help (see a summary with '-h')
+";
+
+    const FORGE_SHORT_HELP: &str = "\
+Build, test, fuzz, debug and deploy Solidity contracts
+
+Usage: forge [OPTIONS] <COMMAND>
+
+Commands:
+  build  Build the project's smart contracts [aliases: b, compile]
+  cache  Manage the Foundry cache
+  help   Print this message or the help of the given subcommand(s)
+
+Options:
+  -j, --threads <THREADS>  Number of threads to use [default: 0]
+      --color <COLOR>      Log color [possible values: auto, always, never]
+  -q, --quiet              Do not print log messages
+  -h, --help               Print help (see more with '--help')
+  -V, --version            Print version
+";
+
+    /// Test that long-format help is parsed into commands, positionals, options, and flags
+    #[test]
+    fn test_parse_long_help() {
+        let page = parse_help(CAST_SEND_HELP);
+
+        assert_eq!(page.about, "Sign and publish a transaction");
+        assert_eq!(page.commands, vec!["--create"]);
+
+        assert_eq!(page.positionals.len(), 2);
+        assert_eq!(page.positionals[0].name, "TO");
+        assert!(!page.positionals[0].required);
+        assert_eq!(page.positionals[1].param_type, "array");
+        assert_eq!(page.positionals[1].index, Some(1));
+
+        let rpc = page.options.iter().find(|o| o.name == "rpc-url").unwrap();
+        assert_eq!(rpc.short.as_deref(), Some("r"));
+        assert_eq!(rpc.value_name.as_deref(), Some("URL"));
+        assert_eq!(rpc.description, "The RPC endpoint");
+
+        let color = page.options.iter().find(|o| o.name == "color").unwrap();
+        assert_eq!(
+            color.enum_values,
+            Some(vec![
+                "auto".to_string(),
+                "always".to_string(),
+                "never".to_string()
+            ])
+        );
+
+        let threads = page.options.iter().find(|o| o.name == "threads").unwrap();
+        assert_eq!(threads.param_type, "number");
+
+        assert_eq!(page.flags.len(), 1);
+        assert_eq!(page.flags[0].name, "async");
+    }
+
+    /// Test that short-format help with inline metadata is parsed
+    #[test]
+    fn test_parse_short_help() {
+        let page = parse_help(FORGE_SHORT_HELP);
+
+        assert_eq!(page.commands, vec!["build", "cache"]);
+
+        let threads = page.options.iter().find(|o| o.name == "threads").unwrap();
+        assert_eq!(threads.default, Some(Value::String("0".to_string())));
+        assert_eq!(threads.description, "Number of threads to use");
+
+        let color = page.options.iter().find(|o| o.name == "color").unwrap();
+        assert_eq!(color.enum_values.as_ref().unwrap().len(), 3);
+
+        // help and version are never exposed as flags
+        let flags: Vec<_> = page.flags.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(flags, vec!["quiet"]);
+    }
+
+    /// Test that tool names follow the underscore and triple-underscore conventions
+    #[test]
+    fn test_tool_name() {
+        assert_eq!(tool_name("forge", &[]), "forge");
+        assert_eq!(
+            tool_name("forge", &["verify-contract".to_string()]),
+            "forge_verify_contract"
+        );
+        assert_eq!(
+            tool_name("cast", &["send".to_string(), "--create".to_string()]),
+            "cast_send___create"
+        );
+    }
+
+    /// Test that subcommands are discovered recursively and grouping commands are skipped
+    #[test]
+    fn test_generate_schema_recursive() {
+        let schema = generate_schema_with(&["forge"], |_, path| {
+            Ok(match path.join(" ").as_str() {
+                "" => FORGE_SHORT_HELP.to_string(),
+                "build" => "Build\n\nUsage: forge build\n\nOptions:\n      --force  Force\n"
+                    .to_string(),
+                "cache" => {
+                    "Manage the cache\n\nUsage: forge cache <COMMAND>\n\nCommands:\n  clean  Clean\n  ls     List\n\nOptions:\n  -h, --help  Print help\n"
+                        .to_string()
+                }
+                _ => "Leaf\n\nUsage: forge cache x\n".to_string(),
+            })
+        })
+        .unwrap();
+
+        let names: Vec<_> = schema.tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "forge",
+                "forge_build",
+                "forge_cache_clean",
+                "forge_cache_ls"
+            ]
+        );
+        assert!(schema.validate().is_empty());
+    }
+}
@@ -3,8 +3,15 @@
 use rmcp::model::*;
 use serde_json::Value;
 use std::sync::Arc;
+use std::time::Duration;
 
-use crate::sessions::SessionManager;
+use crate::cast;
+use crate::error::ToolError;
+use crate::sessions::{NodeState, SessionManager};
+
+/// How long chain state read for `anvil_session_status` is reused before asking the node
+/// again
+const NODE_STATE_TTL: Duration = Duration::from_secs(2);
 
 /// Get all session management tools
 pub fn get_session_tools() -> Vec<Tool> {
@@ -16,6 +23,7 @@ pub fn get_session_tools() -> Vec<Tool> {
         // Chisel session tools
         chisel_session_start_tool(),
         chisel_session_eval_tool(),
+        chisel_session_load_tool(),
         chisel_session_stop_tool(),
         chisel_session_status_tool(),
     ]
@@ -62,6 +70,13 @@ fn anvil_session_start_tool() -> Tool {
             "description": "Block time in seconds (0 = mine on demand, default: 0)"
         }),
     );
+    properties.insert(
+        "auto_restart".to_string(),
+        serde_json::json!({
+            "type": "boolean",
+            "description": "Restart Anvil with the same parameters if it exits unexpectedly; chain state is reset (default: false)"
+        }),
+    );
 
     input_schema.insert("properties".to_string(), Value::Object(properties));
 
@@ -97,7 +112,7 @@ fn anvil_session_status_tool() -> Tool {
 
     Tool::new(
         "anvil_session_status".to_string(),
-        "Check if Anvil is running and get its status".to_string(),
+        "Check if Anvil is running and get its status, including the node's chain ID, current block number and dev accounts, to confirm a fork's state".to_string(),
         Arc::new(input_schema),
     )
 }
@@ -129,6 +144,13 @@ fn chisel_session_eval_tool() -> Tool {
             "description": "Solidity code to execute in the running Chisel session"
         }),
     );
+    properties.insert(
+        "raw".to_string(),
+        serde_json::json!({
+            "type": "boolean",
+            "description": "Return the unfiltered transcript, including the welcome banner and prompts (default: false)"
+        }),
+    );
 
     input_schema.insert("properties".to_string(), Value::Object(properties));
     input_schema.insert(
@@ -138,7 +160,40 @@ fn chisel_session_eval_tool() -> Tool {
 
     Tool::new(
         "chisel_session_eval".to_string(),
-        "Execute Solidity code in a Chisel session. Spawns a fresh chisel process with piped input/output. Returns all chisel output including welcome message and prompts. State persists via Chisel's cache system. 10-second timeout.".to_string(),
+        "Execute Solidity code in a Chisel session. Spawns a fresh chisel process with piped input/output. Returns the output with the welcome banner and prompts stripped, or the full transcript with raw. State persists via Chisel's cache system. 10-second timeout.".to_string(),
+        Arc::new(input_schema),
+    )
+}
+
+fn chisel_session_load_tool() -> Tool {
+    let mut input_schema = serde_json::Map::new();
+    input_schema.insert("type".to_string(), Value::String("object".to_string()));
+
+    let mut properties = serde_json::Map::new();
+    properties.insert(
+        "path".to_string(),
+        serde_json::json!({
+            "type": "string",
+            "description": "Path to the .sol file whose contents are run in the Chisel session"
+        }),
+    );
+    properties.insert(
+        "raw".to_string(),
+        serde_json::json!({
+            "type": "boolean",
+            "description": "Return the unfiltered transcript, including the welcome banner and prompts (default: false)"
+        }),
+    );
+
+    input_schema.insert("properties".to_string(), Value::Object(properties));
+    input_schema.insert(
+        "required".to_string(),
+        Value::Array(vec![Value::String("path".to_string())]),
+    );
+
+    Tool::new(
+        "chisel_session_load".to_string(),
+        "Run a whole Solidity file (e.g. a scratch contract) in the Chisel session, as if its contents were passed to chisel_session_eval. The file must exist and end in .sol. 10-second timeout.".to_string(),
         Arc::new(input_schema),
     )
 }
@@ -206,19 +261,28 @@ pub async fn handle_anvil_session_start(
         .and_then(|a| a.get("block_time"))
         .and_then(|v| v.as_u64());
 
+    let auto_restart = args
+        .as_ref()
+        .and_then(|a| a.get("auto_restart"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
     // Run blocking operation in a background thread
     let foundry_bin_path = foundry_bin_path.clone();
     let result = tokio::task::spawn_blocking(move || {
-        let global_manager = SessionManager::global();
-        let mut manager = global_manager.lock().unwrap();
-        manager.start_anvil(
+        let manager = SessionManager::global();
+        let mut msg = manager.start_anvil(
             &foundry_bin_path,
             port,
             fork_url,
             fork_block_number,
             accounts,
             block_time,
-        )
+        )?;
+        if auto_restart && manager.set_anvil_auto_restart(true) {
+            msg.push_str("\nAuto-restart: on");
+        }
+        Ok::<_, anyhow::Error>(msg)
     })
     .await
     .map_err(|e| rmcp::ErrorData::internal_error(format!("Task error: {}", e), None))?;
@@ -230,15 +294,14 @@ pub async fn handle_anvil_session_start(
             is_error: None,
             meta: None,
         }),
-        Err(e) => Err(rmcp::ErrorData::internal_error(e.to_string(), None)),
+        Err(e) => Err(ToolError::from(e).into()),
     }
 }
 
 /// Handle anvil session stop
 pub async fn handle_anvil_session_stop() -> Result<CallToolResult, rmcp::ErrorData> {
     let result = tokio::task::spawn_blocking(move || {
-        let global_manager = SessionManager::global();
-        let mut manager = global_manager.lock().unwrap();
+        let manager = SessionManager::global();
         manager.stop_anvil()
     })
     .await
@@ -251,40 +314,88 @@ pub async fn handle_anvil_session_stop() -> Result<CallToolResult, rmcp::ErrorDa
             is_error: None,
             meta: None,
         }),
-        Err(e) => Err(rmcp::ErrorData::internal_error(e.to_string(), None)),
+        Err(e) => Err(ToolError::from(e).into()),
     }
 }
 
+/// Read the chain ID, block number and dev accounts from a node with `cast`
+fn query_node_state(cast_path: &str, rpc_url: &str) -> Result<NodeState, ToolError> {
+    let run = |args: &[&str]| {
+        let mut args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        args.extend(["--rpc-url".to_string(), rpc_url.to_string()]);
+        cast::run(cast_path, &args)
+    };
+    let chain_id = cast::parse_u128(&run(&["chain-id"])?, "chain ID")? as u64;
+    let block_number = cast::parse_u128(&run(&["block-number"])?, "block number")? as u64;
+    let accounts = run(&["rpc", "eth_accounts"])?;
+    let accounts = serde_json::from_str(&accounts).map_err(|_| {
+        ToolError::execution_failed(format!("Unexpected eth_accounts result: '{}'", accounts))
+    })?;
+    Ok(NodeState {
+        chain_id,
+        block_number,
+        accounts,
+    })
+}
+
 /// Handle anvil session status
-pub async fn handle_anvil_session_status() -> Result<CallToolResult, rmcp::ErrorData> {
+pub async fn handle_anvil_session_status(
+    foundry_bin_path: &Option<String>,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let cast_path = cast::cast_path(foundry_bin_path);
     let result = tokio::task::spawn_blocking(move || {
-        let global_manager = SessionManager::global();
-        let manager = global_manager.lock().unwrap();
-        manager.anvil_status()
+        let manager = SessionManager::global();
+        let mut status = manager.anvil_status()?;
+        let mut details = serde_json::json!({
+            "running": manager.is_anvil_running(),
+            "rpc_url": manager.anvil_rpc_url(),
+            "start_params": manager.anvil_start_params(),
+            "snapshots": manager.anvil_snapshot_names(),
+        });
+
+        match manager.anvil_node_state(NODE_STATE_TTL, |url| query_node_state(&cast_path, url)) {
+            Some(Ok(state)) => {
+                status.push_str(&format!(
+                    "\n\nChain ID: {}\nBlock number: {}\nAccounts: {}",
+                    state.chain_id,
+                    state.block_number,
+                    state.accounts.len()
+                ));
+                details["chain_id"] = serde_json::json!(state.chain_id);
+                details["block_number"] = serde_json::json!(state.block_number);
+                details["accounts"] = serde_json::json!(state.accounts);
+            }
+            Some(Err(e)) => {
+                status.push_str(&format!("\n\nCould not read the chain state: {}", e));
+                details["chain_state_error"] = serde_json::json!(e.to_string());
+            }
+            None => {}
+        }
+        Ok::<_, anyhow::Error>((status, details))
     })
     .await
     .map_err(|e| rmcp::ErrorData::internal_error(format!("Task error: {}", e), None))?;
 
     match result {
-        Ok(msg) => Ok(CallToolResult {
+        Ok((msg, details)) => Ok(CallToolResult {
             content: vec![Content::text(msg)],
-            structured_content: None,
+            structured_content: Some(details),
             is_error: None,
             meta: None,
         }),
-        Err(e) => Err(rmcp::ErrorData::internal_error(e.to_string(), None)),
+        Err(e) => Err(ToolError::from(e).into()),
     }
 }
 
 /// Handle chisel session start
 pub async fn handle_chisel_session_start(
     foundry_bin_path: &Option<String>,
+    probe_ttl: Duration,
 ) -> Result<CallToolResult, rmcp::ErrorData> {
     let foundry_bin_path = foundry_bin_path.clone();
     let result = tokio::task::spawn_blocking(move || {
-        let global_manager = SessionManager::global();
-        let mut manager = global_manager.lock().unwrap();
-        manager.start_chisel(&foundry_bin_path)
+        let manager = SessionManager::global();
+        manager.start_chisel(&foundry_bin_path, probe_ttl)
     })
     .await
     .map_err(|e| rmcp::ErrorData::internal_error(format!("Task error: {}", e), None))?;
@@ -296,7 +407,7 @@ pub async fn handle_chisel_session_start(
             is_error: None,
             meta: None,
         }),
-        Err(e) => Err(rmcp::ErrorData::internal_error(e.to_string(), None)),
+        Err(e) => Err(ToolError::from(e).into()),
     }
 }
 
@@ -311,12 +422,77 @@ pub async fn handle_chisel_session_eval(
         .and_then(|v| v.as_str())
         .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing 'code' parameter", None))?
         .to_string();
+    let raw = args
+        .as_ref()
+        .and_then(|a| a.get("raw"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let foundry_bin_path = foundry_bin_path.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let manager = SessionManager::global();
+        manager.chisel_eval(code, raw, &foundry_bin_path)
+    })
+    .await
+    .map_err(|e| rmcp::ErrorData::internal_error(format!("Task error: {}", e), None))?;
+
+    match result {
+        Ok(output) => Ok(CallToolResult {
+            content: vec![Content::text(output)],
+            structured_content: None,
+            is_error: None,
+            meta: None,
+        }),
+        Err(e) => Err(ToolError::from(e).into()),
+    }
+}
+
+/// Read the Solidity source a `chisel_session_load` call points at
+fn read_solidity_file(path: &str) -> Result<String, ToolError> {
+    let path = std::path::Path::new(path);
+    if path.extension().and_then(|e| e.to_str()) != Some("sol") {
+        return Err(ToolError::InvalidArguments(format!(
+            "'{}' is not a Solidity file (expected a .sol extension)",
+            path.display()
+        )));
+    }
+    if !path.is_file() {
+        return Err(ToolError::InvalidArguments(format!(
+            "File '{}' does not exist",
+            path.display()
+        )));
+    }
+    std::fs::read_to_string(path).map_err(|e| {
+        ToolError::execution_failed(format!("Could not read '{}': {}", path.display(), e))
+    })
+}
+
+/// Handle chisel session load
+pub async fn handle_chisel_session_load(
+    args: &Option<serde_json::Map<String, Value>>,
+    foundry_bin_path: &Option<String>,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let path = args
+        .as_ref()
+        .and_then(|a| a.get("path"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing 'path' parameter", None))?;
+    let raw = args
+        .as_ref()
+        .and_then(|a| a.get("raw"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let code = match read_solidity_file(path) {
+        Ok(code) => code,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
 
     let foundry_bin_path = foundry_bin_path.clone();
     let result = tokio::task::spawn_blocking(move || {
-        let global_manager = SessionManager::global();
-        let mut manager = global_manager.lock().unwrap();
-        manager.chisel_eval(code, &foundry_bin_path)
+        let manager = SessionManager::global();
+        manager.chisel_eval(code, raw, &foundry_bin_path)
     })
     .await
     .map_err(|e| rmcp::ErrorData::internal_error(format!("Task error: {}", e), None))?;
@@ -328,15 +504,14 @@ pub async fn handle_chisel_session_eval(
             is_error: None,
             meta: None,
         }),
-        Err(e) => Err(rmcp::ErrorData::internal_error(e.to_string(), None)),
+        Err(e) => Err(ToolError::from(e).into()),
     }
 }
 
 /// Handle chisel session stop
 pub async fn handle_chisel_session_stop() -> Result<CallToolResult, rmcp::ErrorData> {
     let result = tokio::task::spawn_blocking(move || {
-        let global_manager = SessionManager::global();
-        let mut manager = global_manager.lock().unwrap();
+        let manager = SessionManager::global();
         manager.stop_chisel()
     })
     .await
@@ -349,15 +524,14 @@ pub async fn handle_chisel_session_stop() -> Result<CallToolResult, rmcp::ErrorD
             is_error: None,
             meta: None,
         }),
-        Err(e) => Err(rmcp::ErrorData::internal_error(e.to_string(), None)),
+        Err(e) => Err(ToolError::from(e).into()),
     }
 }
 
 /// Handle chisel session status
 pub async fn handle_chisel_session_status() -> Result<CallToolResult, rmcp::ErrorData> {
     let result = tokio::task::spawn_blocking(move || {
-        let global_manager = SessionManager::global();
-        let manager = global_manager.lock().unwrap();
+        let manager = SessionManager::global();
         manager.chisel_status()
     })
     .await
@@ -370,7 +544,7 @@ pub async fn handle_chisel_session_status() -> Result<CallToolResult, rmcp::Erro
             is_error: None,
             meta: None,
         }),
-        Err(e) => Err(rmcp::ErrorData::internal_error(e.to_string(), None)),
+        Err(e) => Err(ToolError::from(e).into()),
     }
 }
 
@@ -382,7 +556,7 @@ mod tests {
     #[test]
     fn test_get_session_tools_count() {
         let tools = get_session_tools();
-        assert_eq!(tools.len(), 7); // 3 anvil + 4 chisel
+        assert_eq!(tools.len(), 8); // 3 anvil + 5 chisel
     }
 
     /// Test that all session tools have correct names
@@ -396,6 +570,7 @@ mod tests {
         assert!(names.contains(&"anvil_session_status".to_string()));
         assert!(names.contains(&"chisel_session_start".to_string()));
         assert!(names.contains(&"chisel_session_eval".to_string()));
+        assert!(names.contains(&"chisel_session_load".to_string()));
         assert!(names.contains(&"chisel_session_stop".to_string()));
         assert!(names.contains(&"chisel_session_status".to_string()));
     }
@@ -448,6 +623,7 @@ mod tests {
             .as_object()
             .unwrap();
         assert!(props.contains_key("code"));
+        assert_eq!(props["raw"]["type"], "boolean");
 
         let required = tool
             .input_schema
@@ -461,7 +637,7 @@ mod tests {
     /// Test handle_anvil_session_status when not running
     #[tokio::test]
     async fn test_handle_anvil_session_status_not_running() {
-        let result = handle_anvil_session_status().await;
+        let result = handle_anvil_session_status(&None).await;
         assert!(result.is_ok());
 
         let call_result = result.unwrap();
@@ -480,6 +656,31 @@ mod tests {
         // Successfully got a response
     }
 
+    /// Test that chisel_session_load only accepts existing .sol files
+    #[test]
+    fn test_read_solidity_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("Scratch.sol");
+        std::fs::write(&source, "uint256 x = 1;\n").unwrap();
+        let text = dir.path().join("notes.txt");
+        std::fs::write(&text, "hello").unwrap();
+
+        assert_eq!(
+            read_solidity_file(source.to_str().unwrap()).unwrap(),
+            "uint256 x = 1;\n"
+        );
+        for path in [
+            text,
+            dir.path().join("Missing.sol"),
+            dir.path().to_path_buf(),
+        ] {
+            assert!(matches!(
+                read_solidity_file(path.to_str().unwrap()),
+                Err(ToolError::InvalidArguments(_))
+            ));
+        }
+    }
+
     /// Test handle_anvil_session_stop when not running returns error
     #[tokio::test]
     async fn test_handle_anvil_session_stop_not_running() {
@@ -577,7 +778,7 @@ mod tests {
     #[tokio::test]
     async fn test_handle_chisel_session_start_invalid_path() {
         let foundry_bin_path = Some("/nonexistent".to_string());
-        let result = handle_chisel_session_start(&foundry_bin_path).await;
+        let result = handle_chisel_session_start(&foundry_bin_path, Duration::ZERO).await;
 
         assert!(result.is_err());
     }
@@ -638,7 +839,7 @@ mod tests {
         assert!(start_result.is_ok());
 
         // Check status
-        let status_result = handle_anvil_session_status().await;
+        let status_result = handle_anvil_session_status(&None).await;
         assert!(status_result.is_ok());
 
         // Stop session
@@ -651,7 +852,7 @@ mod tests {
     #[ignore] // Run with --ignored flag only if Foundry is installed
     async fn test_chisel_session_workflow_integration() {
         // Start session
-        let start_result = handle_chisel_session_start(&None).await;
+        let start_result = handle_chisel_session_start(&None, Duration::ZERO).await;
         if start_result.is_err() {
             return; // Skip if Foundry not installed
         }
@@ -0,0 +1,194 @@
+//! Shared HTTP client settings for the chainlist.org and token list downloads
+//!
+//! Every outbound request the server makes itself goes through [`client_builder`], so
+//! `enable_network_tools: false` is enforced here as well as in the tool list.
+//!
+//! Without an explicit `proxy_url`, reqwest picks up the `HTTPS_PROXY`/`HTTP_PROXY`/
+//! `ALL_PROXY` environment variables (and `NO_PROXY`) on its own.
+//!
+//! The fetchers are free functions behind global caches, so the settings they need from
+//! the configuration are held here and replaced whenever the configuration is (re)loaded.
+
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use std::future::Future;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use crate::config::Config;
+
+/// Timeouts and proxy applied to outbound downloads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpSettings {
+    /// Limit on the whole request, including reading the body
+    pub timeout: Duration,
+    /// Limit on establishing the connection
+    pub connect_timeout: Duration,
+    /// Explicit proxy, used instead of the environment's
+    pub proxy_url: Option<String>,
+    /// Whether the server may make outbound requests at all
+    pub enabled: bool,
+}
+
+impl HttpSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            timeout: Duration::from_secs(config.http_timeout_secs),
+            connect_timeout: Duration::from_secs(config.http_connect_timeout_secs),
+            proxy_url: config.proxy_url.clone(),
+            enabled: config.enable_network_tools,
+        }
+    }
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self::from_config(&Config::default())
+    }
+}
+
+static SETTINGS: Lazy<RwLock<HttpSettings>> = Lazy::new(|| RwLock::new(HttpSettings::default()));
+
+/// Use the given configuration for subsequent downloads
+pub fn configure(config: &Config) {
+    *SETTINGS.write().unwrap() = HttpSettings::from_config(config);
+}
+
+/// The settings currently in effect
+pub fn settings() -> HttpSettings {
+    SETTINGS.read().unwrap().clone()
+}
+
+/// Whether outbound requests are allowed by the current configuration
+pub fn network_enabled() -> bool {
+    SETTINGS.read().unwrap().enabled
+}
+
+/// A client builder with the current settings applied
+///
+/// # Errors
+///
+/// Returns an error if network access is disabled or the configured `proxy_url` is invalid.
+pub fn client_builder() -> Result<reqwest::ClientBuilder> {
+    let settings = settings();
+    anyhow::ensure!(
+        settings.enabled,
+        "Network access is disabled by the server configuration (enable_network_tools)"
+    );
+    let mut builder = reqwest::Client::builder()
+        .timeout(settings.timeout)
+        .connect_timeout(settings.connect_timeout);
+
+    if let Some(url) = settings.proxy_url {
+        let proxy = reqwest::Proxy::all(&url)
+            .context("Invalid proxy_url")?
+            .no_proxy(reqwest::NoProxy::from_env());
+        builder = builder.proxy(proxy);
+    }
+    Ok(builder)
+}
+
+/// A client with the current settings applied
+///
+/// # Errors
+///
+/// Returns an error if network access is disabled, the configured `proxy_url` is invalid or
+/// the client can't be built.
+pub fn client() -> Result<reqwest::Client> {
+    Ok(client_builder()?.build()?)
+}
+
+/// Return the `cached` value, or run `fetch` (which fills the cache) when there is none.
+///
+/// Callers that find the cache cold queue on `in_flight` and check it again once they get
+/// the lock, so concurrent callers share one download instead of each starting their own.
+pub async fn fetch_once<T, Fut>(
+    in_flight: &tokio::sync::Mutex<()>,
+    cached: impl Fn() -> Option<T>,
+    fetch: impl FnOnce() -> Fut,
+) -> Result<T>
+where
+    Fut: Future<Output = Result<T>>,
+{
+    if let Some(value) = cached() {
+        return Ok(value);
+    }
+
+    // Wait for any fetch already in flight, then re-check: it may have filled the cache
+    let _fetching = in_flight.lock().await;
+    if let Some(value) = cached() {
+        return Ok(value);
+    }
+    fetch().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that settings are taken from the configured timeouts
+    #[test]
+    fn test_settings_from_config() {
+        let config = Config {
+            http_timeout_secs: 120,
+            http_connect_timeout_secs: 5,
+            ..Default::default()
+        };
+        let settings = HttpSettings::from_config(&config);
+        assert_eq!(settings.timeout, Duration::from_secs(120));
+        assert_eq!(settings.connect_timeout, Duration::from_secs(5));
+        assert!(settings.proxy_url.is_none());
+        assert!(settings.enabled);
+
+        let config = Config {
+            enable_network_tools: false,
+            ..Default::default()
+        };
+        assert!(!HttpSettings::from_config(&config).enabled);
+    }
+
+    /// Test that the defaults match the config defaults
+    #[test]
+    fn test_default_settings() {
+        let settings = HttpSettings::default();
+        assert_eq!(settings.timeout, Duration::from_secs(30));
+        assert_eq!(settings.connect_timeout, Duration::from_secs(10));
+    }
+
+    /// Test that concurrent callers on a cold cache share a single download
+    #[tokio::test]
+    async fn test_fetch_once_single_flight() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Mutex;
+
+        let in_flight = tokio::sync::Mutex::new(());
+        let cache: Mutex<Option<u32>> = Mutex::new(None);
+        let downloads = AtomicUsize::new(0);
+        let cached = || *cache.lock().unwrap();
+        let fetch = || async {
+            downloads.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            *cache.lock().unwrap() = Some(7);
+            Ok(7)
+        };
+
+        let (first, second) = tokio::join!(
+            fetch_once(&in_flight, cached, fetch),
+            fetch_once(&in_flight, cached, fetch)
+        );
+        assert_eq!(first.unwrap(), 7);
+        assert_eq!(second.unwrap(), 7);
+        assert_eq!(downloads.load(Ordering::SeqCst), 1);
+
+        // A warm cache doesn't download again
+        assert_eq!(fetch_once(&in_flight, cached, fetch).await.unwrap(), 7);
+        assert_eq!(downloads.load(Ordering::SeqCst), 1);
+    }
+
+    /// Test that a client can be built with the current settings
+    #[test]
+    fn test_client_builder() {
+        assert!(client_builder().unwrap().build().is_ok());
+        assert!(client().is_ok());
+    }
+}
@@ -4,15 +4,47 @@
 //! (forge, cast, anvil, chisel) through a unified interface, plus blockchain RPC discovery
 //! via chainlist.org and token information via the Optimism token list.
 
+pub mod abi;
+pub mod account;
+pub mod anvil;
+pub mod audit;
+pub mod blocks;
+pub mod calldata;
+pub mod cast;
 pub mod chainlist;
+pub mod coalesce;
+pub mod completions;
 pub mod config;
 pub mod context;
 pub mod conversion;
+pub mod deployment;
+pub mod diagnostics;
+pub mod disassembly;
+pub mod elevation;
+pub mod ens;
+pub mod error;
+pub mod explorer;
+pub mod forge;
+pub mod format;
 pub mod foundry;
+pub mod gas;
+pub mod generator;
 pub mod handlers;
+pub mod http;
+pub mod logs;
+pub mod meta;
+pub mod metrics;
+pub mod multicall;
+pub mod project;
+pub mod ratelimit;
+pub mod redact;
 pub mod schema;
+pub mod selectors;
 pub mod server;
 pub mod sessions;
+pub mod storage;
 pub mod tokenlist;
+pub mod trace;
+pub mod wallet;
 
 pub use server::FoundryMcpHandler;
@@ -0,0 +1,660 @@
+//! Event log queries
+//!
+//! `cast_logs` runs `cast logs --json` over a block range and, given an event signature,
+//! decodes each log into named arguments. Indexed arguments come from the topics and the
+//! rest from the data; static types are decoded here, and only events with dynamic
+//! non-indexed arguments fall back to `cast abi-decode`. The range is checked against
+//! `max_log_block_range` before anything runs, and the result is cut to fit
+//! `max_output_bytes`.
+
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::abi;
+use crate::cast;
+use crate::error::ToolError;
+use crate::foundry::FoundryExecutor;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// An event parameter, e.g. `address indexed from`
+#[derive(Debug, Clone, PartialEq)]
+struct EventParam {
+    ty: String,
+    name: Option<String>,
+    indexed: bool,
+}
+
+/// An event signature such as `Transfer(address indexed from, address indexed to, uint256 value)`
+#[derive(Debug, Clone, PartialEq)]
+struct Event {
+    name: String,
+    params: Vec<EventParam>,
+}
+
+/// Get the log query tools
+pub fn get_log_tools() -> Vec<Tool> {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "from_block".to_string(),
+        json!({
+            "type": "string",
+            "description": "First block of the range: a number or a tag such as 'latest' or 'finalized'"
+        }),
+    );
+    props.insert(
+        "to_block".to_string(),
+        json!({
+            "type": "string",
+            "description": "Last block of the range (default: latest)"
+        }),
+    );
+    props.insert(
+        "address".to_string(),
+        json!({
+            "type": "string",
+            "description": "Only logs emitted by this contract (optional)"
+        }),
+    );
+    props.insert(
+        "event".to_string(),
+        json!({
+            "type": "string",
+            "description": "Event signature to filter on and decode with, marking indexed parameters, e.g. 'Transfer(address indexed from, address indexed to, uint256 value)'"
+        }),
+    );
+    props.insert(
+        "topics".to_string(),
+        json!({
+            "type": "array",
+            "items": { "type": "string" },
+            "description": "Topic filters. With an event, values for its indexed parameters in order; without, topic0 onwards as 32-byte hex"
+        }),
+    );
+    props.insert(
+        "rpc_url".to_string(),
+        json!({
+            "type": "string",
+            "description": "RPC endpoint to query (default: the configured default_rpc_url or default_chain, else the running Anvil session)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+    schema.insert("required".to_string(), json!(["from_block"]));
+
+    vec![Tool::new(
+        "cast_logs".to_string(),
+        "Query event logs over a block range, filtered by contract address and topics, decoding them when an event signature is given. The range may span at most max_log_block_range blocks (10,000 by default). Uses the configured default RPC, else the running Anvil session, unless an rpc_url is given.".to_string(),
+        Arc::new(schema),
+    )]
+}
+
+/// Parse an event signature, with or without the `event` keyword
+fn parse_event(signature: &str) -> Result<Event, ToolError> {
+    let signature = signature.trim();
+    let signature = signature.strip_prefix("event ").unwrap_or(signature).trim();
+    let parsed = abi::parse_signature(signature)?;
+    if parsed.name.is_empty() || parsed.outputs.is_some() {
+        return Err(ToolError::InvalidArguments(format!(
+            "Invalid event signature '{}': expected 'Name(type [indexed] [name], ...)'",
+            signature
+        )));
+    }
+
+    let params = parsed
+        .inputs
+        .iter()
+        .map(|param| {
+            let words: Vec<&str> = param.split_whitespace().collect();
+            let indexed = words.contains(&"indexed");
+            let name = words
+                .iter()
+                .skip(1)
+                .rfind(|word| **word != "indexed")
+                .map(|name| name.to_string());
+            EventParam {
+                ty: words[0].to_string(),
+                name,
+                indexed,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let indexed = params.iter().filter(|p| p.indexed).count();
+    if indexed > 3 {
+        return Err(ToolError::InvalidArguments(format!(
+            "Event '{}' has {} indexed parameters; at most 3 are allowed",
+            parsed.name, indexed
+        )));
+    }
+
+    Ok(Event {
+        name: parsed.name,
+        params,
+    })
+}
+
+/// Whether an ABI type is encoded in place in a single word
+fn is_static(ty: &str) -> bool {
+    matches!(ty, "address" | "bool" | "uint" | "int")
+        || ["uint", "int", "bytes"].iter().any(|prefix| {
+            ty.strip_prefix(prefix)
+                .is_some_and(|bits| !bits.is_empty() && bits.chars().all(|c| c.is_ascii_digit()))
+        })
+}
+
+/// Convert a big-endian hex number of any length to decimal
+fn hex_to_decimal(hex: &str, plus_one: bool) -> String {
+    // Little-endian base 10^9 limbs
+    let mut limbs: Vec<u64> = vec![0];
+    let mut multiply_add = |factor: u64, addend: u64| {
+        let mut carry = addend;
+        for limb in limbs.iter_mut() {
+            let value = *limb * factor + carry;
+            *limb = value % 1_000_000_000;
+            carry = value / 1_000_000_000;
+        }
+        while carry > 0 {
+            limbs.push(carry % 1_000_000_000);
+            carry /= 1_000_000_000;
+        }
+    };
+    for digit in hex.chars().filter_map(|c| c.to_digit(16)) {
+        multiply_add(16, u64::from(digit));
+    }
+    if plus_one {
+        multiply_add(1, 1);
+    }
+
+    let mut decimal = limbs.last().copied().unwrap_or_default().to_string();
+    for limb in limbs.iter().rev().skip(1) {
+        decimal.push_str(&format!("{:09}", limb));
+    }
+    decimal
+}
+
+/// Decode one 32-byte word (64 hex characters) as a static ABI type
+fn decode_word(ty: &str, word: &str) -> Option<Value> {
+    if word.len() != 64 || !word.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    if ty == "address" {
+        return Some(json!(format!("0x{}", &word[24..])));
+    }
+    if ty == "bool" {
+        return Some(json!(word.trim_start_matches('0') == "1"));
+    }
+    if ty.starts_with("uint") {
+        return Some(json!(hex_to_decimal(word, false)));
+    }
+    if ty.starts_with("int") {
+        // Negative values are two's complement: -(!x + 1)
+        let negative = u8::from_str_radix(&word[..1], 16).ok()? >= 8;
+        if !negative {
+            return Some(json!(hex_to_decimal(word, false)));
+        }
+        let inverted: String = word
+            .chars()
+            .filter_map(|c| c.to_digit(16))
+            .filter_map(|d| char::from_digit(15 - d, 16))
+            .collect();
+        return Some(json!(format!("-{}", hex_to_decimal(&inverted, true))));
+    }
+    if let Some(size) = ty
+        .strip_prefix("bytes")
+        .and_then(|n| n.parse::<usize>().ok())
+    {
+        if (1..=32).contains(&size) {
+            return Some(json!(format!("0x{}", &word[..size * 2])));
+        }
+    }
+    None
+}
+
+/// Decode the non-indexed parameters from a log's data
+fn decode_data(types: &[&str], data: &str, cast_path: &str) -> Result<Vec<Value>, ToolError> {
+    let hex = data.trim_start_matches("0x");
+
+    if types.iter().all(|ty| is_static(ty)) {
+        return types
+            .iter()
+            .enumerate()
+            .map(|(i, ty)| {
+                hex.get(i * 64..(i + 1) * 64)
+                    .and_then(|word| decode_word(ty, word))
+                    .ok_or_else(|| {
+                        ToolError::execution_failed(format!(
+                            "Log data is too short for its {} parameters",
+                            types.len()
+                        ))
+                    })
+            })
+            .collect();
+    }
+
+    let signature = format!("f()({})", types.join(","));
+    let output = cast::run(
+        cast_path,
+        &["abi-decode".to_string(), signature, format!("0x{}", hex)],
+    )?;
+    Ok(output.lines().map(|line| json!(line.trim())).collect())
+}
+
+/// Decode a raw log against an event into its named arguments
+fn decode_log(event: &Event, log: &Value, cast_path: &str) -> Result<Value, ToolError> {
+    let topics: Vec<&str> = log
+        .get("topics")
+        .and_then(|v| v.as_array())
+        .map(|topics| topics.iter().filter_map(|t| t.as_str()).collect())
+        .unwrap_or_default();
+    let data = log.get("data").and_then(|v| v.as_str()).unwrap_or("0x");
+
+    let data_types: Vec<&str> = event
+        .params
+        .iter()
+        .filter(|p| !p.indexed)
+        .map(|p| p.ty.as_str())
+        .collect();
+    let mut data_values = decode_data(&data_types, data, cast_path)?.into_iter();
+    // topic0 is the event selector
+    let mut indexed_topics = topics.iter().skip(1);
+
+    let mut args = Vec::new();
+    for (i, param) in event.params.iter().enumerate() {
+        let mut arg = json!({
+            "name": param.name.clone().unwrap_or_else(|| format!("arg{}", i)),
+            "type": param.ty,
+            "indexed": param.indexed,
+        });
+        if param.indexed {
+            let topic = indexed_topics.next().ok_or_else(|| {
+                ToolError::execution_failed(format!(
+                    "Log has too few topics for event '{}'",
+                    event.name
+                ))
+            })?;
+            match decode_word(&param.ty, topic.trim_start_matches("0x")) {
+                Some(value) => arg["value"] = value,
+                None => {
+                    // Indexed dynamic values are stored as their keccak hash
+                    arg["value"] = json!(topic);
+                    arg["hashed"] = json!(true);
+                }
+            }
+        } else {
+            arg["value"] = data_values.next().unwrap_or(Value::Null);
+        }
+        args.push(arg);
+    }
+
+    Ok(json!({ "event": event.name, "args": args }))
+}
+
+/// Parse a block number given as decimal or 0x-prefixed hex
+fn parse_block_number(block: &str) -> Option<u64> {
+    let block = block.trim();
+    match block.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => block.parse().ok(),
+    }
+}
+
+/// Resolve a block number or tag to a number, asking the node for tags
+fn resolve_block(block: &str, rpc_url: &str, executor: &FoundryExecutor) -> Result<u64, ToolError> {
+    if let Some(number) = parse_block_number(block) {
+        return Ok(number);
+    }
+    if !block.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(ToolError::InvalidArguments(format!(
+            "'{}' is not a block number or tag",
+            block
+        )));
+    }
+
+    let mut args = JsonObject::new();
+    args.insert("block".to_string(), json!(block));
+    args.insert("rpc-url".to_string(), json!(rpc_url));
+    let output = cast::run_tool(executor, "cast_block_number", args, &[])?;
+    parse_block_number(&output).ok_or_else(|| {
+        ToolError::execution_failed(format!("Unexpected block number from cast: '{}'", output))
+    })
+}
+
+/// Check that a range is in order and no wider than `max_range` blocks
+fn check_range(from: u64, to: u64, max_range: u64) -> Result<(), ToolError> {
+    if from > to {
+        return Err(ToolError::InvalidArguments(format!(
+            "from_block ({}) is after to_block ({})",
+            from, to
+        )));
+    }
+    let width = to - from + 1;
+    if width > max_range {
+        return Err(ToolError::InvalidArguments(format!(
+            "The range {}..={} spans {} blocks, more than the maximum of {} (max_log_block_range). Query a narrower range, e.g. {}..={}",
+            from,
+            to,
+            width,
+            max_range,
+            to.saturating_sub(max_range - 1).max(from),
+            to
+        )));
+    }
+    Ok(())
+}
+
+/// Keep as many events as fit in `limit` bytes of JSON
+fn fit_events(events: Vec<Value>, limit: Option<usize>) -> (Vec<Value>, bool) {
+    let Some(limit) = limit else {
+        return (events, false);
+    };
+
+    let mut size = 0;
+    let fitting = events
+        .iter()
+        .take_while(|event| {
+            size += event.to_string().len() + 1;
+            size <= limit
+        })
+        .count();
+    let truncated = fitting < events.len();
+    (events.into_iter().take(fitting).collect(), truncated)
+}
+
+/// A one-line summary of an event for the text output
+fn event_line(event: &Value) -> String {
+    let location = format!(
+        "block {} tx {} log {}",
+        event["block_number"], event["transaction_hash"], event["log_index"]
+    )
+    .replace('"', "");
+
+    match event.get("decoded") {
+        Some(decoded) => {
+            let args = decoded["args"]
+                .as_array()
+                .map(|args| {
+                    args.iter()
+                        .map(|arg| format!("{}={}", arg["name"], arg["value"]).replace('"', ""))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .unwrap_or_default();
+            format!(
+                "{} ({}): {}({})",
+                location, event["address"], decoded["event"], args
+            )
+            .replace('"', "")
+        }
+        None => format!(
+            "{} ({}): topics {}",
+            location, event["address"], event["topics"]
+        )
+        .replace('"', ""),
+    }
+}
+
+/// Run `cast logs` over the checked range and decode the results
+async fn query_logs(
+    args: &JsonObject,
+    from_block: &str,
+    event: Option<&Event>,
+    executor: &FoundryExecutor,
+    cast_path: &str,
+) -> Result<Value, ToolError> {
+    let get = |name: &str| args.get(name).and_then(|v| v.as_str());
+    let config = executor.config();
+    let rpc_url = cast::resolve_rpc_url(get("rpc_url"), &config).await?;
+
+    let from = resolve_block(from_block, &rpc_url, executor)?;
+    let to = resolve_block(get("to_block").unwrap_or("latest"), &rpc_url, executor)?;
+    check_range(from, to, config.max_log_block_range)?;
+
+    let mut logs_args = JsonObject::new();
+    logs_args.insert("from-block".to_string(), json!(from.to_string()));
+    logs_args.insert("to-block".to_string(), json!(to.to_string()));
+    logs_args.insert("rpc-url".to_string(), json!(rpc_url));
+    if let Some(address) = get("address") {
+        logs_args.insert("address".to_string(), json!(address));
+    }
+
+    let topics: Vec<String> = args
+        .get("topics")
+        .and_then(|v| v.as_array())
+        .map(|topics| {
+            topics
+                .iter()
+                .map(|t| t.as_str().map_or_else(|| t.to_string(), str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut topics = topics.into_iter();
+    let sig_or_topic = match event {
+        Some(_) => get("event").map(|sig| sig.trim().trim_start_matches("event ").to_string()),
+        None => topics.next(),
+    };
+    if let Some(sig_or_topic) = sig_or_topic {
+        logs_args.insert("sig_or_topic".to_string(), json!(sig_or_topic));
+        let rest: Vec<String> = topics.collect();
+        if !rest.is_empty() {
+            logs_args.insert("topics_or_args".to_string(), json!(rest));
+        }
+    }
+
+    let output = cast::run_tool(executor, "cast_logs", logs_args, &["--json".to_string()])?;
+    let logs: Vec<Value> = serde_json::from_str(&output).map_err(|e| {
+        ToolError::execution_failed(format!("Could not parse cast_logs output as JSON: {}", e))
+    })?;
+
+    let mut events = Vec::with_capacity(logs.len());
+    for log in &logs {
+        let mut entry = json!({
+            "address": log.get("address").cloned().unwrap_or(Value::Null),
+            "block_number": log
+                .get("blockNumber")
+                .and_then(|v| v.as_str())
+                .and_then(parse_block_number),
+            "transaction_hash": log.get("transactionHash").cloned().unwrap_or(Value::Null),
+            "log_index": log
+                .get("logIndex")
+                .and_then(|v| v.as_str())
+                .and_then(parse_block_number),
+            "topics": log.get("topics").cloned().unwrap_or(json!([])),
+            "data": log.get("data").cloned().unwrap_or(json!("0x")),
+        });
+        if let Some(event) = event {
+            match decode_log(event, log, cast_path) {
+                Ok(decoded) => entry["decoded"] = decoded,
+                Err(e) => entry["decode_error"] = json!(e.to_string()),
+            }
+        }
+        events.push(entry);
+    }
+
+    let total = events.len();
+    let (events, truncated) = fit_events(events, config.max_output_bytes_for("cast_logs"));
+    Ok(json!({
+        "from_block": from,
+        "to_block": to,
+        "total": total,
+        "returned": events.len(),
+        "truncated": truncated,
+        "events": events,
+    }))
+}
+
+/// Handle cast_logs tool call
+pub async fn handle_cast_logs(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+    cast_path: &str,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let args = arguments
+        .as_ref()
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing arguments", None))?;
+    let from_block = args
+        .get("from_block")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing 'from_block' parameter", None))?;
+    let event = args
+        .get("event")
+        .and_then(|v| v.as_str())
+        .map(parse_event)
+        .transpose()?;
+
+    let structured = match query_logs(args, from_block, event.as_ref(), executor, cast_path).await {
+        Ok(structured) => structured,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut text = format!(
+        "{} logs in blocks {}..={}",
+        structured["total"], structured["from_block"], structured["to_block"]
+    );
+    if structured["truncated"] == json!(true) {
+        text.push_str(&format!(
+            " (showing the first {} to stay within max_output_bytes; narrow the range or filters to see the rest)",
+            structured["returned"]
+        ));
+    }
+    for event in structured["events"].as_array().into_iter().flatten() {
+        text.push('\n');
+        text.push_str(&event_line(event));
+    }
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(structured);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(hex: &str) -> String {
+        format!("{:0>64}", hex)
+    }
+
+    /// Test that event parameters keep their type, name and indexed marker
+    #[test]
+    fn test_parse_event() {
+        let event =
+            parse_event("event Transfer(address indexed from, address indexed to, uint256)")
+                .unwrap();
+        assert_eq!(event.name, "Transfer");
+        assert_eq!(
+            event.params[0],
+            EventParam {
+                ty: "address".to_string(),
+                name: Some("from".to_string()),
+                indexed: true,
+            }
+        );
+        assert_eq!(
+            event.params[2],
+            EventParam {
+                ty: "uint256".to_string(),
+                name: None,
+                indexed: false,
+            }
+        );
+
+        for sig in [
+            "Transfer",
+            "(address,uint256)",
+            "E(uint8 indexed a, uint8 indexed b, uint8 indexed c, uint8 indexed d)",
+        ] {
+            assert!(matches!(
+                parse_event(sig),
+                Err(ToolError::InvalidArguments(_))
+            ));
+        }
+    }
+
+    /// Test that static types decode from a word, including negative ints
+    #[test]
+    fn test_decode_word() {
+        let address = "11".repeat(20);
+        assert_eq!(
+            decode_word("address", &word(&address)),
+            Some(json!(format!("0x{}", address)))
+        );
+        assert_eq!(decode_word("bool", &word("1")), Some(json!(true)));
+        assert_eq!(decode_word("uint256", &word("ff")), Some(json!("255")));
+        assert_eq!(
+            decode_word("uint256", &"f".repeat(64)),
+            Some(json!(
+                "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+            ))
+        );
+        assert_eq!(decode_word("int256", &"f".repeat(64)), Some(json!("-1")));
+        assert_eq!(
+            decode_word("int8", &format!("{}{}", "f".repeat(62), "80")),
+            Some(json!("-128"))
+        );
+        assert_eq!(
+            decode_word("bytes4", &format!("deadbeef{}", "0".repeat(56))),
+            Some(json!("0xdeadbeef"))
+        );
+        assert_eq!(decode_word("string", &word("1")), None);
+        assert_eq!(decode_word("uint256", "1234"), None);
+    }
+
+    /// Test that a Transfer log decodes from its topics and data without cast
+    #[test]
+    fn test_decode_log() {
+        let event =
+            parse_event("Transfer(address indexed from, address indexed to, uint256 value)")
+                .unwrap();
+        let log = json!({
+            "topics": [
+                "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+                format!("0x{}", word(&"aa".repeat(20))),
+                format!("0x{}", word(&"bb".repeat(20))),
+            ],
+            "data": format!("0x{}", word("de0b6b3a7640000")),
+        });
+
+        let decoded = decode_log(&event, &log, "cast").unwrap();
+        assert_eq!(decoded["event"], json!("Transfer"));
+        assert_eq!(decoded["args"][0]["name"], json!("from"));
+        assert_eq!(
+            decoded["args"][1]["value"],
+            json!(format!("0x{}", "bb".repeat(20)))
+        );
+        assert_eq!(decoded["args"][2]["value"], json!("1000000000000000000"));
+
+        let short = json!({ "topics": [log["topics"][0]], "data": "0x" });
+        assert!(decode_log(&event, &short, "cast").is_err());
+    }
+
+    /// Test that reversed and over-wide ranges are rejected with a narrower suggestion
+    #[test]
+    fn test_check_range() {
+        assert!(check_range(100, 100, 1).is_ok());
+        assert!(check_range(1, 10_000, 10_000).is_ok());
+        assert!(check_range(10, 9, 100).is_err());
+
+        let err = check_range(0, 20_000, 10_000).unwrap_err();
+        assert!(matches!(err, ToolError::InvalidArguments(_)));
+        assert!(err.to_string().contains("10001..=20000"));
+    }
+
+    /// Test that events are dropped from the end to fit the byte limit
+    #[test]
+    fn test_fit_events() {
+        let events: Vec<Value> = (0..10).map(|i| json!({ "i": i })).collect();
+        let (all, truncated) = fit_events(events.clone(), None);
+        assert_eq!(all.len(), 10);
+        assert!(!truncated);
+
+        let (some, truncated) = fit_events(events, Some(20));
+        assert_eq!(some.len(), 2);
+        assert!(truncated);
+    }
+}
@@ -1,11 +1,16 @@
 //! Foundry MCP Server - Entry point
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::Parser;
 use rmcp::service::ServiceExt;
 
 use foundry_mcp::{
-    config::Config, foundry::FoundryExecutor, schema::SchemaFile, FoundryMcpHandler,
+    config::{Config, Severity},
+    foundry::FoundryExecutor,
+    generator,
+    schema::SchemaFile,
+    sessions::SessionManager,
+    FoundryMcpHandler,
 };
 
 /// Foundry MCP Server - Model Context Protocol server for Foundry CLI tools
@@ -13,29 +18,73 @@ use foundry_mcp::{
 #[command(name = "foundry-mcp")]
 #[command(author, version, about, long_about = None)]
 struct Cli {
-    /// Path to configuration file
+    /// Path to configuration file; separate several with commas to merge them in order
+    #[arg(short, long, value_name = "FILE", value_delimiter = ',')]
+    config: Vec<String>,
+
+    /// Path to a schemas.json file to use instead of the embedded schema
     #[arg(short, long, value_name = "FILE")]
-    config: Option<String>,
+    schema: Option<String>,
+
+    /// Generate a schemas.json from the installed Foundry's --help output and exit
+    #[arg(long, value_name = "FILE")]
+    generate_schema: Option<String>,
+
+    /// Read-only mode: forbid every state-changing command and flag
+    #[arg(long)]
+    read_only: bool,
+}
+
+/// Schemas embedded at compile time, used when no `--schema` is given
+const SCHEMA_JSON: &str = include_str!("../schemas.json");
+
+/// Load the tool schema from an external file if given, otherwise the embedded copy
+fn load_schema(path: Option<&str>) -> Result<SchemaFile> {
+    match path {
+        Some(path) => SchemaFile::from_file(path),
+        None => serde_json::from_str(SCHEMA_JSON).context("Failed to parse embedded schemas.json"),
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    // Load configuration from CLI flag or default
-    let config = match cli.config {
-        Some(ref config_path) => Config::from_file(config_path)?,
-        None => Config::load_default(),
+    if let Some(ref output_path) = cli.generate_schema {
+        return generate_schema_file(output_path);
+    }
+
+    // Load configuration from CLI flag or default; config_reload re-runs the same loader
+    let config_paths = cli.config.clone();
+    let read_only = cli.read_only;
+    let load_config = move || -> Result<Config> {
+        let mut config = if config_paths.is_empty() {
+            Config::load_default()
+        } else {
+            Config::from_files(&config_paths)?
+        };
+        if read_only {
+            config.read_only = true;
+        }
+        Ok(config)
     };
+    let config = load_config()?;
 
     // Log configuration status for visibility
     log_config_status(&config);
 
-    // Load schema from embedded schemas.json at compile time
-    const SCHEMA_JSON: &str = include_str!("../schemas.json");
+    // Load schema from --schema or the embedded schemas.json
+    let schema_file = load_schema(cli.schema.as_deref())?;
+    if let Some(ref schema_path) = cli.schema {
+        eprintln!(
+            "✓ Loaded {} tool schemas from: {}",
+            schema_file.tools.len(),
+            schema_path
+        );
+    }
 
-    let schema_file: SchemaFile =
-        serde_json::from_str(SCHEMA_JSON).context("Failed to parse embedded schemas.json")?;
+    // Catch typos and contradictions before they silently change behavior
+    check_config(&config, &schema_file)?;
 
     // Create the Foundry executor with configuration
     let executor = FoundryExecutor::with_config(schema_file, config);
@@ -50,15 +99,122 @@ async fn main() -> Result<()> {
     }
 
     // Create the MCP handler
-    let handler = FoundryMcpHandler::new(executor);
+    let handler = FoundryMcpHandler::new(executor).with_config_loader(load_config);
 
     // Serve using stdio transport
     let stdin = tokio::io::stdin();
     let stdout = tokio::io::stdout();
 
     let service = handler.serve((stdin, stdout)).await?;
-    service.waiting().await?;
+    let cancel = service.cancellation_token();
+
+    // Run until the client disconnects or we receive SIGINT/SIGTERM
+    let result = tokio::select! {
+        result = service.waiting() => result.map(|_| ()),
+        signal = shutdown_signal() => {
+            eprintln!("Received {}, shutting down", signal);
+            cancel.cancel();
+            Ok(())
+        }
+    };
+
+    // The global session manager lives in a static that isn't guaranteed to drop on exit,
+    // so stop any running Anvil/Chisel processes explicitly to avoid orphaning them. This
+    // runs before a serve error is reported, so a failed transport doesn't leak them either.
+    shutdown_sessions();
+
+    result?;
+    Ok(())
+}
+
+/// Generate a schema file from the installed Foundry binaries and write it to disk.
+fn generate_schema_file(output_path: &str) -> Result<()> {
+    let foundry_bin_path = FoundryExecutor::detect_foundry_path();
+    if let Some(ref path) = foundry_bin_path {
+        eprintln!("✓ Foundry detected at: {}", path);
+    }
+
+    let schema_file = generator::generate_schema(foundry_bin_path.as_deref())?;
+    for problem in schema_file.validate() {
+        eprintln!("⚠ Schema warning: {}", problem);
+    }
 
+    let json = serde_json::to_string_pretty(&schema_file)?;
+    std::fs::write(output_path, json)
+        .with_context(|| format!("Failed to write schema file: {}", output_path))?;
+
+    eprintln!(
+        "✓ Wrote {} tool schemas to: {}",
+        schema_file.tools.len(),
+        output_path
+    );
+    Ok(())
+}
+
+/// Wait for a shutdown signal, returning its name.
+#[cfg(unix)]
+async fn shutdown_signal() -> &'static str {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut sigterm = match signal(SignalKind::terminate()) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("⚠ Warning: failed to install SIGTERM handler: {}", e);
+            let _ = tokio::signal::ctrl_c().await;
+            return "SIGINT";
+        }
+    };
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => "SIGINT",
+        _ = sigterm.recv() => "SIGTERM",
+    }
+}
+
+/// Wait for a shutdown signal, returning its name.
+#[cfg(not(unix))]
+async fn shutdown_signal() -> &'static str {
+    let _ = tokio::signal::ctrl_c().await;
+    "Ctrl-C"
+}
+
+/// Stop all running sessions held by the global session manager.
+fn shutdown_sessions() {
+    for message in SessionManager::global().stop_all() {
+        eprintln!("{}", message);
+    }
+}
+
+/// Validate the configuration against all known tools, printing every issue.
+///
+/// Fails if any issue is a hard error.
+fn check_config(config: &Config, schema_file: &SchemaFile) -> Result<()> {
+    let known_tools: Vec<String> = schema_file
+        .tools
+        .iter()
+        .map(|tool| tool.name.clone())
+        .chain(
+            FoundryMcpHandler::builtin_tools()
+                .iter()
+                .map(|tool| tool.name.to_string()),
+        )
+        .collect();
+
+    let issues = config.validate(&known_tools);
+    for issue in &issues {
+        match issue.severity {
+            Severity::Warning => eprintln!("⚠ Config warning: {}", issue.message),
+            Severity::Error => eprintln!("✗ Config error: {}", issue.message),
+        }
+    }
+
+    let errors = issues
+        .iter()
+        .filter(|issue| issue.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        bail!("Invalid configuration ({} error(s), see above)", errors);
+    }
     Ok(())
 }
 
@@ -66,6 +222,9 @@ async fn main() -> Result<()> {
 ///
 /// This helps users understand what restrictions are active.
 fn log_config_status(config: &Config) {
+    if config.read_only {
+        eprintln!("🔒 Read-only mode: state-changing commands and flags are forbidden");
+    }
     if !config.forbidden_commands.is_empty() {
         eprintln!("🔒 Forbidden commands: {:?}", config.forbidden_commands);
     }
@@ -87,6 +246,7 @@ mod tests {
             forbidden_commands: vec!["anvil".to_string()],
             forbidden_flags: vec!["broadcast".to_string()],
             allow_dangerous: false,
+            ..Default::default()
         };
 
         // Should not panic
@@ -100,32 +260,111 @@ mod tests {
             forbidden_commands: vec![],
             forbidden_flags: vec![],
             allow_dangerous: true,
+            ..Default::default()
         };
 
         // Should not panic
         log_config_status(&config);
     }
 
+    /// Test that shutting down sessions with nothing running doesn't panic
+    #[test]
+    fn test_shutdown_sessions_when_idle() {
+        shutdown_sessions();
+    }
+
+    /// Test that config checking passes warnings but rejects hard errors
+    #[test]
+    fn test_check_config() {
+        let schema = load_schema(None).unwrap();
+        assert!(check_config(&Config::safe_default(), &schema).is_ok());
+
+        let config = Config {
+            forbidden_commands: vec!["forge_biuld".to_string()],
+            ..Default::default()
+        };
+        assert!(check_config(&config, &schema).is_ok());
+
+        let config = Config {
+            max_calls_per_minute: Some(0),
+            ..Default::default()
+        };
+        assert!(check_config(&config, &schema).is_err());
+    }
+
     /// Test that CLI parses correctly without any arguments
     #[test]
     fn test_cli_parsing() {
         // Test that CLI can be parsed
-        let cli = Cli::parse_from(&["foundry-mcp"]);
-        assert!(cli.config.is_none());
+        let cli = Cli::parse_from(["foundry-mcp"]);
+        assert!(cli.config.is_empty());
     }
 
     /// Test that CLI correctly parses --config flag with path argument
     #[test]
     fn test_cli_with_config_path() {
-        let cli = Cli::parse_from(&["foundry-mcp", "--config", "/path/to/config.json"]);
-        assert_eq!(cli.config, Some("/path/to/config.json".to_string()));
+        let cli = Cli::parse_from(["foundry-mcp", "--config", "/path/to/config.json"]);
+        assert_eq!(cli.config, vec!["/path/to/config.json"]);
     }
 
     /// Test that CLI correctly parses short -c flag with path argument
     #[test]
     fn test_cli_with_short_config_flag() {
-        let cli = Cli::parse_from(&["foundry-mcp", "-c", "/path/to/config.json"]);
-        assert_eq!(cli.config, Some("/path/to/config.json".to_string()));
+        let cli = Cli::parse_from(["foundry-mcp", "-c", "/path/to/config.json"]);
+        assert_eq!(cli.config, vec!["/path/to/config.json"]);
+    }
+
+    /// Test that CLI splits a comma-separated --config into several files
+    #[test]
+    fn test_cli_with_multiple_config_paths() {
+        let cli = Cli::parse_from(["foundry-mcp", "--config", "base.json,local.json"]);
+        assert_eq!(cli.config, vec!["base.json", "local.json"]);
+    }
+
+    /// Test that CLI correctly parses --schema flag with path argument
+    #[test]
+    fn test_cli_with_schema_path() {
+        let cli = Cli::parse_from(["foundry-mcp", "--schema", "/path/to/schemas.json"]);
+        assert_eq!(cli.schema, Some("/path/to/schemas.json".to_string()));
+    }
+
+    /// Test that CLI correctly parses --generate-schema flag with output path
+    #[test]
+    fn test_cli_with_generate_schema() {
+        let cli = Cli::parse_from(["foundry-mcp", "--generate-schema", "schemas.json"]);
+        assert_eq!(cli.generate_schema, Some("schemas.json".to_string()));
+    }
+
+    /// Test that CLI correctly parses the --read-only flag
+    #[test]
+    fn test_cli_with_read_only() {
+        assert!(!Cli::parse_from(["foundry-mcp"]).read_only);
+        assert!(Cli::parse_from(["foundry-mcp", "--read-only"]).read_only);
+    }
+
+    /// Test that the embedded schema is used when no --schema path is given
+    #[test]
+    fn test_load_schema_defaults_to_embedded() {
+        let schema = load_schema(None).unwrap();
+        assert!(!schema.tools.is_empty());
+    }
+
+    /// Test that --schema loads tools from disk and rejects invalid files
+    #[test]
+    fn test_load_schema_from_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let schema_path = temp_dir.path().join("schemas.json");
+        fs::write(
+            &schema_path,
+            r#"{"tools": [{"name": "forge_build", "description": "Build"}]}"#,
+        )
+        .unwrap();
+
+        let schema = load_schema(schema_path.to_str()).unwrap();
+        assert_eq!(schema.tools.len(), 1);
+
+        fs::write(&schema_path, "{}").unwrap();
+        assert!(load_schema(schema_path.to_str()).is_err());
     }
 
     /// Test that embedded schemas.json file is valid and contains tools
@@ -0,0 +1,523 @@
+//! Server meta tools that operate on the combined tool list
+//!
+//! These tools don't shell out to Foundry or fetch remote data; they help clients
+//! navigate the (large) set of tools the server exposes.
+
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde_json::Value;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::error::ToolError;
+use crate::metrics::MetricsRegistry;
+
+/// All tool categories, in display order
+pub const CATEGORIES: &[&str] = &[
+    "forge",
+    "cast",
+    "anvil",
+    "chisel",
+    "session",
+    "chainlist",
+    "tokenlist",
+    "conversion",
+    "server",
+];
+
+/// Static category mapping for tools that don't follow the `<binary>_<subcommand>` naming
+const STATIC_CATEGORIES: &[(&str, &str)] = &[
+    ("search_rpc_url", "chainlist"),
+    ("search_chains", "chainlist"),
+    ("list_popular_chains", "chainlist"),
+    ("chain_details", "chainlist"),
+    ("chain_gas_price", "chainlist"),
+    ("contract_verified", "chainlist"),
+    ("fetch_abi", "chainlist"),
+    ("search_tokens", "tokenlist"),
+    ("get_token_by_address", "tokenlist"),
+    ("list_chain_tokens", "tokenlist"),
+    ("list_supported_chains", "tokenlist"),
+    ("cast_convert", "conversion"),
+    ("addresses_checksum", "conversion"),
+    ("conversion_capabilities", "conversion"),
+    ("estimate_gas", "cast"),
+    ("decode_calldata", "cast"),
+    ("abi_encode", "cast"),
+    ("encode_calldata", "cast"),
+    ("abi_decode", "cast"),
+    ("list_tools_by_category", "server"),
+    ("server_stats", "server"),
+    ("config_reload", "server"),
+    ("unlock", "server"),
+    ("lock", "server"),
+    ("get_config", "server"),
+    ("describe_tool", "server"),
+];
+
+/// Determine the category of a tool from its name.
+///
+/// Non-Foundry tools are looked up in a static mapping; session tools are recognized by
+/// their `_session_` infix; everything else is categorized by its binary prefix.
+pub fn tool_category(name: &str) -> Option<&'static str> {
+    if let Some((_, category)) = STATIC_CATEGORIES.iter().find(|(n, _)| *n == name) {
+        return Some(category);
+    }
+
+    if name.starts_with("anvil_session_") || name.starts_with("chisel_session_") {
+        return Some("session");
+    }
+
+    let prefix = name.split('_').next().unwrap_or(name);
+    ["forge", "cast", "anvil", "chisel"]
+        .into_iter()
+        .find(|binary| *binary == prefix)
+}
+
+/// Whether a tool fetches from chainlist.org, the token list or a block explorer, and so
+/// is switched off by `enable_network_tools`
+pub fn is_network_tool(name: &str) -> bool {
+    matches!(tool_category(name), Some("chainlist" | "tokenlist"))
+}
+
+/// Whether a tool runs a Foundry process (forge, cast, anvil or chisel)
+pub fn runs_process(name: &str) -> bool {
+    // addresses_checksum is computed in-process
+    name != "addresses_checksum"
+        && matches!(
+            tool_category(name),
+            Some("forge" | "cast" | "anvil" | "chisel" | "session" | "conversion")
+        )
+}
+
+/// Filter a tool list down to the tools in the given category
+pub fn filter_by_category(tools: Vec<Tool>, category: &str) -> Vec<Tool> {
+    tools
+        .into_iter()
+        .filter(|t| tool_category(&t.name) == Some(category))
+        .collect()
+}
+
+/// Get server meta MCP tools
+pub fn get_meta_tools() -> Vec<Tool> {
+    vec![
+        list_tools_by_category_tool(),
+        describe_tool_tool(),
+        Tool::new(
+            "server_stats".to_string(),
+            "Get per-tool usage statistics for this server: invocation counts, success/failure tallies, and cumulative/average execution time.".to_string(),
+            Arc::new({
+                let mut schema = serde_json::Map::new();
+                schema.insert("type".to_string(), Value::String("object".to_string()));
+                schema.insert("properties".to_string(), Value::Object(serde_json::Map::new()));
+                schema
+            }),
+        ),
+        Tool::new(
+            "config_reload".to_string(),
+            "Re-read the server configuration file and apply it without restarting. Re-filters the exposed tools, reports which tools appeared or disappeared, and notifies the client that the tool list changed. Invalid configurations are rejected and the active one is kept.".to_string(),
+            Arc::new({
+                let mut schema = serde_json::Map::new();
+                schema.insert("type".to_string(), Value::String("object".to_string()));
+                schema.insert("properties".to_string(), Value::Object(serde_json::Map::new()));
+                schema
+            }),
+        ),
+        Tool::new(
+            "get_config".to_string(),
+            "Show the server's effective configuration after defaults, config files, environment overrides, and hardcoded restrictions are merged. Each forbidden command and flag is labelled with where it came from (user or hardcoded). Use this to answer \"why is this tool or flag blocked?\". Secrets are omitted.".to_string(),
+            Arc::new({
+                let mut schema = serde_json::Map::new();
+                schema.insert("type".to_string(), Value::String("object".to_string()));
+                schema.insert("properties".to_string(), Value::Object(serde_json::Map::new()));
+                schema
+            }),
+        ),
+        Tool::new(
+            "unlock".to_string(),
+            "Temporarily lift every forbidden command and flag restriction for this session. Requires the passphrase from the server configuration; the operator must supply it. Restrictions return automatically when the time runs out, or on `lock`.".to_string(),
+            Arc::new({
+                let mut props = serde_json::Map::new();
+                props.insert(
+                    "passphrase".to_string(),
+                    serde_json::json!({
+                        "type": "string",
+                        "description": "Unlock passphrase configured by the operator"
+                    }),
+                );
+                props.insert(
+                    "duration_secs".to_string(),
+                    serde_json::json!({
+                        "type": "integer",
+                        "description": "How long to stay unlocked (capped at the configured unlock_duration_secs)",
+                        "minimum": 1
+                    }),
+                );
+
+                let mut schema = serde_json::Map::new();
+                schema.insert("type".to_string(), Value::String("object".to_string()));
+                schema.insert("properties".to_string(), Value::Object(props));
+                schema.insert(
+                    "required".to_string(),
+                    Value::Array(vec![Value::String("passphrase".to_string())]),
+                );
+                schema
+            }),
+        ),
+        Tool::new(
+            "lock".to_string(),
+            "End an elevation started with `unlock` and restore the configured restrictions immediately.".to_string(),
+            Arc::new({
+                let mut schema = serde_json::Map::new();
+                schema.insert("type".to_string(), Value::String("object".to_string()));
+                schema.insert("properties".to_string(), Value::Object(serde_json::Map::new()));
+                schema
+            }),
+        ),
+    ]
+}
+
+fn list_tools_by_category_tool() -> Tool {
+    Tool::new(
+        "list_tools_by_category".to_string(),
+        "List the tools in a single category (forge, cast, anvil, chisel, session, chainlist, tokenlist, conversion, server). Useful for narrowing down the large tool list.".to_string(),
+        Arc::new({
+            let mut props = serde_json::Map::new();
+            props.insert(
+                "category".to_string(),
+                serde_json::json!({
+                    "type": "string",
+                    "description": "Tool category to list",
+                    "enum": CATEGORIES
+                }),
+            );
+
+            let mut schema = serde_json::Map::new();
+            schema.insert("type".to_string(), Value::String("object".to_string()));
+            schema.insert("properties".to_string(), Value::Object(props));
+            schema.insert(
+                "required".to_string(),
+                Value::Array(vec![Value::String("category".to_string())]),
+            );
+            schema
+        }),
+    )
+}
+
+fn describe_tool_tool() -> Tool {
+    Tool::new(
+        "describe_tool".to_string(),
+        "Get the full description and JSON input schema of a single tool by name, without listing every tool. Unknown names are answered with the closest matching tool names.".to_string(),
+        Arc::new({
+            let mut props = serde_json::Map::new();
+            props.insert(
+                "name".to_string(),
+                serde_json::json!({
+                    "type": "string",
+                    "description": "Exact tool name, e.g. \"cast_call\""
+                }),
+            );
+
+            let mut schema = serde_json::Map::new();
+            schema.insert("type".to_string(), Value::String("object".to_string()));
+            schema.insert("properties".to_string(), Value::Object(props));
+            schema.insert(
+                "required".to_string(),
+                Value::Array(vec![Value::String("name".to_string())]),
+            );
+            schema
+        }),
+    )
+}
+
+/// Edit distance between two names
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Up to five tool names that look like `name`: the ones containing it, then the ones
+/// within a few edits of it
+fn close_matches<'a>(name: &str, names: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let name = name.to_lowercase();
+    let max_distance = (name.len() / 3).max(2);
+
+    let mut matches: Vec<(usize, &str)> = names
+        .filter_map(|candidate| {
+            let distance = if candidate.contains(&name) || name.contains(candidate) {
+                0
+            } else {
+                edit_distance(&name, candidate)
+            };
+            (distance <= max_distance).then_some((distance, candidate))
+        })
+        .collect();
+    matches.sort();
+    matches.into_iter().take(5).map(|(_, n)| n).collect()
+}
+
+/// Handle describe_tool tool call
+pub fn handle_describe_tool(
+    args: &serde_json::Map<String, Value>,
+    tools: Vec<Tool>,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let name = args.get("name").and_then(|v| v.as_str()).ok_or_else(|| {
+        rmcp::ErrorData::invalid_params("Missing or invalid 'name' parameter", None)
+    })?;
+
+    let Some(tool) = tools.iter().find(|t| t.name == name) else {
+        let matches = close_matches(name, tools.iter().map(|t| t.name.as_ref()));
+        let hint = if matches.is_empty() {
+            "Use list_tools_by_category to browse the available tools".to_string()
+        } else {
+            format!("Did you mean: {}?", matches.join(", "))
+        };
+        return Err(ToolError::ToolNotFound(format!("Tool '{}' not found. {}", name, hint)).into());
+    };
+
+    let description = serde_json::json!({
+        "name": tool.name,
+        "category": tool_category(name),
+        "description": tool.description,
+        "input_schema": Value::Object(tool.input_schema.as_ref().clone()),
+    });
+    let text = serde_json::to_string_pretty(&description)
+        .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?;
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(description);
+    Ok(result)
+}
+
+/// Handle list_tools_by_category tool call
+pub fn handle_list_tools_by_category(
+    args: &serde_json::Map<String, Value>,
+    tools: Vec<Tool>,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let category = args
+        .get("category")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| {
+            rmcp::ErrorData::invalid_params("Missing or invalid 'category' parameter", None)
+        })?;
+
+    if !CATEGORIES.contains(&category) {
+        return Err(rmcp::ErrorData::invalid_params(
+            format!(
+                "Unknown category '{}'. Valid categories: {}",
+                category,
+                CATEGORIES.join(", ")
+            ),
+            None,
+        ));
+    }
+
+    let matching = filter_by_category(tools, category);
+
+    let mut response = format!(
+        "Found {} tools in category '{}'\n\n",
+        matching.len(),
+        category
+    );
+    for tool in matching {
+        let summary = tool
+            .description
+            .as_deref()
+            .and_then(|d| d.lines().next())
+            .unwrap_or("");
+        response.push_str(&format!("• {}\n  {}\n", tool.name, summary));
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(response)]))
+}
+
+/// Handle get_config tool call
+pub fn handle_get_config(
+    config: &Config,
+    elevated: bool,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let mut description = config.describe();
+    description["elevated"] = Value::Bool(elevated);
+
+    let text = serde_json::to_string_pretty(&description)
+        .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?;
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(description);
+    Ok(result)
+}
+
+/// Handle server_stats tool call
+pub fn handle_server_stats(metrics: &MetricsRegistry) -> Result<CallToolResult, rmcp::ErrorData> {
+    let stats = metrics.snapshot();
+
+    let mut response = format!("Server Statistics ({} tools used)\n\n", stats.len());
+    for s in &stats {
+        response.push_str(&format!(
+            "• {}\n  Calls: {} (ok: {}, failed: {})\n  Time: {}ms total, {}ms avg\n",
+            s.tool, s.calls, s.successes, s.failures, s.total_ms, s.avg_ms
+        ));
+    }
+
+    let mut result = CallToolResult::success(vec![Content::text(response)]);
+    result.structured_content = Some(serde_json::json!({ "tools": stats }));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str) -> Tool {
+        Tool::new(
+            name.to_string(),
+            format!("{} description", name),
+            Arc::new(serde_json::Map::new()),
+        )
+    }
+
+    /// Test that Foundry tools are categorized by binary prefix
+    #[test]
+    fn test_tool_category_by_prefix() {
+        assert_eq!(tool_category("forge_build"), Some("forge"));
+        assert_eq!(tool_category("cast_call"), Some("cast"));
+        assert_eq!(tool_category("anvil"), Some("anvil"));
+        assert_eq!(tool_category("chisel_list"), Some("chisel"));
+    }
+
+    /// Test that session and non-Foundry tools use the static mapping
+    #[test]
+    fn test_tool_category_static_mapping() {
+        assert_eq!(tool_category("anvil_session_start"), Some("session"));
+        assert_eq!(tool_category("chisel_session_eval"), Some("session"));
+        assert_eq!(tool_category("search_rpc_url"), Some("chainlist"));
+        assert_eq!(tool_category("search_tokens"), Some("tokenlist"));
+        assert_eq!(tool_category("cast_convert"), Some("conversion"));
+        assert_eq!(tool_category("unknown_tool"), None);
+    }
+
+    /// Test that chainlist and tokenlist tools count as network tools
+    #[test]
+    fn test_is_network_tool() {
+        assert!(is_network_tool("search_rpc_url"));
+        assert!(is_network_tool("contract_verified"));
+        assert!(is_network_tool("search_tokens"));
+        assert!(!is_network_tool("cast_call"));
+        assert!(!is_network_tool("anvil_session_start"));
+    }
+
+    /// Test that Foundry-backed tools are recognised as running a process
+    #[test]
+    fn test_runs_process() {
+        assert!(runs_process("forge_build"));
+        assert!(runs_process("cast_convert"));
+        assert!(runs_process("chisel_session_eval"));
+        assert!(!runs_process("search_rpc_url"));
+        assert!(!runs_process("unlock"));
+        assert!(!runs_process("addresses_checksum"));
+    }
+
+    /// Test that filtering keeps only tools in the requested category
+    #[test]
+    fn test_filter_by_category() {
+        let tools = vec![
+            tool("forge_build"),
+            tool("cast_call"),
+            tool("cast_convert"),
+            tool("anvil_session_start"),
+        ];
+
+        let cast: Vec<String> = filter_by_category(tools, "cast")
+            .iter()
+            .map(|t| t.name.to_string())
+            .collect();
+        assert_eq!(cast, vec!["cast_call"]);
+    }
+
+    /// Test that server_stats reports recorded metrics as structured content
+    #[test]
+    fn test_handle_server_stats() {
+        let metrics = MetricsRegistry::new();
+        metrics.record("forge_build", true, std::time::Duration::from_millis(3));
+
+        let result = handle_server_stats(&metrics).unwrap();
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["tools"][0]["tool"], "forge_build");
+        assert_eq!(structured["tools"][0]["calls"], 1);
+    }
+
+    /// Test that get_config returns the described config plus the elevation state
+    #[test]
+    fn test_handle_get_config() {
+        let result = handle_get_config(&Config::safe_default(), true).unwrap();
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["elevated"], true);
+        assert_eq!(structured["config"]["allow_dangerous"], false);
+        assert_eq!(structured["forbidden_commands"][0]["source"], "hardcoded");
+    }
+
+    /// Test that an unknown category is rejected with the valid list
+    #[test]
+    fn test_handle_list_tools_by_category_unknown() {
+        let mut args = serde_json::Map::new();
+        args.insert("category".to_string(), Value::String("bogus".to_string()));
+
+        let err = handle_list_tools_by_category(&args, vec![]).unwrap_err();
+        assert!(err.message.contains("forge"));
+    }
+
+    /// Test that describe_tool returns the named tool's description and schema
+    #[test]
+    fn test_handle_describe_tool() {
+        let mut args = serde_json::Map::new();
+        args.insert("name".to_string(), Value::String("cast_call".to_string()));
+
+        let result =
+            handle_describe_tool(&args, vec![tool("forge_build"), tool("cast_call")]).unwrap();
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["name"], "cast_call");
+        assert_eq!(structured["category"], "cast");
+        assert_eq!(structured["description"], "cast_call description");
+        assert!(structured["input_schema"].is_object());
+    }
+
+    /// Test that an unknown tool name is rejected with close matches
+    #[test]
+    fn test_handle_describe_tool_unknown() {
+        let tools = vec![
+            tool("cast_call"),
+            tool("cast_calldata"),
+            tool("forge_build"),
+        ];
+        let mut args = serde_json::Map::new();
+        args.insert("name".to_string(), Value::String("cast_cal".to_string()));
+
+        let err = handle_describe_tool(&args, tools).unwrap_err();
+        assert!(err
+            .message
+            .contains("Did you mean: cast_call, cast_calldata?"));
+    }
+
+    /// Test that close matches prefer names containing the query, then small typos
+    #[test]
+    fn test_close_matches() {
+        let names = ["forge_build", "forge_test", "cast_balance", "anvil"];
+        assert_eq!(
+            close_matches("forge_biuld", names.into_iter()),
+            vec!["forge_build"]
+        );
+        assert_eq!(
+            close_matches("balance", names.into_iter()),
+            vec!["cast_balance"]
+        );
+        assert!(close_matches("zzzz", names.into_iter()).is_empty());
+    }
+}
@@ -0,0 +1,128 @@
+//! Request-level metrics for tool invocations
+//!
+//! Tracks per-tool call counts, success/failure tallies, and cumulative execution time
+//! so operators can see which tools agents actually use and which ones fail.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Counters for a single tool
+#[derive(Debug, Default)]
+struct ToolCounters {
+    calls: AtomicU64,
+    successes: AtomicU64,
+    failures: AtomicU64,
+    total_micros: AtomicU64,
+}
+
+/// Aggregated statistics for a single tool
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolStats {
+    pub tool: String,
+    pub calls: u64,
+    pub successes: u64,
+    pub failures: u64,
+    pub total_ms: u64,
+    pub avg_ms: u64,
+}
+
+/// Registry of per-tool counters.
+///
+/// The map lock is only held to look up or insert a tool's counters; the counters
+/// themselves are atomics, so concurrent calls to different tools don't contend.
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    tools: Mutex<HashMap<String, Arc<ToolCounters>>>,
+}
+
+impl MetricsRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a completed tool invocation
+    pub fn record(&self, tool: &str, success: bool, elapsed: Duration) {
+        let counters = {
+            let mut tools = self.tools.lock().unwrap();
+            tools.entry(tool.to_string()).or_default().clone()
+        };
+
+        counters.calls.fetch_add(1, Ordering::Relaxed);
+        if success {
+            counters.successes.fetch_add(1, Ordering::Relaxed);
+        } else {
+            counters.failures.fetch_add(1, Ordering::Relaxed);
+        }
+        counters
+            .total_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Snapshot all tool statistics, most-called first
+    pub fn snapshot(&self) -> Vec<ToolStats> {
+        let tools = self.tools.lock().unwrap();
+        let mut stats: Vec<ToolStats> = tools
+            .iter()
+            .map(|(name, c)| {
+                let calls = c.calls.load(Ordering::Relaxed);
+                let total_ms = c.total_micros.load(Ordering::Relaxed) / 1000;
+                ToolStats {
+                    tool: name.clone(),
+                    calls,
+                    successes: c.successes.load(Ordering::Relaxed),
+                    failures: c.failures.load(Ordering::Relaxed),
+                    total_ms,
+                    avg_ms: total_ms.checked_div(calls).unwrap_or(0),
+                }
+            })
+            .collect();
+
+        stats.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.tool.cmp(&b.tool)));
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that successes and failures are tallied per tool
+    #[test]
+    fn test_record_tallies_success_and_failure() {
+        let registry = MetricsRegistry::new();
+        registry.record("forge_build", true, Duration::from_millis(10));
+        registry.record("forge_build", false, Duration::from_millis(30));
+        registry.record("cast_call", true, Duration::from_millis(5));
+
+        let stats = registry.snapshot();
+        let build = stats.iter().find(|s| s.tool == "forge_build").unwrap();
+        assert_eq!(build.calls, 2);
+        assert_eq!(build.successes, 1);
+        assert_eq!(build.failures, 1);
+        assert_eq!(build.total_ms, 40);
+        assert_eq!(build.avg_ms, 20);
+    }
+
+    /// Test that the snapshot is ordered by call count
+    #[test]
+    fn test_snapshot_sorted_by_calls() {
+        let registry = MetricsRegistry::new();
+        registry.record("cast_call", true, Duration::ZERO);
+        registry.record("forge_build", true, Duration::ZERO);
+        registry.record("forge_build", true, Duration::ZERO);
+
+        let stats = registry.snapshot();
+        assert_eq!(stats[0].tool, "forge_build");
+        assert_eq!(stats[1].tool, "cast_call");
+    }
+
+    /// Test that an empty registry produces an empty snapshot
+    #[test]
+    fn test_empty_snapshot() {
+        assert!(MetricsRegistry::new().snapshot().is_empty());
+    }
+}
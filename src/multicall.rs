@@ -0,0 +1,338 @@
+//! Batched read calls
+//!
+//! `cast_multicall` runs a list of read-only `cast call`s in parallel and returns their
+//! results in input order, so inspecting many values takes one tool call instead of N.
+//! Each call goes through the schema `cast_call` tool, so the allowlist applies to every
+//! one. Signatures and argument counts are checked for the whole batch before anything
+//! runs; a call that fails at run time is reported in its slot without failing the rest.
+
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::thread;
+
+use crate::abi::{self, Signature};
+use crate::cast;
+use crate::error::ToolError;
+use crate::foundry::FoundryExecutor;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// Most calls accepted in one batch
+const MAX_CALLS: usize = 50;
+
+/// Most `cast call` processes running at once
+const MAX_PARALLEL: usize = 8;
+
+/// A validated read call
+#[derive(Debug, Clone, PartialEq)]
+struct ReadCall {
+    address: String,
+    signature: String,
+    args: Vec<String>,
+    outputs: Option<Vec<String>>,
+}
+
+/// Get the multicall tools
+pub fn get_multicall_tools() -> Vec<Tool> {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "calls".to_string(),
+        json!({
+            "type": "array",
+            "description": format!("Read calls to make, at most {}", MAX_CALLS),
+            "items": {
+                "type": "object",
+                "properties": {
+                    "address": { "type": "string", "description": "Contract to call" },
+                    "signature": {
+                        "type": "string",
+                        "description": "Function signature with return types, e.g. 'balanceOf(address)(uint256)'"
+                    },
+                    "args": {
+                        "type": "array",
+                        "items": {},
+                        "description": "Arguments, one per parameter"
+                    }
+                },
+                "required": ["address", "signature"]
+            }
+        }),
+    );
+    props.insert(
+        "block".to_string(),
+        json!({
+            "type": "string",
+            "description": "Block number or tag to read at, the same for every call (default: latest)"
+        }),
+    );
+    props.insert(
+        "rpc_url".to_string(),
+        json!({
+            "type": "string",
+            "description": "RPC endpoint to call (default: the configured default_rpc_url or default_chain, else the running Anvil session)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+    schema.insert("required".to_string(), json!(["calls"]));
+
+    vec![Tool::new(
+        "cast_multicall".to_string(),
+        "Make several read-only contract calls at once with parallel cast calls, returning one result per call in input order. Include return types in each signature to get decoded values. Uses the configured default RPC, else the running Anvil session, unless an rpc_url is given.".to_string(),
+        Arc::new(schema),
+    )]
+}
+
+/// Validate every call in the batch up front
+fn parse_calls(calls: &[Value]) -> Result<Vec<ReadCall>, ToolError> {
+    if calls.is_empty() {
+        return Err(ToolError::InvalidArguments("'calls' is empty".to_string()));
+    }
+    if calls.len() > MAX_CALLS {
+        return Err(ToolError::InvalidArguments(format!(
+            "{} calls given; at most {} are allowed per batch",
+            calls.len(),
+            MAX_CALLS
+        )));
+    }
+
+    calls
+        .iter()
+        .enumerate()
+        .map(|(i, call)| {
+            let invalid =
+                |reason: String| ToolError::InvalidArguments(format!("Call {}: {}", i, reason));
+            let call = call
+                .as_object()
+                .ok_or_else(|| invalid("expected an object".to_string()))?;
+            let field = |name: &str| {
+                call.get(name)
+                    .and_then(|v| v.as_str())
+                    .map(str::trim)
+                    .ok_or_else(|| invalid(format!("missing '{}'", name)))
+            };
+            let address = field("address")?;
+            let signature = field("signature")?;
+
+            let Signature { outputs, .. } = abi::parse_signature(signature)
+                .and_then(|parsed| {
+                    let args = abi::arg_strings(call);
+                    abi::check_arg_count(&parsed, &args)?;
+                    Ok(parsed)
+                })
+                .map_err(|e| invalid(e.to_string()))?;
+
+            Ok(ReadCall {
+                address: address.to_string(),
+                signature: signature.to_string(),
+                args: abi::arg_strings(call),
+                outputs,
+            })
+        })
+        .collect()
+}
+
+/// Run one call and shape its result
+fn run_call(
+    call: &ReadCall,
+    rpc_url: &str,
+    block: Option<&str>,
+    executor: &FoundryExecutor,
+) -> Value {
+    let mut call_args = JsonObject::new();
+    call_args.insert("to".to_string(), json!(call.address));
+    call_args.insert("sig".to_string(), json!(call.signature));
+    if !call.args.is_empty() {
+        call_args.insert("args".to_string(), json!(call.args));
+    }
+    call_args.insert("rpc-url".to_string(), json!(rpc_url));
+    if let Some(block) = block {
+        call_args.insert("block".to_string(), json!(block));
+    }
+
+    let mut result = json!({
+        "address": call.address,
+        "signature": call.signature,
+    });
+    match cast::run_tool(executor, "cast_call", call_args, &[]) {
+        Ok(output) => {
+            let values: Vec<String> = output.lines().map(|l| l.trim().to_string()).collect();
+            result["success"] = json!(true);
+            result["values"] = match &call.outputs {
+                Some(types) if types.len() == values.len() => {
+                    json!(abi::typed_values(types, &values))
+                }
+                _ => json!(values
+                    .iter()
+                    .map(|v| json!({ "value": v }))
+                    .collect::<Vec<_>>()),
+            };
+        }
+        Err(e) => {
+            result["success"] = json!(false);
+            result["error"] = json!(e.to_string());
+        }
+    }
+    result
+}
+
+/// Run the calls, at most `MAX_PARALLEL` at a time, keeping input order
+fn run_calls(
+    calls: &[ReadCall],
+    rpc_url: &str,
+    block: Option<&str>,
+    executor: &FoundryExecutor,
+) -> Vec<Value> {
+    calls
+        .chunks(MAX_PARALLEL)
+        .flat_map(|chunk| {
+            thread::scope(|scope| {
+                let handles: Vec<_> = chunk
+                    .iter()
+                    .map(|call| scope.spawn(move || run_call(call, rpc_url, block, executor)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| {
+                        handle.join().unwrap_or_else(
+                            |_| json!({ "success": false, "error": "call panicked" }),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect()
+}
+
+fn result_line(index: usize, result: &Value) -> String {
+    let call =
+        format!("[{}] {} {}", index, result["address"], result["signature"]).replace('"', "");
+    if result["success"] == json!(true) {
+        let values = result["values"]
+            .as_array()
+            .map(|values| {
+                values
+                    .iter()
+                    .map(|v| v["value"].as_str().unwrap_or_default().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default();
+        format!("{} => {}", call, values)
+    } else {
+        format!(
+            "{} failed: {}",
+            call,
+            result["error"].as_str().unwrap_or_default()
+        )
+    }
+}
+
+/// Handle cast_multicall tool call
+pub async fn handle_cast_multicall(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let args = arguments
+        .as_ref()
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing arguments", None))?;
+    let calls = args
+        .get("calls")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing 'calls' parameter", None))?;
+    let calls = parse_calls(calls)?;
+    let rpc_url = cast::resolve_rpc_url(
+        args.get("rpc_url").and_then(|v| v.as_str()),
+        &executor.config(),
+    )
+    .await?;
+    let block = args.get("block").and_then(|v| v.as_str());
+
+    let results = run_calls(&calls, &rpc_url, block, executor);
+    let succeeded = results
+        .iter()
+        .filter(|r| r["success"] == json!(true))
+        .count();
+
+    let mut text = format!("{} of {} calls succeeded", succeeded, results.len());
+    for (i, result) in results.iter().enumerate() {
+        text.push('\n');
+        text.push_str(&result_line(i, result));
+    }
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(json!({
+        "succeeded": succeeded,
+        "failed": results.len() - succeeded,
+        "results": results,
+    }));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that calls are validated as a batch, with the index of the bad call reported
+    #[test]
+    fn test_parse_calls() {
+        let calls = json!([
+            {"address": "0x01", "signature": "balanceOf(address)(uint256)", "args": ["0x02"]},
+            {"address": "0x01", "signature": "totalSupply()(uint256)"}
+        ]);
+        let parsed = parse_calls(calls.as_array().unwrap()).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].args, vec!["0x02"]);
+        assert_eq!(parsed[1].outputs, Some(vec!["uint256".to_string()]));
+
+        let calls = json!([
+            {"address": "0x01", "signature": "totalSupply()(uint256)"},
+            {"address": "0x01", "signature": "balanceOf(address)(uint256)"}
+        ]);
+        let err = parse_calls(calls.as_array().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("Call 1"));
+
+        for calls in [
+            json!([]),
+            json!([{"signature": "f()"}]),
+            json!(["0x01"]),
+            json!(vec![
+                json!({"address": "0x01", "signature": "f()"});
+                MAX_CALLS + 1
+            ]),
+        ] {
+            assert!(matches!(
+                parse_calls(calls.as_array().unwrap()),
+                Err(ToolError::InvalidArguments(_))
+            ));
+        }
+    }
+
+    /// Test that results keep input order and a failing call doesn't fail the batch
+    #[test]
+    fn test_run_calls_reports_failures_in_place() {
+        let executor = FoundryExecutor::with_config(
+            crate::schema::SchemaFile { tools: vec![] },
+            crate::config::Config::default(),
+        );
+        let calls = (0..MAX_PARALLEL + 2)
+            .map(|i| ReadCall {
+                address: format!("0x{:02x}", i),
+                signature: "f()".to_string(),
+                args: vec![],
+                outputs: None,
+            })
+            .collect::<Vec<_>>();
+
+        let results = run_calls(&calls, "http://127.0.0.1:1", None, &executor);
+        assert_eq!(results.len(), calls.len());
+        for (call, result) in calls.iter().zip(&results) {
+            assert_eq!(result["address"], json!(call.address));
+            assert_eq!(result["success"], json!(false));
+            assert!(result["error"].as_str().unwrap().contains("disabled"));
+        }
+    }
+}
@@ -0,0 +1,486 @@
+//! Foundry project detection
+//!
+//! `forge_project_info` gives a quick orientation in an unfamiliar repository: whether a
+//! directory is a Foundry project, the main settings of its `foundry.toml` profile
+//! (compiler version, source and output directories, remappings), and the contracts,
+//! interfaces and libraries under its source directory. Everything is read from disk;
+//! no forge command runs.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::error::ToolError;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// Most source files listed, so a huge repository can't flood the response
+const MAX_SOURCE_FILES: usize = 200;
+
+/// A contract, interface or library declaration, e.g. "abstract contract Token is ERC20"
+static DECLARATION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*(?:abstract\s+)?(?P<kind>contract|interface|library)\s+(?P<name>\w+)").unwrap()
+});
+
+/// Settings read from a `foundry.toml` profile
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct ProjectSettings {
+    profile: String,
+    /// Pinned compiler version, if any (`solc` or `solc_version`)
+    solc: Option<String>,
+    src: String,
+    out: String,
+    test: String,
+    script: String,
+    libs: Vec<String>,
+    evm_version: Option<String>,
+    optimizer: Option<bool>,
+    optimizer_runs: Option<i64>,
+    via_ir: Option<bool>,
+}
+
+/// Declarations found in one source file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct SourceFile {
+    /// Path relative to the project root
+    file: String,
+    contracts: Vec<Declaration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct Declaration {
+    name: String,
+    /// `contract`, `interface` or `library`
+    kind: String,
+}
+
+/// Get the project tools
+pub fn get_project_tools() -> Vec<Tool> {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "root".to_string(),
+        json!({
+            "type": "string",
+            "description": "Project root directory (default: the server's working directory)"
+        }),
+    );
+    props.insert(
+        "profile".to_string(),
+        json!({
+            "type": "string",
+            "description": "foundry.toml profile to read (default: FOUNDRY_PROFILE, else 'default')"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+
+    vec![Tool::new(
+        "forge_project_info".to_string(),
+        "Check whether a directory is a Foundry project and summarize it: solc version, src/out/test directories, libraries, remappings from foundry.toml, and the contracts, interfaces and libraries in the source directory.".to_string(),
+        Arc::new(schema),
+    )]
+}
+
+/// Look up a key in the profile, falling back to the default profile
+fn profile_value<'a>(config: &'a toml::Value, profile: &str, key: &str) -> Option<&'a toml::Value> {
+    let profiles = config.get("profile")?;
+    profiles
+        .get(profile)
+        .and_then(|p| p.get(key))
+        .or_else(|| profiles.get("default").and_then(|p| p.get(key)))
+}
+
+fn parse_settings(config: &toml::Value, profile: &str) -> ProjectSettings {
+    let get = |key: &str| profile_value(config, profile, key);
+    let string = |key: &str, default: &str| {
+        get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or(default)
+            .to_string()
+    };
+
+    ProjectSettings {
+        profile: profile.to_string(),
+        solc: get("solc")
+            .or_else(|| get("solc_version"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        src: string("src", "src"),
+        out: string("out", "out"),
+        test: string("test", "test"),
+        script: string("script", "script"),
+        libs: get("libs")
+            .and_then(|v| v.as_array())
+            .map(|libs| {
+                libs.iter()
+                    .filter_map(|l| l.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_else(|| vec!["lib".to_string()]),
+        evm_version: get("evm_version")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        optimizer: get("optimizer").and_then(|v| v.as_bool()),
+        optimizer_runs: get("optimizer_runs").and_then(|v| v.as_integer()),
+        via_ir: get("via_ir").and_then(|v| v.as_bool()),
+    }
+}
+
+/// The profile to read: the one asked for, else `FOUNDRY_PROFILE`, else `default`
+fn active_profile(profile: Option<&str>) -> String {
+    profile
+        .map(str::to_string)
+        .or_else(|| std::env::var("FOUNDRY_PROFILE").ok())
+        .unwrap_or_else(|| "default".to_string())
+}
+
+/// Remappings listed in the profile
+fn toml_remappings(config: &toml::Value, profile: &str) -> Option<Vec<String>> {
+    let remappings = profile_value(config, profile, "remappings")?.as_array()?;
+    Some(
+        remappings
+            .iter()
+            .filter_map(|r| r.as_str().map(str::to_string))
+            .collect(),
+    )
+}
+
+/// Remappings listed in the project's `remappings.txt`
+fn remappings_txt(root: &Path) -> Option<Vec<String>> {
+    let content = std::fs::read_to_string(root.join("remappings.txt")).ok()?;
+    Some(
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// Remappings from the profile, or else from `remappings.txt`, with where they came from
+fn read_remappings(
+    root: &Path,
+    config: &toml::Value,
+    profile: &str,
+) -> (Vec<String>, Option<&'static str>) {
+    if let Some(remappings) = toml_remappings(config, profile) {
+        return (remappings, Some("foundry.toml"));
+    }
+    match remappings_txt(root) {
+        Some(remappings) => (remappings, Some("remappings.txt")),
+        None => (Vec::new(), None),
+    }
+}
+
+/// Every remapping configured by hand for the project at `root`, with the file it is in
+/// (`foundry.toml` or `remappings.txt`). Anything else forge reports was auto-detected.
+pub fn configured_remappings(root: &Path) -> Vec<(String, &'static str)> {
+    let config = std::fs::read_to_string(root.join("foundry.toml"))
+        .ok()
+        .and_then(|content| toml::from_str::<toml::Value>(&content).ok());
+    let from_toml = config
+        .and_then(|config| toml_remappings(&config, &active_profile(None)))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|r| (r, "foundry.toml"));
+    let from_txt = remappings_txt(root)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|r| (r, "remappings.txt"));
+    from_toml.chain(from_txt).collect()
+}
+
+/// Find the contracts, interfaces and libraries declared in Solidity source
+fn parse_declarations(source: &str) -> Vec<Declaration> {
+    source
+        .lines()
+        .filter_map(|line| DECLARATION.captures(line))
+        .map(|caps| Declaration {
+            name: caps["name"].to_string(),
+            kind: caps["kind"].to_string(),
+        })
+        .collect()
+}
+
+/// Collect the `.sol` files under `dir` in path order, stopping once there are more than `limit`
+fn collect_sources(dir: &Path, files: &mut Vec<PathBuf>, limit: usize) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+    paths.sort();
+
+    for path in paths {
+        if files.len() > limit {
+            return;
+        }
+        if path.is_dir() {
+            collect_sources(&path, files, limit);
+        } else if path.extension().is_some_and(|ext| ext == "sol") {
+            files.push(path);
+        }
+    }
+}
+
+/// List the source files with their declarations, and whether the list was cut short
+fn list_sources(root: &Path, src: &str) -> (Vec<SourceFile>, bool) {
+    let mut files = Vec::new();
+    collect_sources(&root.join(src), &mut files, MAX_SOURCE_FILES);
+    let truncated = files.len() > MAX_SOURCE_FILES;
+    files.truncate(MAX_SOURCE_FILES);
+
+    let sources = files
+        .iter()
+        .map(|path| SourceFile {
+            file: path
+                .strip_prefix(root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .into_owned(),
+            contracts: std::fs::read_to_string(path)
+                .map(|source| parse_declarations(&source))
+                .unwrap_or_default(),
+        })
+        .collect();
+    (sources, truncated)
+}
+
+/// Describe the project at `root`
+fn project_info(root: &Path, profile: Option<&str>) -> Result<Value, ToolError> {
+    if !root.is_dir() {
+        return Err(ToolError::InvalidArguments(format!(
+            "'{}' is not a directory",
+            root.display()
+        )));
+    }
+
+    let config_path = root.join("foundry.toml");
+    if !config_path.is_file() {
+        return Ok(json!({
+            "root": root.display().to_string(),
+            "is_foundry_project": false,
+        }));
+    }
+
+    let content = std::fs::read_to_string(&config_path)
+        .map_err(|e| ToolError::execution_failed(format!("Could not read foundry.toml: {}", e)))?;
+    let config: toml::Value = toml::from_str(&content).map_err(|e| {
+        ToolError::execution_failed(format!("foundry.toml is not valid TOML: {}", e))
+    })?;
+
+    let profile = active_profile(profile);
+    let settings = parse_settings(&config, &profile);
+    let (remappings, remappings_source) = read_remappings(root, &config, &profile);
+    let (sources, truncated) = list_sources(root, &settings.src);
+
+    Ok(json!({
+        "root": root.display().to_string(),
+        "is_foundry_project": true,
+        "settings": settings,
+        "remappings": remappings,
+        "remappings_source": remappings_source,
+        "sources": sources,
+        "sources_truncated": truncated,
+    }))
+}
+
+fn info_text(info: &Value) -> String {
+    let root = info["root"].as_str().unwrap_or_default();
+    if info["is_foundry_project"] != json!(true) {
+        return format!("{} is not a Foundry project (no foundry.toml)", root);
+    }
+
+    let settings = &info["settings"];
+    let setting = |key: &str| match &settings[key] {
+        Value::String(s) => s.clone(),
+        Value::Null => "unset".to_string(),
+        other => other.to_string(),
+    };
+    let mut text = format!(
+        "Foundry project at {} (profile {})\nsolc: {}\nsrc: {}, out: {}, test: {}, script: {}\nlibs: {}",
+        root,
+        setting("profile"),
+        setting("solc"),
+        setting("src"),
+        setting("out"),
+        setting("test"),
+        setting("script"),
+        settings["libs"]
+            .as_array()
+            .map(|libs| libs.iter().filter_map(|l| l.as_str()).collect::<Vec<_>>().join(", "))
+            .unwrap_or_default()
+    );
+
+    let remappings = info["remappings"].as_array().cloned().unwrap_or_default();
+    if !remappings.is_empty() {
+        text.push_str(&format!("\n\nRemappings ({}):", info["remappings_source"]).replace('"', ""));
+        for remapping in &remappings {
+            text.push_str(&format!("\n  {}", remapping.as_str().unwrap_or_default()));
+        }
+    }
+
+    let sources = info["sources"].as_array().cloned().unwrap_or_default();
+    text.push_str(&format!("\n\nSources ({} files):", sources.len()));
+    for source in &sources {
+        let contracts = source["contracts"]
+            .as_array()
+            .map(|contracts| {
+                contracts
+                    .iter()
+                    .map(|c| format!("{} {}", c["kind"], c["name"]).replace('"', ""))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default();
+        text.push_str(&format!(
+            "\n  {}: {}",
+            source["file"].as_str().unwrap_or_default(),
+            contracts
+        ));
+    }
+    if info["sources_truncated"] == json!(true) {
+        text.push_str(&format!(
+            "\n  ... (only the first {} files listed)",
+            MAX_SOURCE_FILES
+        ));
+    }
+    text
+}
+
+/// Handle forge_project_info tool call
+pub fn handle_forge_project_info(
+    arguments: &Option<JsonObject>,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let empty = JsonObject::new();
+    let args = arguments.as_ref().unwrap_or(&empty);
+    let root = args
+        .get("root")
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let profile = args.get("profile").and_then(|v| v.as_str());
+
+    let info = match project_info(&root, profile) {
+        Ok(info) => info,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut result = CallToolResult::success(vec![Content::text(info_text(&info))]);
+    result.structured_content = Some(info);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const FOUNDRY_TOML: &str = r#"
+[profile.default]
+src = "contracts"
+solc = "0.8.24"
+libs = ["lib", "node_modules"]
+remappings = ["@openzeppelin/=lib/openzeppelin-contracts/"]
+optimizer = true
+optimizer_runs = 200
+
+[profile.ci]
+via_ir = true
+optimizer_runs = 10000
+"#;
+
+    /// Test that profile settings fall back to the default profile and forge's defaults
+    #[test]
+    fn test_parse_settings() {
+        let config: toml::Value = toml::from_str(FOUNDRY_TOML).unwrap();
+
+        let default = parse_settings(&config, "default");
+        assert_eq!(default.src, "contracts");
+        assert_eq!(default.out, "out");
+        assert_eq!(default.solc.as_deref(), Some("0.8.24"));
+        assert_eq!(default.libs, vec!["lib", "node_modules"]);
+        assert_eq!(default.optimizer_runs, Some(200));
+        assert_eq!(default.via_ir, None);
+
+        let ci = parse_settings(&config, "ci");
+        assert_eq!(ci.src, "contracts");
+        assert_eq!(ci.optimizer_runs, Some(10000));
+        assert_eq!(ci.via_ir, Some(true));
+    }
+
+    /// Test that contracts, interfaces and libraries are found in source
+    #[test]
+    fn test_parse_declarations() {
+        let source = "pragma solidity ^0.8.0;\n\ninterface IToken {}\nlibrary Math {}\nabstract contract Base {}\ncontract Token is Base, IToken {\n    // contract Fake\n}\n";
+        let names: Vec<(String, String)> = parse_declarations(source)
+            .into_iter()
+            .map(|d| (d.kind, d.name))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("interface".to_string(), "IToken".to_string()),
+                ("library".to_string(), "Math".to_string()),
+                ("contract".to_string(), "Base".to_string()),
+                ("contract".to_string(), "Token".to_string()),
+            ]
+        );
+    }
+
+    /// Test that a project on disk is described, and a plain directory is not a project
+    #[test]
+    fn test_project_info() {
+        let dir = TempDir::new().unwrap();
+        let info = project_info(dir.path(), None).unwrap();
+        assert_eq!(info["is_foundry_project"], json!(false));
+
+        fs::write(dir.path().join("foundry.toml"), "[profile.default]\n").unwrap();
+        fs::write(
+            dir.path().join("remappings.txt"),
+            "forge-std/=lib/forge-std/src/\n",
+        )
+        .unwrap();
+        fs::create_dir_all(dir.path().join("src/tokens")).unwrap();
+        fs::write(
+            dir.path().join("src/tokens/Token.sol"),
+            "contract Token {}\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("src/README.md"), "not solidity").unwrap();
+
+        let info = project_info(dir.path(), Some("default")).unwrap();
+        assert_eq!(info["is_foundry_project"], json!(true));
+        assert_eq!(info["remappings_source"], json!("remappings.txt"));
+        assert_eq!(
+            info["remappings"][0],
+            json!("forge-std/=lib/forge-std/src/")
+        );
+        assert_eq!(info["sources"].as_array().unwrap().len(), 1);
+        assert_eq!(info["sources"][0]["file"], json!("src/tokens/Token.sol"));
+        assert_eq!(info["sources"][0]["contracts"][0]["name"], json!("Token"));
+    }
+
+    /// Test that a missing root or broken foundry.toml is reported
+    #[test]
+    fn test_project_info_errors() {
+        assert!(matches!(
+            project_info(Path::new("/nonexistent/project"), None),
+            Err(ToolError::InvalidArguments(_))
+        ));
+
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("foundry.toml"), "[profile.default\n").unwrap();
+        assert!(project_info(dir.path(), None)
+            .unwrap_err()
+            .is_execution_error());
+    }
+}
@@ -0,0 +1,138 @@
+//! Per-tool rate limiting
+//!
+//! An agent stuck in a loop can call the same tool hundreds of times a minute, each call
+//! spawning a process. When `Config.max_calls_per_minute` (or a per-tool override) is
+//! set, every tool gets a token bucket that refills at the configured rate; calls made
+//! while the bucket is empty are rejected with the time until the next token.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+
+/// Token bucket for a single tool
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token-bucket rate limiter keyed by tool name
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    default_limit: Option<u32>,
+    tool_limits: HashMap<String, u32>,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    /// Create a limiter from the configured call limits
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            default_limit: config.max_calls_per_minute,
+            tool_limits: config.tool_max_calls_per_minute.clone(),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Calls per minute allowed for a tool, if it is limited
+    pub fn limit_for(&self, tool: &str) -> Option<u32> {
+        self.tool_limits.get(tool).copied().or(self.default_limit)
+    }
+
+    /// Take a token for a call to `tool`.
+    ///
+    /// Returns the time to wait before retrying if the tool's bucket is empty.
+    pub fn check(&self, tool: &str) -> Result<(), Duration> {
+        self.check_at(tool, Instant::now())
+    }
+
+    fn check_at(&self, tool: &str, now: Instant) -> Result<(), Duration> {
+        let Some(limit) = self.limit_for(tool) else {
+            return Ok(());
+        };
+        if limit == 0 {
+            return Err(Duration::from_secs(60));
+        }
+
+        let capacity = f64::from(limit);
+        let per_second = capacity / 60.0;
+
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(tool.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_second).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(default_limit: Option<u32>, tool_limits: &[(&str, u32)]) -> RateLimiter {
+        RateLimiter::from_config(&Config {
+            max_calls_per_minute: default_limit,
+            tool_max_calls_per_minute: tool_limits
+                .iter()
+                .map(|(tool, limit)| (tool.to_string(), *limit))
+                .collect(),
+            ..Default::default()
+        })
+    }
+
+    /// Test that tools are unlimited when no limit is configured
+    #[test]
+    fn test_unlimited_by_default() {
+        let limiter = RateLimiter::from_config(&Config::default());
+        for _ in 0..1000 {
+            assert!(limiter.check("forge_build").is_ok());
+        }
+    }
+
+    /// Test that a full bucket allows a burst, then rejects with a retry delay
+    #[test]
+    fn test_bucket_exhausts_and_refills() {
+        let limiter = limiter(Some(6), &[]);
+        let start = Instant::now();
+
+        for _ in 0..6 {
+            assert!(limiter.check_at("forge_build", start).is_ok());
+        }
+        let retry_after = limiter.check_at("forge_build", start).unwrap_err();
+        assert_eq!(retry_after.as_secs(), 10);
+
+        // Other tools have their own bucket
+        assert!(limiter.check_at("forge_test", start).is_ok());
+
+        // One token refills every 10 seconds at 6 calls per minute
+        let later = start + Duration::from_secs(10);
+        assert!(limiter.check_at("forge_build", later).is_ok());
+        assert!(limiter.check_at("forge_build", later).is_err());
+    }
+
+    /// Test that per-tool limits override the default limit
+    #[test]
+    fn test_tool_limit_overrides_default() {
+        let limiter = limiter(Some(100), &[("forge_build", 1), ("cast_call", 0)]);
+        let now = Instant::now();
+
+        assert_eq!(limiter.limit_for("forge_build"), Some(1));
+        assert_eq!(limiter.limit_for("forge_test"), Some(100));
+        assert!(limiter.check_at("forge_build", now).is_ok());
+        assert!(limiter.check_at("forge_build", now).is_err());
+        assert!(limiter.check_at("cast_call", now).is_err());
+    }
+}
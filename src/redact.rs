@@ -0,0 +1,215 @@
+//! Redaction of secret-looking argument values
+//!
+//! Values passed to arguments such as `--private-key` or `--mnemonic` must never appear
+//! in logs, audit records, or error messages echoed back to the client. This module masks
+//! them with a fixed placeholder wherever arguments are rendered.
+
+use serde_json::Value;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// Placeholder written in place of sensitive values
+pub const REDACTED: &str = "***redacted***";
+
+/// Argument names that are always treated as sensitive
+pub const DEFAULT_SENSITIVE_ARGUMENTS: &[&str] = &[
+    "private-key",
+    "private-keys",
+    "raw-private-key",
+    "mnemonic",
+    "mnemonics",
+    "mnemonic-passphrase",
+    "mnemonic-passphrases",
+    "mnemonic-seed-unsafe",
+    "password",
+    "passphrase",
+    "unsafe-password",
+    "unsafe-new-password",
+    "jwt-secret",
+    "etherscan-api-key",
+    "verifier-api-key",
+    "auth",
+];
+
+/// Values shorter than this are not scrubbed from free text, to avoid mangling output
+const MIN_SCRUB_LEN: usize = 4;
+
+/// Redacts sensitive arguments using the default names plus any configured extras
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    extra: Vec<String>,
+}
+
+impl Redactor {
+    /// Create a redactor that also treats the given argument names as sensitive
+    pub fn new(extra: &[String]) -> Self {
+        Self {
+            extra: extra.iter().map(|name| normalize(name)).collect(),
+        }
+    }
+
+    /// Whether an argument name (in any of `private-key`, `private_key`, `--private-key`
+    /// forms) is sensitive
+    pub fn is_sensitive(&self, name: &str) -> bool {
+        let name = normalize(name);
+        DEFAULT_SENSITIVE_ARGUMENTS.contains(&name.as_str()) || self.extra.contains(&name)
+    }
+
+    /// Copy tool arguments with sensitive values replaced by the placeholder
+    pub fn redact_arguments(&self, arguments: &Option<JsonObject>) -> Value {
+        let Some(args) = arguments else {
+            return Value::Object(JsonObject::new());
+        };
+
+        let redacted = args
+            .iter()
+            .map(|(key, value)| {
+                if self.is_sensitive(key) {
+                    (key.clone(), Value::String(REDACTED.to_string()))
+                } else {
+                    (key.clone(), value.clone())
+                }
+            })
+            .collect();
+        Value::Object(redacted)
+    }
+
+    /// Collect the string values of sensitive arguments, for scrubbing free text
+    pub fn secret_values(&self, arguments: &Option<JsonObject>) -> Vec<String> {
+        let Some(args) = arguments else {
+            return vec![];
+        };
+
+        let mut secrets = Vec::new();
+        for (key, value) in args {
+            if !self.is_sensitive(key) {
+                continue;
+            }
+            match value {
+                Value::String(s) => secrets.push(s.clone()),
+                Value::Array(items) => secrets.extend(
+                    items
+                        .iter()
+                        .filter_map(|item| item.as_str().map(String::from)),
+                ),
+                Value::Null | Value::Bool(_) => {}
+                other => secrets.push(other.to_string()),
+            }
+        }
+        secrets
+    }
+
+    /// Render a command line, masking values that follow sensitive flags
+    pub fn redact_command_line(&self, program: &str, args: &[String]) -> String {
+        let mut rendered = vec![program.to_string()];
+        let mut mask_next = false;
+
+        for arg in args {
+            if mask_next {
+                rendered.push(REDACTED.to_string());
+                mask_next = false;
+                continue;
+            }
+
+            match arg.strip_prefix("--").map(|flag| flag.split_once('=')) {
+                Some(Some((flag, _))) if self.is_sensitive(flag) => {
+                    rendered.push(format!("--{}={}", flag, REDACTED));
+                }
+                Some(None) if self.is_sensitive(arg) => {
+                    rendered.push(arg.clone());
+                    mask_next = true;
+                }
+                _ => rendered.push(arg.clone()),
+            }
+        }
+
+        rendered.join(" ")
+    }
+}
+
+/// Replace every occurrence of the given secret values in `text` with the placeholder
+pub fn scrub(text: &str, secrets: &[String]) -> String {
+    let mut scrubbed = text.to_string();
+    for secret in secrets {
+        if secret.len() >= MIN_SCRUB_LEN {
+            scrubbed = scrubbed.replace(secret.as_str(), REDACTED);
+        }
+    }
+    scrubbed
+}
+
+fn normalize(name: &str) -> String {
+    name.trim_start_matches('-')
+        .to_lowercase()
+        .replace('_', "-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    /// Test that default and configured names are recognized in every spelling
+    #[test]
+    fn test_is_sensitive() {
+        let redactor = Redactor::new(&["session-token".to_string()]);
+        assert!(redactor.is_sensitive("private-key"));
+        assert!(redactor.is_sensitive("private_key"));
+        assert!(redactor.is_sensitive("--mnemonic"));
+        assert!(redactor.is_sensitive("SESSION_TOKEN"));
+        assert!(!redactor.is_sensitive("rpc-url"));
+    }
+
+    /// Test that sensitive argument values are replaced in JSON arguments
+    #[test]
+    fn test_redact_arguments() {
+        let mut args = JsonObject::new();
+        args.insert("private-key".to_string(), json!(KEY));
+        args.insert("rpc-url".to_string(), json!("http://localhost:8545"));
+
+        let redacted = Redactor::default().redact_arguments(&Some(args));
+        assert_eq!(redacted["private-key"], REDACTED);
+        assert_eq!(redacted["rpc-url"], "http://localhost:8545");
+    }
+
+    /// Test that values following sensitive flags are masked in command lines
+    #[test]
+    fn test_redact_command_line() {
+        let args: Vec<String> = [
+            "send",
+            "--private-key",
+            KEY,
+            "--mnemonic=test test junk",
+            "--rpc-url",
+            "http://localhost:8545",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let line = Redactor::default().redact_command_line("cast", &args);
+        assert!(!line.contains(KEY));
+        assert!(!line.contains("junk"));
+        assert_eq!(
+            line,
+            "cast send --private-key ***redacted*** --mnemonic=***redacted*** --rpc-url http://localhost:8545"
+        );
+    }
+
+    /// Test that secret values are scrubbed from free text such as error output
+    #[test]
+    fn test_scrub_secret_values() {
+        let mut args = JsonObject::new();
+        args.insert("private_key".to_string(), json!(KEY));
+        args.insert("to".to_string(), json!("0xdead"));
+
+        let secrets = Redactor::default().secret_values(&Some(args));
+        let error = format!("Error: invalid private key {}", KEY);
+        assert_eq!(
+            scrub(&error, &secrets),
+            "Error: invalid private key ***redacted***"
+        );
+    }
+}
@@ -1,9 +1,15 @@
 //! Schema definitions for Foundry CLI tools
 
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+/// Parameter types understood when converting schemas to MCP tools
+pub const KNOWN_PARAM_TYPES: &[&str] = &["string", "number", "boolean", "array", "path", "object"];
 
 /// Schema definition for a positional argument
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct PositionalSchema {
     pub name: String,
     #[serde(rename = "type")]
@@ -12,10 +18,32 @@ pub struct PositionalSchema {
     pub required: bool,
     #[serde(default)]
     pub index: Option<usize>,
+    #[serde(default)]
+    pub default: Option<serde_json::Value>,
+    #[serde(default)]
+    pub minimum: Option<f64>,
+    #[serde(default)]
+    pub maximum: Option<f64>,
+    #[serde(default)]
+    pub pattern: Option<String>,
+    #[serde(default)]
+    pub semantic_type: Option<String>,
+    #[serde(default)]
+    pub deprecated: bool,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+impl PositionalSchema {
+    /// The regex this positional must match: an explicit `pattern`, else one inferred
+    /// from `semantic_type`.
+    pub fn effective_pattern(&self) -> Option<&str> {
+        resolve_pattern(&self.pattern, &self.semantic_type)
+    }
 }
 
 /// Schema definition for an option (flag with value)
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct OptionSchema {
     pub name: String,
     #[serde(rename = "type")]
@@ -28,10 +56,54 @@ pub struct OptionSchema {
     pub value_name: Option<String>,
     #[serde(default)]
     pub default: Option<serde_json::Value>,
+    #[serde(default)]
+    pub enum_values: Option<Vec<String>>,
+    #[serde(default)]
+    pub minimum: Option<f64>,
+    #[serde(default)]
+    pub maximum: Option<f64>,
+    #[serde(default)]
+    pub pattern: Option<String>,
+    #[serde(default)]
+    pub semantic_type: Option<String>,
+    #[serde(default)]
+    pub deprecated: bool,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+impl OptionSchema {
+    /// The regex this option's value must match: an explicit `pattern`, else one
+    /// inferred from `semantic_type`.
+    pub fn effective_pattern(&self) -> Option<&str> {
+        resolve_pattern(&self.pattern, &self.semantic_type)
+    }
+}
+
+/// Infer a default regex pattern from a semantic type hint.
+///
+/// Supported hints are `address` (20-byte hex), `bytes32` (32-byte hex), and
+/// `hexdata` (any even-length `0x`-prefixed hex string).
+pub fn default_pattern(semantic_type: &str) -> Option<&'static str> {
+    match semantic_type {
+        "address" => Some("^0x[0-9a-fA-F]{40}$"),
+        "bytes32" => Some("^0x[0-9a-fA-F]{64}$"),
+        "hexdata" => Some("^0x([0-9a-fA-F]{2})*$"),
+        _ => None,
+    }
+}
+
+fn resolve_pattern<'a>(
+    pattern: &'a Option<String>,
+    semantic_type: &Option<String>,
+) -> Option<&'a str> {
+    pattern
+        .as_deref()
+        .or_else(|| semantic_type.as_deref().and_then(default_pattern))
 }
 
 /// Schema definition for a flag (boolean)
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct FlagSchema {
     pub name: String,
     #[serde(rename = "type")]
@@ -40,10 +112,14 @@ pub struct FlagSchema {
     pub required: bool,
     #[serde(default)]
     pub short: Option<String>,
+    #[serde(default)]
+    pub deprecated: bool,
+    #[serde(default)]
+    pub hidden: bool,
 }
 
 /// Schema definition for a tool
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct ToolSchema {
     pub name: String,
     pub description: String,
@@ -53,6 +129,10 @@ pub struct ToolSchema {
     pub options: Vec<OptionSchema>,
     #[serde(default)]
     pub flags: Vec<FlagSchema>,
+    #[serde(default)]
+    pub deprecated: bool,
+    #[serde(default)]
+    pub hidden: bool,
 }
 
 /// Schema container
@@ -61,6 +141,113 @@ pub struct SchemaFile {
     pub tools: Vec<ToolSchema>,
 }
 
+impl SchemaFile {
+    /// Load a schema file from disk.
+    ///
+    /// This allows using schemas generated for a newer Foundry release without
+    /// rebuilding the binary.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path_ref = path.as_ref();
+        let content = std::fs::read_to_string(path_ref)
+            .with_context(|| format!("Failed to read schema file: {}", path_ref.display()))?;
+
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse schema file: {}", path_ref.display()))
+    }
+
+    /// Check the schema for problems that deserialization doesn't catch.
+    ///
+    /// Returns a human-readable description of each problem found: duplicate tool names
+    /// (which would otherwise silently shadow each other), duplicate or missing positional
+    /// indices (which scramble argument order), and unknown parameter types.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut seen_names = HashSet::new();
+
+        for tool in &self.tools {
+            if !seen_names.insert(tool.name.as_str()) {
+                problems.push(format!("duplicate tool name '{}'", tool.name));
+            }
+            problems.extend(tool.validate());
+        }
+
+        problems
+    }
+}
+
+impl ToolSchema {
+    /// Check this tool's parameters for index collisions and unknown types
+    fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        let mut indices = BTreeSet::new();
+        for pos in &self.positionals {
+            match pos.index {
+                Some(index) if !indices.insert(index) => problems.push(format!(
+                    "{}: duplicate positional index {} ('{}')",
+                    self.name, index, pos.name
+                )),
+                Some(_) => {}
+                None if self.positionals.len() > 1 => problems.push(format!(
+                    "{}: positional '{}' has no index",
+                    self.name, pos.name
+                )),
+                None => {}
+            }
+        }
+        if let Some(&max) = indices.last() {
+            for index in (0..max).filter(|i| !indices.contains(i)) {
+                problems.push(format!("{}: missing positional index {}", self.name, index));
+            }
+        }
+
+        let params = self
+            .positionals
+            .iter()
+            .map(|p| (&p.name, &p.param_type))
+            .chain(self.options.iter().map(|o| (&o.name, &o.param_type)))
+            .chain(self.flags.iter().map(|f| (&f.name, &f.param_type)));
+        for (name, param_type) in params {
+            if !KNOWN_PARAM_TYPES.contains(&param_type.as_str()) {
+                problems.push(format!(
+                    "{}: parameter '{}' has unknown type '{}'",
+                    self.name, name, param_type
+                ));
+            }
+        }
+
+        let patterned = self
+            .positionals
+            .iter()
+            .map(|p| (&p.name, &p.pattern, &p.semantic_type))
+            .chain(
+                self.options
+                    .iter()
+                    .map(|o| (&o.name, &o.pattern, &o.semantic_type)),
+            );
+        for (name, pattern, semantic_type) in patterned {
+            if let Some(semantic_type) = semantic_type {
+                if default_pattern(semantic_type).is_none() {
+                    problems.push(format!(
+                        "{}: parameter '{}' has unknown semantic type '{}'",
+                        self.name, name, semantic_type
+                    ));
+                }
+            }
+            if let Some(pattern) = pattern {
+                if let Err(e) = regex::Regex::new(pattern) {
+                    problems.push(format!(
+                        "{}: parameter '{}' has invalid pattern: {}",
+                        self.name, name, e
+                    ));
+                }
+            }
+        }
+
+        problems
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,6 +272,22 @@ mod tests {
         assert_eq!(pos.index, Some(0));
     }
 
+    /// Test that positional schema deserializes an optional default value
+    #[test]
+    fn test_positional_schema_default() {
+        let json = r#"{
+            "name": "PATH",
+            "type": "string",
+            "description": "Project root",
+            "required": false,
+            "default": ".",
+            "index": 0
+        }"#;
+
+        let pos: PositionalSchema = serde_json::from_str(json).unwrap();
+        assert_eq!(pos.default, Some(serde_json::json!(".")));
+    }
+
     /// Test that positional schema works without optional index field
     #[test]
     fn test_positional_schema_without_index() {
@@ -300,6 +503,7 @@ mod tests {
             description: "Test param".to_string(),
             required: true,
             index: Some(0),
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&pos).unwrap();
@@ -311,6 +515,134 @@ mod tests {
         assert_eq!(deserialized.index, pos.index);
     }
 
+    /// Test that option enum_values deserialize and survive a serialization roundtrip
+    #[test]
+    fn test_option_enum_values_roundtrip() {
+        let json = r#"{
+            "name": "evm-version",
+            "type": "string",
+            "description": "The target EVM version",
+            "required": false,
+            "enum_values": ["paris", "shanghai", "cancun"]
+        }"#;
+
+        let opt: OptionSchema = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            opt.enum_values,
+            Some(vec![
+                "paris".to_string(),
+                "shanghai".to_string(),
+                "cancun".to_string()
+            ])
+        );
+
+        let serialized = serde_json::to_string(&opt).unwrap();
+        let deserialized: OptionSchema = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.enum_values, opt.enum_values);
+    }
+
+    /// Test that numeric bounds deserialize on positionals and options
+    #[test]
+    fn test_numeric_bounds_deserialization() {
+        let json = r#"{
+            "name": "accounts",
+            "type": "number",
+            "description": "Number of accounts",
+            "required": false,
+            "minimum": 1,
+            "maximum": 255
+        }"#;
+
+        let opt: OptionSchema = serde_json::from_str(json).unwrap();
+        assert_eq!(opt.minimum, Some(1.0));
+        assert_eq!(opt.maximum, Some(255.0));
+
+        let pos: PositionalSchema = serde_json::from_str(json).unwrap();
+        assert_eq!(pos.minimum, Some(1.0));
+        assert_eq!(pos.maximum, Some(255.0));
+    }
+
+    /// Test that semantic types infer a default pattern and explicit patterns win
+    #[test]
+    fn test_effective_pattern() {
+        let mut opt = OptionSchema {
+            name: "to".to_string(),
+            param_type: "string".to_string(),
+            semantic_type: Some("address".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(opt.effective_pattern(), Some("^0x[0-9a-fA-F]{40}$"));
+
+        opt.pattern = Some("^0x".to_string());
+        assert_eq!(opt.effective_pattern(), Some("^0x"));
+
+        let pos = PositionalSchema {
+            semantic_type: Some("bytes32".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(pos.effective_pattern(), Some("^0x[0-9a-fA-F]{64}$"));
+        assert_eq!(PositionalSchema::default().effective_pattern(), None);
+    }
+
+    /// Test that default patterns accept and reject the expected values
+    #[test]
+    fn test_default_patterns_match() {
+        let address = regex::Regex::new(default_pattern("address").unwrap()).unwrap();
+        assert!(address.is_match("0x000000000000000000000000000000000000dEaD"));
+        assert!(!address.is_match("0xdead"));
+
+        let hexdata = regex::Regex::new(default_pattern("hexdata").unwrap()).unwrap();
+        assert!(hexdata.is_match("0x"));
+        assert!(hexdata.is_match("0xa9059cbb"));
+        assert!(!hexdata.is_match("0xabc"));
+
+        assert_eq!(default_pattern("uint256"), None);
+    }
+
+    /// Test that invalid patterns and unknown semantic types are reported
+    #[test]
+    fn test_validate_patterns() {
+        let mut pos = positional("a", Some(0));
+        pos.pattern = Some("([".to_string());
+        pos.semantic_type = Some("ens".to_string());
+        let schema_file = SchemaFile {
+            tools: vec![tool("cast_thing", vec![pos])],
+        };
+        let problems = schema_file.validate();
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("unknown semantic type 'ens'"));
+        assert!(problems[1].contains("invalid pattern"));
+    }
+
+    /// Test that deprecated and hidden default to false and deserialize when present
+    #[test]
+    fn test_deprecated_and_hidden_fields() {
+        let minimal: ToolSchema =
+            serde_json::from_str(r#"{"name": "forge_build", "description": "Build"}"#).unwrap();
+        assert!(!minimal.deprecated);
+        assert!(!minimal.hidden);
+
+        let json = r#"{
+            "name": "legacy",
+            "type": "boolean",
+            "description": "Legacy flag",
+            "required": false,
+            "deprecated": true,
+            "hidden": true
+        }"#;
+        let flag: FlagSchema = serde_json::from_str(json).unwrap();
+        assert!(flag.deprecated);
+        assert!(flag.hidden);
+    }
+
+    /// Test that enum_values defaults to None when absent
+    #[test]
+    fn test_option_enum_values_absent() {
+        let json = r#"{"name": "out", "type": "path", "description": "Out", "required": false}"#;
+        let opt: OptionSchema = serde_json::from_str(json).unwrap();
+        assert_eq!(opt.enum_values, None);
+    }
+
     /// Test that tool schema can be serialized and deserialized without data loss
     #[test]
     fn test_serialization_roundtrip_tool() {
@@ -320,6 +652,7 @@ mod tests {
             positionals: vec![],
             options: vec![],
             flags: vec![],
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&tool).unwrap();
@@ -367,6 +700,126 @@ mod tests {
         assert!(result.is_err());
     }
 
+    /// Test that a schema file can be loaded from disk
+    #[test]
+    fn test_schema_file_from_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("schemas.json");
+        std::fs::write(
+            &path,
+            r#"{"tools": [{"name": "forge_build", "description": "Build"}]}"#,
+        )
+        .unwrap();
+
+        let schema_file = SchemaFile::from_file(&path).unwrap();
+        assert_eq!(schema_file.tools.len(), 1);
+        assert_eq!(schema_file.tools[0].name, "forge_build");
+    }
+
+    /// Test that loading a missing or malformed schema file returns an error
+    #[test]
+    fn test_schema_file_from_file_errors() {
+        assert!(SchemaFile::from_file("/nonexistent/schemas.json").is_err());
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("bad.json");
+        std::fs::write(&path, "not json").unwrap();
+        let err = SchemaFile::from_file(&path).unwrap_err();
+        assert!(err.to_string().contains("Failed to parse schema file"));
+    }
+
+    fn positional(name: &str, index: Option<usize>) -> PositionalSchema {
+        PositionalSchema {
+            name: name.to_string(),
+            param_type: "string".to_string(),
+            description: String::new(),
+            required: true,
+            index,
+            ..Default::default()
+        }
+    }
+
+    fn tool(name: &str, positionals: Vec<PositionalSchema>) -> ToolSchema {
+        ToolSchema {
+            name: name.to_string(),
+            description: String::new(),
+            positionals,
+            options: vec![],
+            flags: vec![],
+            ..Default::default()
+        }
+    }
+
+    /// Test that a well-formed schema has no validation problems
+    #[test]
+    fn test_validate_clean_schema() {
+        let schema_file = SchemaFile {
+            tools: vec![tool(
+                "cast_call",
+                vec![positional("to", Some(0)), positional("sig", Some(1))],
+            )],
+        };
+        assert!(schema_file.validate().is_empty());
+    }
+
+    /// Test that duplicate tool names are reported
+    #[test]
+    fn test_validate_duplicate_tool_names() {
+        let schema_file = SchemaFile {
+            tools: vec![tool("forge_build", vec![]), tool("forge_build", vec![])],
+        };
+        let problems = schema_file.validate();
+        assert_eq!(problems, vec!["duplicate tool name 'forge_build'"]);
+    }
+
+    /// Test that duplicate, absent, and skipped positional indices are reported
+    #[test]
+    fn test_validate_positional_indices() {
+        let schema_file = SchemaFile {
+            tools: vec![
+                tool(
+                    "dup",
+                    vec![positional("a", Some(0)), positional("b", Some(0))],
+                ),
+                tool(
+                    "absent",
+                    vec![positional("a", Some(0)), positional("b", None)],
+                ),
+                tool(
+                    "gap",
+                    vec![positional("a", Some(0)), positional("b", Some(2))],
+                ),
+            ],
+        };
+        let problems = schema_file.validate();
+        assert!(problems.contains(&"dup: duplicate positional index 0 ('b')".to_string()));
+        assert!(problems.contains(&"absent: positional 'b' has no index".to_string()));
+        assert!(problems.contains(&"gap: missing positional index 1".to_string()));
+    }
+
+    /// Test that unknown parameter types are reported
+    #[test]
+    fn test_validate_unknown_param_type() {
+        let mut bad = positional("a", Some(0));
+        bad.param_type = "bytes32".to_string();
+        let schema_file = SchemaFile {
+            tools: vec![tool("cast_thing", vec![bad])],
+        };
+        let problems = schema_file.validate();
+        assert_eq!(
+            problems,
+            vec!["cast_thing: parameter 'a' has unknown type 'bytes32'"]
+        );
+    }
+
+    /// Test that the embedded schema passes validation
+    #[test]
+    fn test_embedded_schema_validates() {
+        let schema_file: SchemaFile =
+            serde_json::from_str(include_str!("../schemas.json")).unwrap();
+        assert_eq!(schema_file.validate(), Vec::<String>::new());
+    }
+
     /// Test that parameter names with special characters (hyphens, etc.) are preserved
     #[test]
     fn test_schema_with_special_characters_in_names() {
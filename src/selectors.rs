@@ -0,0 +1,420 @@
+//! Function selector listing and collision checks
+//!
+//! `cast_selectors` lists the function selectors of one or more contracts and flags any
+//! selector shared between them, such as a proxy function shadowing an implementation's or
+//! two diamond facets clashing. A contract can be given as bytecode, an address (its code
+//! is fetched with `cast code`), or a compiled artifact, whose `methodIdentifiers` give the
+//! exact signatures. Bytecode goes through `cast selectors`.
+
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::cast;
+use crate::error::ToolError;
+use crate::foundry::FoundryExecutor;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// Most contracts compared in one call
+const MAX_CONTRACTS: usize = 10;
+
+/// A function found in a contract
+#[derive(Debug, Clone, PartialEq)]
+struct Selector {
+    selector: String,
+    /// Full signature when known (artifacts, or bytecode with `resolve`)
+    signature: Option<String>,
+    /// Argument types guessed from the bytecode
+    arguments: Option<String>,
+}
+
+/// Get the selector tools
+pub fn get_selector_tools() -> Vec<Tool> {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "contracts".to_string(),
+        json!({
+            "type": "array",
+            "description": format!("Contracts to list, at most {}. Selectors shared between them are flagged as collisions", MAX_CONTRACTS),
+            "items": {
+                "type": "object",
+                "properties": {
+                    "label": { "type": "string", "description": "Name to report the contract under (optional)" },
+                    "bytecode": { "type": "string", "description": "Runtime bytecode as hex" },
+                    "address": { "type": "string", "description": "Deployed contract whose code is fetched over RPC" },
+                    "artifact": { "type": "string", "description": "Path to a compiled artifact JSON, e.g. out/Token.sol/Token.json" }
+                }
+            }
+        }),
+    );
+    props.insert(
+        "resolve".to_string(),
+        json!({
+            "type": "boolean",
+            "description": "Look up signatures for selectors taken from bytecode in the signature database"
+        }),
+    );
+    props.insert(
+        "rpc_url".to_string(),
+        json!({
+            "type": "string",
+            "description": "RPC endpoint for fetching code by address (default: the configured default_rpc_url or default_chain, else the running Anvil session)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+    schema.insert("required".to_string(), json!(["contracts"]));
+
+    vec![Tool::new(
+        "cast_selectors".to_string(),
+        "List the function selectors of contracts given as bytecode, address or artifact path, and flag selectors that collide between them (e.g. a proxy and its implementation, or diamond facets).".to_string(),
+        Arc::new(schema),
+    )]
+}
+
+/// Normalize a selector to lowercase 0x-prefixed hex
+fn normalize_selector(selector: &str) -> String {
+    let hex = selector.trim().trim_start_matches("0x");
+    format!("0x{}", hex.to_ascii_lowercase())
+}
+
+/// Parse `cast selectors` output: `selector<TAB>arguments[<TAB>signature]` per line
+fn parse_cast_selectors(output: &str) -> Vec<Selector> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t').map(str::trim);
+            let selector = fields.next().filter(|s| s.starts_with("0x"))?;
+            let arguments = fields.next().filter(|s| !s.is_empty()).map(str::to_string);
+            let signature = fields.find(|s| !s.is_empty()).map(str::to_string);
+            Some(Selector {
+                selector: normalize_selector(selector),
+                signature,
+                arguments,
+            })
+        })
+        .collect()
+}
+
+/// Selectors from an artifact's `methodIdentifiers`, or `None` if it has none
+fn artifact_selectors(artifact: &Value) -> Option<Vec<Selector>> {
+    let identifiers = artifact.get("methodIdentifiers")?.as_object()?;
+    let mut selectors: Vec<Selector> = identifiers
+        .iter()
+        .filter_map(|(signature, selector)| {
+            Some(Selector {
+                selector: normalize_selector(selector.as_str()?),
+                signature: Some(signature.clone()),
+                arguments: None,
+            })
+        })
+        .collect();
+    selectors.sort_by(|a, b| a.selector.cmp(&b.selector));
+    Some(selectors)
+}
+
+/// Runtime bytecode from an artifact
+fn artifact_bytecode(artifact: &Value) -> Option<String> {
+    let bytecode = artifact.get("deployedBytecode")?;
+    bytecode
+        .get("object")
+        .unwrap_or(bytecode)
+        .as_str()
+        .filter(|code| !code.trim_start_matches("0x").is_empty())
+        .map(str::to_string)
+}
+
+/// Run `cast selectors` on bytecode
+fn bytecode_selectors(
+    bytecode: &str,
+    resolve: bool,
+    executor: &FoundryExecutor,
+) -> Result<Vec<Selector>, ToolError> {
+    let mut args = JsonObject::new();
+    args.insert("bytecode".to_string(), json!(bytecode));
+    if resolve {
+        args.insert("resolve".to_string(), json!(true));
+    }
+    let output = cast::run_tool(executor, "cast_selectors", args, &[])?;
+    Ok(parse_cast_selectors(&output))
+}
+
+/// List the selectors of one contract entry
+async fn contract_selectors(
+    contract: &JsonObject,
+    resolve: bool,
+    rpc_url: Option<&str>,
+    executor: &FoundryExecutor,
+) -> Result<Vec<Selector>, ToolError> {
+    let get = |name: &str| contract.get(name).and_then(|v| v.as_str());
+
+    match (get("bytecode"), get("address"), get("artifact")) {
+        (Some(bytecode), None, None) => bytecode_selectors(bytecode, resolve, executor),
+        (None, Some(address), None) => {
+            let mut args = JsonObject::new();
+            args.insert("who".to_string(), json!(address));
+            let rpc_url = cast::resolve_rpc_url(rpc_url, &executor.config()).await?;
+            args.insert("rpc-url".to_string(), json!(rpc_url));
+            let code = cast::run_tool(executor, "cast_code", args, &[])?;
+            if code.trim_start_matches("0x").is_empty() {
+                return Err(ToolError::InvalidArguments(format!(
+                    "{} has no code",
+                    address
+                )));
+            }
+            bytecode_selectors(&code, resolve, executor)
+        }
+        (None, None, Some(path)) => {
+            if !path.ends_with(".json") {
+                return Err(ToolError::InvalidArguments(format!(
+                    "'{}' is not a JSON artifact",
+                    path
+                )));
+            }
+            let content = std::fs::read_to_string(path).map_err(|e| {
+                ToolError::InvalidArguments(format!("Could not read artifact '{}': {}", path, e))
+            })?;
+            let artifact: Value = serde_json::from_str(&content).map_err(|e| {
+                ToolError::InvalidArguments(format!("Artifact '{}' is not valid JSON: {}", path, e))
+            })?;
+            if let Some(selectors) = artifact_selectors(&artifact) {
+                return Ok(selectors);
+            }
+            let bytecode = artifact_bytecode(&artifact).ok_or_else(|| {
+                ToolError::InvalidArguments(format!(
+                    "Artifact '{}' has neither methodIdentifiers nor deployed bytecode",
+                    path
+                ))
+            })?;
+            bytecode_selectors(&bytecode, resolve, executor)
+        }
+        _ => Err(ToolError::InvalidArguments(
+            "Each contract needs exactly one of 'bytecode', 'address' or 'artifact'".to_string(),
+        )),
+    }
+}
+
+/// Label a contract by its given label, else its source
+fn contract_label(index: usize, contract: &JsonObject) -> String {
+    ["label", "artifact", "address"]
+        .iter()
+        .find_map(|name| contract.get(*name).and_then(|v| v.as_str()))
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("contract {}", index))
+}
+
+/// Selectors that appear in more than one contract, with where they appear
+fn find_collisions(contracts: &[(String, Vec<Selector>)]) -> Vec<Value> {
+    let mut seen: BTreeMap<&str, Vec<Value>> = BTreeMap::new();
+    for (label, selectors) in contracts {
+        for selector in selectors {
+            seen.entry(&selector.selector).or_default().push(json!({
+                "contract": label,
+                "signature": selector.signature,
+            }));
+        }
+    }
+
+    seen.into_iter()
+        .filter(|(_, entries)| entries.len() > 1)
+        .map(|(selector, entries)| json!({ "selector": selector, "functions": entries }))
+        .collect()
+}
+
+fn selector_json(selector: &Selector) -> Value {
+    let mut value = json!({ "selector": selector.selector });
+    if let Some(signature) = &selector.signature {
+        value["signature"] = json!(signature);
+    }
+    if let Some(arguments) = &selector.arguments {
+        value["arguments"] = json!(arguments);
+    }
+    value
+}
+
+/// Handle cast_selectors tool call
+pub async fn handle_cast_selectors(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let args = arguments
+        .as_ref()
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing arguments", None))?;
+    let contracts = args
+        .get("contracts")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing 'contracts' parameter", None))?;
+    if contracts.is_empty() || contracts.len() > MAX_CONTRACTS {
+        return Err(ToolError::InvalidArguments(format!(
+            "Give between 1 and {} contracts",
+            MAX_CONTRACTS
+        ))
+        .into());
+    }
+    let resolve = args.get("resolve").and_then(|v| v.as_bool()) == Some(true);
+    let rpc_url = args.get("rpc_url").and_then(|v| v.as_str());
+
+    let mut listed = Vec::with_capacity(contracts.len());
+    for (i, contract) in contracts.iter().enumerate() {
+        let contract = contract.as_object().ok_or_else(|| {
+            ToolError::InvalidArguments(format!("Contract {} must be an object", i))
+        })?;
+        let selectors = match contract_selectors(contract, resolve, rpc_url, executor).await {
+            Ok(selectors) => selectors,
+            Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+            Err(e) => return Err(e.into()),
+        };
+        listed.push((contract_label(i, contract), selectors));
+    }
+    let collisions = find_collisions(&listed);
+
+    let mut text = String::new();
+    for (label, selectors) in &listed {
+        text.push_str(&format!("{} ({} functions)\n", label, selectors.len()));
+        for selector in selectors {
+            let description = selector
+                .signature
+                .clone()
+                .or_else(|| selector.arguments.as_ref().map(|a| format!("({})", a)))
+                .unwrap_or_default();
+            text.push_str(&format!("  {} {}\n", selector.selector, description));
+        }
+    }
+    if collisions.is_empty() {
+        text.push_str("No selector collisions");
+    } else {
+        text.push_str(&format!("{} selector collisions:", collisions.len()));
+        for collision in &collisions {
+            let contracts = collision["functions"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|f| f["contract"].as_str().unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join(", ");
+            text.push_str(&format!(
+                "\n  {} in {}",
+                collision["selector"].as_str().unwrap_or_default(),
+                contracts
+            ));
+        }
+    }
+
+    let structured = json!({
+        "contracts": listed
+            .iter()
+            .map(|(label, selectors)| json!({
+                "label": label,
+                "selectors": selectors.iter().map(selector_json).collect::<Vec<_>>(),
+            }))
+            .collect::<Vec<_>>(),
+        "collisions": collisions,
+    });
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(structured);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that cast selectors output is parsed with and without resolved signatures
+    #[test]
+    fn test_parse_cast_selectors() {
+        let output = "0x06fdde03\t\n0x095ea7b3\taddress,uint256\tapprove(address,uint256)\n";
+        let selectors = parse_cast_selectors(output);
+        assert_eq!(
+            selectors,
+            vec![
+                Selector {
+                    selector: "0x06fdde03".to_string(),
+                    signature: None,
+                    arguments: None,
+                },
+                Selector {
+                    selector: "0x095ea7b3".to_string(),
+                    signature: Some("approve(address,uint256)".to_string()),
+                    arguments: Some("address,uint256".to_string()),
+                },
+            ]
+        );
+    }
+
+    /// Test that artifacts give exact signatures, and bytecode is the fallback
+    #[test]
+    fn test_artifact_selectors() {
+        let artifact = json!({
+            "methodIdentifiers": {"transfer(address,uint256)": "a9059cbb", "name()": "06fdde03"}
+        });
+        let selectors = artifact_selectors(&artifact).unwrap();
+        assert_eq!(selectors[0].selector, "0x06fdde03");
+        assert_eq!(
+            selectors[1].signature.as_deref(),
+            Some("transfer(address,uint256)")
+        );
+
+        let artifact = json!({"deployedBytecode": {"object": "0x6080"}});
+        assert!(artifact_selectors(&artifact).is_none());
+        assert_eq!(artifact_bytecode(&artifact).as_deref(), Some("0x6080"));
+        assert_eq!(
+            artifact_bytecode(&json!({"deployedBytecode": {"object": "0x"}})),
+            None
+        );
+    }
+
+    /// Test that only selectors shared between contracts are reported
+    #[test]
+    fn test_find_collisions() {
+        let selector = |selector: &str, signature: &str| Selector {
+            selector: selector.to_string(),
+            signature: Some(signature.to_string()),
+            arguments: None,
+        };
+        let contracts = vec![
+            (
+                "Proxy".to_string(),
+                vec![selector("0x42966c68", "collate_propagate_storage(bytes16)")],
+            ),
+            (
+                "Implementation".to_string(),
+                vec![
+                    selector("0x42966c68", "burn(uint256)"),
+                    selector("0xa9059cbb", "transfer(address,uint256)"),
+                ],
+            ),
+        ];
+
+        let collisions = find_collisions(&contracts);
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0]["selector"], json!("0x42966c68"));
+        assert_eq!(
+            collisions[0]["functions"][1]["contract"],
+            json!("Implementation")
+        );
+    }
+
+    /// Test that each contract needs exactly one source
+    #[tokio::test]
+    async fn test_contract_needs_one_source() {
+        let executor = FoundryExecutor::with_config(
+            crate::schema::SchemaFile { tools: vec![] },
+            crate::config::Config::default(),
+        );
+        for contract in [
+            json!({}),
+            json!({"bytecode": "0x6080", "address": "0x01"}),
+            json!({"artifact": "out/Token.sol/Token.txt"}),
+        ] {
+            assert!(matches!(
+                contract_selectors(contract.as_object().unwrap(), false, None, &executor).await,
+                Err(ToolError::InvalidArguments(_))
+            ));
+        }
+    }
+}
@@ -1,35 +1,430 @@
 //! MCP server handler implementation
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use once_cell::sync::Lazy;
 use rmcp::{
     model::*,
-    service::{RequestContext, RoleServer},
+    service::{Peer, RequestContext, RoleServer},
     ErrorData as McpError, ServerHandler,
 };
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
 
+use crate::abi;
+use crate::account;
+use crate::anvil;
+use crate::audit::{self, AuditEntry, AuditLog};
+use crate::blocks;
+use crate::calldata;
+use crate::cast;
 use crate::chainlist::{self, fetch_chainlist};
+use crate::coalesce::{self, Coalescer};
+use crate::completions;
+use crate::config::{Config, Severity};
 use crate::conversion;
+use crate::deployment;
+use crate::diagnostics;
+use crate::disassembly;
+use crate::elevation::{self, Elevation};
+use crate::ens;
+use crate::error::ToolError;
+use crate::explorer;
+use crate::forge;
 use crate::foundry::FoundryExecutor;
+use crate::gas;
 use crate::handlers;
+use crate::http;
+use crate::logs;
+use crate::meta;
+use crate::metrics::MetricsRegistry;
+use crate::multicall;
+use crate::project;
+use crate::ratelimit::RateLimiter;
+use crate::selectors;
+use crate::sessions::SessionManager;
+use crate::storage;
 use crate::tokenlist;
+use crate::trace;
+use crate::wallet;
+
+type JsonObject = serde_json::Map<String, serde_json::Value>;
+
+/// Names of [`FoundryMcpHandler::builtin_tools`]
+static BUILTIN_TOOL_NAMES: Lazy<HashSet<String>> = Lazy::new(|| {
+    FoundryMcpHandler::builtin_tools()
+        .into_iter()
+        .map(|tool| tool.name.to_string())
+        .collect()
+});
+
+/// Tools that change which tools are exposed, triggering `tools/list_changed`
+const LIST_CHANGING_TOOLS: &[&str] = &["config_reload", "unlock", "lock"];
+
+/// Resource publishing the live session registry
+const SESSIONS_URI: &str = "sessions://active";
+
+/// JSON-RPC error code for a request the client cancelled
+const REQUEST_CANCELLED: ErrorCode = ErrorCode(-32800);
+
+/// Run `work` until it finishes or `cancelled` resolves, whichever comes first.
+///
+/// rmcp only signals a client's `notifications/cancelled` through the request's
+/// cancellation token, so without this a cancelled call would keep fetching the
+/// chainlist or tokenlist in the background. Dropping `work` aborts the HTTP request
+/// at its next await point; the caches are only written once a response has been
+/// fully read and parsed, so an aborted fetch never leaves a partial entry behind.
+async fn until_cancelled<T>(
+    cancelled: impl std::future::Future<Output = ()>,
+    work: impl std::future::Future<Output = Result<T, McpError>>,
+) -> Result<T, McpError> {
+    tokio::select! {
+        result = work => result,
+        _ = cancelled => Err(McpError::new(
+            REQUEST_CANCELLED,
+            "Request cancelled by the client",
+            None,
+        )),
+    }
+}
+
+/// Read the `sessions://active` resource from the global session registry
+async fn read_sessions_resource(uri: String) -> Result<ReadResourceResult, McpError> {
+    let sessions = tokio::task::spawn_blocking(|| {
+        let manager = SessionManager::global();
+        manager.snapshot()
+    })
+    .await
+    .map_err(|e| McpError::internal_error(format!("Task error: {}", e), None))?;
+
+    let json = serde_json::to_string_pretty(&serde_json::json!({ "sessions": sessions }))
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+    Ok(ReadResourceResult {
+        contents: vec![ResourceContents::TextResourceContents {
+            uri,
+            mime_type: Some("application/json".to_string()),
+            text: json,
+            meta: None,
+        }],
+    })
+}
+
+/// Re-reads the configuration from its original source when `config_reload` is called
+pub type ConfigLoader = Arc<dyn Fn() -> Result<Config> + Send + Sync>;
+
+/// Per-call policy derived from the configuration, swapped on reload
+struct CallPolicy {
+    audit_log: Option<Arc<AuditLog>>,
+    rate_limiter: Arc<RateLimiter>,
+    /// Bounds how many Foundry processes run at once (`max_concurrent_commands`)
+    commands: Arc<Semaphore>,
+    /// Shares results between identical concurrent read calls (`coalesce_read_calls`)
+    coalescer: Option<Arc<Coalescer>>,
+}
+
+impl CallPolicy {
+    fn from_config(config: &Config) -> Self {
+        Self {
+            audit_log: config
+                .audit_log_path
+                .as_ref()
+                .map(|path| Arc::new(AuditLog::new(path))),
+            rate_limiter: Arc::new(RateLimiter::from_config(config)),
+            commands: Arc::new(Semaphore::new(config.max_concurrent_commands)),
+            coalescer: config
+                .coalesce_read_calls
+                .then(|| Arc::new(Coalescer::new())),
+        }
+    }
+}
 
 /// MCP server handler
 #[derive(Clone)]
 pub struct FoundryMcpHandler {
     foundry: Arc<FoundryExecutor>,
+    metrics: Arc<MetricsRegistry>,
+    policy: Arc<RwLock<Arc<CallPolicy>>>,
+    config_loader: ConfigLoader,
+    elevation: Arc<Elevation>,
 }
 
 impl FoundryMcpHandler {
     pub fn new(foundry: FoundryExecutor) -> Self {
+        let policy = CallPolicy::from_config(&foundry.config());
+        http::configure(&foundry.config());
+        chainlist::configure(&foundry.config());
+
         Self {
             foundry: Arc::new(foundry),
+            metrics: Arc::new(MetricsRegistry::new()),
+            policy: Arc::new(RwLock::new(Arc::new(policy))),
+            config_loader: Arc::new(|| Ok(Config::load_default())),
+            elevation: Arc::new(Elevation::new()),
         }
     }
 
+    /// Set how `config_reload` obtains the configuration (defaults to
+    /// [`Config::load_default`]).
+    pub fn with_config_loader<F>(mut self, loader: F) -> Self
+    where
+        F: Fn() -> Result<Config> + Send + Sync + 'static,
+    {
+        self.config_loader = Arc::new(loader);
+        self
+    }
+
+    fn policy(&self) -> Arc<CallPolicy> {
+        self.policy.read().unwrap().clone()
+    }
+
+    /// Re-read the configuration and apply it without restarting.
+    ///
+    /// The new configuration is validated first; on hard errors the active configuration
+    /// is kept. Returns a summary of the tools that appeared or disappeared.
+    pub fn reload_config(&self) -> Result<String> {
+        let config = (self.config_loader)()?;
+
+        let known_tools: Vec<String> = self
+            .foundry
+            .schema_tool_names()
+            .into_iter()
+            .chain(Self::builtin_tools().iter().map(|t| t.name.to_string()))
+            .collect();
+        let issues = config.validate(&known_tools);
+        let errors: Vec<&str> = issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .map(|issue| issue.message.as_str())
+            .collect();
+        if !errors.is_empty() {
+            bail!(
+                "Configuration not reloaded, it has errors:\n- {}",
+                errors.join("\n- ")
+            );
+        }
+
+        let tool_names = |tools: Vec<Tool>| -> HashSet<String> {
+            tools.iter().map(|t| t.name.to_string()).collect()
+        };
+        let before = tool_names(self.foundry.tool_list());
+
+        // Removing the passphrase also revokes an elevation in progress
+        if config.unlock_passphrase.is_none() && self.elevation.lock() {
+            self.foundry.set_elevated(false);
+            eprintln!("🔒 Elevation revoked: unlock_passphrase was removed");
+        }
+
+        let policy = CallPolicy::from_config(&config);
+        http::configure(&config);
+        chainlist::configure(&config);
+        SessionManager::global().forget_chisel_probe();
+        self.foundry.reload_config(config);
+        *self.policy.write().unwrap() = Arc::new(policy);
+
+        let after = tool_names(self.foundry.tool_list());
+        let mut added: Vec<&String> = after.difference(&before).collect();
+        let mut removed: Vec<&String> = before.difference(&after).collect();
+        added.sort();
+        removed.sort();
+
+        eprintln!(
+            "✓ Reloaded config: {} Foundry tools exposed (+{}, -{})",
+            after.len(),
+            added.len(),
+            removed.len()
+        );
+
+        let mut summary = format!(
+            "Configuration reloaded. {} Foundry tools exposed.\n",
+            after.len()
+        );
+        if !added.is_empty() {
+            summary.push_str(&format!("\nNow available ({}):\n", added.len()));
+            for name in added {
+                summary.push_str(&format!("• {}\n", name));
+            }
+        }
+        if !removed.is_empty() {
+            summary.push_str(&format!("\nNo longer available ({}):\n", removed.len()));
+            for name in removed {
+                summary.push_str(&format!("• {}\n", name));
+            }
+        }
+        for issue in issues {
+            summary.push_str(&format!("\n⚠ {}", issue.message));
+        }
+        Ok(summary)
+    }
+
+    /// Get the tool invocation metrics registry
+    pub fn metrics(&self) -> &MetricsRegistry {
+        &self.metrics
+    }
+
     pub fn foundry_bin_path(&self) -> &Option<String> {
         self.foundry.foundry_bin_path()
     }
+
+    /// Check if a built-in tool is exposed under the configuration.
+    ///
+    /// An allowlist takes precedence over the forbidden lists, as it does for schema tools.
+    fn is_builtin_allowed(config: &Config, name: &str) -> bool {
+        if config.allowed_commands.is_some() {
+            config.is_tool_allowlisted(name)
+        } else {
+            !config.is_command_forbidden(name)
+        }
+    }
+
+    /// Get the combined list of all tools exposed by the server
+    pub fn all_tools(&self) -> Vec<Tool> {
+        let config = self.foundry.config();
+        let mut tools = self.foundry.tool_list();
+        tools.extend(
+            Self::builtin_tools()
+                .into_iter()
+                .filter(|tool| Self::is_builtin_allowed(&config, &tool.name)),
+        );
+
+        // Elevation tools only make sense when the operator configured a passphrase
+        if self.foundry.config().unlock_passphrase.is_none() {
+            tools.retain(|tool| tool.name != "unlock" && tool.name != "lock");
+        }
+        if !self.foundry.config().enable_network_tools {
+            tools.retain(|tool| !meta::is_network_tool(&tool.name));
+        }
+
+        // Sorted so the list is the same across restarts, whatever order the schema
+        // tools come out of their map in
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        tools
+    }
+
+    /// Check the passphrase and lift all restrictions for a bounded time
+    fn handle_unlock(&self, arguments: &Option<JsonObject>) -> Result<CallToolResult, McpError> {
+        let config = self.foundry.config();
+        let Some(expected) = config.unlock_passphrase.as_deref() else {
+            return Err(ToolError::ForbiddenCommand(
+                "Elevation is disabled: no unlock_passphrase is configured".to_string(),
+            )
+            .into());
+        };
+
+        let args = arguments.as_ref();
+        let passphrase = args
+            .and_then(|a| a.get("passphrase"))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                ToolError::InvalidArguments("Missing 'passphrase' parameter".to_string())
+            })?;
+        if !elevation::passphrase_matches(expected, passphrase) {
+            eprintln!("🔒 Rejected unlock attempt: wrong passphrase");
+            return Err(
+                ToolError::ForbiddenCommand("Invalid unlock passphrase".to_string()).into(),
+            );
+        }
+
+        let duration_secs = match args.and_then(|a| a.get("duration_secs")) {
+            Some(value) => value.as_u64().filter(|secs| *secs > 0).ok_or_else(|| {
+                ToolError::InvalidArguments(
+                    "'duration_secs' must be a positive integer".to_string(),
+                )
+            })?,
+            None => config.unlock_duration_secs,
+        }
+        .min(config.unlock_duration_secs);
+
+        self.elevation.unlock(Duration::from_secs(duration_secs));
+        self.foundry.set_elevated(true);
+        eprintln!(
+            "🔓 Session elevated for {}s: all command and flag restrictions lifted",
+            duration_secs
+        );
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Unlocked for {} seconds. All forbidden commands and flags are available until then, or until `lock` is called.",
+            duration_secs
+        ))]))
+    }
+
+    /// End the current elevation early
+    fn handle_lock(&self) -> Result<CallToolResult, McpError> {
+        let was_elevated = self.elevation.lock();
+        self.foundry.set_elevated(false);
+
+        let message = if was_elevated {
+            eprintln!("🔒 Session locked: restrictions restored");
+            "Locked. The configured restrictions apply again."
+        } else {
+            "The session was not unlocked."
+        };
+        Ok(CallToolResult::success(vec![Content::text(message)]))
+    }
+
+    /// Restore restrictions if the elevation has run out. Returns whether it had.
+    fn expire_elevation(&self) -> bool {
+        let expired = self.elevation.expire();
+        if expired {
+            self.foundry.set_elevated(false);
+            eprintln!("🔒 Elevation expired: restrictions restored");
+        }
+        expired
+    }
+
+    /// Tell the client to re-fetch the tool list
+    async fn notify_tools_changed(peer: &Peer<RoleServer>) {
+        if let Err(e) = peer.notify_tool_list_changed().await {
+            eprintln!("⚠ Warning: Failed to send tools/list_changed: {}", e);
+        }
+    }
+
+    /// Get the tools implemented by the server itself rather than Foundry binaries
+    pub fn builtin_tools() -> Vec<Tool> {
+        let mut tools = Vec::new();
+
+        // Add chainlist tools
+        tools.extend(chainlist::get_chainlist_tools());
+        tools.extend(explorer::get_explorer_tools());
+
+        // Add tokenlist tools
+        tools.extend(tokenlist::get_tokenlist_tools());
+
+        // Add session management tools
+        tools.extend(handlers::get_session_tools());
+        tools.extend(anvil::get_anvil_tools());
+
+        // Add unified conversion tool
+        tools.push(conversion::get_conversion_tool());
+        tools.push(conversion::get_addresses_checksum_tool());
+        tools.push(conversion::get_conversion_capabilities_tool());
+
+        // Add transaction helper tools
+        tools.extend(gas::get_gas_tools());
+        tools.extend(calldata::get_calldata_tools());
+        tools.extend(abi::get_abi_tools());
+        tools.extend(storage::get_storage_tools());
+        tools.extend(wallet::get_wallet_tools());
+        tools.extend(blocks::get_block_tools());
+        tools.extend(logs::get_log_tools());
+        tools.extend(multicall::get_multicall_tools());
+        tools.extend(deployment::get_deployment_tools());
+        tools.extend(selectors::get_selector_tools());
+        tools.extend(ens::get_ens_tools());
+        tools.extend(account::get_account_tools());
+        tools.extend(disassembly::get_disassembly_tools());
+        tools.extend(trace::get_trace_tools());
+
+        // Add forge workflow tools
+        tools.extend(forge::get_forge_tools());
+        tools.extend(project::get_project_tools());
+
+        // Add server meta tools
+        tools.extend(meta::get_meta_tools());
+
+        tools
+    }
 }
 
 impl ServerHandler for FoundryMcpHandler {
@@ -43,10 +438,10 @@ impl ServerHandler for FoundryMcpHandler {
                     list_changed: None,
                 }),
                 tools: Some(ToolsCapability {
-                    list_changed: None,
+                    list_changed: Some(true),
                 }),
                 logging: None,
-                completions: None,
+                completions: Some(serde_json::Map::new()),
                 experimental: None,
             },
             server_info: Implementation {
@@ -63,21 +458,15 @@ impl ServerHandler for FoundryMcpHandler {
     async fn list_tools(
         &self,
         _request: Option<PaginatedRequestParam>,
-        _context: RequestContext<RoleServer>,
+        context: RequestContext<RoleServer>,
     ) -> Result<ListToolsResult, McpError> {
-        let mut tools = self.foundry.tool_list().to_vec();
-
-        // Add chainlist tools
-        tools.extend(chainlist::get_chainlist_tools());
-
-        // Add tokenlist tools
-        tools.extend(tokenlist::get_tokenlist_tools());
-
-        // Add session management tools
-        tools.extend(handlers::get_session_tools());
+        self.expire_elevation();
+        let mut tools = self.all_tools();
 
-        // Add unified conversion tool
-        tools.push(conversion::get_conversion_tool());
+        // Optionally narrow the list via a `category` field in the request meta
+        if let Some(category) = context.meta.get("category").and_then(|v| v.as_str()) {
+            tools = meta::filter_by_category(tools, category);
+        }
 
         Ok(ListToolsResult {
             tools,
@@ -85,6 +474,21 @@ impl ServerHandler for FoundryMcpHandler {
         })
     }
 
+    async fn complete(
+        &self,
+        request: CompleteRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<CompleteResult, McpError> {
+        let network_enabled = self.foundry.config().enable_network_tools;
+        let completion = completions::complete_argument(&request.argument, network_enabled)
+            .await
+            .map_err(|e| {
+                McpError::internal_error(format!("Failed to complete argument: {}", e), None)
+            })?;
+
+        Ok(CompleteResult { completion })
+    }
+
     async fn list_resources(
         &self,
         _request: Option<PaginatedRequestParam>,
@@ -104,10 +508,19 @@ impl ServerHandler for FoundryMcpHandler {
         );
         tokenlist_resource.mime_type = Some("application/json".to_string());
 
-        let resources = vec![
-            chainlist_resource.no_annotation(),
-            tokenlist_resource.no_annotation(),
-        ];
+        let mut sessions_resource = RawResource::new(SESSIONS_URI, "Active Sessions");
+        sessions_resource.description = Some(
+            "Live Anvil and Chisel sessions with their type, process ID, port, and uptime"
+                .to_string(),
+        );
+        sessions_resource.mime_type = Some("application/json".to_string());
+
+        // The chainlist and token list resources are downloaded on read
+        let mut resources = vec![sessions_resource.no_annotation()];
+        if self.foundry.config().enable_network_tools {
+            resources.push(chainlist_resource.no_annotation());
+            resources.push(tokenlist_resource.no_annotation());
+        }
 
         Ok(ListResourcesResult {
             resources,
@@ -118,8 +531,123 @@ impl ServerHandler for FoundryMcpHandler {
     async fn read_resource(
         &self,
         request: ReadResourceRequestParam,
-        _context: RequestContext<RoleServer>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        until_cancelled(context.ct.cancelled(), self.resource_contents(request)).await
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let tool_name = request.name.to_string();
+        if self.expire_elevation() {
+            Self::notify_tools_changed(&context.peer).await;
+        }
+
+        let started = Instant::now();
+        let policy = self.policy();
+
+        // Only keep a copy of the arguments if this call will be audited
+        let audit = policy
+            .audit_log
+            .as_ref()
+            .filter(|_| audit::is_audited(&tool_name))
+            .map(|log| (log.clone(), request.arguments.clone()));
+
+        let result = match policy.rate_limiter.check(&tool_name) {
+            Ok(()) => {
+                let coalesce_key = policy
+                    .coalescer
+                    .as_ref()
+                    .filter(|_| coalesce::is_coalescable(&tool_name))
+                    .map(|_| coalesce::call_key(&tool_name, &request.arguments));
+                let work = async {
+                    // Calls beyond max_concurrent_commands wait here for a slot
+                    let _permit = if meta::runs_process(&tool_name) {
+                        policy.commands.acquire().await.ok()
+                    } else {
+                        None
+                    };
+                    self.dispatch_tool(request).await
+                };
+                match (policy.coalescer.as_ref(), coalesce_key) {
+                    (Some(coalescer), Some(key)) => {
+                        until_cancelled(context.ct.cancelled(), coalescer.run(key, work)).await
+                    }
+                    _ => until_cancelled(context.ct.cancelled(), work).await,
+                }
+            }
+            Err(retry_after) => {
+                let retry_after_secs = retry_after.as_secs_f64().ceil() as u64;
+                Ok(ToolError::RateLimited {
+                    message: format!(
+                        "Tool '{}' is rate limited, retry after {} s",
+                        tool_name, retry_after_secs
+                    ),
+                    retry_after_secs,
+                }
+                .into_call_tool_result())
+            }
+        };
+
+        let elapsed = started.elapsed();
+        let success = matches!(&result, Ok(r) if r.is_error != Some(true));
+        self.metrics.record(&tool_name, success, elapsed);
+
+        if let Some((log, arguments)) = audit {
+            let exit_code = result
+                .as_ref()
+                .ok()
+                .and_then(|r| r.structured_content.as_ref())
+                .and_then(|c| c["error"]["exit_code"].as_i64())
+                .map(|code| code as i32);
+            let entry = AuditEntry::new(
+                &self.foundry.redactor(),
+                &tool_name,
+                &arguments,
+                success,
+                exit_code,
+                elapsed,
+            );
+            if let Err(e) = log.record(&entry) {
+                eprintln!("⚠ Warning: {:#}", e);
+            }
+        }
+
+        if success && LIST_CHANGING_TOOLS.contains(&tool_name.as_str()) {
+            Self::notify_tools_changed(&context.peer).await;
+        }
+
+        result
+    }
+}
+
+impl FoundryMcpHandler {
+    /// Refuse chainlist, token list and explorer access when network tools are disabled
+    fn check_network_enabled(&self) -> Result<(), McpError> {
+        if self.foundry.config().enable_network_tools {
+            return Ok(());
+        }
+        Err(ToolError::ForbiddenCommand(
+            "Network tools are disabled by the server configuration (enable_network_tools)"
+                .to_string(),
+        )
+        .into())
+    }
+
+    /// Read a resource's contents
+    async fn resource_contents(
+        &self,
+        request: ReadResourceRequestParam,
     ) -> Result<ReadResourceResult, McpError> {
+        if request.uri == SESSIONS_URI {
+            return read_sessions_resource(request.uri).await;
+        }
+
+        // Everything else is downloaded
+        self.check_network_enabled()?;
         match request.uri.as_str() {
             "chainlist://all" => match fetch_chainlist().await {
                 Ok(chains) => {
@@ -135,10 +663,11 @@ impl ServerHandler for FoundryMcpHandler {
                         }],
                     })
                 }
-                Err(e) => Err(McpError::internal_error(
-                    format!("Failed to fetch chainlist data: {}", e),
-                    None,
-                )),
+                Err(e) => Err(ToolError::RpcUnavailable(format!(
+                    "Failed to fetch chainlist data: {}",
+                    e
+                ))
+                .into()),
             },
             "tokenlist://all" => match tokenlist::fetch_tokenlist().await {
                 Ok(tokens) => {
@@ -154,10 +683,11 @@ impl ServerHandler for FoundryMcpHandler {
                         }],
                     })
                 }
-                Err(e) => Err(McpError::internal_error(
-                    format!("Failed to fetch token list: {}", e),
-                    None,
-                )),
+                Err(e) => Err(ToolError::RpcUnavailable(format!(
+                    "Failed to fetch token list: {}",
+                    e
+                ))
+                .into()),
             },
             _ => Err(McpError::invalid_params(
                 format!("Unknown resource URI: {}", request.uri),
@@ -166,20 +696,84 @@ impl ServerHandler for FoundryMcpHandler {
         }
     }
 
-    async fn call_tool(
+    /// Run a Foundry tool on the blocking thread pool, so a long-running command doesn't
+    /// stall the other requests
+    async fn execute_blocking(
+        &self,
+        name: &str,
+        arguments: Option<JsonObject>,
+    ) -> Result<String, ToolError> {
+        let foundry = self.foundry.clone();
+        let name = name.to_string();
+        tokio::task::spawn_blocking(move || foundry.execute_tool(&name, &arguments))
+            .await
+            .unwrap_or_else(|e| {
+                Err(ToolError::execution_failed(format!(
+                    "Foundry command did not complete: {}",
+                    e
+                )))
+            })
+    }
+
+    /// Run a built-in handler on the blocking thread pool, like [`Self::execute_blocking`],
+    /// since most of them wait on a Foundry process
+    async fn run_blocking<F, Fut>(
+        &self,
+        arguments: Option<JsonObject>,
+        handler: F,
+    ) -> Result<CallToolResult, McpError>
+    where
+        F: FnOnce(Option<JsonObject>, Arc<FoundryExecutor>) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<CallToolResult, McpError>>,
+    {
+        let foundry = self.foundry.clone();
+        let runtime = tokio::runtime::Handle::current();
+        tokio::task::spawn_blocking(move || runtime.block_on(handler(arguments, foundry)))
+            .await
+            .unwrap_or_else(|e| {
+                Ok(
+                    ToolError::execution_failed(format!("Tool did not complete: {}", e))
+                        .into_call_tool_result(),
+                )
+            })
+    }
+
+    /// Route a tool call to the handler that implements it
+    async fn dispatch_tool(
         &self,
         request: CallToolRequestParam,
-        _context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
-        // Handle chainlist tools
         let tool_name: &str = &request.name;
+        if meta::is_network_tool(tool_name) {
+            self.check_network_enabled()?;
+        }
+        // Schema tools are filtered by the executor; built-in ones are checked here
+        if BUILTIN_TOOL_NAMES.contains(tool_name)
+            && !Self::is_builtin_allowed(&self.foundry.config(), tool_name)
+        {
+            return Err(ToolError::ForbiddenCommand(format!(
+                "'{}' is forbidden by the server configuration",
+                tool_name
+            ))
+            .into());
+        }
+
+        // Handle chainlist tools
         match tool_name {
             "search_rpc_url" => {
-                let args = request
-                    .arguments
+                let mut arguments = request.arguments;
+                let default_chain = chainlist::apply_default_chain(
+                    &mut arguments,
+                    "chain",
+                    self.foundry.config().default_chain.as_deref(),
+                );
+                let args = arguments
                     .as_ref()
                     .ok_or_else(|| McpError::invalid_params("Missing arguments", None))?;
-                return chainlist::handle_search_rpc_url(args).await;
+                return chainlist::default_chain_note(
+                    chainlist::handle_search_rpc_url(args).await,
+                    default_chain,
+                );
             }
             "search_chains" => {
                 let args = request
@@ -191,29 +785,101 @@ impl ServerHandler for FoundryMcpHandler {
             "list_popular_chains" => {
                 let empty_map = serde_json::Map::new();
                 let args = request.arguments.as_ref().unwrap_or(&empty_map);
-                return chainlist::handle_list_popular_chains(args).await;
+                let config = self.foundry.config();
+                return chainlist::handle_list_popular_chains(args, &config.popular_chain_ids)
+                    .await;
             }
-            // Handle tokenlist tools
-            "search_tokens" => {
+            "chain_details" => {
                 let args = request
                     .arguments
                     .as_ref()
                     .ok_or_else(|| McpError::invalid_params("Missing arguments", None))?;
-                return tokenlist::handle_search_tokens(args).await;
+                return chainlist::handle_chain_details(args).await;
             }
-            "get_token_by_address" => {
+            "chain_gas_price" => {
                 let args = request
                     .arguments
                     .as_ref()
                     .ok_or_else(|| McpError::invalid_params("Missing arguments", None))?;
-                return tokenlist::handle_get_token_by_address(args).await;
+                return chainlist::handle_chain_gas_price(args).await;
             }
-            "list_chain_tokens" => {
-                let args = request
+            "contract_verified" => {
+                let config = self.foundry.config();
+                return explorer::handle_contract_verified(
+                    &request.arguments,
+                    config.etherscan_api_key.as_deref(),
+                )
+                .await;
+            }
+            "fetch_abi" => {
+                let config = self.foundry.config();
+                return explorer::handle_fetch_abi(
+                    &request.arguments,
+                    config.etherscan_api_key.as_deref(),
+                )
+                .await;
+            }
+            "cast_interface" => {
+                // Only looking up a contract's ABI reaches the network
+                let by_address = request
                     .arguments
+                    .as_ref()
+                    .is_some_and(|args| args.contains_key("address"));
+                if by_address {
+                    self.check_network_enabled()?;
+                }
+                let api_key = self.foundry.config().etherscan_api_key.clone();
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        explorer::handle_cast_interface(&args, &foundry, api_key.as_deref()).await
+                    })
+                    .await;
+            }
+            // Handle tokenlist tools
+            "search_tokens" => {
+                let mut arguments = request.arguments;
+                let default_chain = chainlist::apply_default_chain(
+                    &mut arguments,
+                    "chain",
+                    self.foundry.config().default_chain.as_deref(),
+                );
+                let args = arguments
+                    .as_ref()
+                    .ok_or_else(|| McpError::invalid_params("Missing arguments", None))?;
+                return chainlist::default_chain_note(
+                    tokenlist::handle_search_tokens(args).await,
+                    default_chain,
+                );
+            }
+            "get_token_by_address" => {
+                let mut arguments = request.arguments;
+                let default_chain = chainlist::apply_default_chain(
+                    &mut arguments,
+                    "chain",
+                    self.foundry.config().default_chain.as_deref(),
+                );
+                let args = arguments
+                    .as_ref()
+                    .ok_or_else(|| McpError::invalid_params("Missing arguments", None))?;
+                return chainlist::default_chain_note(
+                    tokenlist::handle_get_token_by_address(args).await,
+                    default_chain,
+                );
+            }
+            "list_chain_tokens" => {
+                let mut arguments = request.arguments;
+                let default_chain = chainlist::apply_default_chain(
+                    &mut arguments,
+                    "chain",
+                    self.foundry.config().default_chain.as_deref(),
+                );
+                let args = arguments
                     .as_ref()
                     .ok_or_else(|| McpError::invalid_params("Missing arguments", None))?;
-                return tokenlist::handle_list_chain_tokens(args).await;
+                return chainlist::default_chain_note(
+                    tokenlist::handle_list_chain_tokens(args).await,
+                    default_chain,
+                );
             }
             "list_supported_chains" => {
                 let empty_map = serde_json::Map::new();
@@ -232,10 +898,56 @@ impl ServerHandler for FoundryMcpHandler {
                 return handlers::handle_anvil_session_stop().await;
             }
             "anvil_session_status" => {
-                return handlers::handle_anvil_session_status().await;
+                return handlers::handle_anvil_session_status(self.foundry_bin_path()).await;
+            }
+            "anvil_set_time" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        anvil::handle_anvil_set_time(&args, &foundry).await
+                    })
+                    .await;
+            }
+            "anvil_set_basefee" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        anvil::handle_anvil_set_basefee(&args, &foundry).await
+                    })
+                    .await;
+            }
+            "anvil_impersonate" | "anvil_stop_impersonate" => {
+                let impersonate = tool_name == "anvil_impersonate";
+                return self
+                    .run_blocking(request.arguments, move |args, foundry| async move {
+                        anvil::handle_anvil_impersonate(&args, &foundry, impersonate).await
+                    })
+                    .await;
+            }
+            "anvil_snapshot_save" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        anvil::handle_anvil_snapshot_save(&args, &foundry).await
+                    })
+                    .await;
+            }
+            "anvil_snapshot_restore" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        anvil::handle_anvil_snapshot_restore(&args, &foundry).await
+                    })
+                    .await;
+            }
+            "anvil_set_balance" | "anvil_set_code" | "anvil_set_storage_at" => {
+                let tool = tool_name.to_string();
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        anvil::handle_anvil_set_state(&tool, &args, &foundry).await
+                    })
+                    .await;
             }
             "chisel_session_start" => {
-                return handlers::handle_chisel_session_start(self.foundry_bin_path()).await;
+                let probe_ttl = Duration::from_secs(self.foundry.config().chisel_probe_ttl_secs);
+                return handlers::handle_chisel_session_start(self.foundry_bin_path(), probe_ttl)
+                    .await;
             }
             "chisel_session_eval" => {
                 return handlers::handle_chisel_session_eval(
@@ -244,29 +956,292 @@ impl ServerHandler for FoundryMcpHandler {
                 )
                 .await;
             }
+            "chisel_session_load" => {
+                return handlers::handle_chisel_session_load(
+                    &request.arguments,
+                    self.foundry_bin_path(),
+                )
+                .await;
+            }
             "chisel_session_stop" => {
                 return handlers::handle_chisel_session_stop().await;
             }
             "chisel_session_status" => {
                 return handlers::handle_chisel_session_status().await;
             }
+            // Handle server meta tools
+            "list_tools_by_category" => {
+                let args = request
+                    .arguments
+                    .as_ref()
+                    .ok_or_else(|| McpError::invalid_params("Missing arguments", None))?;
+                return meta::handle_list_tools_by_category(args, self.all_tools());
+            }
+            "describe_tool" => {
+                let args = request
+                    .arguments
+                    .as_ref()
+                    .ok_or_else(|| McpError::invalid_params("Missing arguments", None))?;
+                return meta::handle_describe_tool(args, self.all_tools());
+            }
+            "server_stats" => {
+                return meta::handle_server_stats(&self.metrics);
+            }
+            "get_config" => {
+                return meta::handle_get_config(&self.foundry.config(), self.foundry.is_elevated());
+            }
+            "unlock" => {
+                return self.handle_unlock(&request.arguments);
+            }
+            "lock" => {
+                return self.handle_lock();
+            }
+            "config_reload" => {
+                return Ok(match self.reload_config() {
+                    Ok(summary) => CallToolResult::success(vec![Content::text(summary)]),
+                    Err(e) => {
+                        ToolError::execution_failed(format!("{:#}", e)).into_call_tool_result()
+                    }
+                });
+            }
             // Handle unified conversion tool
             "cast_convert" => {
-                let cast_path = self
-                    .foundry_bin_path()
-                    .as_ref()
-                    .map(|p| format!("{}/cast", p))
-                    .unwrap_or_else(|| "cast".to_string());
-                return conversion::handle_cast_convert(&request.arguments, &cast_path).await;
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        let cast_path = cast::cast_path(foundry.foundry_bin_path());
+                        conversion::handle_cast_convert(&args, &cast_path).await
+                    })
+                    .await;
+            }
+            "addresses_checksum" => {
+                return conversion::handle_addresses_checksum(&request.arguments);
+            }
+            "conversion_capabilities" => {
+                let cast_path = cast::cast_path(self.foundry_bin_path());
+                return conversion::handle_conversion_capabilities(&request.arguments, cast_path)
+                    .await;
+            }
+            "estimate_gas" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        gas::handle_estimate_gas(&args, &foundry).await
+                    })
+                    .await;
+            }
+            "decode_calldata" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        calldata::handle_decode_calldata(&args, &foundry).await
+                    })
+                    .await;
+            }
+            "abi_encode" | "encode_calldata" => {
+                let tool = tool_name.to_string();
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        abi::handle_abi_encode(&tool, &args, &foundry).await
+                    })
+                    .await;
+            }
+            "cast_storage" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        let cast_path = cast::cast_path(foundry.foundry_bin_path());
+                        storage::handle_cast_storage(&args, &foundry, &cast_path).await
+                    })
+                    .await;
+            }
+            "cast_wallet_address" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        let cast_path = cast::cast_path(foundry.foundry_bin_path());
+                        wallet::handle_cast_wallet_address(&args, &foundry, &cast_path)
+                    })
+                    .await;
+            }
+            "cast_block" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        blocks::handle_cast_block(&args, &foundry).await
+                    })
+                    .await;
+            }
+            "cast_tx" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        blocks::handle_cast_tx(&args, &foundry).await
+                    })
+                    .await;
+            }
+            "cast_logs" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        let cast_path = cast::cast_path(foundry.foundry_bin_path());
+                        logs::handle_cast_logs(&args, &foundry, &cast_path).await
+                    })
+                    .await;
+            }
+            "cast_multicall" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        multicall::handle_cast_multicall(&args, &foundry).await
+                    })
+                    .await;
+            }
+            "cast_compute_address" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        deployment::handle_cast_compute_address(&args, &foundry).await
+                    })
+                    .await;
+            }
+            "cast_selectors" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        selectors::handle_cast_selectors(&args, &foundry).await
+                    })
+                    .await;
+            }
+            "cast_resolve_ens" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        ens::handle_cast_resolve_ens(&args, &foundry).await
+                    })
+                    .await;
+            }
+            "cast_nonce" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        account::handle_cast_nonce(&args, &foundry).await
+                    })
+                    .await;
+            }
+            "cast_balance" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        account::handle_cast_balance(&args, &foundry).await
+                    })
+                    .await;
+            }
+            "cast_disassemble" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        disassembly::handle_cast_disassemble(&args, &foundry).await
+                    })
+                    .await;
+            }
+            "cast_run" => {
+                return trace::handle_cast_run(&request.arguments, self.foundry.clone()).await;
+            }
+            "abi_decode" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        abi::handle_abi_decode(&args, &foundry).await
+                    })
+                    .await;
+            }
+            // Handle forge workflow tools
+            "forge_test_summary" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        forge::handle_forge_test_summary(&args, &foundry)
+                    })
+                    .await;
+            }
+            "forge_test_focused" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        forge::handle_forge_test_focused(&args, &foundry)
+                    })
+                    .await;
+            }
+            "forge_coverage_summary" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        forge::handle_forge_coverage_summary(&args, &foundry)
+                    })
+                    .await;
+            }
+            "forge_fmt_check" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        forge::handle_forge_fmt_check(&args, &foundry)
+                    })
+                    .await;
+            }
+            "forge_project_info" => {
+                return project::handle_forge_project_info(&request.arguments);
+            }
+            "forge_remappings" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        forge::handle_forge_remappings(&args, &foundry)
+                    })
+                    .await;
+            }
+            "forge_inspect" => {
+                return self
+                    .run_blocking(request.arguments, |args, foundry| async move {
+                        forge::handle_forge_inspect(&args, &foundry)
+                    })
+                    .await;
             }
             _ => {}
         }
 
-        // Handle Foundry tools (sync)
-        match self.foundry.execute_tool(&request.name, &request.arguments) {
-            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
-            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        // Fill in the configured default rpc-url (or else default chain), then resolve an
+        // rpc-url given as a chain name. A default is always resolved; an argument only
+        // when enabled.
+        let mut arguments = request.arguments;
+        let default_rpc_url = self
+            .foundry
+            .apply_default_rpc_url(&request.name, &mut arguments);
+        let default_chain = if default_rpc_url {
+            None
+        } else {
+            self.foundry
+                .apply_default_chain(&request.name, &mut arguments)
+        };
+        if default_rpc_url
+            || default_chain.is_some()
+            || self.foundry.config().resolve_rpc_chain_names
+        {
+            match chainlist::resolve_rpc_url_argument(&mut arguments).await {
+                Ok(()) => {}
+                Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+                Err(e) => return Err(e.into()),
+            }
         }
+
+        // A forbidden send is previewed with `cast call` when simulation is enabled
+        if request.name == "cast_send" && self.foundry.simulates_send() {
+            let result = match self.execute_blocking("cast_call", arguments).await {
+                Ok(output) => {
+                    let text = format!(
+                        "SIMULATED with `cast call`, not broadcast: cast_send is forbidden by the server configuration\n\n{}",
+                        output
+                    );
+                    let mut result = CallToolResult::success(vec![Content::text(text)]);
+                    result.structured_content = Some(serde_json::json!({
+                        "simulated": true,
+                        "broadcast": false,
+                        "output": output,
+                    }));
+                    Ok(result)
+                }
+                Err(e) if e.is_execution_error() => Ok(diagnostics::error_result(e)),
+                Err(e) => Err(e.into()),
+            };
+            return chainlist::default_chain_note(result, default_chain);
+        }
+
+        // Handle Foundry tools
+        let result = match self.execute_blocking(&request.name, arguments).await {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
+            Err(e) if e.is_execution_error() => Ok(diagnostics::error_result(e)),
+            Err(e) => Err(e.into()),
+        };
+        chainlist::default_chain_note(result, default_chain)
     }
 }
 
@@ -275,7 +1250,7 @@ mod tests {
     use super::*;
     use crate::config::Config;
     use crate::foundry::FoundryExecutor;
-    use crate::schema::SchemaFile;
+    use crate::schema::{SchemaFile, ToolSchema};
 
     fn create_test_handler() -> FoundryMcpHandler {
         let schema = SchemaFile { tools: vec![] };
@@ -306,7 +1281,7 @@ mod tests {
         assert!(info.instructions.is_some());
     }
 
-    /// Test that server advertises correct MCP capabilities (resources, tools, but not prompts)
+    /// Test that server advertises correct MCP capabilities (resources, tools, completions, but not prompts)
     #[test]
     fn test_get_info_capabilities() {
         let handler = create_test_handler();
@@ -318,6 +1293,9 @@ mod tests {
         // Should support tools
         assert!(info.capabilities.tools.is_some());
 
+        // Should support argument completion
+        assert!(info.capabilities.completions.is_some());
+
         // Should not support prompts by default
         assert!(info.capabilities.prompts.is_none());
     }
@@ -351,6 +1329,7 @@ mod tests {
             forbidden_commands: vec!["anvil".to_string()],
             forbidden_flags: vec!["broadcast".to_string()],
             allow_dangerous: false,
+            ..Default::default()
         };
         let executor = FoundryExecutor::with_config(schema, config);
         let _handler = FoundryMcpHandler::new(executor);
@@ -401,6 +1380,179 @@ mod tests {
         assert_eq!(handler.foundry_bin_path(), &bin_path);
     }
 
+    /// Test that the combined tool list includes the non-Foundry tool groups
+    #[test]
+    fn test_all_tools_includes_builtin_groups() {
+        let handler = create_test_handler();
+        let names: Vec<String> = handler
+            .all_tools()
+            .iter()
+            .map(|t| t.name.to_string())
+            .collect();
+
+        assert!(names.contains(&"search_rpc_url".to_string()));
+        assert!(names.contains(&"search_tokens".to_string()));
+        assert!(names.contains(&"anvil_session_start".to_string()));
+        assert!(names.contains(&"cast_convert".to_string()));
+        assert!(names.contains(&"list_tools_by_category".to_string()));
+    }
+
+    /// Test that a fresh handler starts with no recorded metrics
+    #[test]
+    fn test_handler_metrics_start_empty() {
+        let handler = create_test_handler();
+        assert!(handler.metrics().snapshot().is_empty());
+    }
+
+    /// Test that the audit log is only enabled when configured
+    #[test]
+    fn test_handler_audit_log_from_config() {
+        assert!(create_test_handler().policy().audit_log.is_none());
+
+        let config = Config {
+            audit_log_path: Some("/tmp/foundry-mcp-audit.jsonl".to_string()),
+            ..Default::default()
+        };
+        let executor = FoundryExecutor::with_config(SchemaFile { tools: vec![] }, config);
+        let handler = FoundryMcpHandler::new(executor);
+        assert!(handler.policy().audit_log.is_some());
+    }
+
+    /// Test that the rate limiter picks up the configured limits
+    #[test]
+    fn test_handler_rate_limits_from_config() {
+        assert!(create_test_handler()
+            .policy()
+            .rate_limiter
+            .limit_for("forge_build")
+            .is_none());
+
+        let config = Config {
+            max_calls_per_minute: Some(30),
+            tool_max_calls_per_minute: [("forge_build".to_string(), 5)].into_iter().collect(),
+            ..Default::default()
+        };
+        let executor = FoundryExecutor::with_config(SchemaFile { tools: vec![] }, config);
+        let handler = FoundryMcpHandler::new(executor);
+        let policy = handler.policy();
+        assert_eq!(policy.rate_limiter.limit_for("forge_build"), Some(5));
+        assert_eq!(policy.rate_limiter.limit_for("cast_call"), Some(30));
+    }
+
+    /// Test that reloading re-filters the tool list and swaps the call policy
+    #[test]
+    fn test_reload_config_refilters_tools() {
+        let schema = SchemaFile {
+            tools: vec![
+                ToolSchema {
+                    name: "forge_build".to_string(),
+                    description: "Build".to_string(),
+                    ..Default::default()
+                },
+                ToolSchema {
+                    name: "cast_call".to_string(),
+                    description: "Call".to_string(),
+                    ..Default::default()
+                },
+            ],
+        };
+        let executor = FoundryExecutor::with_config(schema, Config::default());
+        let handler = FoundryMcpHandler::new(executor).with_config_loader(|| {
+            Ok(Config {
+                forbidden_commands: vec!["forge_build".to_string()],
+                max_calls_per_minute: Some(10),
+                ..Default::default()
+            })
+        });
+
+        let summary = handler.reload_config().unwrap();
+        assert!(summary.contains("No longer available (1)"));
+        assert!(summary.contains("forge_build"));
+
+        let names: Vec<String> = handler
+            .all_tools()
+            .iter()
+            .map(|t| t.name.to_string())
+            .collect();
+        assert!(!names.contains(&"forge_build".to_string()));
+        assert!(names.contains(&"cast_call".to_string()));
+        assert_eq!(
+            handler.policy().rate_limiter.limit_for("cast_call"),
+            Some(10)
+        );
+    }
+
+    /// Test that a reload with configuration errors keeps the active configuration
+    #[test]
+    fn test_reload_config_rejects_invalid_config() {
+        let handler = create_test_handler().with_config_loader(|| {
+            Ok(Config {
+                max_calls_per_minute: Some(0),
+                ..Default::default()
+            })
+        });
+
+        let err = handler.reload_config().unwrap_err();
+        assert!(err.to_string().contains("max_calls_per_minute"));
+        assert!(handler
+            .policy()
+            .rate_limiter
+            .limit_for("cast_call")
+            .is_none());
+    }
+
+    /// Test that unlock requires the configured passphrase and lock restores restrictions
+    #[test]
+    fn test_unlock_and_lock() {
+        let schema = SchemaFile {
+            tools: vec![ToolSchema {
+                name: "cast_send".to_string(),
+                description: "Send".to_string(),
+                ..Default::default()
+            }],
+        };
+        let config = Config {
+            unlock_passphrase: Some("correct horse battery staple".to_string()),
+            unlock_duration_secs: 60,
+            ..Config::read_only()
+        };
+        let handler = FoundryMcpHandler::new(FoundryExecutor::with_config(schema, config));
+        let has_send = |h: &FoundryMcpHandler| h.all_tools().iter().any(|t| t.name == "cast_send");
+        assert!(!has_send(&handler));
+
+        let args = |passphrase: &str| {
+            let mut args = serde_json::Map::new();
+            args.insert("passphrase".to_string(), serde_json::json!(passphrase));
+            args.insert("duration_secs".to_string(), serde_json::json!(3600));
+            Some(args)
+        };
+
+        let err = handler.handle_unlock(&args("wrong")).unwrap_err();
+        assert_eq!(err.code, ErrorCode(-32011));
+        assert!(!has_send(&handler));
+
+        handler
+            .handle_unlock(&args("correct horse battery staple"))
+            .unwrap();
+        assert!(has_send(&handler));
+        // Requested durations are capped by the configuration
+        assert!(handler.elevation.remaining().unwrap() <= Duration::from_secs(60));
+
+        handler.handle_lock().unwrap();
+        assert!(!has_send(&handler));
+    }
+
+    /// Test that elevation tools are hidden and refused when no passphrase is configured
+    #[test]
+    fn test_unlock_disabled_without_passphrase() {
+        let handler = create_test_handler();
+        assert!(!handler.all_tools().iter().any(|t| t.name == "unlock"));
+
+        let mut args = serde_json::Map::new();
+        args.insert("passphrase".to_string(), serde_json::json!("anything"));
+        assert!(handler.handle_unlock(&Some(args)).is_err());
+    }
+
     /// Test that multiple handlers can be created and used independently
     #[test]
     fn test_multiple_handlers_can_coexist() {
@@ -413,4 +1565,179 @@ mod tests {
 
         assert_eq!(info1.server_info.name, info2.server_info.name);
     }
+
+    /// Test that the combined tool list is sorted by name
+    #[test]
+    fn test_all_tools_sorted() {
+        let schema = SchemaFile {
+            tools: ["forge_build", "cast_call", "anvil"]
+                .into_iter()
+                .map(|name| ToolSchema {
+                    name: name.to_string(),
+                    description: name.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        };
+        let handler =
+            FoundryMcpHandler::new(FoundryExecutor::with_config(schema, Config::default()));
+
+        let names: Vec<String> = handler
+            .all_tools()
+            .iter()
+            .map(|t| t.name.to_string())
+            .collect();
+        assert!(names.contains(&"forge_build".to_string()));
+        assert!(names.contains(&"search_rpc_url".to_string()));
+        assert!(names.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    /// Test that disabling network tools hides and refuses the chainlist and tokenlist tools
+    #[tokio::test]
+    async fn test_network_tools_disabled() {
+        let config = Config {
+            enable_network_tools: false,
+            ..Config::default()
+        };
+        let handler = FoundryMcpHandler::new(FoundryExecutor::with_config(
+            SchemaFile { tools: vec![] },
+            config,
+        ));
+        let tools = handler.all_tools();
+        assert!(!tools.iter().any(|t| meta::is_network_tool(&t.name)));
+        assert!(tools.iter().any(|t| t.name == "anvil_session_start"));
+
+        let request = CallToolRequestParam {
+            name: "search_rpc_url".into(),
+            arguments: Some(serde_json::Map::new()),
+        };
+        let err = handler.dispatch_tool(request).await.unwrap_err();
+        assert!(err.message.contains("enable_network_tools"));
+    }
+
+    /// Test that a read-only server hides and refuses session tools and signer flags
+    #[tokio::test]
+    async fn test_read_only_enforced() {
+        let schema = SchemaFile {
+            tools: vec![ToolSchema {
+                name: "cast_call".to_string(),
+                description: "Call a contract".to_string(),
+                options: vec![crate::schema::OptionSchema {
+                    name: "private-key".to_string(),
+                    param_type: "string".to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+        let handler =
+            FoundryMcpHandler::new(FoundryExecutor::with_config(schema, Config::read_only()));
+        let tools = handler.all_tools();
+        assert!(!tools.iter().any(|t| t.name == "chisel_session_eval"));
+        assert!(!tools.iter().any(|t| t.name == "anvil_session_start"));
+        assert!(tools.iter().any(|t| t.name == "cast_call"));
+
+        let call = |name: &str, args: serde_json::Value| CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: args.as_object().cloned(),
+        };
+        let err = handler
+            .dispatch_tool(call(
+                "chisel_session_eval",
+                serde_json::json!({"code": "1"}),
+            ))
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("forbidden"));
+
+        let err = handler
+            .dispatch_tool(call(
+                "cast_call",
+                serde_json::json!({"private-key": "0xabc"}),
+            ))
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("private-key"));
+    }
+
+    /// Test that an allowlist hides and refuses built-in tools it does not list
+    #[tokio::test]
+    async fn test_allowlist_covers_builtin_tools() {
+        let schema = SchemaFile {
+            tools: vec![ToolSchema {
+                name: "cast_call".to_string(),
+                description: "Call a contract".to_string(),
+                ..Default::default()
+            }],
+        };
+        let config = Config {
+            allowed_commands: Some(vec!["cast_call".to_string()]),
+            ..Config::default()
+        };
+        let handler = FoundryMcpHandler::new(FoundryExecutor::with_config(schema, config));
+        let tools = handler.all_tools();
+        assert!(tools.iter().any(|t| t.name == "cast_call"));
+        assert!(!tools
+            .iter()
+            .any(|t| BUILTIN_TOOL_NAMES.contains(t.name.as_ref())));
+
+        for name in BUILTIN_TOOL_NAMES.iter() {
+            let err = handler
+                .dispatch_tool(CallToolRequestParam {
+                    name: name.clone().into(),
+                    arguments: Some(JsonObject::new()),
+                })
+                .await
+                .unwrap_err();
+            assert!(err.message.contains("forbidden"), "{name}: {}", err.message);
+        }
+    }
+
+    /// Test that the sessions resource is readable as JSON without network access
+    #[tokio::test]
+    async fn test_read_sessions_resource() {
+        let result = read_sessions_resource(SESSIONS_URI.to_string())
+            .await
+            .unwrap();
+        let ResourceContents::TextResourceContents { uri, text, .. } = &result.contents[0] else {
+            panic!("expected text contents");
+        };
+        assert_eq!(uri, SESSIONS_URI);
+        let json: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert!(json["sessions"].is_array());
+    }
+
+    /// Test that the command limit and coalescing follow the configuration across a reload
+    #[test]
+    fn test_command_limit_reloads() {
+        let config = Config {
+            max_concurrent_commands: 2,
+            ..Default::default()
+        };
+        let executor = FoundryExecutor::with_config(SchemaFile { tools: vec![] }, config);
+        let handler = FoundryMcpHandler::new(executor).with_config_loader(|| {
+            Ok(Config {
+                max_concurrent_commands: 5,
+                coalesce_read_calls: true,
+                ..Default::default()
+            })
+        });
+        assert_eq!(handler.policy().commands.available_permits(), 2);
+        assert!(handler.policy().coalescer.is_none());
+
+        handler.reload_config().unwrap();
+        assert_eq!(handler.policy().commands.available_permits(), 5);
+        assert!(handler.policy().coalescer.is_some());
+    }
+
+    /// Test that a cancelled request stops waiting on its work and reports the cancellation
+    #[tokio::test]
+    async fn test_until_cancelled() {
+        let result: Result<(), McpError> =
+            until_cancelled(std::future::ready(()), std::future::pending()).await;
+        assert_eq!(result.unwrap_err().code, REQUEST_CANCELLED);
+
+        let result = until_cancelled(std::future::pending(), async { Ok(7) }).await;
+        assert_eq!(result.unwrap(), 7);
+    }
 }
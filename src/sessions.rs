@@ -2,14 +2,19 @@
 
 use anyhow::{Context, Result};
 use once_cell::sync::Lazy;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::io::Write;
 use std::process::{Child, Command, Stdio};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::config::redact_url_path;
+use crate::error::ToolError;
+use crate::foundry::binary_path;
 
 /// Global session manager instance
-static SESSION_MANAGER: Lazy<Arc<Mutex<SessionManager>>> =
-    Lazy::new(|| Arc::new(Mutex::new(SessionManager::new())));
+static SESSION_MANAGER: Lazy<Arc<SessionManager>> = Lazy::new(|| Arc::new(SessionManager::new()));
 
 /// Type of background session
 #[derive(Debug, Clone, PartialEq)]
@@ -18,17 +23,124 @@ pub enum SessionType {
     Chisel,
 }
 
+impl SessionType {
+    /// Lowercase name of the session type, e.g. "anvil"
+    pub fn name(&self) -> &'static str {
+        match self {
+            SessionType::Anvil => "anvil",
+            SessionType::Chisel => "chisel",
+        }
+    }
+}
+
+/// Arguments an Anvil session was started with, kept so it can be restarted and reported
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnvilParams {
+    pub foundry_bin_path: Option<String>,
+    pub port: u16,
+    /// Reported with its path and query hidden, since providers put API keys there
+    #[serde(serialize_with = "serialize_redacted_url")]
+    pub fork_url: Option<String>,
+    pub fork_block_number: Option<u64>,
+    pub accounts: Option<u32>,
+    pub block_time: Option<u64>,
+}
+
+impl AnvilParams {
+    /// Human-readable lines describing how the node was configured
+    pub fn describe(&self) -> String {
+        let mut lines = vec![format!("Port: {}", self.port)];
+        match &self.fork_url {
+            Some(url) => {
+                let block = self
+                    .fork_block_number
+                    .map(|b| b.to_string())
+                    .unwrap_or_else(|| "latest".to_string());
+                lines.push(format!("Fork: {} at block {}", redact_url_path(url), block));
+            }
+            None => lines.push("Fork: none".to_string()),
+        }
+        lines.push(format!(
+            "Accounts: {}",
+            self.accounts
+                .map(|a| a.to_string())
+                .unwrap_or_else(|| "default (10)".to_string())
+        ));
+        lines.push(format!(
+            "Block time: {}",
+            self.block_time
+                .map(|t| format!("{}s", t))
+                .unwrap_or_else(|| "mine on demand".to_string())
+        ));
+        lines.join("\n")
+    }
+}
+
+fn serialize_redacted_url<S: serde::Serializer>(
+    url: &Option<String>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    url.as_deref().map(redact_url_path).serialize(serializer)
+}
+
+/// Chain state read from a running Anvil node
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeState {
+    pub chain_id: u64,
+    pub block_number: u64,
+    /// Unlocked dev accounts (`eth_accounts`)
+    pub accounts: Vec<String>,
+}
+
 /// Information about a running session
 pub struct SessionInfo {
     pub session_type: SessionType,
-    pub process: Child,
+    /// The long-running process; `None` for Chisel, which spawns a process per eval
+    pub process: Option<Child>,
     pub port: Option<u16>,
     pub created_at: std::time::SystemTime,
+    /// How the session was started (Anvil only)
+    pub start_params: Option<AnvilParams>,
+    /// Restart the process with `start_params` if it exits unexpectedly
+    pub auto_restart: bool,
+    /// How many times the process has been restarted
+    pub restarts: u32,
+    /// Chain state last read from the node, and when (Anvil only)
+    pub node_state: Option<(Instant, NodeState)>,
+    /// Named `evm_snapshot` IDs as `(name, id)`, oldest first (Anvil only)
+    pub snapshots: Vec<(String, String)>,
+}
+
+/// A session's live state, as published by the `sessions://active` resource
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionSnapshot {
+    pub key: String,
+    #[serde(rename = "type")]
+    pub session_type: &'static str,
+    /// Process ID of the long-running process; Chisel has none between evals
+    pub pid: Option<u32>,
+    pub port: Option<u16>,
+    pub uptime_secs: u64,
+    pub auto_restart: bool,
+    pub restarts: u32,
 }
 
-/// Manages long-running background processes
+/// A session's own lock, holding the session while one is running
+type SessionSlot = Arc<Mutex<Option<SessionInfo>>>;
+
+/// Lock a session slot, recovering it if a previous holder panicked
+fn lock(slot: &SessionSlot) -> MutexGuard<'_, Option<SessionInfo>> {
+    slot.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Manages long-running background processes.
+///
+/// Each session has its own lock, so a slow Chisel eval doesn't hold up an Anvil status
+/// check. The map lock is only held long enough to look up a session's slot.
 pub struct SessionManager {
-    sessions: HashMap<String, SessionInfo>,
+    sessions: Mutex<HashMap<String, SessionSlot>>,
+    /// The chisel binary that last passed the `--help` probe, and when
+    chisel_probe: Mutex<Option<(String, Instant)>>,
 }
 
 impl Default for SessionManager {
@@ -41,18 +153,60 @@ impl SessionManager {
     /// Create a new session manager
     pub fn new() -> Self {
         Self {
-            sessions: HashMap::new(),
+            sessions: Mutex::new(HashMap::new()),
+            chisel_probe: Mutex::new(None),
         }
     }
 
     /// Get the global session manager instance
-    pub fn global() -> Arc<Mutex<SessionManager>> {
+    pub fn global() -> Arc<SessionManager> {
         SESSION_MANAGER.clone()
     }
 
+    /// The lock for the session under `key`, created on first use
+    fn slot(&self, key: &str) -> SessionSlot {
+        let mut sessions = self.sessions.lock().unwrap_or_else(|p| p.into_inner());
+        sessions.entry(key.to_string()).or_default().clone()
+    }
+
+    /// The state of every session, sorted by key
+    pub fn snapshot(&self) -> Vec<SessionSnapshot> {
+        // Copy the slots out first, so the map isn't locked while waiting on a session
+        let slots: Vec<(String, SessionSlot)> = self
+            .sessions
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .iter()
+            .map(|(key, slot)| (key.clone(), slot.clone()))
+            .collect();
+
+        let mut sessions: Vec<SessionSnapshot> = slots
+            .iter()
+            .filter_map(|(key, slot)| {
+                let session = lock(slot);
+                let info = session.as_ref()?;
+                Some(SessionSnapshot {
+                    key: key.clone(),
+                    session_type: info.session_type.name(),
+                    pid: info.process.as_ref().map(Child::id),
+                    port: info.port,
+                    uptime_secs: info
+                        .created_at
+                        .elapsed()
+                        .map(|d| d.as_secs())
+                        .unwrap_or_default(),
+                    auto_restart: info.auto_restart,
+                    restarts: info.restarts,
+                })
+            })
+            .collect();
+        sessions.sort_by(|a, b| a.key.cmp(&b.key));
+        sessions
+    }
+
     /// Start an Anvil session
     pub fn start_anvil(
-        &mut self,
+        &self,
         foundry_bin_path: &Option<String>,
         port: u16,
         fork_url: Option<String>,
@@ -60,33 +214,66 @@ impl SessionManager {
         accounts: Option<u32>,
         block_time: Option<u64>,
     ) -> Result<String> {
+        let slot = self.slot("anvil");
+        let mut anvil = lock(&slot);
+
+        // A session whose process died doesn't count as running
+        Self::reap(&mut anvil);
+
         // Check if anvil is already running
-        if self.is_anvil_running() {
+        if anvil.is_some() {
             anyhow::bail!("Anvil is already running. Stop it first with anvil_session_stop.");
         }
 
-        let anvil_cmd = if let Some(bin_path) = foundry_bin_path {
-            format!("{}/anvil", bin_path)
-        } else {
-            "anvil".to_string()
+        let params = AnvilParams {
+            foundry_bin_path: foundry_bin_path.clone(),
+            port,
+            fork_url,
+            fork_block_number,
+            accounts,
+            block_time,
         };
+        let child = Self::spawn_anvil(&params)?;
+        let pid = child.id();
+
+        *anvil = Some(SessionInfo {
+            session_type: SessionType::Anvil,
+            process: Some(child),
+            port: Some(port),
+            created_at: std::time::SystemTime::now(),
+            start_params: Some(params),
+            auto_restart: false,
+            restarts: 0,
+            node_state: None,
+            snapshots: Vec::new(),
+        });
+
+        Ok(format!(
+            "Anvil started successfully on port {}. RPC URL: http://localhost:{}\nProcess ID: {}",
+            port, port, pid
+        ))
+    }
+
+    /// Spawn an Anvil process and give it a moment to start listening
+    fn spawn_anvil(params: &AnvilParams) -> Result<Child> {
+        let anvil_cmd = binary_path(params.foundry_bin_path.as_deref(), "anvil");
 
         let mut cmd = Command::new(&anvil_cmd);
-        cmd.arg("--port").arg(port.to_string());
+        cmd.arg("--port").arg(params.port.to_string());
 
-        if let Some(url) = fork_url {
+        if let Some(url) = &params.fork_url {
             cmd.arg("--fork-url").arg(url);
         }
 
-        if let Some(block_num) = fork_block_number {
+        if let Some(block_num) = params.fork_block_number {
             cmd.arg("--fork-block-number").arg(block_num.to_string());
         }
 
-        if let Some(acc) = accounts {
+        if let Some(acc) = params.accounts {
             cmd.arg("--accounts").arg(acc.to_string());
         }
 
-        if let Some(time) = block_time {
+        if let Some(time) = params.block_time {
             cmd.arg("--block-time").arg(time.to_string());
         }
 
@@ -96,47 +283,108 @@ impl SessionManager {
             .spawn()
             .context("Failed to start Anvil. Is Foundry installed?")?;
 
-        let pid = child.id();
-
         // Wait a moment for anvil to start
         std::thread::sleep(std::time::Duration::from_millis(1000));
 
-        self.sessions.insert(
-            "anvil".to_string(),
-            SessionInfo {
-                session_type: SessionType::Anvil,
-                process: child,
-                port: Some(port),
-                created_at: std::time::SystemTime::now(),
-            },
-        );
+        Ok(child)
+    }
 
-        Ok(format!(
-            "Anvil started successfully on port {}. RPC URL: http://localhost:{}\nProcess ID: {}",
-            port, port, pid
-        ))
+    /// Enable or disable restarting Anvil when it exits unexpectedly.
+    ///
+    /// Returns `false` if no Anvil session is running.
+    pub fn set_anvil_auto_restart(&self, enabled: bool) -> bool {
+        match lock(&self.slot("anvil")).as_mut() {
+            Some(session) => {
+                session.auto_restart = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove a session if its process has exited, returning the session and a
+    /// description of how it exited
+    fn reap(slot: &mut Option<SessionInfo>) -> Option<(SessionInfo, String)> {
+        let session = slot.as_mut()?;
+        // Sessions without a long-running process never exit on their own
+        let status = match session.process.as_mut()?.try_wait() {
+            Ok(Some(status)) => status.to_string(),
+            // Still running, or we can't tell; either way leave it alone
+            Ok(None) | Err(_) => return None,
+        };
+        let session = slot.take()?;
+        Some((session, status))
+    }
+
+    /// Check that the Anvil process is still alive.
+    ///
+    /// If it has exited, its session is removed, or, when auto-restart is enabled, it is
+    /// restarted with its original parameters. Returns a note describing what happened.
+    pub fn check_anvil(&self) -> Option<String> {
+        Self::check_anvil_slot(&mut lock(&self.slot("anvil")))
+    }
+
+    /// [`Self::check_anvil`] on an already locked Anvil slot
+    fn check_anvil_slot(anvil: &mut Option<SessionInfo>) -> Option<String> {
+        let (session, status) = Self::reap(anvil)?;
+
+        let params = match session.start_params {
+            Some(params) if session.auto_restart => params,
+            _ => {
+                return Some(format!(
+                    "Anvil exited unexpectedly ({}). Start it again with anvil_session_start.",
+                    status
+                ))
+            }
+        };
+
+        match Self::spawn_anvil(&params) {
+            Ok(child) => {
+                let restarts = session.restarts + 1;
+                *anvil = Some(SessionInfo {
+                    session_type: SessionType::Anvil,
+                    process: Some(child),
+                    port: Some(params.port),
+                    created_at: std::time::SystemTime::now(),
+                    start_params: Some(params),
+                    auto_restart: true,
+                    restarts,
+                    node_state: None,
+                    snapshots: Vec::new(),
+                });
+                Some(format!(
+                    "Anvil exited unexpectedly ({}) and was restarted with its original parameters (restart #{}). Chain state was reset.",
+                    status, restarts
+                ))
+            }
+            Err(e) => Some(format!(
+                "Anvil exited unexpectedly ({}) and could not be restarted: {:#}",
+                status, e
+            )),
+        }
     }
 
     /// Stop the Anvil session
-    pub fn stop_anvil(&mut self) -> Result<String> {
-        if let Some(mut session) = self.sessions.remove("anvil") {
-            session
-                .process
-                .kill()
-                .context("Failed to kill Anvil process")?;
-            session
-                .process
-                .wait()
-                .context("Failed to wait for Anvil process")?;
+    pub fn stop_anvil(&self) -> Result<String> {
+        let session = lock(&self.slot("anvil")).take();
+        if let Some(session) = session {
+            if let Some(mut process) = session.process {
+                process.kill().context("Failed to kill Anvil process")?;
+                process.wait().context("Failed to wait for Anvil process")?;
+            }
             Ok("Anvil has been stopped successfully.".to_string())
         } else {
             anyhow::bail!("No Anvil session is currently running.")
         }
     }
 
-    /// Get Anvil session status
+    /// Get Anvil session status, noticing if the process has died
     pub fn anvil_status(&self) -> Result<String> {
-        if let Some(session) = self.sessions.get("anvil") {
+        let slot = self.slot("anvil");
+        let mut anvil = lock(&slot);
+        let note = Self::check_anvil_slot(&mut anvil);
+
+        let mut status = if let Some(session) = anvil.as_ref() {
             let port = session.port.unwrap_or(8545);
             let uptime = session
                 .created_at
@@ -144,53 +392,185 @@ impl SessionManager {
                 .map(|d| format!("{}s", d.as_secs()))
                 .unwrap_or_else(|_| "unknown".to_string());
 
-            Ok(format!(
+            let mut status = format!(
                 "Anvil is running on port {}. RPC URL: http://localhost:{}\nUptime: {}",
                 port, port, uptime
-            ))
+            );
+            if let Some(params) = &session.start_params {
+                status.push_str(&format!("\n\nStarted with:\n{}", params.describe()));
+            }
+            if session.auto_restart {
+                status.push_str(&format!(
+                    "\nAuto-restart: on ({} restart(s) so far)",
+                    session.restarts
+                ));
+            }
+            status
         } else {
-            Ok("Anvil is not currently running.".to_string())
+            "Anvil is not currently running.".to_string()
+        };
+
+        if let Some(note) = note {
+            status = format!("{}\n\n{}", note, status);
         }
+        Ok(status)
+    }
+
+    /// Chain state of the running Anvil node, or `None` when Anvil isn't running.
+    ///
+    /// A reading younger than `max_age` is reused; otherwise `query` is called with the
+    /// node's RPC URL and a successful answer is kept.
+    pub fn anvil_node_state(
+        &self,
+        max_age: Duration,
+        query: impl FnOnce(&str) -> Result<NodeState, ToolError>,
+    ) -> Option<Result<NodeState, ToolError>> {
+        let slot = self.slot("anvil");
+        let mut anvil = lock(&slot);
+        let session = anvil.as_mut()?;
+        if let Some((read_at, state)) = &session.node_state {
+            if read_at.elapsed() < max_age {
+                return Some(Ok(state.clone()));
+            }
+        }
+
+        let url = format!("http://localhost:{}", session.port.unwrap_or(8545));
+        let result = query(&url);
+        if let Ok(state) = &result {
+            session.node_state = Some((Instant::now(), state.clone()));
+        }
+        Some(result)
+    }
+
+    /// Store a snapshot ID under a name, replacing an older snapshot of the same name.
+    /// Fails when Anvil isn't running.
+    pub fn save_anvil_snapshot(&self, name: &str, id: &str) -> Result<(), ToolError> {
+        let slot = self.slot("anvil");
+        let mut anvil = lock(&slot);
+        let session = anvil.as_mut().ok_or_else(Self::anvil_not_running)?;
+        session.snapshots.retain(|(saved, _)| saved != name);
+        session.snapshots.push((name.to_string(), id.to_string()));
+        Ok(())
+    }
+
+    /// The snapshot ID saved under a name
+    pub fn anvil_snapshot_id(&self, name: &str) -> Result<Option<String>, ToolError> {
+        let slot = self.slot("anvil");
+        let anvil = lock(&slot);
+        let session = anvil.as_ref().ok_or_else(Self::anvil_not_running)?;
+        Ok(session
+            .snapshots
+            .iter()
+            .find(|(saved, _)| saved == name)
+            .map(|(_, id)| id.clone()))
+    }
+
+    /// Forget a snapshot that was reverted to, along with every snapshot taken after it,
+    /// which a revert invalidates. Returns the names of those later snapshots.
+    pub fn prune_anvil_snapshots(&self, name: &str) -> Vec<String> {
+        let slot = self.slot("anvil");
+        let mut anvil = lock(&slot);
+        let Some(session) = anvil.as_mut() else {
+            return Vec::new();
+        };
+        let Some(position) = session
+            .snapshots
+            .iter()
+            .position(|(saved, _)| saved == name)
+        else {
+            return Vec::new();
+        };
+        session
+            .snapshots
+            .split_off(position)
+            .into_iter()
+            .skip(1)
+            .map(|(saved, _)| saved)
+            .collect()
+    }
+
+    /// Names of the saved snapshots, oldest first
+    pub fn anvil_snapshot_names(&self) -> Vec<String> {
+        lock(&self.slot("anvil"))
+            .as_ref()
+            .map(|session| {
+                session
+                    .snapshots
+                    .iter()
+                    .map(|(name, _)| name.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The error for an Anvil tool called with no session running
+    pub fn anvil_not_running() -> ToolError {
+        ToolError::InvalidArguments(
+            "No Anvil session is running. Start one with anvil_session_start.".to_string(),
+        )
+    }
+
+    /// Arguments the running Anvil instance was started with, if any
+    pub fn anvil_start_params(&self) -> Option<AnvilParams> {
+        lock(&self.slot("anvil")).as_ref()?.start_params.clone()
+    }
+
+    /// RPC URL of the running Anvil instance, if any
+    pub fn anvil_rpc_url(&self) -> Option<String> {
+        lock(&self.slot("anvil"))
+            .as_ref()
+            .map(|session| format!("http://localhost:{}", session.port.unwrap_or(8545)))
     }
 
     /// Check if Anvil is running
     pub fn is_anvil_running(&self) -> bool {
-        self.sessions.contains_key("anvil")
+        lock(&self.slot("anvil")).is_some()
     }
 
     /// Start a Chisel session (validates chisel is available)
-    pub fn start_chisel(&mut self, foundry_bin_path: &Option<String>) -> Result<String> {
+    ///
+    /// The check runs `chisel --help`, and is skipped when the same binary passed it less
+    /// than `probe_ttl` ago.
+    pub fn start_chisel(
+        &self,
+        foundry_bin_path: &Option<String>,
+        probe_ttl: Duration,
+    ) -> Result<String> {
+        let slot = self.slot("chisel");
+        let mut chisel = lock(&slot);
+
         // Check if chisel is already running
-        if self.is_chisel_running() {
+        if chisel.is_some() {
             anyhow::bail!("Chisel is already running. Stop it first with chisel_session_stop.");
         }
 
-        let chisel_cmd = if let Some(bin_path) = foundry_bin_path {
-            format!("{}/chisel", bin_path)
-        } else {
-            "chisel".to_string()
-        };
+        let chisel_cmd = binary_path(foundry_bin_path.as_deref(), "chisel");
 
         // Validate chisel is available by trying to run --help
-        let test_result = Command::new(&chisel_cmd)
-            .arg("--help")
-            .output()
-            .context("Failed to start Chisel. Is Foundry installed?")?;
-
-        if !test_result.status.success() {
-            anyhow::bail!("Chisel command failed. Is Foundry installed?");
+        if !self.chisel_probe_fresh(&chisel_cmd, probe_ttl) {
+            let test_result = Command::new(&chisel_cmd)
+                .arg("--help")
+                .output()
+                .context("Failed to start Chisel. Is Foundry installed?")?;
+
+            if !test_result.status.success() {
+                anyhow::bail!("Chisel command failed. Is Foundry installed?");
+            }
+            *self.chisel_probe.lock().unwrap() = Some((chisel_cmd, Instant::now()));
         }
 
         // Mark chisel session as active (we spawn fresh processes per eval)
-        self.sessions.insert(
-            "chisel".to_string(),
-            SessionInfo {
-                session_type: SessionType::Chisel,
-                process: Command::new("true").spawn()?, // Dummy process for tracking
-                port: None,
-                created_at: std::time::SystemTime::now(),
-            },
-        );
+        *chisel = Some(SessionInfo {
+            session_type: SessionType::Chisel,
+            process: None,
+            port: None,
+            created_at: std::time::SystemTime::now(),
+            start_params: None,
+            auto_restart: false,
+            restarts: 0,
+            node_state: None,
+            snapshots: Vec::new(),
+        });
 
         Ok(
             "Chisel REPL session started successfully.\n\nSession is ready for code execution. Use chisel_session_eval to execute Solidity code.\n\nNote: Each eval spawns a fresh chisel process. State persists via Chisel's cache system.\n\nTips:\n- Variables and functions are cached between eval calls\n- Use semicolons to suppress output\n- Use !help for chisel commands"
@@ -198,25 +578,40 @@ impl SessionManager {
         )
     }
 
+    /// Whether `chisel_cmd` passed the availability probe less than `ttl` ago
+    fn chisel_probe_fresh(&self, chisel_cmd: &str, ttl: Duration) -> bool {
+        matches!(
+            &*self.chisel_probe.lock().unwrap(),
+            Some((probed, at)) if probed == chisel_cmd && at.elapsed() < ttl
+        )
+    }
+
+    /// Forget the cached chisel probe, so the next start checks the binary again
+    pub fn forget_chisel_probe(&self) {
+        *self.chisel_probe.lock().unwrap() = None;
+    }
+
     /// Evaluate Solidity code in the running Chisel session
     ///
     /// Note: This spawns a fresh chisel process for each eval to avoid blocking I/O issues.
-    /// Chisel's cache system preserves state across invocations.
+    /// Chisel's cache system preserves state across invocations. Unless `raw` is set, the
+    /// welcome banner and prompts are stripped from the output.
     pub fn chisel_eval(
-        &mut self,
+        &self,
         code: String,
+        raw: bool,
         foundry_bin_path: &Option<String>,
     ) -> Result<String> {
+        // Held for the whole eval, so evals in the session run one at a time
+        let slot = self.slot("chisel");
+        let chisel = lock(&slot);
+
         // Verify session is active
-        if !self.is_chisel_running() {
+        if chisel.is_none() {
             anyhow::bail!("No Chisel session is running. Start one with chisel_session_start.");
         }
 
-        let chisel_cmd = if let Some(bin_path) = foundry_bin_path {
-            format!("{}/chisel", bin_path)
-        } else {
-            "chisel".to_string()
-        };
+        let chisel_cmd = binary_path(foundry_bin_path.as_deref(), "chisel");
 
         // Use chisel with piped input - it processes line by line and exits on EOF
         let mut cmd = Command::new(&chisel_cmd);
@@ -224,7 +619,14 @@ impl SessionManager {
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        let mut child = cmd.spawn().context("Failed to start Chisel")?;
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                // The binary may have moved or been removed since it was probed
+                self.forget_chisel_probe();
+                return Err(e).context("Failed to start Chisel");
+            }
+        };
 
         // Write the code and close stdin (signals EOF to chisel)
         if let Some(mut stdin) = child.stdin.take() {
@@ -243,9 +645,10 @@ impl SessionManager {
                 None => {
                     if start.elapsed() >= timeout {
                         child.kill()?;
-                        return Err(anyhow::anyhow!(
-                            "Chisel execution timed out after 10 seconds"
-                        ));
+                        return Err(ToolError::Timeout(
+                            "Chisel execution timed out after 10 seconds".to_string(),
+                        )
+                        .into());
                     }
                     std::thread::sleep(std::time::Duration::from_millis(100));
                 }
@@ -259,66 +662,17 @@ impl SessionManager {
 
         let combined = format!("{}{}", stdout, stderr);
 
-        // Filter out welcome message and prompts, keep only the actual output
-        let lines: Vec<&str> = combined.lines().collect();
-        let mut filtered_lines = Vec::new();
-        let mut skip_welcome = true;
-
-        for line in lines {
-            let trimmed = line.trim();
-
-            // Skip welcome message and prompts
-            if skip_welcome {
-                if trimmed.is_empty()
-                    || trimmed == "➜"
-                    || trimmed.contains("Welcome to Chisel")
-                    || trimmed.contains("Type `!help`")
-                {
-                    continue;
-                }
-                // Once we see actual content, stop skipping welcome
-                skip_welcome = false;
-            }
-
-            // Skip standalone prompts
-            if trimmed == "➜" {
-                continue;
-            }
-
-            // Remove leading prompt from lines with content after it
-            let cleaned = if line.starts_with("➜ ") {
-                line.chars().skip(2).collect::<String>() // Skip "➜ " (multi-byte safe)
-            } else {
-                line.to_string()
-            };
-
-            filtered_lines.push(cleaned);
-        }
-
-        let result = filtered_lines.join("\n").trim().to_string();
-
-        if result.is_empty() {
-            Ok("Code executed (no output)".to_string())
-        } else {
-            Ok(result)
+        if raw {
+            return Ok(combined);
         }
+        Ok(filter_chisel_output(&combined))
     }
 
     /// Stop the Chisel session
-    pub fn stop_chisel(&mut self) -> Result<String> {
-        if let Some(mut session) = self.sessions.remove("chisel") {
-            // Try to exit gracefully first
-            if let Some(stdin) = session.process.stdin.as_mut() {
-                let _ = writeln!(stdin, "!quit");
-                let _ = stdin.flush();
-            }
-
-            // Wait a moment, then force kill if needed
-            std::thread::sleep(std::time::Duration::from_millis(500));
-
-            let _ = session.process.kill();
-            let _ = session.process.wait();
-
+    pub fn stop_chisel(&self) -> Result<String> {
+        let session = lock(&self.slot("chisel")).take();
+        // Evals run in their own processes, so there is nothing left to kill
+        if session.is_some() {
             Ok("Chisel session has been stopped successfully.".to_string())
         } else {
             anyhow::bail!("No Chisel session is currently running.")
@@ -327,7 +681,7 @@ impl SessionManager {
 
     /// Get Chisel session status
     pub fn chisel_status(&self) -> Result<String> {
-        if let Some(session) = self.sessions.get("chisel") {
+        if let Some(session) = lock(&self.slot("chisel")).as_ref() {
             let uptime = session
                 .created_at
                 .elapsed()
@@ -345,11 +699,11 @@ impl SessionManager {
 
     /// Check if Chisel is running
     pub fn is_chisel_running(&self) -> bool {
-        self.sessions.contains_key("chisel")
+        lock(&self.slot("chisel")).is_some()
     }
 
     /// Stop all sessions (cleanup)
-    pub fn stop_all(&mut self) -> Vec<String> {
+    pub fn stop_all(&self) -> Vec<String> {
         let mut results = Vec::new();
 
         if self.is_anvil_running() {
@@ -377,10 +731,73 @@ impl Drop for SessionManager {
     }
 }
 
+/// Strip Chisel's welcome banner and prompts from an eval transcript, keeping only the
+/// actual output
+fn filter_chisel_output(output: &str) -> String {
+    let lines: Vec<&str> = output.lines().collect();
+    let mut filtered_lines = Vec::new();
+    let mut skip_welcome = true;
+
+    for line in lines {
+        let trimmed = line.trim();
+
+        // Skip welcome message and prompts
+        if skip_welcome {
+            if trimmed.is_empty()
+                || trimmed == "➜"
+                || trimmed.contains("Welcome to Chisel")
+                || trimmed.contains("Type `!help`")
+            {
+                continue;
+            }
+            // Once we see actual content, stop skipping welcome
+            skip_welcome = false;
+        }
+
+        // Skip standalone prompts
+        if trimmed == "➜" {
+            continue;
+        }
+
+        // Remove leading prompt from lines with content after it
+        let cleaned = if line.starts_with("➜ ") {
+            line.chars().skip(2).collect::<String>() // Skip "➜ " (multi-byte safe)
+        } else {
+            line.to_string()
+        };
+
+        filtered_lines.push(cleaned);
+    }
+
+    let result = filtered_lines.join("\n").trim().to_string();
+
+    if result.is_empty() {
+        "Code executed (no output)".to_string()
+    } else {
+        result
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Test that the welcome banner and prompts are stripped from a chisel transcript
+    #[test]
+    fn test_filter_chisel_output() {
+        let transcript = "Welcome to Chisel! Type `!help` to show available commands.\n➜ \n➜ 1 + 1\nType: uint256\n├ Hex: 0x2\n➜ \n";
+        assert_eq!(
+            filter_chisel_output(transcript),
+            "1 + 1\nType: uint256\n├ Hex: 0x2"
+        );
+        assert_eq!(
+            filter_chisel_output(
+                "Welcome to Chisel! Type `!help` to show available commands.\n➜ \n"
+            ),
+            "Code executed (no output)"
+        );
+    }
+
     /// Test that session manager can be created successfully
     #[test]
     fn test_session_manager_creation() {
@@ -418,7 +835,7 @@ mod tests {
     /// Test stopping anvil when not running returns error
     #[test]
     fn test_stop_anvil_when_not_running() {
-        let mut manager = SessionManager::new();
+        let manager = SessionManager::new();
         let result = manager.stop_anvil();
         assert!(
             result.is_err(),
@@ -429,7 +846,7 @@ mod tests {
     /// Test stopping chisel when not running returns error
     #[test]
     fn test_stop_chisel_when_not_running() {
-        let mut manager = SessionManager::new();
+        let manager = SessionManager::new();
         let result = manager.stop_chisel();
         assert!(
             result.is_err(),
@@ -444,6 +861,243 @@ mod tests {
         assert!(!manager.is_anvil_running());
     }
 
+    /// Test that there is no Anvil RPC URL when Anvil isn't running
+    #[test]
+    fn test_anvil_rpc_url_when_not_running() {
+        let manager = SessionManager::new();
+        assert!(manager.anvil_rpc_url().is_none());
+    }
+
+    /// Test that a recent node state reading is reused and an old one refreshed
+    #[test]
+    fn test_anvil_node_state_cache() {
+        let manager = SessionManager::new();
+        let state = |block_number| NodeState {
+            chain_id: 31337,
+            block_number,
+            accounts: vec![],
+        };
+        assert!(manager
+            .anvil_node_state(Duration::from_secs(60), |_| Ok(state(1)))
+            .is_none());
+
+        insert_dead_anvil(&manager, false);
+        let first = manager.anvil_node_state(Duration::from_secs(60), |url| {
+            assert_eq!(url, "http://localhost:18547");
+            Ok(state(1))
+        });
+        assert_eq!(first.unwrap().unwrap(), state(1));
+
+        let cached = manager.anvil_node_state(Duration::from_secs(60), |_| Ok(state(2)));
+        assert_eq!(cached.unwrap().unwrap().block_number, 1);
+
+        let refreshed = manager.anvil_node_state(Duration::ZERO, |_| Ok(state(2)));
+        assert_eq!(refreshed.unwrap().unwrap().block_number, 2);
+    }
+
+    /// Test that restoring a snapshot forgets it and every later one
+    #[test]
+    fn test_anvil_snapshots() {
+        let manager = SessionManager::new();
+        assert!(manager.save_anvil_snapshot("base", "0x0").is_err());
+
+        insert_dead_anvil(&manager, false);
+        for (name, id) in [("base", "0x0"), ("funded", "0x1"), ("deployed", "0x2")] {
+            manager.save_anvil_snapshot(name, id).unwrap();
+        }
+        manager.save_anvil_snapshot("base", "0x3").unwrap();
+        assert_eq!(
+            manager.anvil_snapshot_names(),
+            vec!["funded", "deployed", "base"]
+        );
+        assert_eq!(
+            manager.anvil_snapshot_id("funded").unwrap().as_deref(),
+            Some("0x1")
+        );
+
+        assert_eq!(
+            manager.prune_anvil_snapshots("funded"),
+            vec!["deployed", "base"]
+        );
+        assert!(manager.anvil_snapshot_names().is_empty());
+        assert!(manager.anvil_snapshot_id("funded").unwrap().is_none());
+    }
+
+    /// Insert an Anvil session whose process has already exited
+    fn insert_dead_anvil(manager: &SessionManager, auto_restart: bool) {
+        let mut process = Command::new("true").spawn().unwrap();
+        process.wait().unwrap();
+
+        *lock(&manager.slot("anvil")) = Some(SessionInfo {
+            session_type: SessionType::Anvil,
+            process: Some(process),
+            port: Some(18547),
+            created_at: std::time::SystemTime::now(),
+            start_params: Some(AnvilParams {
+                foundry_bin_path: Some("/invalid".to_string()),
+                port: 18547,
+                fork_url: None,
+                fork_block_number: None,
+                accounts: None,
+                block_time: None,
+            }),
+            auto_restart,
+            restarts: 0,
+            node_state: None,
+            snapshots: Vec::new(),
+        });
+    }
+
+    /// Test that a dead Anvil process is noticed and its session removed
+    #[test]
+    fn test_check_anvil_detects_exit() {
+        let manager = SessionManager::new();
+        insert_dead_anvil(&manager, false);
+        assert!(manager.is_anvil_running());
+
+        let status = manager.anvil_status().unwrap();
+        assert!(status.contains("exited unexpectedly"));
+        assert!(status.contains("not currently running"));
+        assert!(!manager.is_anvil_running());
+        assert!(manager.check_anvil().is_none());
+    }
+
+    /// Test that a chisel probe is reused only for the same binary within its TTL
+    #[test]
+    fn test_chisel_probe_cache() {
+        let manager = SessionManager::new();
+        let ttl = Duration::from_secs(60);
+        assert!(!manager.chisel_probe_fresh("chisel", ttl));
+
+        *manager.chisel_probe.lock().unwrap() = Some(("chisel".to_string(), Instant::now()));
+        assert!(manager.chisel_probe_fresh("chisel", ttl));
+        assert!(!manager.chisel_probe_fresh("/opt/foundry/bin/chisel", ttl));
+        assert!(!manager.chisel_probe_fresh("chisel", Duration::ZERO));
+
+        manager.forget_chisel_probe();
+        assert!(!manager.chisel_probe_fresh("chisel", ttl));
+    }
+
+    /// Test that a Chisel session starts and stops without spawning any process
+    #[test]
+    fn test_chisel_start_stop_without_process() {
+        let manager = SessionManager::new();
+        // A fresh probe skips the `chisel --help` check, so nothing is spawned at all
+        *manager.chisel_probe.lock().unwrap() = Some(("chisel".to_string(), Instant::now()));
+
+        manager
+            .start_chisel(&None, Duration::from_secs(60))
+            .unwrap();
+        assert!(manager.is_chisel_running());
+        let snapshot = manager.snapshot();
+        assert_eq!(snapshot[0].session_type, "chisel");
+        assert_eq!(snapshot[0].pid, None);
+
+        manager.stop_chisel().unwrap();
+        assert!(!manager.is_chisel_running());
+    }
+
+    /// Test that a busy Chisel session doesn't hold up Anvil operations
+    #[test]
+    fn test_sessions_lock_independently() {
+        let manager = SessionManager::new();
+        let chisel = manager.slot("chisel");
+        // As if an eval were running
+        let _busy = lock(&chisel);
+
+        assert!(!manager.is_anvil_running());
+        assert!(manager
+            .anvil_status()
+            .unwrap()
+            .contains("not currently running"));
+    }
+
+    /// Test that the snapshot reports each session's type, process and port
+    #[test]
+    fn test_snapshot() {
+        let manager = SessionManager::new();
+        assert!(manager.snapshot().is_empty());
+
+        insert_dead_anvil(&manager, true);
+        let pid = lock(&manager.slot("anvil"))
+            .as_ref()
+            .unwrap()
+            .process
+            .as_ref()
+            .unwrap()
+            .id();
+        let snapshot = manager.snapshot();
+        assert_eq!(
+            snapshot,
+            vec![SessionSnapshot {
+                key: "anvil".to_string(),
+                session_type: "anvil",
+                pid: Some(pid),
+                port: Some(18547),
+                uptime_secs: 0,
+                auto_restart: true,
+                restarts: 0,
+            }]
+        );
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(json[0]["type"], "anvil");
+    }
+
+    /// Test that a failed auto-restart is reported and leaves no session behind
+    #[test]
+    fn test_check_anvil_restart_failure() {
+        let manager = SessionManager::new();
+        insert_dead_anvil(&manager, true);
+
+        let note = manager.check_anvil().unwrap();
+        assert!(note.contains("could not be restarted"));
+        assert!(!manager.is_anvil_running());
+    }
+
+    /// Test that the start parameters are described for status output
+    #[test]
+    fn test_anvil_params_describe() {
+        let params = AnvilParams {
+            foundry_bin_path: None,
+            port: 8545,
+            fork_url: Some("https://eth.llamarpc.com".to_string()),
+            fork_block_number: Some(19_000_000),
+            accounts: Some(3),
+            block_time: None,
+        };
+        let description = params.describe();
+        assert!(description.contains("Port: 8545"));
+        assert!(description.contains("Fork: https://eth.llamarpc.com at block 19000000"));
+        assert!(description.contains("Accounts: 3"));
+        assert!(description.contains("Block time: mine on demand"));
+
+        let params = AnvilParams {
+            fork_url: Some("https://eth-mainnet.g.alchemy.com/v2/SECRET".to_string()),
+            ..params
+        };
+        assert!(!params.describe().contains("SECRET"));
+        assert!(!serde_json::to_string(&params).unwrap().contains("SECRET"));
+        assert!(params
+            .describe()
+            .contains("https://eth-mainnet.g.alchemy.com/"));
+
+        let manager = SessionManager::new();
+        assert!(manager.anvil_start_params().is_none());
+        insert_dead_anvil(&manager, false);
+        assert_eq!(manager.anvil_start_params().unwrap().port, 18547);
+    }
+
+    /// Test that auto-restart can only be enabled on a running session
+    #[test]
+    fn test_set_anvil_auto_restart() {
+        let manager = SessionManager::new();
+        assert!(!manager.set_anvil_auto_restart(true));
+
+        insert_dead_anvil(&manager, false);
+        assert!(manager.set_anvil_auto_restart(true));
+        assert!(lock(&manager.slot("anvil")).as_ref().unwrap().auto_restart);
+    }
+
     /// Test that is_chisel_running returns false initially
     #[test]
     fn test_is_chisel_running_initially_false() {
@@ -454,7 +1108,7 @@ mod tests {
     /// Test stop_all on empty manager
     #[test]
     fn test_stop_all_when_empty() {
-        let mut manager = SessionManager::new();
+        let manager = SessionManager::new();
         let results = manager.stop_all();
         assert!(results.is_empty());
     }
@@ -478,7 +1132,7 @@ mod tests {
     /// Test that start_anvil with invalid binary path fails gracefully
     #[test]
     fn test_start_anvil_with_invalid_path() {
-        let mut manager = SessionManager::new();
+        let manager = SessionManager::new();
         let invalid_path = Some("/nonexistent/path/to/foundry".to_string());
 
         let result = manager.start_anvil(&invalid_path, 8545, None, None, None, None);
@@ -489,10 +1143,10 @@ mod tests {
     /// Test that start_chisel with invalid binary path fails gracefully
     #[test]
     fn test_start_chisel_with_invalid_path() {
-        let mut manager = SessionManager::new();
+        let manager = SessionManager::new();
         let invalid_path = Some("/nonexistent/path/to/foundry".to_string());
 
-        let result = manager.start_chisel(&invalid_path);
+        let result = manager.start_chisel(&invalid_path, Duration::ZERO);
 
         assert!(result.is_err());
     }
@@ -500,19 +1154,19 @@ mod tests {
     /// Test that multiple sessions can be tracked
     #[test]
     fn test_sessions_hashmap() {
-        let mut manager = SessionManager::new();
-        assert_eq!(manager.sessions.len(), 0);
+        let manager = SessionManager::new();
+        assert!(manager.snapshot().is_empty());
 
         // After failed starts, should still be 0
         let _ = manager.start_anvil(&Some("/invalid".to_string()), 8545, None, None, None, None);
-        assert_eq!(manager.sessions.len(), 0);
+        assert!(manager.snapshot().is_empty());
     }
 
     /// Test chisel eval without running session
     #[test]
     fn test_chisel_eval_without_session() {
-        let mut manager = SessionManager::new();
-        let result = manager.chisel_eval("uint256 x = 42;".to_string(), &None);
+        let manager = SessionManager::new();
+        let result = manager.chisel_eval("uint256 x = 42;".to_string(), false, &None);
 
         assert!(
             result.is_err(),
@@ -524,7 +1178,7 @@ mod tests {
     #[test]
     #[ignore] // Run with --ignored flag only if Foundry is installed
     fn test_anvil_lifecycle_integration() {
-        let mut manager = SessionManager::new();
+        let manager = SessionManager::new();
 
         // Start anvil
         let start_result = manager.start_anvil(&None, 18545, None, None, None, None);
@@ -551,10 +1205,10 @@ mod tests {
     #[test]
     #[ignore] // Run with --ignored flag only if Foundry is installed
     fn test_chisel_lifecycle_integration() {
-        let mut manager = SessionManager::new();
+        let manager = SessionManager::new();
 
         // Start chisel
-        let start_result = manager.start_chisel(&None);
+        let start_result = manager.start_chisel(&None, Duration::ZERO);
         if start_result.is_err() {
             // Skip test if Foundry not installed
             return;
@@ -568,7 +1222,7 @@ mod tests {
         assert!(status.contains("active"));
 
         // Eval code
-        let eval_result = manager.chisel_eval("uint256 x = 42;".to_string(), &None);
+        let eval_result = manager.chisel_eval("uint256 x = 42;".to_string(), false, &None);
         // May succeed or fail depending on chisel behavior, just check it doesn't panic
         let _ = eval_result;
 
@@ -582,7 +1236,7 @@ mod tests {
     #[test]
     #[ignore] // Integration test
     fn test_start_anvil_twice_fails() {
-        let mut manager = SessionManager::new();
+        let manager = SessionManager::new();
 
         // Start once
         let first_start = manager.start_anvil(&None, 18546, None, None, None, None);
@@ -606,16 +1260,16 @@ mod tests {
     #[test]
     #[ignore] // Integration test
     fn test_start_chisel_twice_fails() {
-        let mut manager = SessionManager::new();
+        let manager = SessionManager::new();
 
         // Start once
-        let first_start = manager.start_chisel(&None);
+        let first_start = manager.start_chisel(&None, Duration::ZERO);
         if first_start.is_err() {
             return; // Skip if Foundry not installed
         }
 
         // Try to start again
-        let second_start = manager.start_chisel(&None);
+        let second_start = manager.start_chisel(&None, Duration::ZERO);
         assert!(second_start.is_err());
         assert!(second_start
             .unwrap_err()
@@ -0,0 +1,344 @@
+//! Contract storage reader
+//!
+//! `cast_storage` reads a storage slot with `cast storage`, optionally computing the slot of
+//! a mapping entry with `cast index` first, and can decode the raw word as an address,
+//! integer or bytes32.
+
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::cast;
+use crate::conversion::{self, ConversionParams};
+use crate::error::ToolError;
+use crate::foundry::FoundryExecutor;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// How to decode a raw storage word
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DecodeAs {
+    Address,
+    Uint,
+    Bytes32,
+}
+
+impl DecodeAs {
+    fn parse(name: &str) -> Result<Self, ToolError> {
+        match name {
+            "address" => Ok(Self::Address),
+            "uint" | "uint256" => Ok(Self::Uint),
+            "bytes32" => Ok(Self::Bytes32),
+            other => Err(ToolError::InvalidArguments(format!(
+                "Unknown decode_as '{}'. Use 'address', 'uint' or 'bytes32'",
+                other
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Address => "address",
+            Self::Uint => "uint",
+            Self::Bytes32 => "bytes32",
+        }
+    }
+}
+
+/// Where the value is read from: a slot, or a mapping entry under a base slot
+#[derive(Debug, Clone, PartialEq)]
+enum SlotRef {
+    Slot(String),
+    MappingEntry {
+        key: String,
+        key_type: String,
+        base_slot: String,
+    },
+}
+
+/// Get the storage reader tools
+pub fn get_storage_tools() -> Vec<Tool> {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "address".to_string(),
+        json!({
+            "type": "string",
+            "description": "Contract address (or ENS name) to read from"
+        }),
+    );
+    props.insert(
+        "slot".to_string(),
+        json!({
+            "type": "string",
+            "description": "Storage slot, as a decimal or 0x-prefixed number (omit when using key/base_slot)"
+        }),
+    );
+    props.insert(
+        "key".to_string(),
+        json!({
+            "type": "string",
+            "description": "Mapping key; the slot is computed from it and base_slot with cast index"
+        }),
+    );
+    props.insert(
+        "key_type".to_string(),
+        json!({
+            "type": "string",
+            "description": "Solidity type of the mapping key (default: 'address')"
+        }),
+    );
+    props.insert(
+        "base_slot".to_string(),
+        json!({
+            "type": "string",
+            "description": "Slot the mapping is declared at"
+        }),
+    );
+    props.insert(
+        "decode_as".to_string(),
+        json!({
+            "type": "string",
+            "enum": ["address", "uint", "bytes32"],
+            "description": "Also decode the raw 32-byte word as this type (optional)"
+        }),
+    );
+    props.insert(
+        "block".to_string(),
+        json!({
+            "type": "string",
+            "description": "Block number or tag to read at (default: latest)"
+        }),
+    );
+    props.insert(
+        "rpc_url".to_string(),
+        json!({
+            "type": "string",
+            "description": "RPC endpoint to read from (default: the configured default_rpc_url or default_chain, else the running Anvil session)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+    schema.insert("required".to_string(), json!(["address"]));
+
+    vec![Tool::new(
+        "cast_storage".to_string(),
+        "Read a contract storage slot, either directly or for a mapping entry given its key and the mapping's base slot, optionally decoding the value as an address, uint or bytes32. Uses the configured default RPC, else the running Anvil session, unless an rpc_url is given.".to_string(),
+        Arc::new(schema),
+    )]
+}
+
+/// Work out which slot to read from the arguments
+fn slot_ref(args: &JsonObject) -> Result<SlotRef, ToolError> {
+    let get = |name: &str| args.get(name).and_then(|v| v.as_str()).map(str::to_string);
+
+    match (get("slot"), get("key"), get("base_slot")) {
+        (Some(_), Some(_), _) => Err(ToolError::InvalidArguments(
+            "Give either 'slot' or 'key' with 'base_slot', not both".to_string(),
+        )),
+        (Some(slot), None, _) => Ok(SlotRef::Slot(slot)),
+        (None, Some(key), Some(base_slot)) => Ok(SlotRef::MappingEntry {
+            key,
+            key_type: get("key_type").unwrap_or_else(|| "address".to_string()),
+            base_slot,
+        }),
+        (None, Some(_), None) => Err(ToolError::InvalidArguments(
+            "'key' needs the mapping's 'base_slot'".to_string(),
+        )),
+        (None, None, _) => Err(ToolError::InvalidArguments(
+            "Give a 'slot', or a mapping 'key' with its 'base_slot'".to_string(),
+        )),
+    }
+}
+
+/// Decode a raw storage word, using the conversion tool for checksums and decimals
+fn decode(word: &str, decode_as: DecodeAs, cast_path: &str) -> Result<String, ToolError> {
+    let hex = word.trim().trim_start_matches("0x").to_ascii_lowercase();
+    let convert = |conversion_type: &str, value: String| {
+        conversion::execute_conversion(
+            ConversionParams {
+                conversion_type: conversion_type.to_string(),
+                value: Some(value),
+                ..Default::default()
+            },
+            cast_path,
+        )
+    };
+
+    match decode_as {
+        DecodeAs::Bytes32 => Ok(format!("0x{:0>64}", hex)),
+        DecodeAs::Uint => convert("to-dec", format!("0x{}", hex)),
+        DecodeAs::Address => {
+            let address = format!("{:0>40}", &hex[hex.len().saturating_sub(40)..]);
+            convert("to-check-sum-address", format!("0x{}", address))
+        }
+    }
+}
+
+/// Resolve the slot (via `cast index` for a mapping entry) and read it
+async fn read_slot(
+    args: &JsonObject,
+    address: &str,
+    slot_ref: &SlotRef,
+    executor: &FoundryExecutor,
+) -> Result<(String, String), ToolError> {
+    let rpc_url = cast::resolve_rpc_url(
+        args.get("rpc_url").and_then(|v| v.as_str()),
+        &executor.config(),
+    )
+    .await?;
+
+    let slot = match slot_ref {
+        SlotRef::Slot(slot) => slot.clone(),
+        SlotRef::MappingEntry {
+            key,
+            key_type,
+            base_slot,
+        } => {
+            let mut index_args = JsonObject::new();
+            index_args.insert("key_type".to_string(), json!(key_type));
+            index_args.insert("key".to_string(), json!(key));
+            index_args.insert("slot_number".to_string(), json!(base_slot));
+            cast::run_tool(executor, "cast_index", index_args, &[])?
+        }
+    };
+
+    let mut storage_args = JsonObject::new();
+    storage_args.insert("address".to_string(), json!(address));
+    storage_args.insert("slot".to_string(), json!(slot));
+    storage_args.insert("rpc-url".to_string(), json!(rpc_url));
+    if let Some(block) = args.get("block").and_then(|v| v.as_str()) {
+        storage_args.insert("block".to_string(), json!(block));
+    }
+    let value = cast::run_tool(executor, "cast_storage", storage_args, &[])?;
+
+    Ok((slot, value))
+}
+
+/// Handle cast_storage tool call
+pub async fn handle_cast_storage(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+    cast_path: &str,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let args = arguments
+        .as_ref()
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing arguments", None))?;
+    let address = args
+        .get("address")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| rmcp::ErrorData::invalid_params("Missing 'address' parameter", None))?;
+    let slot_ref = slot_ref(args)?;
+    let decode_as = args
+        .get("decode_as")
+        .and_then(|v| v.as_str())
+        .map(DecodeAs::parse)
+        .transpose()?;
+
+    let (slot, value) = match read_slot(args, address, &slot_ref, executor).await {
+        Ok(values) => values,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+    let decoded = match decode_as
+        .map(|kind| decode(&value, kind, cast_path))
+        .transpose()
+    {
+        Ok(decoded) => decoded,
+        Err(e) => return Ok(e.into_call_tool_result()),
+    };
+
+    let mut text = format!("Slot {} of {}: {}", slot, address, value);
+    if let (Some(kind), Some(decoded)) = (decode_as, &decoded) {
+        text.push_str(&format!("\nAs {}: {}", kind.name(), decoded));
+    }
+
+    let mut structured = json!({
+        "address": address,
+        "slot": slot,
+        "value": value,
+    });
+    if let SlotRef::MappingEntry {
+        key,
+        key_type,
+        base_slot,
+    } = &slot_ref
+    {
+        structured["mapping"] = json!({ "key": key, "key_type": key_type, "base_slot": base_slot });
+    }
+    if let (Some(kind), Some(decoded)) = (decode_as, decoded) {
+        structured["decode_as"] = json!(kind.name());
+        structured["decoded"] = json!(decoded);
+    }
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(structured);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that a slot or a mapping key with its base slot is required, but not both
+    #[test]
+    fn test_slot_ref() {
+        let args = json!({"slot": "0x5"});
+        assert_eq!(
+            slot_ref(args.as_object().unwrap()).unwrap(),
+            SlotRef::Slot("0x5".to_string())
+        );
+
+        let args = json!({"key": "0x0000000000000000000000000000000000000001", "base_slot": "3"});
+        assert_eq!(
+            slot_ref(args.as_object().unwrap()).unwrap(),
+            SlotRef::MappingEntry {
+                key: "0x0000000000000000000000000000000000000001".to_string(),
+                key_type: "address".to_string(),
+                base_slot: "3".to_string(),
+            }
+        );
+
+        for args in [
+            json!({}),
+            json!({"key": "1"}),
+            json!({"slot": "0", "key": "1", "base_slot": "3"}),
+        ] {
+            assert!(matches!(
+                slot_ref(args.as_object().unwrap()),
+                Err(ToolError::InvalidArguments(_))
+            ));
+        }
+    }
+
+    /// Test that decode types parse, with uint256 accepted as an alias
+    #[test]
+    fn test_decode_as_parse() {
+        assert_eq!(DecodeAs::parse("address").unwrap(), DecodeAs::Address);
+        assert_eq!(DecodeAs::parse("uint256").unwrap(), DecodeAs::Uint);
+        assert_eq!(DecodeAs::parse("bytes32").unwrap(), DecodeAs::Bytes32);
+        assert!(DecodeAs::parse("string").is_err());
+    }
+
+    /// Test that bytes32 decoding pads without needing cast
+    #[test]
+    fn test_decode_bytes32() {
+        assert_eq!(
+            decode("0x1", DecodeAs::Bytes32, "cast").unwrap(),
+            format!("0x{:0>64}", "1")
+        );
+    }
+
+    /// Test that the tool requires an address
+    #[test]
+    fn test_get_storage_tools() {
+        let tools = get_storage_tools();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "cast_storage");
+        assert_eq!(
+            tools[0].input_schema.get("required"),
+            Some(&json!(["address"]))
+        );
+    }
+}
@@ -11,6 +11,9 @@ use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
+use crate::error::ToolError;
+use crate::format::{self, ListFormat};
+
 /// Token list standard format (EIP-3770)
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -85,19 +88,33 @@ pub fn chain_id_to_name(id: u64) -> Option<&'static str> {
 /// Global cache for tokenlist data
 static TOKENLIST_CACHE: Lazy<Mutex<Option<TokenList>>> = Lazy::new(|| Mutex::new(None));
 
+/// Held while a fetch is in flight so concurrent callers on a cold cache share one download
+static TOKENLIST_FETCH: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mutex::new(()));
+
+/// The cached data, if it has been fetched
+fn cached() -> Option<TokenList> {
+    TOKENLIST_CACHE.lock().unwrap().clone()
+}
+
 /// Fetches and caches token data from the Optimism token list
 pub async fn fetch_tokenlist() -> Result<TokenList> {
-    // Check cache first
-    {
-        let cache = TOKENLIST_CACHE.lock().unwrap();
-        if let Some(ref cached) = *cache {
-            return Ok(cached.clone());
+    crate::http::fetch_once(&TOKENLIST_FETCH, cached, || async {
+        let tokenlist = download_tokenlist().await?;
+
+        // Update cache
+        {
+            let mut cache = TOKENLIST_CACHE.lock().unwrap();
+            *cache = Some(tokenlist.clone());
         }
-    }
 
-    // Fetch from GitHub
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
+        Ok(tokenlist)
+    })
+    .await
+}
+
+/// Download the Optimism token list from GitHub
+async fn download_tokenlist() -> Result<TokenList> {
+    let client = crate::http::client_builder()?
         .user_agent("foundry-mcp-rs")
         .build()?;
 
@@ -110,16 +127,8 @@ pub async fn fetch_tokenlist() -> Result<TokenList> {
     let text = response.text().await?;
 
     // Try to parse the JSON
-    let tokenlist: TokenList = serde_json::from_str(&text)
-        .context("Failed to parse token list response. This might be due to API format changes.")?;
-
-    // Update cache
-    {
-        let mut cache = TOKENLIST_CACHE.lock().unwrap();
-        *cache = Some(tokenlist.clone());
-    }
-
-    Ok(tokenlist)
+    serde_json::from_str(&text)
+        .context("Failed to parse token list response. This might be due to API format changes.")
 }
 
 /// Clear the tokenlist cache to force a refresh
@@ -232,6 +241,166 @@ pub fn format_token_info(token: &TokenInfo, show_chain: bool) -> String {
     info
 }
 
+/// Token fields `list_chain_tokens` can be limited to, with their labels
+const TOKEN_FIELDS: &[(&str, &str)] = &[
+    ("symbol", "Symbol"),
+    ("name", "Name"),
+    ("address", "Address"),
+    ("decimals", "Decimals"),
+    ("chain", "Chain"),
+    ("logo", "Logo"),
+    ("bridge", "Bridge"),
+];
+
+/// Fields shown when `compact` is set without `fields`, or in a table
+const DEFAULT_TOKEN_FIELDS: &[&str] = &["symbol", "name", "address", "decimals"];
+
+/// Which token fields to show, and whether one line per token
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenView {
+    /// Fields in display order; `None` shows the full token information
+    pub fields: Option<Vec<String>>,
+    pub compact: bool,
+}
+
+impl TokenView {
+    /// Read the `fields` and `compact` arguments
+    pub fn from_args(args: &serde_json::Map<String, Value>) -> Result<Self, ToolError> {
+        let fields = match args.get("fields") {
+            None | Some(Value::Null) => None,
+            Some(Value::Array(values)) => {
+                let mut fields = Vec::new();
+                for value in values {
+                    let field = value.as_str().map(str::to_lowercase).unwrap_or_default();
+                    if !TOKEN_FIELDS.iter().any(|(name, _)| *name == field) {
+                        return Err(ToolError::InvalidArguments(format!(
+                            "Unknown token field {}, expected one of: {}",
+                            value,
+                            TOKEN_FIELDS
+                                .iter()
+                                .map(|(name, _)| *name)
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )));
+                    }
+                    fields.push(field);
+                }
+                if fields.is_empty() {
+                    return Err(ToolError::InvalidArguments("'fields' is empty".to_string()));
+                }
+                Some(fields)
+            }
+            Some(_) => {
+                return Err(ToolError::InvalidArguments(
+                    "'fields' must be an array of field names".to_string(),
+                ))
+            }
+        };
+        let compact = args
+            .get("compact")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        Ok(Self { fields, compact })
+    }
+
+    /// The fields to show, falling back to the basic ones
+    pub fn field_names(&self, show_chain: bool) -> Vec<&str> {
+        match &self.fields {
+            Some(fields) => fields.iter().map(String::as_str).collect(),
+            None => {
+                let mut fields = DEFAULT_TOKEN_FIELDS.to_vec();
+                if show_chain {
+                    fields.push("chain");
+                }
+                fields
+            }
+        }
+    }
+
+    /// Column headers for a table of these fields
+    pub fn labels(&self, show_chain: bool) -> Vec<&'static str> {
+        self.field_names(show_chain)
+            .into_iter()
+            .filter_map(|field| TOKEN_FIELDS.iter().find(|(name, _)| *name == field))
+            .map(|(_, label)| *label)
+            .collect()
+    }
+
+    /// A token's values for these fields, empty where it has none
+    pub fn values(&self, token: &TokenInfo, show_chain: bool) -> Vec<String> {
+        self.field_names(show_chain)
+            .into_iter()
+            .map(|field| token_field(token, field).unwrap_or_default())
+            .collect()
+    }
+}
+
+/// One field of a token as text, if the token has it
+fn token_field(token: &TokenInfo, field: &str) -> Option<String> {
+    match field {
+        "symbol" => Some(token.symbol.clone()),
+        "name" => Some(token.name.clone()),
+        "address" => Some(token.address.clone()),
+        "decimals" => Some(token.decimals.to_string()),
+        "chain" => Some(match chain_id_to_name(token.chain_id) {
+            Some(name) => format!("{} (ID: {})", name, token.chain_id),
+            None => token.chain_id.to_string(),
+        }),
+        "logo" => token.logo_uri.clone(),
+        "bridge" => {
+            let bridges = token
+                .extensions
+                .as_ref()?
+                .get("bridgeInfo")?
+                .as_object()?
+                .iter()
+                .filter_map(|(chain, data)| {
+                    let address = data.get("tokenAddress")?.as_str()?;
+                    Some(format!("{} → {}", chain, address))
+                })
+                .collect::<Vec<_>>();
+            (!bridges.is_empty()).then(|| bridges.join(", "))
+        }
+        _ => None,
+    }
+}
+
+/// Format a token as [`format_token_info`] does, limited to the view's fields, or on one
+/// line when compact
+pub fn format_token(token: &TokenInfo, show_chain: bool, view: &TokenView) -> String {
+    if view.fields.is_none() && !view.compact {
+        return format_token_info(token, show_chain);
+    }
+
+    let fields = view.field_names(show_chain);
+    let values = view.values(token, show_chain);
+    if view.compact {
+        let values: Vec<&str> = values
+            .iter()
+            .map(String::as_str)
+            .filter(|v| !v.is_empty())
+            .collect();
+        return format!("• {}\n", values.join(" | "));
+    }
+
+    let mut info = String::new();
+    for (i, (field, value)) in fields.iter().zip(&values).enumerate() {
+        if value.is_empty() {
+            continue;
+        }
+        if i == 0 {
+            info.push_str(&format!("• {}\n", value));
+        } else {
+            let label = TOKEN_FIELDS
+                .iter()
+                .find(|(name, _)| name == field)
+                .map_or(*field, |(_, label)| *label);
+            info.push_str(&format!("  {}: {}\n", label, value));
+        }
+    }
+    info
+}
+
 /// Get tokenlist MCP tools
 pub fn get_tokenlist_tools() -> Vec<Tool> {
     vec![
@@ -247,7 +416,7 @@ pub fn get_tokenlist_tools() -> Vec<Tool> {
                 }));
                 props.insert("chain".to_string(), serde_json::json!({
                     "type": "string",
-                    "description": "Optional: Filter by chain name or ID (e.g., 'ethereum', 'optimism', '10')"
+                    "description": "Optional: Filter by chain name or ID (e.g., 'ethereum', 'optimism', '10'). Defaults to the server's default_chain, if configured; 'all' searches every chain"
                 }));
 
                 let mut schema = serde_json::Map::new();
@@ -269,7 +438,7 @@ pub fn get_tokenlist_tools() -> Vec<Tool> {
                 }));
                 props.insert("chain".to_string(), serde_json::json!({
                     "type": "string",
-                    "description": "Optional: Chain name or ID to search on (e.g., 'ethereum', 'optimism', '10')"
+                    "description": "Optional: Chain name or ID to search on (e.g., 'ethereum', 'optimism', '10'). Defaults to the server's default_chain, if configured; 'all' searches every chain"
                 }));
 
                 let mut schema = serde_json::Map::new();
@@ -287,17 +456,29 @@ pub fn get_tokenlist_tools() -> Vec<Tool> {
                 let mut props = serde_json::Map::new();
                 props.insert("chain".to_string(), serde_json::json!({
                     "type": "string",
-                    "description": "Chain name or ID (e.g., 'ethereum', 'optimism', '10', 'base')"
+                    "description": "Chain name or ID (e.g., 'ethereum', 'optimism', '10', 'base'). Defaults to the server's default_chain, if configured"
                 }));
                 props.insert("limit".to_string(), serde_json::json!({
                     "type": "number",
                     "description": "Maximum number of tokens to return (default: 50)"
                 }));
+                props.insert("format".to_string(), format::format_property());
+                props.insert("fields".to_string(), serde_json::json!({
+                    "type": "array",
+                    "items": {
+                        "type": "string",
+                        "enum": ["symbol", "name", "address", "decimals", "chain", "logo", "bridge"]
+                    },
+                    "description": "Only show these token fields, in this order (default: all)"
+                }));
+                props.insert("compact".to_string(), serde_json::json!({
+                    "type": "boolean",
+                    "description": "One line per token (default: false)"
+                }));
 
                 let mut schema = serde_json::Map::new();
                 schema.insert("type".to_string(), Value::String("object".to_string()));
                 schema.insert("properties".to_string(), Value::Object(props));
-                schema.insert("required".to_string(), Value::Array(vec![Value::String("chain".to_string())]));
                 schema
             }),
         ),
@@ -340,7 +521,10 @@ pub async fn handle_search_tokens(
 
     // Fetch token data
     let tokenlist = fetch_tokenlist().await.map_err(|e| {
-        rmcp::ErrorData::internal_error(format!("Failed to fetch token list: {}", e), None)
+        rmcp::ErrorData::from(ToolError::RpcUnavailable(format!(
+            "Failed to fetch token list: {}",
+            e
+        )))
     })?;
 
     // Search tokens
@@ -401,7 +585,10 @@ pub async fn handle_get_token_by_address(
 
     // Fetch token data
     let tokenlist = fetch_tokenlist().await.map_err(|e| {
-        rmcp::ErrorData::internal_error(format!("Failed to fetch token list: {}", e), None)
+        rmcp::ErrorData::from(ToolError::RpcUnavailable(format!(
+            "Failed to fetch token list: {}",
+            e
+        )))
     })?;
 
     // Find token by address
@@ -456,10 +643,15 @@ pub async fn handle_list_chain_tokens(
         .and_then(|v| v.as_u64())
         .unwrap_or(50)
         .min(200) as usize;
+    let list_format = format::list_format(args)?;
+    let view = TokenView::from_args(args)?;
 
     // Fetch token data
     let tokenlist = fetch_tokenlist().await.map_err(|e| {
-        rmcp::ErrorData::internal_error(format!("Failed to fetch token list: {}", e), None)
+        rmcp::ErrorData::from(ToolError::RpcUnavailable(format!(
+            "Failed to fetch token list: {}",
+            e
+        )))
     })?;
 
     // Get tokens for chain
@@ -478,9 +670,21 @@ pub async fn handle_list_chain_tokens(
         response.push_str("No tokens found for this chain.\n");
     } else {
         let display_tokens = tokens.iter().take(limit);
-        for token in display_tokens {
-            response.push_str(&format_token_info(token, false));
-            response.push('\n');
+        match list_format {
+            ListFormat::List => {
+                for token in display_tokens {
+                    response.push_str(&format_token(token, false, &view));
+                    if !view.compact {
+                        response.push('\n');
+                    }
+                }
+            }
+            ListFormat::Table => {
+                let rows: Vec<Vec<String>> = display_tokens
+                    .map(|token| view.values(token, false))
+                    .collect();
+                response.push_str(&format::render_table(&view.labels(false), &rows));
+            }
         }
 
         if tokens.len() > limit {
@@ -508,3 +712,86 @@ pub async fn handle_list_supported_chains(
 
     Ok(CallToolResult::success(vec![Content::text(response)]))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn token() -> TokenInfo {
+        serde_json::from_value(json!({
+            "chainId": 10,
+            "address": "0x0b2C639c533813f4Aa9D7837CAf62653d097Ff85",
+            "name": "USD Coin",
+            "symbol": "USDC",
+            "decimals": 6,
+            "logoURI": "https://example.com/usdc.png",
+            "extensions": {
+                "bridgeInfo": {"1": {"tokenAddress": "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"}}
+            }
+        }))
+        .unwrap()
+    }
+
+    /// Test that the full format is kept unless fields or compact are asked for
+    #[test]
+    fn test_format_token_default() {
+        let token = token();
+        assert_eq!(
+            format_token(&token, true, &TokenView::default()),
+            format_token_info(&token, true)
+        );
+    }
+
+    /// Test that only the requested fields are shown, in order
+    #[test]
+    fn test_format_token_fields() {
+        let view = TokenView::from_args(
+            json!({"fields": ["symbol", "address"]})
+                .as_object()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            format_token(&token(), false, &view),
+            "• USDC\n  Address: 0x0b2C639c533813f4Aa9D7837CAf62653d097Ff85\n"
+        );
+        assert_eq!(view.labels(false), vec!["Symbol", "Address"]);
+    }
+
+    /// Test that compact mode puts each token on one line
+    #[test]
+    fn test_format_token_compact() {
+        let view = TokenView::from_args(json!({"compact": true}).as_object().unwrap()).unwrap();
+        assert_eq!(
+            format_token(&token(), false, &view),
+            "• USDC | USD Coin | 0x0b2C639c533813f4Aa9D7837CAf62653d097Ff85 | 6\n"
+        );
+
+        let view = TokenView::from_args(
+            json!({"compact": true, "fields": ["symbol", "bridge"]})
+                .as_object()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            format_token(&token(), false, &view),
+            "• USDC | 1 → 0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48\n"
+        );
+    }
+
+    /// Test that unknown or empty field lists are rejected
+    #[test]
+    fn test_token_view_validation() {
+        for args in [
+            json!({"fields": ["symbol", "price"]}),
+            json!({"fields": []}),
+            json!({"fields": "symbol"}),
+        ] {
+            assert!(matches!(
+                TokenView::from_args(args.as_object().unwrap()),
+                Err(ToolError::InvalidArguments(_))
+            ));
+        }
+    }
+}
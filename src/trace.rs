@@ -0,0 +1,269 @@
+//! Transaction replay and tracing
+//!
+//! `cast_run` replays a mined transaction with `cast run` on a fork of the chain at the
+//! block it was included in, and returns the decoded call trace. The endpoint must be
+//! given (or configured as `default_rpc_url` or `default_chain`), since tracing against
+//! the local Anvil session is rarely what's meant, and a chain name is resolved to a live
+//! endpoint from chainlist.org. Replays fetch a lot of state, so the run has its own timeout, and traces
+//! beyond `max_output_bytes` are truncated.
+
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::chainlist;
+use crate::error::ToolError;
+use crate::foundry::{self, FoundryExecutor};
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// How long a replay may take unless the caller says otherwise
+const DEFAULT_RUN_TIMEOUT_SECS: u64 = 300;
+
+/// Get the trace tools
+pub fn get_trace_tools() -> Vec<Tool> {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "tx_hash".to_string(),
+        json!({
+            "type": "string",
+            "description": "Hash of the transaction to replay"
+        }),
+    );
+    props.insert(
+        "rpc_url".to_string(),
+        json!({
+            "type": "string",
+            "description": "Archive-capable RPC endpoint of the transaction's chain"
+        }),
+    );
+    props.insert(
+        "chain".to_string(),
+        json!({
+            "type": "string",
+            "description": "Chain name or ID, resolved to a public endpoint from chainlist.org, instead of rpc_url"
+        }),
+    );
+    props.insert(
+        "quick".to_string(),
+        json!({
+            "type": "boolean",
+            "description": "Replay against the previous block's state only, skipping the transactions before it in the same block (default: false)"
+        }),
+    );
+    props.insert(
+        "decode_internal".to_string(),
+        json!({
+            "type": "boolean",
+            "description": "Identify internal functions in the trace (default: false)"
+        }),
+    );
+    props.insert(
+        "timeout_secs".to_string(),
+        json!({
+            "type": "integer",
+            "description": format!("Stop the replay after this many seconds (default: {})", DEFAULT_RUN_TIMEOUT_SECS)
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+    schema.insert("required".to_string(), json!(["tx_hash"]));
+
+    vec![Tool::new(
+        "cast_run".to_string(),
+        "Replay a mined transaction on a fork with cast run and return its decoded call trace, for debugging failed or unexpected transactions. Needs rpc_url or chain; long traces are truncated to the server's output limit.".to_string(),
+        Arc::new(schema),
+    )]
+}
+
+/// Check that `hash` is a 32-byte hex transaction hash
+fn check_tx_hash(hash: &str) -> Result<(), ToolError> {
+    let hex = hash.strip_prefix("0x").unwrap_or_default();
+    if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ToolError::InvalidArguments(format!(
+            "'{}' is not a transaction hash",
+            hash
+        )));
+    }
+    Ok(())
+}
+
+/// Pick the endpoint to replay against: `rpc_url` or `chain`, else the configured default.
+/// It may still be a chain name to resolve.
+fn run_endpoint(args: &JsonObject, default_rpc_url: Option<&str>) -> Result<String, ToolError> {
+    let get = |name: &str| {
+        args.get(name)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+    match (get("rpc_url"), get("chain")) {
+        (Some(_), Some(_)) => Err(ToolError::InvalidArguments(
+            "Give either 'rpc_url' or 'chain', not both".to_string(),
+        )),
+        (Some(endpoint), None) | (None, Some(endpoint)) => Ok(endpoint.to_string()),
+        (None, None) => default_rpc_url.map(str::to_string).ok_or_else(|| {
+            ToolError::InvalidArguments(
+                "cast_run needs 'rpc_url' or 'chain' for the transaction's network".to_string(),
+            )
+        }),
+    }
+}
+
+/// Read `timeout_secs`, defaulting to [`DEFAULT_RUN_TIMEOUT_SECS`]
+fn run_timeout(args: &JsonObject) -> Result<Duration, ToolError> {
+    let secs = match args.get("timeout_secs") {
+        None => DEFAULT_RUN_TIMEOUT_SECS,
+        Some(value) => value.as_u64().filter(|s| *s > 0).ok_or_else(|| {
+            ToolError::InvalidArguments("'timeout_secs' must be a positive integer".to_string())
+        })?,
+    };
+    Ok(Duration::from_secs(secs))
+}
+
+/// Handle cast_run tool call
+pub async fn handle_cast_run(
+    arguments: &Option<JsonObject>,
+    executor: Arc<FoundryExecutor>,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let empty = JsonObject::new();
+    let args = arguments.as_ref().unwrap_or(&empty);
+
+    let tx_hash = args
+        .get("tx_hash")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .ok_or_else(|| ToolError::InvalidArguments("Missing 'tx_hash' parameter".to_string()))?;
+    check_tx_hash(tx_hash)?;
+    let timeout = run_timeout(args)?;
+    // The configured default_rpc_url, else default_chain, stands in for a missing endpoint
+    let config = executor.config();
+    let default_chain = match config.default_rpc_url {
+        Some(_) => None,
+        None => config.default_chain.clone(),
+    };
+    let default = config
+        .default_rpc_url
+        .clone()
+        .or_else(|| default_chain.clone());
+    let endpoint = run_endpoint(args, default.as_deref())?;
+    let default_chain = default_chain.filter(|chain| *chain == endpoint);
+
+    let rpc_url = if chainlist::looks_like_chain_name(&endpoint) {
+        match chainlist::resolve_chain_rpc_url(&endpoint).await {
+            Ok(url) => url,
+            Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+            Err(e) => return Err(e.into()),
+        }
+    } else {
+        endpoint
+    };
+
+    let mut run_args = JsonObject::new();
+    run_args.insert("tx_hash".to_string(), json!(tx_hash));
+    run_args.insert("rpc-url".to_string(), json!(rpc_url));
+    for (from, to) in [("quick", "quick"), ("decode_internal", "decode-internal")] {
+        if args.get(from).and_then(|v| v.as_bool()) == Some(true) {
+            run_args.insert(to.to_string(), Value::Bool(true));
+        }
+    }
+    let run_args = Some(run_args);
+
+    // A replay can take minutes, so keep it off the async workers
+    let runner = executor.clone();
+    let run = tokio::task::spawn_blocking(move || {
+        let output = runner.run_tool("cast_run", &run_args, &[], Some(timeout))?;
+        if output.success {
+            Ok(output.stdout)
+        } else {
+            Err(runner.output_error(&run_args, &output))
+        }
+    })
+    .await
+    .unwrap_or_else(|e| {
+        Err(ToolError::execution_failed(format!(
+            "cast run did not complete: {}",
+            e
+        )))
+    });
+    let trace = match run {
+        Ok(trace) => trace.trim().to_string(),
+        Err(ToolError::ToolNotFound(_)) => {
+            return Err(ToolError::ForbiddenCommand(
+                "'cast_run' is disabled by the server configuration".to_string(),
+            )
+            .into())
+        }
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let limit = executor.config().max_output_bytes_for("cast_run");
+    let truncated = limit.is_some_and(|limit| trace.len() > limit);
+    let trace = foundry::truncate_output(trace, limit);
+
+    let mut result = CallToolResult::success(vec![Content::text(trace.clone())]);
+    result.structured_content = Some(json!({
+        "tx_hash": tx_hash,
+        "trace": trace,
+        "truncated": truncated,
+    }));
+    chainlist::default_chain_note(Ok(result), default_chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that only 32-byte hex hashes are accepted
+    #[test]
+    fn test_check_tx_hash() {
+        let hash = format!("0x{}", "ab".repeat(32));
+        assert!(check_tx_hash(&hash).is_ok());
+        assert!(check_tx_hash(&hash[2..]).is_err());
+        assert!(check_tx_hash("0x1234").is_err());
+        assert!(check_tx_hash(&format!("0x{}", "zz".repeat(32))).is_err());
+    }
+
+    /// Test that an endpoint is required, with the configured default as a fallback
+    #[test]
+    fn test_run_endpoint() {
+        let args = |v: Value| v.as_object().cloned().unwrap();
+        assert_eq!(
+            run_endpoint(&args(json!({"rpc_url": "https://rpc.example"})), None).unwrap(),
+            "https://rpc.example"
+        );
+        assert_eq!(
+            run_endpoint(&args(json!({"chain": "base"})), Some("https://default")).unwrap(),
+            "base"
+        );
+        assert_eq!(
+            run_endpoint(&args(json!({})), Some("https://default")).unwrap(),
+            "https://default"
+        );
+        assert!(run_endpoint(&args(json!({})), None).is_err());
+        assert!(run_endpoint(
+            &args(json!({"rpc_url": "https://a", "chain": "base"})),
+            None
+        )
+        .is_err());
+    }
+
+    /// Test that the timeout defaults and must be positive
+    #[test]
+    fn test_run_timeout() {
+        let args = |v: Value| v.as_object().cloned().unwrap();
+        assert_eq!(
+            run_timeout(&args(json!({}))).unwrap(),
+            Duration::from_secs(DEFAULT_RUN_TIMEOUT_SECS)
+        );
+        assert_eq!(
+            run_timeout(&args(json!({"timeout_secs": 30}))).unwrap(),
+            Duration::from_secs(30)
+        );
+        assert!(run_timeout(&args(json!({"timeout_secs": 0}))).is_err());
+    }
+}
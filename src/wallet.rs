@@ -0,0 +1,277 @@
+//! Wallet address derivation
+//!
+//! `cast_wallet_address` derives an address from public information only: an uncompressed
+//! public key is hashed with `cast keccak`, so no private key is ever handled. Reading the
+//! address of a keystore needs its password and is only offered when dangerous commands are
+//! allowed (or the server is unlocked). Unlike the schema-generated `cast_wallet_address`,
+//! it never takes a private key.
+
+use rmcp::model::{CallToolResult, Content, Tool};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::cast;
+use crate::conversion::{self, ConversionParams};
+use crate::error::ToolError;
+use crate::foundry::FoundryExecutor;
+
+type JsonObject = serde_json::Map<String, Value>;
+
+/// Argument names that would carry key material, which this tool never accepts
+const SECRET_INPUTS: &[&str] = &["private_key", "private-key", "mnemonic"];
+
+/// Get the wallet tools
+pub fn get_wallet_tools() -> Vec<Tool> {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        "public_key".to_string(),
+        json!({
+            "type": "string",
+            "description": "Uncompressed secp256k1 public key: 64 bytes of hex, optionally with the 0x04 prefix"
+        }),
+    );
+    props.insert(
+        "keystore".to_string(),
+        json!({
+            "type": "string",
+            "description": "Path to a keystore file (only when dangerous commands are allowed)"
+        }),
+    );
+    props.insert(
+        "password".to_string(),
+        json!({
+            "type": "string",
+            "description": "Keystore password (only with keystore)"
+        }),
+    );
+    props.insert(
+        "expected".to_string(),
+        json!({
+            "type": "string",
+            "description": "Address to compare the derived one against, e.g. to verify a vanity address (optional)"
+        }),
+    );
+
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), Value::String("object".to_string()));
+    schema.insert("properties".to_string(), Value::Object(props));
+
+    vec![Tool::new(
+        "cast_wallet_address".to_string(),
+        "Derive an Ethereum address from a public key, optionally checking it against an expected address. Private keys and mnemonics are refused; a keystore can be used only when dangerous commands are allowed.".to_string(),
+        Arc::new(schema),
+    )]
+}
+
+/// Validate an uncompressed public key and return its 64-byte body as lowercase hex
+fn normalize_public_key(public_key: &str) -> Result<String, ToolError> {
+    let hex = public_key.trim();
+    let hex = hex
+        .strip_prefix("0x")
+        .or_else(|| hex.strip_prefix("0X"))
+        .unwrap_or(hex);
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ToolError::InvalidArguments(
+            "'public_key' is not hex".to_string(),
+        ));
+    }
+
+    match hex.len() {
+        128 => Ok(hex.to_ascii_lowercase()),
+        130 if hex.starts_with("04") => Ok(hex[2..].to_ascii_lowercase()),
+        64 => Err(ToolError::InvalidArguments(
+            "'public_key' is 32 bytes, which is the size of a private key; this tool only accepts public keys".to_string(),
+        )),
+        66 => Err(ToolError::InvalidArguments(
+            "Compressed public keys are not supported; pass the uncompressed 64-byte key".to_string(),
+        )),
+        _ => Err(ToolError::InvalidArguments(
+            "'public_key' must be an uncompressed public key (64 bytes, or 65 with the 04 prefix)".to_string(),
+        )),
+    }
+}
+
+/// Address from the `cast keccak` hash of a public key: its last 20 bytes
+fn address_from_hash(hash: &str) -> Result<String, ToolError> {
+    let hex = hash.trim().trim_start_matches("0x");
+    if hex.len() != 64 {
+        return Err(ToolError::execution_failed(format!(
+            "Unexpected hash from cast keccak: '{}'",
+            hash
+        )));
+    }
+    Ok(format!("0x{}", &hex[24..]))
+}
+
+/// Derive the checksummed address of a public key
+fn public_key_address(public_key: &str, cast_path: &str) -> Result<String, ToolError> {
+    let body = normalize_public_key(public_key)?;
+    let hash = cast::run(cast_path, &["keccak".to_string(), format!("0x{}", body)])?;
+    let address = address_from_hash(&hash)?;
+
+    conversion::execute_conversion(
+        ConversionParams {
+            conversion_type: "to-check-sum-address".to_string(),
+            value: Some(address),
+            ..Default::default()
+        },
+        cast_path,
+    )
+}
+
+/// Read a keystore's address with the schema `cast_wallet_address` tool
+fn keystore_address(
+    keystore: &str,
+    password: Option<&str>,
+    executor: &FoundryExecutor,
+) -> Result<String, ToolError> {
+    let mut args = JsonObject::new();
+    args.insert("keystore".to_string(), json!(keystore));
+    if let Some(password) = password {
+        args.insert("password".to_string(), json!(password));
+    }
+    let args = Some(args);
+
+    let output = executor.run_tool("cast_wallet_address", &args, &[], None)?;
+    if !output.success {
+        return Err(executor.output_error(&args, &output));
+    }
+    Ok(output.stdout.trim().to_string())
+}
+
+/// Handle cast_wallet_address tool call
+pub fn handle_cast_wallet_address(
+    arguments: &Option<JsonObject>,
+    executor: &FoundryExecutor,
+    cast_path: &str,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let empty = JsonObject::new();
+    let args = arguments.as_ref().unwrap_or(&empty);
+    let get = |name: &str| args.get(name).and_then(|v| v.as_str());
+
+    if let Some(name) = SECRET_INPUTS.iter().find(|name| args.contains_key(**name)) {
+        return Err(ToolError::ForbiddenCommand(format!(
+            "'{}' is not accepted: cast_wallet_address only derives addresses from public keys (or keystores, when dangerous commands are allowed)",
+            name
+        ))
+        .into());
+    }
+
+    let (address, source) = match (get("public_key"), get("keystore")) {
+        (Some(_), Some(_)) => {
+            return Err(ToolError::InvalidArguments(
+                "Give either 'public_key' or 'keystore', not both".to_string(),
+            )
+            .into())
+        }
+        (None, None) => {
+            return Err(
+                ToolError::InvalidArguments("Missing 'public_key' parameter".to_string()).into(),
+            )
+        }
+        (Some(public_key), None) => (public_key_address(public_key, cast_path), "public_key"),
+        (None, Some(keystore)) => {
+            let dangerous_allowed = executor.config().allow_dangerous || executor.is_elevated();
+            if !dangerous_allowed {
+                return Err(ToolError::ForbiddenCommand(
+                    "Keystores need allow_dangerous (or an unlocked server); only public keys are accepted".to_string(),
+                )
+                .into());
+            }
+            (
+                keystore_address(keystore, get("password"), executor),
+                "keystore",
+            )
+        }
+    };
+    let address = match address {
+        Ok(address) => address,
+        Err(e) if e.is_execution_error() => return Ok(e.into_call_tool_result()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut text = format!("Address: {}", address);
+    let mut structured = json!({ "address": address, "source": source });
+    if let Some(expected) = get("expected") {
+        let matches = expected.trim().eq_ignore_ascii_case(&address);
+        text.push_str(if matches {
+            "\nMatches the expected address"
+        } else {
+            "\nDoes NOT match the expected address"
+        });
+        structured["expected"] = json!(expected);
+        structured["matches"] = json!(matches);
+    }
+
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(structured);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that uncompressed keys are accepted and anything key-sized otherwise is refused
+    #[test]
+    fn test_normalize_public_key() {
+        let body = "ab".repeat(64);
+        assert_eq!(normalize_public_key(&body).unwrap(), body);
+        assert_eq!(
+            normalize_public_key(&format!("0x04{}", body.to_uppercase())).unwrap(),
+            body
+        );
+
+        for key in [
+            "ab".repeat(32),
+            format!("0x02{}", "ab".repeat(32)),
+            "zz".repeat(64),
+            format!("05{}", body),
+        ] {
+            assert!(matches!(
+                normalize_public_key(&key),
+                Err(ToolError::InvalidArguments(_))
+            ));
+        }
+    }
+
+    /// Test that the address is the last 20 bytes of the hash
+    #[test]
+    fn test_address_from_hash() {
+        let hash = format!("0x{}{}", "00".repeat(12), "11".repeat(20));
+        assert_eq!(
+            address_from_hash(&hash).unwrap(),
+            format!("0x{}", "11".repeat(20))
+        );
+        assert!(address_from_hash("0x1234").is_err());
+    }
+
+    /// Test that private key material is refused before anything runs
+    #[test]
+    fn test_refuses_private_keys() {
+        let executor = FoundryExecutor::with_config(
+            crate::schema::SchemaFile { tools: vec![] },
+            crate::config::Config::default(),
+        );
+        for args in [
+            json!({"private_key": "0x01"}),
+            json!({"mnemonic": "test test test"}),
+        ] {
+            let args = Some(args.as_object().unwrap().clone());
+            assert!(handle_cast_wallet_address(&args, &executor, "cast").is_err());
+        }
+    }
+
+    /// Test that keystores are refused unless dangerous commands are allowed
+    #[test]
+    fn test_keystore_needs_dangerous() {
+        let executor = FoundryExecutor::with_config(
+            crate::schema::SchemaFile { tools: vec![] },
+            crate::config::Config::safe_default(),
+        );
+        let args = json!({"keystore": "/tmp/keystore.json"});
+        let args = Some(args.as_object().unwrap().clone());
+        let err = handle_cast_wallet_address(&args, &executor, "cast").unwrap_err();
+        assert!(err.message.contains("allow_dangerous"));
+    }
+}